@@ -6,6 +6,8 @@
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use axum::extract::{Path as UrlPath, State};
+use axum::Json;
 use cli::{Cli, Commands, DeploymentMode};
 use config::{generate_default_config, load_config, save_config, validate_config, MasterConfig};
 use instrument::api::handlers::InstrumentApiState;
@@ -13,51 +15,73 @@ use instrument::db::models::Environment;
 use instrument::db::postgres::PostgresInstrumentStore;
 use instrument::worker::service::{InstrumentWorker, StaticSpotPriceProvider};
 use observability::{init_logging, LogFormat};
-use server::{ports, CombinedServer, ServerConfig, ServerExt};
+use server::{ports, CombinedServer, GrpcServerBuilder, ServerConfig, ServerExt};
 use common::addressbook::AddressBook;
-use common::types::{Side, TimeInForce as CommonTimeInForce};
+use common::types::{
+    OrderType as CommonOrderType, PostOnly as CommonPostOnly, Side, TimeInForce as CommonTimeInForce,
+};
 use oms::{
-    OrderManager, PostgresOrderStore, MockMatchingClient,
+    OrderFill, OrderManager, PostgresOrderStore, MockMatchingClient, spawn_expiry_sweeper,
     api::{handlers::OmsApiState, routes::create_router as create_oms_router, forwarding::OmsForwardingState, forwarding::OmsForwarder},
-    clients::matching::http::HttpMatchingClient,
+    clients::matching::{grpc::GrpcMatchingClient, http::HttpMatchingClient, MassQuoteOutcome},
 };
 use risk_engine::{
     MarginConfig, RiskEngine,
     api::{RiskApiState, create_router as create_risk_router},
 };
 use matching_engine::{
-    domain::{BookOrder, OrderSide, TimeInForce as MeTimeInForce},
+    block::BlockTrade,
+    combo::{ComboDefinition, ComboLeg},
+    domain::{
+        underlying_of, BookOrder, MassCancelFilter as MeMassCancelFilter, OrderSide,
+        OrderType as MeOrderType, PostOnly as MePostOnly, TimeInForce as MeTimeInForce, Trade,
+    },
     engine::MatchingEngine,
-    api::create_dyn_router,
-    store::{create_store_from_config, InMemoryStore, MatchingStore},
-    circuit_breaker::CircuitBreakerConfig,
+    error::MatchingError,
+    mmp,
+    api::{create_dyn_router, handlers::CircuitBreakersResponse},
+    store::{create_store_from_config, snapshot_interval, spawn_snapshots, InMemoryStore, MatchingStore},
+    clock::SequenceIds,
+    event::MatchingEvent,
+    grpc::{MatchingGrpcService, MatchingServiceServer},
+    feed::{EventSubscription, SubscriptionError},
+    price::PriceScale,
+    shard::{Shard, ShardConfig, ShardError, ShardedEngine},
+    wal::{WalConfig, WriteAheadLog},
 };
+use market_data::MarketDataCoordinator;
 use sqlx::postgres::PgPoolOptions;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::{watch, RwLock};
+use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 // ==================== Monolith Matching Client ====================
 
 /// A matching client that runs in-process (monolith mode)
-/// Wraps the matching engine directly without network overhead
+///
+/// Orders go straight to the sharded matching runtime without network
+/// overhead; each instrument's book lives on the shard task that owns it.
 pub struct MonolithMatchingClient {
-    engine: Arc<RwLock<MatchingEngine>>,
+    runtime: ShardedEngine,
 }
 
 impl MonolithMatchingClient {
     /// Create a new monolith matching client
-    pub fn new(engine: MatchingEngine) -> Self {
-        Self {
-            engine: Arc::new(RwLock::new(engine)),
-        }
+    pub fn new(runtime: ShardedEngine) -> Self {
+        Self { runtime }
     }
 
     /// Convert OMS order to matching engine BookOrder
-    fn oms_to_book_order(order: &oms::types::Order) -> BookOrder {
+    ///
+    /// The decimal limit price is converted to ticks with the instrument's
+    /// price scale; an off-tick price fails unless the scale rounds it.
+    fn oms_to_book_order(
+        order: &oms::types::Order,
+        scale: &PriceScale,
+    ) -> oms::store::traits::OmsResult<BookOrder> {
         let side = match order.side {
             Side::Buy => OrderSide::Buy,
             Side::Sell => OrderSide::Sell,
@@ -67,33 +91,149 @@ impl MonolithMatchingClient {
             CommonTimeInForce::Gtc => MeTimeInForce::Gtc,
             CommonTimeInForce::Ioc => MeTimeInForce::Ioc,
             CommonTimeInForce::Fok => MeTimeInForce::Fok,
-            CommonTimeInForce::Day => MeTimeInForce::Gtc, // DAY treated as GTC
+            CommonTimeInForce::Gtd => MeTimeInForce::Gtd,
+            CommonTimeInForce::Day => MeTimeInForce::Day,
+        };
+
+        let order_type = match order.order_type {
+            CommonOrderType::Market => MeOrderType::Market,
+            _ => MeOrderType::Limit,
+        };
+
+        let post_only = match order.post_only {
+            CommonPostOnly::None => MePostOnly::None,
+            CommonPostOnly::Reject => MePostOnly::Reject,
+            CommonPostOnly::Reprice => MePostOnly::Reprice,
+        };
+
+        // Market orders without a price get their protection limit in the engine
+        let price = match order.price {
+            Some(price) => scale
+                .order_ticks(price, side)
+                .map_err(|e| oms::OmsError::InvalidOrder(e.to_string()))?,
+            None => 0,
         };
 
-        BookOrder::new(
+        Ok(BookOrder::new(
             order.order_id,
             order.user_id,
             side,
-            order.price.unwrap_or(0.0),
+            price,
             order.quantity,
             0, // sequence - engine assigns
             time_in_force,
         )
         .with_instrument_id(order.instrument_id.clone())
+        .with_order_type(order_type)
+        .with_post_only(post_only)
+        .with_all_or_none(order.all_or_none)
+        .with_min_quantity(order.min_quantity)
+        .with_expires_at(order.expires_at))
     }
+
+    /// Combo an order's legs define, if it is a combo order
+    fn combo_definition(order: &oms::types::Order) -> Option<ComboDefinition> {
+        if !order.is_combo() {
+            return None;
+        }
+        let legs = order
+            .legs
+            .iter()
+            .map(|leg| {
+                let side = match leg.side {
+                    Side::Buy => OrderSide::Buy,
+                    Side::Sell => OrderSide::Sell,
+                };
+                ComboLeg::new(leg.instrument_id.clone(), side, leg.ratio)
+            })
+            .collect();
+        Some(ComboDefinition::new(order.instrument_id.clone(), legs))
+    }
+
+    /// Define a combo in an engine, returning the event if it is new
+    fn define_combo(
+        engine: &mut MatchingEngine,
+        combo: ComboDefinition,
+    ) -> oms::store::traits::OmsResult<Vec<MatchingEvent>> {
+        let combo_id = combo.combo_id.clone();
+        match engine.define_combo(combo) {
+            Ok(Some(_)) => {
+                let defined = engine.combo(&combo_id).expect("combo was just defined");
+                Ok(vec![MatchingEvent::combo_defined(defined)])
+            }
+            Ok(None) => Ok(Vec::new()),
+            Err(e) => Err(oms::OmsError::InvalidOrder(e.to_string())),
+        }
+    }
+
+    /// Subscribe to every shard from its next event, in shard order
+    pub async fn subscribe_live(&self) -> Result<Vec<EventSubscription>, ShardError> {
+        let sequences = self
+            .runtime
+            .broadcast(|engine| (engine.sequence(), Vec::new()))
+            .await?;
+
+        let mut subscriptions = Vec::with_capacity(sequences.len());
+        for (shard, sequence) in sequences.into_iter().enumerate() {
+            subscriptions.push(self.runtime.subscribe(shard, sequence + 1).await?);
+        }
+        Ok(subscriptions)
+    }
+
+    /// Price scales of some instruments, in one shard call
+    ///
+    /// Scales come from the configuration, so every shard has the same ones.
+    async fn price_scales(&self, instrument_ids: BTreeSet<String>) -> Result<HashMap<String, PriceScale>, ShardError> {
+        let Some(first) = instrument_ids.first().cloned() else {
+            return Ok(HashMap::new());
+        };
+        self.runtime
+            .on_instrument(&first, move |engine| {
+                let scales = instrument_ids
+                    .into_iter()
+                    .map(|id| {
+                        let scale = engine.price_scale(&id);
+                        (id, scale)
+                    })
+                    .collect();
+                (scales, Vec::new())
+            })
+            .await
+    }
+}
+
+/// Map a runtime failure to the OMS error for an unreachable engine
+fn matching_unavailable(e: ShardError) -> oms::OmsError {
+    oms::OmsError::MatchingUnavailable(e.to_string())
 }
 
 #[async_trait]
 impl oms::clients::matching::MatchingClient for MonolithMatchingClient {
     async fn submit_order(&self, order: &oms::types::Order) -> oms::store::traits::OmsResult<()> {
-        let book_order = Self::oms_to_book_order(order);
-
-        let mut engine = self.engine.write().await;
-        let result = engine.match_order(book_order);
+        let order = order.clone();
+        let combo = Self::combo_definition(&order);
+        let result = self
+            .runtime
+            .on_instrument(&order.instrument_id.clone(), move |engine| {
+                let mut events = match combo.map(|combo| Self::define_combo(engine, combo)).transpose() {
+                    Ok(events) => events.unwrap_or_default(),
+                    Err(e) => return (Err(e), Vec::new()),
+                };
+                let book_order = match Self::oms_to_book_order(&order, &engine.price_scale(&order.instrument_id)) {
+                    Ok(book_order) => book_order,
+                    Err(e) => return (Err(e), events),
+                };
+                let result = engine.match_order(book_order.clone());
+                events.extend(MatchingEvent::from_match(&book_order, &result));
+                (Ok((order.order_id, result)), events)
+            })
+            .await
+            .map_err(matching_unavailable)?;
+        let (order_id, result) = result?;
 
         if result.has_trades() {
             info!(
-                order_id = %order.order_id,
+                order_id = %order_id,
                 trades = result.trades.len(),
                 filled = result.filled_quantity(),
                 "Order matched"
@@ -103,23 +243,637 @@ impl oms::clients::matching::MatchingClient for MonolithMatchingClient {
         Ok(())
     }
 
-    async fn cancel_order(&self, order_id: Uuid) -> oms::store::traits::OmsResult<()> {
-        let mut engine = self.engine.write().await;
-        // Note: We'd need instrument_id to properly cancel - for now iterate all books
-        for instrument_id in engine.instruments() {
-            engine.cancel_order(&instrument_id, order_id);
-        }
+    async fn cancel_order(&self, instrument_id: &str, order_id: Uuid) -> oms::store::traits::OmsResult<()> {
+        self.runtime
+            .on_instrument(instrument_id, move |engine| match engine.cancel_by_id(order_id) {
+                Some(cancelled) => {
+                    let event = MatchingEvent::OrderCancelled {
+                        order_id,
+                        instrument_id: cancelled.instrument_id,
+                        sequence: engine.sequence(),
+                    };
+                    ((), vec![event])
+                }
+                None => ((), Vec::new()),
+            })
+            .await
+            .map_err(matching_unavailable)?;
         Ok(())
     }
 
+    async fn mass_cancel(
+        &self,
+        filter: &oms::types::MassCancelFilter,
+    ) -> oms::store::traits::OmsResult<Vec<Uuid>> {
+        let filter = MeMassCancelFilter {
+            user_id: filter.user_id,
+            instrument_id: filter.instrument_id.clone(),
+            underlying: filter.underlying.clone(),
+            side: filter.side.map(|side| match side {
+                Side::Buy => OrderSide::Buy,
+                Side::Sell => OrderSide::Sell,
+            }),
+        };
+
+        // A single instrument lives on one shard; anything wider spans them all
+        let instrument_id = filter.instrument_id.clone();
+        let mass_cancel = move |engine: &mut MatchingEngine| {
+            let result = engine.mass_cancel(&filter);
+            let events = MatchingEvent::from_mass_cancel(&result).into_iter().collect();
+            (result.order_ids(), events)
+        };
+        let order_ids = match instrument_id {
+            Some(instrument_id) => self.runtime.on_instrument(&instrument_id, mass_cancel).await,
+            None => self.runtime.broadcast(mass_cancel).await.map(|ids| ids.concat()),
+        }
+        .map_err(matching_unavailable)?;
+
+        info!(orders = order_ids.len(), "Orders mass cancelled");
+
+        Ok(order_ids)
+    }
+
+    async fn expire_orders(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> oms::store::traits::OmsResult<Vec<Uuid>> {
+        let expired: Vec<Uuid> = self
+            .runtime
+            .broadcast(move |engine| {
+                let expired = engine.expire_orders(now);
+                let order_ids: Vec<Uuid> = expired.iter().map(|e| e.order.order_id).collect();
+                (order_ids, MatchingEvent::from_expired(&expired))
+            })
+            .await
+            .map_err(matching_unavailable)?
+            .concat();
+
+        if !expired.is_empty() {
+            info!(orders = expired.len(), "Orders expired");
+        }
+
+        Ok(expired)
+    }
+
     async fn modify_order(
         &self,
         old_order_id: Uuid,
         new_order: &oms::types::Order,
     ) -> oms::store::traits::OmsResult<()> {
-        self.cancel_order(old_order_id).await?;
+        // The replacement is on the same instrument
+        self.cancel_order(&new_order.instrument_id, old_order_id).await?;
         self.submit_order(new_order).await
     }
+
+    async fn amend_order(
+        &self,
+        instrument_id: &str,
+        order_id: Uuid,
+        new_price: Option<f64>,
+        new_quantity: Option<u32>,
+    ) -> oms::store::traits::OmsResult<()> {
+        let amended = self
+            .runtime
+            .on_instrument(instrument_id, move |engine| {
+                let Some(resting) = engine.get_order(order_id) else {
+                    return (None, Vec::new());
+                };
+                let scale = engine.price_scale(&resting.instrument_id);
+                let new_price = match new_price.map(|price| scale.order_ticks(price, resting.side)).transpose() {
+                    Ok(new_price) => new_price,
+                    Err(e) => return (Some(Err(oms::OmsError::InvalidOrder(e.to_string()))), Vec::new()),
+                };
+                match engine.amend_order(order_id, new_price, new_quantity) {
+                    Ok(result) => {
+                        let events = MatchingEvent::from_amend(order_id, new_price, new_quantity, &result);
+                        (Some(Ok(result)), events)
+                    }
+                    Err(e) => (Some(Err(oms::OmsError::OrderNotModifiable(e.to_string()))), Vec::new()),
+                }
+            })
+            .await
+            .map_err(matching_unavailable)?
+            .ok_or_else(|| oms::OmsError::OrderNotModifiable(format!("Order {} is not resting", order_id)))?;
+        let result = amended?;
+
+        info!(
+            order_id = %order_id,
+            priority_retained = result.priority_retained,
+            trades = result.trades.len(),
+            "Order amended"
+        );
+
+        Ok(())
+    }
+
+    async fn book_block_trade(
+        &self,
+        rfq_id: Uuid,
+        taker: &oms::types::Order,
+        maker: &oms::types::Order,
+    ) -> oms::store::traits::OmsResult<()> {
+        let combo = Self::combo_definition(taker);
+        let (taker, maker) = (taker.clone(), maker.clone());
+        // A combo's shard also holds its legs, so their halts are checked
+        let trades = self
+            .runtime
+            .on_instrument(&taker.instrument_id.clone(), move |engine| {
+                let mut events = match combo.map(|combo| Self::define_combo(engine, combo)).transpose() {
+                    Ok(events) => events.unwrap_or_default(),
+                    Err(e) => return (Err(e), Vec::new()),
+                };
+                let taker_side = match taker.side {
+                    Side::Buy => OrderSide::Buy,
+                    Side::Sell => OrderSide::Sell,
+                };
+                let price = match engine
+                    .price_scale(&taker.instrument_id)
+                    .order_ticks(taker.price.unwrap_or(0.0), taker_side)
+                {
+                    Ok(price) => price,
+                    Err(e) => return (Err(oms::OmsError::InvalidOrder(e.to_string())), events),
+                };
+                let block = BlockTrade {
+                    rfq_id,
+                    instrument_id: taker.instrument_id.clone(),
+                    price,
+                    quantity: taker.quantity,
+                    taker_order_id: taker.order_id,
+                    taker_user_id: taker.user_id,
+                    taker_side,
+                    maker_order_id: maker.order_id,
+                    maker_user_id: maker.user_id,
+                };
+                match engine.book_block_trade(&block) {
+                    Ok(trades) => {
+                        events.extend(MatchingEvent::from_block_trade(&trades));
+                        (Ok(trades), events)
+                    }
+                    Err(e) => (Err(oms::OmsError::InvalidOrder(e.to_string())), events),
+                }
+            })
+            .await
+            .map_err(matching_unavailable)??;
+
+        info!(rfq_id = %rfq_id, trades = trades.len(), "Block trade booked");
+
+        Ok(())
+    }
+
+    async fn mass_quote(
+        &self,
+        user_id: Uuid,
+        quotes: &[oms::types::Order],
+    ) -> oms::store::traits::OmsResult<MassQuoteOutcome> {
+        // Each shard replaces the quotes on its own underlyings, whose
+        // market maker protection state it holds
+        let mut by_shard: BTreeMap<usize, Vec<oms::types::Order>> = BTreeMap::new();
+        for quote in quotes {
+            by_shard
+                .entry(self.runtime.shard_of(&quote.instrument_id))
+                .or_default()
+                .push(quote.clone());
+        }
+
+        let mut outcome = MassQuoteOutcome::default();
+        let mut first_error = None;
+        let mut quoted = false;
+        for quotes in by_shard.into_values() {
+            let quote_ids: Vec<Uuid> = quotes.iter().map(|quote| quote.order_id).collect();
+            let result = self
+                .runtime
+                .on_instrument(&quotes[0].instrument_id.clone(), move |engine| {
+                    let book_orders = quotes
+                        .iter()
+                        .map(|quote| Self::oms_to_book_order(quote, &engine.price_scale(&quote.instrument_id)))
+                        .collect::<oms::store::traits::OmsResult<Vec<_>>>();
+                    let book_orders = match book_orders {
+                        Ok(book_orders) => book_orders,
+                        Err(e) => return (Err(e), Vec::new()),
+                    };
+                    match engine.mass_quote(user_id, book_orders) {
+                        Ok(result) => {
+                            let events = MatchingEvent::from_mass_quote(&result);
+                            (Ok((result.replaced.order_ids(), result.rejected_order_ids())), events)
+                        }
+                        Err(e @ MatchingError::MmpFrozen(_)) => {
+                            (Err(oms::OmsError::QuotingFrozen(e.to_string())), Vec::new())
+                        }
+                        Err(e) => (Err(oms::OmsError::InvalidOrder(e.to_string())), Vec::new()),
+                    }
+                })
+                .await
+                .map_err(matching_unavailable)?;
+
+            // A shard that refuses its quotes does not undo the others
+            match result {
+                Ok((replaced, rejected)) => {
+                    quoted = true;
+                    outcome.replaced.extend(replaced);
+                    outcome.rejected.extend(rejected);
+                }
+                Err(e) => {
+                    outcome.rejected.extend(quote_ids);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if let Some(e) = first_error.filter(|_| !quoted) {
+            return Err(e);
+        }
+
+        info!(
+            user_id = %user_id,
+            replaced = outcome.replaced.len(),
+            rejected = outcome.rejected.len(),
+            "Mass quote applied"
+        );
+
+        Ok(outcome)
+    }
+
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> oms::store::traits::OmsResult<bool> {
+        let underlying = underlying.to_string();
+        self.runtime
+            .on_instrument(&underlying.clone(), move |engine| match engine.reset_mmp(user_id, &underlying) {
+                Some(sequence) => {
+                    let event = MatchingEvent::MmpReset {
+                        user_id,
+                        underlying,
+                        sequence,
+                    };
+                    (true, vec![event])
+                }
+                None => (false, Vec::new()),
+            })
+            .await
+            .map_err(matching_unavailable)
+    }
+}
+
+// ==================== Fill Forwarding ====================
+
+/// Apply every trade the matching shards execute to both orders in the OMS
+///
+/// Quotes cancelled by market maker protection and orders cancelled by
+/// self-trade prevention are cancelled in the OMS too. One task follows
+/// each shard's event stream. A forwarder that lags resubscribes from where
+/// it stopped and skips trades it already applied; shards without a
+/// journal have no backlog, so such a gap is only logged.
+fn spawn_fill_forwarders(
+    client: Arc<MonolithMatchingClient>,
+    subscriptions: Vec<EventSubscription>,
+    manager: Arc<OrderManager>,
+    envs: Vec<oms::Environment>,
+) {
+    for (shard, subscription) in subscriptions.into_iter().enumerate() {
+        tokio::spawn(forward_fills(
+            shard,
+            client.clone(),
+            subscription,
+            manager.clone(),
+            envs.clone(),
+        ));
+    }
+}
+
+/// Forward one shard's trades and cancellations until its feed closes
+///
+/// Events already published when one arrives are handled with it as a
+/// batch, whose trades are priced with one lookup of their price scales.
+async fn forward_fills(
+    shard: usize,
+    client: Arc<MonolithMatchingClient>,
+    mut subscription: EventSubscription,
+    manager: Arc<OrderManager>,
+    envs: Vec<oms::Environment>,
+) {
+    let mut applied = 0;
+    loop {
+        let mut received = vec![subscription.recv().await];
+        while received.last().is_some_and(Result::is_ok) {
+            match subscription.try_recv() {
+                Some(next) => received.push(next),
+                None => break,
+            }
+        }
+
+        let traded = received
+            .iter()
+            .filter_map(|event| match event {
+                Ok(MatchingEvent::TradeExecuted { trade, sequence }) if *sequence > applied => {
+                    Some(trade.instrument_id.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let scales = match client.price_scales(traded).await {
+            Ok(scales) => scales,
+            Err(e) => {
+                error!(shard, error = %e, "Could not price trades for fills");
+                HashMap::new()
+            }
+        };
+
+        for event in received {
+            match event {
+                Ok(MatchingEvent::TradeExecuted { trade, sequence }) if sequence > applied => {
+                    applied = sequence;
+                    match scales.get(&trade.instrument_id) {
+                        Some(scale) => apply_trade(&manager, &envs, &trade, scale.to_price(trade.price)).await,
+                        None => error!(trade_id = %trade.trade_id, "Trade not applied: no price scale"),
+                    }
+                }
+                Ok(MatchingEvent::MmpTriggered { user_id, underlying, order_ids, sequence, .. }) if sequence > applied => {
+                    applied = sequence;
+                    warn!(user_id = %user_id, underlying, quotes = order_ids.len(), "Market maker protection tripped");
+                    apply_cancels(&manager, &envs, &order_ids).await;
+                }
+                // Carries the incoming order's sequence, below that of its own
+                // trades, so it is not deduplicated; cancelling twice is a no-op
+                Ok(MatchingEvent::SelfTradePrevented { order_id, cancelled_order_ids, .. }) => {
+                    debug!(order_id = %order_id, cancelled = cancelled_order_ids.len(), "Self-trade prevented");
+                    apply_cancels(&manager, &envs, &cancelled_order_ids).await;
+                }
+                Ok(_) => {}
+                Err(SubscriptionError::Lagged { skipped, resume_from }) => {
+                    warn!(shard, skipped, "Fill forwarder lagged, resubscribing from sequence {}", resume_from);
+                    match client.runtime.subscribe(shard, resume_from).await {
+                        Ok(resumed) => subscription = resumed,
+                        Err(e) => {
+                            error!(shard, error = %e, "Fill forwarder stopped");
+                            return;
+                        }
+                    }
+                }
+                Err(SubscriptionError::Closed) => return,
+            }
+        }
+    }
+}
+
+/// Cancel orders the engine removed on its own in every environment
+async fn apply_cancels(manager: &OrderManager, envs: &[oms::Environment], order_ids: &[Uuid]) {
+    for env in envs {
+        if let Err(e) = manager.apply_cancels(order_ids, *env).await {
+            error!(orders = order_ids.len(), error = %e, "Could not cancel orders in the OMS");
+        }
+    }
+}
+
+/// Record a trade at its decimal `price` as a fill on its taker and maker
+/// orders
+async fn apply_trade(manager: &OrderManager, envs: &[oms::Environment], trade: &Trade, price: f64) {
+    let sides = [
+        (trade.taker_order_id, trade.maker_order_id, false),
+        (trade.maker_order_id, trade.taker_order_id, true),
+    ];
+    for (order_id, counterparty_order_id, is_maker) in sides {
+        let mut fill = OrderFill::new(order_id, trade.trade_id, trade.quantity, price, is_maker);
+        fill.counterparty_order_id = Some(counterparty_order_id);
+        fill.executed_at = trade.timestamp;
+        // Leg trades of a combo fill name the leg they belong to
+        if trade.combo_id.is_some() {
+            fill.instrument_id = Some(trade.instrument_id.clone());
+        }
+
+        // The order lives in exactly one environment
+        for env in envs {
+            match manager.apply_fill(order_id, fill.clone(), *env).await {
+                Ok(_) => break,
+                Err(oms::OmsError::NotFound(_)) => continue,
+                Err(e) => {
+                    warn!(order_id = %order_id, trade_id = %trade.trade_id, error = %e, "Failed to apply fill");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// ==================== Shard Snapshots ====================
+
+/// Snapshot every shard's engine next to its journal every `interval`
+///
+/// A failed round is logged and retried on the next tick.
+fn spawn_shard_snapshots(client: Arc<MonolithMatchingClient>, interval: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick is immediate, and the shards were just recovered
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = client.runtime.snapshot().await {
+                warn!(error = %e, "Shard snapshot failed");
+            }
+        }
+    });
+}
+
+// ==================== Reference Price Feed ====================
+
+/// Push index and mark prices from market data into the matching shards
+///
+/// Every `interval` each instrument with a book gets its underlying's index
+/// price, and every option among them a mark price from the market-data
+/// mark price engine. Mark prices move the price bands; MMP Greeks are
+/// taken at the index price. Stops the new prices release are matched and
+/// published like any other order.
+fn spawn_reference_price_feed(
+    client: Arc<MonolithMatchingClient>,
+    spot_prices: HashMap<String, f64>,
+    interval: std::time::Duration,
+) {
+    tokio::spawn(async move {
+        let market_data = MarketDataCoordinator::new();
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = publish_reference_prices(&client, &market_data, &spot_prices).await {
+                error!(error = %e, "Reference price feed stopped");
+                return;
+            }
+        }
+    });
+}
+
+/// Set one round of index and mark prices on every shard
+///
+/// Instruments of underlyings without a spot price are left alone.
+async fn publish_reference_prices(
+    client: &MonolithMatchingClient,
+    market_data: &MarketDataCoordinator,
+    spot_prices: &HashMap<String, f64>,
+) -> Result<(), ShardError> {
+    for (underlying, spot) in spot_prices {
+        market_data.update_index_price(underlying.clone(), *spot).await;
+    }
+
+    let instruments = client
+        .runtime
+        .broadcast(|engine| (engine.instruments(), Vec::new()))
+        .await?;
+    let mut by_underlying: BTreeMap<String, Vec<(String, Option<f64>)>> = BTreeMap::new();
+    for instrument_id in instruments.into_iter().flatten() {
+        let underlying = underlying_of(&instrument_id).to_string();
+        if !spot_prices.contains_key(&underlying) {
+            continue;
+        }
+        let mark = match mmp::parse_option(&instrument_id) {
+            Some((expiry, strike, option_type)) => Some(
+                market_data
+                    .calculate_mark_price(&instrument_id, &underlying, strike, expiry.timestamp(), option_type)
+                    .await,
+            ),
+            None => None,
+        };
+        by_underlying.entry(underlying).or_default().push((instrument_id, mark));
+    }
+
+    for (underlying, prices) in by_underlying {
+        let spot = spot_prices[&underlying];
+        client
+            .runtime
+            .on_instrument(&underlying, move |engine| {
+                let mut triggered = Vec::new();
+                for (instrument_id, mark) in prices {
+                    let scale = engine.price_scale(&instrument_id);
+                    triggered.extend(engine.set_index_price(&instrument_id, scale.nearest_ticks(spot)));
+                    if let Some(mark) = mark {
+                        triggered.extend(engine.set_mark_price(&instrument_id, scale.nearest_ticks(mark)));
+                    }
+                }
+                ((), MatchingEvent::from_triggers(&triggered))
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+// ==================== Matching Admin Routes ====================
+
+/// Circuit breaker routes of the in-process matching runtime
+///
+/// The paths and responses of the standalone matching service's admin
+/// routes. An instrument and its underlying live on one shard, which
+/// takes the halt.
+fn matching_admin_routes(client: Arc<MonolithMatchingClient>) -> axum::Router {
+    use axum::routing::{get, post};
+
+    axum::Router::new()
+        .route(
+            "/api/v1/internal/circuit-breakers",
+            get(get_circuit_breakers),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/instruments/:instrument_id/halt",
+            post(halt_instrument),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/instruments/:instrument_id/resume",
+            post(resume_instrument),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/underlyings/:underlying/halt",
+            post(halt_underlying),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/underlyings/:underlying/resume",
+            post(resume_underlying),
+        )
+        .with_state(client)
+}
+
+/// Breaker, halt and price band status of every shard
+async fn get_circuit_breakers(
+    State(client): State<Arc<MonolithMatchingClient>>,
+) -> Json<CircuitBreakersResponse> {
+    let status = client
+        .runtime
+        .broadcast(|engine| ((engine.circuit_breaker_status(), engine.halted_underlyings()), Vec::new()))
+        .await;
+
+    match status {
+        Ok(shards) => {
+            let mut instruments = Vec::new();
+            let mut halted_underlyings = Vec::new();
+            for (status, halted) in shards {
+                instruments.extend(status);
+                halted_underlyings.extend(halted);
+            }
+            instruments.sort_by(|a, b| a.instrument_id.cmp(&b.instrument_id));
+            halted_underlyings.sort();
+            Json(CircuitBreakersResponse {
+                success: true,
+                instruments,
+                halted_underlyings,
+                message: None,
+            })
+        }
+        Err(e) => Json(CircuitBreakersResponse {
+            success: false,
+            instruments: vec![],
+            halted_underlyings: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Halt an instrument until resumed
+async fn halt_instrument(
+    State(client): State<Arc<MonolithMatchingClient>>,
+    UrlPath(instrument_id): UrlPath<String>,
+) -> Json<serde_json::Value> {
+    admin_action(&client, instrument_id, MatchingEngine::halt_instrument, "Instrument halted").await
+}
+
+/// Lift every halt of an instrument
+async fn resume_instrument(
+    State(client): State<Arc<MonolithMatchingClient>>,
+    UrlPath(instrument_id): UrlPath<String>,
+) -> Json<serde_json::Value> {
+    admin_action(&client, instrument_id, MatchingEngine::resume_instrument, "Instrument resumed").await
+}
+
+/// Halt every instrument of an underlying until resumed
+async fn halt_underlying(
+    State(client): State<Arc<MonolithMatchingClient>>,
+    UrlPath(underlying): UrlPath<String>,
+) -> Json<serde_json::Value> {
+    admin_action(&client, underlying, MatchingEngine::halt_underlying, "Underlying halted").await
+}
+
+/// Lift a halt of an underlying
+async fn resume_underlying(
+    State(client): State<Arc<MonolithMatchingClient>>,
+    UrlPath(underlying): UrlPath<String>,
+) -> Json<serde_json::Value> {
+    admin_action(&client, underlying, MatchingEngine::resume_underlying, "Underlying resumed").await
+}
+
+/// Run an admin action on the shard owning an instrument or underlying
+async fn admin_action(
+    client: &MonolithMatchingClient,
+    id: String,
+    action: fn(&mut MatchingEngine, &str),
+    message: &str,
+) -> Json<serde_json::Value> {
+    let result = client
+        .runtime
+        .on_instrument(&id.clone(), move |engine| (action(engine, &id), Vec::new()))
+        .await;
+
+    match result {
+        Ok(()) => Json(serde_json::json!({
+            "success": true,
+            "message": message
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
 }
 
 #[tokio::main]
@@ -217,6 +971,8 @@ async fn start_exchange<P: AsRef<Path>>(
         anyhow::bail!("Cannot start exchange due to configuration errors");
     }
 
+    init_metrics_exporter(&config);
+
     let (default_http, default_grpc, default_ws) = get_default_ports(&mode);
     let http_port = http_override.unwrap_or(default_http);
     let grpc_port = grpc_override.unwrap_or(default_grpc);
@@ -232,6 +988,25 @@ async fn start_exchange<P: AsRef<Path>>(
     start_service_with_ports(&mode, &config, http_port, grpc_port, ws_port).await
 }
 
+/// Start the Prometheus exporter if the configuration enables it
+///
+/// Failing to start it (e.g. the port is taken by another service on the
+/// same host) only leaves metrics unexported.
+fn init_metrics_exporter(config: &MasterConfig) {
+    let Some(metrics) = config.monitoring.as_ref().map(|m| &m.metrics) else {
+        return;
+    };
+    if !metrics.enabled || metrics.provider != "prometheus" {
+        return;
+    }
+    let Some(prometheus) = &metrics.prometheus else {
+        return;
+    };
+    if let Err(e) = observability::metrics::init_metrics(prometheus.port) {
+        warn!(port = prometheus.port, error = %e, "Failed to start Prometheus exporter");
+    }
+}
+
 async fn start_service_with_ports(
     mode: &DeploymentMode,
     config: &MasterConfig,
@@ -253,7 +1028,7 @@ async fn start_service_with_ports(
             start_risk_service(config, http_port).await
         }
         DeploymentMode::Matching => {
-            start_matching_service(config, http_port).await
+            start_matching_service(config, http_port, grpc_port).await
         }
         DeploymentMode::Wallet | DeploymentMode::Settlement | DeploymentMode::Market => {
             // Future: start_other_service(config, http_port, grpc_port, ws_port, mode).await
@@ -349,7 +1124,7 @@ async fn start_gateway_or_monolith(
     }
 
     // Initialize OMS service - use direct client in monolith, HTTP in gateway
-    let oms_state = if is_gateway {
+    let monolith_oms = if is_gateway {
         // Gateway mode: Use forwarding
         None
     } else if let Some(ref _risk) = risk_state {
@@ -374,12 +1149,18 @@ async fn start_gateway_or_monolith(
         http_router = http_router.merge(
             oms::api::forwarding_routes::oms_forwarding_routes(forwarding_state)
         );
-    } else if let Some(ref state) = oms_state {
+    } else if let Some(ref oms) = monolith_oms {
         // Monolith mode: Add direct routes
         info!("Monolith mode: Mounting direct OMS API routes");
         http_router = http_router.merge(
-            create_oms_router(Arc::clone(state))
+            create_oms_router(Arc::clone(&oms.state))
         );
+        if let Some(ref matching) = oms.matching {
+            info!("Monolith mode: Mounting matching admin routes");
+            http_router = http_router.merge(
+                matching_admin_routes(Arc::clone(matching))
+            );
+        }
     }
 
     // Create server config
@@ -573,30 +1354,29 @@ async fn start_risk_service(
 async fn start_matching_service(
     config: &MasterConfig,
     http_port: u16,
+    grpc_port: u16,
 ) -> Result<()> {
     info!(
-        "Starting Matching Service (HTTP on port {})",
-        http_port
+        "Starting Matching Service (HTTP on port {}, gRPC on port {})",
+        http_port, grpc_port
     );
 
     // Create store based on configuration
-    let store: Arc<dyn MatchingStore + Send + Sync> = if let Some(ref me_config) = config.matching_engine {
-        match create_store_from_config(&me_config.orderbook_store).await {
-            Ok(s) => {
-                info!("Created matching store from config");
-                let boxed: Box<dyn MatchingStore> = s;
-                let dyn_store: Box<dyn MatchingStore + Send + Sync> = boxed;
-                Arc::from(dyn_store)
-            }
-            Err(e) => {
-                warn!("Failed to create store from config: {}, using in-memory", e);
-                Arc::new(InMemoryStore::new()) as Arc<dyn MatchingStore + Send + Sync>
-            }
+    let store: Arc<dyn MatchingStore + Send + Sync> = match create_store_from_config(config).await {
+        Ok(s) => {
+            info!("Created matching store from config");
+            let boxed: Box<dyn MatchingStore> = s;
+            let dyn_store: Box<dyn MatchingStore + Send + Sync> = boxed;
+            Arc::from(dyn_store)
+        }
+        Err(e) => {
+            warn!("Failed to create store from config: {}, using in-memory", e);
+            Arc::new(InMemoryStore::with_engine(MatchingEngine::from_config(config))) as Arc<dyn MatchingStore + Send + Sync>
         }
-    } else {
-        info!("No matching engine config, using in-memory store");
-        Arc::new(InMemoryStore::new()) as Arc<dyn MatchingStore + Send + Sync>
     };
+    if let Some(interval) = snapshot_interval(config) {
+        spawn_snapshots(store.clone(), interval);
+    }
 
     let http_router: axum::Router = axum::Router::new()
         .route(
@@ -607,16 +1387,21 @@ async fn start_matching_service(
             "/",
             axum::routing::get(|| async { "OpenExchange Matching Service" }),
         )
-        .merge(create_dyn_router(store));
+        .merge(create_dyn_router(store.clone()));
 
     let server_config = ServerConfig {
         host: "0.0.0.0".to_string(),
         http_port: Some(http_port),
-        grpc_port: None,
+        grpc_port: Some(grpc_port),
         websocket_port: None,
     };
 
-    let server = CombinedServer::with_http_router(server_config, http_router);
+    let grpc_service = MatchingServiceServer::new(MatchingGrpcService::new(store));
+    let grpc_server = GrpcServerBuilder::new(server_config.clone())
+        .with_tonic_server(move |mut builder| builder.add_service(grpc_service.clone()))
+        .build();
+
+    let server = CombinedServer::with_http_router(server_config, http_router).with_grpc_server(grpc_server);
     server.validate_ports().await?;
 
     info!("Matching service started successfully");
@@ -624,6 +1409,7 @@ async fn start_matching_service(
     info!("  Cancel orders: DELETE /api/v1/internal/orders/:instrument_id/:order_id");
     info!("  Order book:    GET /api/v1/internal/books/:instrument_id");
     info!("  Health check:  GET /api/v1/matching/health");
+    info!("  gRPC:          openexchange.matching.v1.MatchingService");
 
     server.run_with_ctrl_c().await?;
 
@@ -735,16 +1521,7 @@ async fn initialize_instrument_service(
     }
 
     // Create static spot price provider
-    let spot_prices = config.instrument.static_prices.as_ref()
-        .map(|sp| sp.prices.clone())
-        .unwrap_or_else(|| {
-            let mut prices = HashMap::new();
-            prices.insert("BTC".to_string(), 50000.0);
-            prices.insert("ETH".to_string(), 3000.0);
-            prices.insert("SOL".to_string(), 100.0);
-            prices
-        });
-    let spot_provider = Arc::new(StaticSpotPriceProvider::new(spot_prices));
+    let spot_provider = Arc::new(StaticSpotPriceProvider::new(static_spot_prices(config)));
 
     // Create worker if configured
     let worker = if config.instrument.worker.as_ref().map(|w| w.enabled).unwrap_or(true) {
@@ -824,24 +1601,35 @@ async fn initialize_oms_service(
             let risk_client: Arc<dyn oms::clients::risk::RiskClient> =
                 Arc::new(oms::HttpRiskClient::new(&risk_service_url));
 
-            // In distributed mode, connect to matching service via HTTP
-            let matching_service_url = get_service_url("matching", 8084);
-            info!("Connecting to Matching service at {}", matching_service_url);
-            
+            // In distributed mode, connect to matching service via gRPC if
+            // MATCHING_GRPC_ENDPOINT is set, otherwise via HTTP
             let matching_client: Arc<dyn oms::clients::matching::MatchingClient> =
-                Arc::new(HttpMatchingClient::new(&matching_service_url));
+                match std::env::var("MATCHING_GRPC_ENDPOINT") {
+                    Ok(endpoint) => {
+                        info!("Connecting to Matching service over gRPC at {}", endpoint);
+                        Arc::new(GrpcMatchingClient::new(&endpoint)?)
+                    }
+                    Err(_) => {
+                        let matching_service_url = get_service_url("matching", 8084);
+                        info!("Connecting to Matching service at {}", matching_service_url);
+                        Arc::new(HttpMatchingClient::new(&matching_service_url))
+                    }
+                };
             let address_book = AddressBook::new();
 
-            let manager = OrderManager::new(
-                order_store,
-                risk_client,
-                matching_client,
-                address_book,
+            let manager = Arc::new(
+                OrderManager::new(
+                    order_store,
+                    risk_client,
+                    matching_client,
+                    address_book,
+                )
+                .with_trading_hours(config.exchange.trading_hours.clone())
+                .with_rfq_config(config.oms.as_ref().map(|o| o.rfq.clone()).unwrap_or_default()),
             );
+            spawn_expiry_sweeper(manager.clone(), oms_environments(config), EXPIRY_SWEEP_INTERVAL);
 
-            let state = OmsApiState {
-                manager: Arc::new(manager),
-            };
+            let state = OmsApiState { manager };
 
             Ok(Some(Arc::new(state)))
         }
@@ -854,40 +1642,104 @@ async fn initialize_oms_service(
     }
 }
 
-/// Initialize the matching engine based on configuration
-async fn initialize_matching_engine(
-    config: &MasterConfig,
-) -> Result<MatchingEngine> {
+/// Configured spot price of each underlying asset
+fn static_spot_prices(config: &MasterConfig) -> HashMap<String, f64> {
+    config.instrument.static_prices.as_ref()
+        .map(|sp| sp.prices.clone())
+        .unwrap_or_else(|| {
+            let mut prices = HashMap::new();
+            prices.insert("BTC".to_string(), 50000.0);
+            prices.insert("ETH".to_string(), 3000.0);
+            prices.insert("SOL".to_string(), 100.0);
+            prices
+        })
+}
+
+/// How often mark prices reach the matching engine, or None if the mark
+/// price feed is disabled
+fn mark_price_interval(config: &MasterConfig) -> Option<std::time::Duration> {
+    match config.market_data.as_ref().map(|md| &md.feeds.mark_price) {
+        Some(feed) if !feed.enabled => None,
+        Some(feed) => Some(std::time::Duration::from_secs(feed.update_frequency_seconds.max(1))),
+        None => Some(DEFAULT_MARK_PRICE_INTERVAL),
+    }
+}
+
+/// Mark price interval when market data is not configured
+const DEFAULT_MARK_PRICE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How often resting GTD and DAY orders are checked for expiry
+const EXPIRY_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// OMS environments served for the exchange mode
+fn oms_environments(config: &MasterConfig) -> Vec<oms::Environment> {
+    match config.exchange.mode {
+        config::ExchangeMode::Production => vec![oms::Environment::Prod],
+        config::ExchangeMode::Virtual => vec![oms::Environment::Virtual],
+        config::ExchangeMode::Both => vec![oms::Environment::Prod, oms::Environment::Virtual],
+    }
+}
+
+/// Initialize the sharded matching engine based on configuration
+///
+/// Every shard gets an identically configured engine. With the `file`
+/// store type each shard's journal is opened and replayed into its engine,
+/// and new events keep being appended to it. Trade IDs are derived from
+/// sequences; without a journal, sequences restart at zero, so each run
+/// gets its own ID namespace.
+async fn initialize_matching_engine(config: &MasterConfig) -> Result<ShardedEngine> {
     let store_type = config.matching_engine.as_ref()
         .map(|m| m.orderbook_store.store_type.as_str())
         .unwrap_or("inmemory");
+    let shard_config = config.matching_engine.as_ref()
+        .map(|m| ShardConfig::from(&m.performance))
+        .unwrap_or_default();
 
-    info!("Initializing matching engine with store type: {}", store_type);
-
-    // Create engine with circuit breakers and metrics
-    let mut engine = if let Some(ref me_config) = config.matching_engine {
-        let has_cb = me_config.circuit_breakers.enabled;
-        
-        if has_cb {
-            info!("Circuit breakers enabled");
-            let cb = CircuitBreakerConfig::from(&me_config.circuit_breakers);
-            MatchingEngine::new_with_all(cb)
-        } else {
-            MatchingEngine::new_with_metrics()
-        }
-    } else {
-        MatchingEngine::new_with_metrics()
-    };
+    info!(
+        "Initializing matching engine with store type: {} ({} shards)",
+        store_type, shard_config.shards
+    );
+
+    let run_namespace = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+
+    let mut shards = Vec::with_capacity(shard_config.shards);
+    for index in 0..shard_config.shards {
+        let mut engine = MatchingEngine::from_config(config);
+        let shard = match config.matching_engine.as_ref() {
+            Some(me_config) if store_type.eq_ignore_ascii_case("file") => {
+                let wal_config = me_config.orderbook_store.journal.as_ref()
+                    .map(WalConfig::from)
+                    .unwrap_or_default()
+                    .for_shard(index, shard_config.shards);
+                let journal = WriteAheadLog::open(wal_config)?;
+                let events = journal.recover(&mut engine)?;
+                info!("Recovered matching shard {} from {} journal events", index, events.len());
+                Shard::new(engine).with_journal(journal)
+            }
+            _ => {
+                engine.set_id_generator(Arc::new(SequenceIds::new(run_namespace)));
+                Shard::new(engine)
+            }
+        };
+        shards.push(shard);
+    }
 
     info!("Matching engine initialized successfully");
 
-    Ok(engine)
+    Ok(ShardedEngine::spawn(shards, &shard_config))
+}
+
+/// OMS of the monolith and the matching runtime behind it
+struct MonolithOms {
+    state: Arc<OmsApiState>,
+    /// None when the OMS fell back to the mock matching client
+    matching: Option<Arc<MonolithMatchingClient>>,
 }
 
 /// Initialize OMS service (monolith mode - uses HTTP to Risk)
 async fn initialize_oms_service_with_risk(
     config: &MasterConfig,
-) -> Result<Option<Arc<OmsApiState>>> {
+) -> Result<Option<MonolithOms>> {
     // In monolith mode, we still use HTTP client to communicate with Risk Engine
     // This keeps the architecture consistent and allows for easier future separation
     let database_url = std::env::var("OMS_DB_URL")
@@ -931,7 +1783,7 @@ async fn initialize_oms_service_with_risk(
 
             // Initialize matching engine based on config
             let matching_engine = match initialize_matching_engine(config).await {
-                Ok(engine) => engine,
+                Ok(initialized) => initialized,
                 Err(e) => {
                     warn!("Failed to initialize matching engine: {}", e);
                     warn!("Using MockMatchingClient as fallback");
@@ -950,27 +1802,48 @@ async fn initialize_oms_service_with_risk(
                         manager: Arc::new(manager),
                     };
 
-                    return Ok(Some(Arc::new(state)));
+                    return Ok(Some(MonolithOms {
+                        state: Arc::new(state),
+                        matching: None,
+                    }));
                 }
             };
 
             // Use MonolithMatchingClient for in-process matching
+            let monolith_client = Arc::new(MonolithMatchingClient::new(matching_engine));
+            let subscriptions = monolith_client
+                .subscribe_live()
+                .await
+                .context("Failed to subscribe to matching events")?;
             let matching_client: Arc<dyn oms::clients::matching::MatchingClient> =
-                Arc::new(MonolithMatchingClient::new(matching_engine));
+                monolith_client.clone();
             let address_book = AddressBook::new();
 
-            let manager = OrderManager::new(
-                order_store,
-                risk_client,
-                matching_client,
-                address_book,
+            let manager = Arc::new(
+                OrderManager::new(
+                    order_store,
+                    risk_client,
+                    matching_client,
+                    address_book,
+                )
+                .with_trading_hours(config.exchange.trading_hours.clone())
+                .with_rfq_config(config.oms.as_ref().map(|o| o.rfq.clone()).unwrap_or_default()),
             );
+            spawn_expiry_sweeper(manager.clone(), oms_environments(config), EXPIRY_SWEEP_INTERVAL);
+            spawn_fill_forwarders(monolith_client.clone(), subscriptions, manager.clone(), oms_environments(config));
+            if let Some(interval) = mark_price_interval(config) {
+                spawn_reference_price_feed(monolith_client.clone(), static_spot_prices(config), interval);
+            }
+            if let Some(interval) = snapshot_interval(config) {
+                spawn_shard_snapshots(monolith_client.clone(), interval);
+            }
 
-            let state = OmsApiState {
-                manager: Arc::new(manager),
-            };
+            let state = OmsApiState { manager };
 
-            Ok(Some(Arc::new(state)))
+            Ok(Some(MonolithOms {
+                state: Arc::new(state),
+                matching: Some(monolith_client),
+            }))
         }
         Err(e) => {
             warn!("Could not connect to OMS database: {}", e);
@@ -1084,3 +1957,149 @@ async fn init_command<P: AsRef<Path>>(output_path: P) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matching_engine::circuit_breaker::CircuitBreakerConfig;
+    use matching_engine::domain::SelfTradePrevention;
+
+    #[tokio::test]
+    async fn test_monolith_feeds_reference_prices_and_halts_underlyings() {
+        let breakers = CircuitBreakerConfig {
+            enabled: true,
+            price_band_enabled: true,
+            ..CircuitBreakerConfig::default()
+        };
+        let shards = (0..4)
+            .map(|_| Shard::new(MatchingEngine::new_with_all(breakers.clone())))
+            .collect();
+        let client = Arc::new(MonolithMatchingClient::new(ShardedEngine::spawn(shards, &ShardConfig::default())));
+        let call = "BTC-20271231-60000-C";
+        let order = BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), OrderSide::Buy, 100, 1, 0, MeTimeInForce::Gtc)
+            .with_instrument_id(call);
+        client
+            .runtime
+            .on_instrument(call, move |engine| {
+                engine.match_order(order);
+                ((), Vec::new())
+            })
+            .await
+            .unwrap();
+
+        // Market data marks the resting option, which bands it
+        let spot_prices = HashMap::from([("BTC".to_string(), 50_000.0)]);
+        publish_reference_prices(&client, &MarketDataCoordinator::new(), &spot_prices)
+            .await
+            .unwrap();
+        let prices = client
+            .runtime
+            .on_instrument(call, move |engine| (engine.reference_prices(call), Vec::new()))
+            .await
+            .unwrap();
+        assert!(prices.mark.is_some() && prices.index.is_some());
+        let status = get_circuit_breakers(State(client.clone())).await.0;
+        assert!(status
+            .instruments
+            .iter()
+            .any(|breaker| breaker.instrument_id == call && breaker.price_band.is_some()));
+
+        // An underlying halt reaches the shard trading its options
+        let halted = halt_underlying(State(client.clone()), UrlPath("BTC".to_string())).await.0;
+        assert_eq!(halted["success"], true);
+        let is_halted = client
+            .runtime
+            .on_instrument(call, move |engine| (engine.is_halted(call), Vec::new()))
+            .await
+            .unwrap();
+        assert!(is_halted);
+        let status = get_circuit_breakers(State(client.clone())).await.0;
+        assert_eq!(status.halted_underlyings, vec!["BTC".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_amend_are_routed_to_the_order_shard() {
+        let shards = (0..4).map(|_| Shard::new(MatchingEngine::new())).collect();
+        let client = Arc::new(MonolithMatchingClient::new(ShardedEngine::spawn(shards, &ShardConfig::default())));
+        let manager = OrderManager::new(
+            Arc::new(oms::InMemoryOrderStore::new()),
+            Arc::new(oms::MockRiskClient::new()),
+            client.clone(),
+            AddressBook::new(),
+        );
+        let env = oms::Environment::Static;
+        let order = oms::Order::new(
+            Uuid::new_v4(),
+            "ETH-20271231-3000-C".to_string(),
+            Side::Buy,
+            CommonOrderType::Limit,
+            CommonTimeInForce::Gtc,
+            Some(150.0),
+            10,
+        );
+        let order = manager.submit_order(order, env).await.unwrap();
+        let resting = |client: Arc<MonolithMatchingClient>, order_id: Uuid| async move {
+            client
+                .runtime
+                .on_instrument("ETH", move |engine| (engine.get_order(order_id).map(|o| o.quantity), Vec::new()))
+                .await
+                .unwrap()
+        };
+
+        manager.amend_order(order.order_id, None, Some(4), env).await.unwrap();
+        assert_eq!(resting(client.clone(), order.order_id).await, Some(4));
+        manager.cancel_order(order.order_id, env).await.unwrap();
+        assert_eq!(resting(client.clone(), order.order_id).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_self_trade_prevention_cancels_orders_in_the_oms() {
+        let runtime = ShardedEngine::spawn(vec![Shard::new(MatchingEngine::new())], &ShardConfig::default());
+        let client = Arc::new(MonolithMatchingClient::new(runtime));
+        let manager = Arc::new(OrderManager::new(
+            Arc::new(oms::InMemoryOrderStore::new()),
+            Arc::new(oms::MockRiskClient::new()),
+            client.clone(),
+            AddressBook::new(),
+        ));
+        let env = oms::Environment::Static;
+        let subscriptions = client.subscribe_live().await.unwrap();
+        spawn_fill_forwarders(client.clone(), subscriptions, manager.clone(), vec![env]);
+
+        let bid = oms::Order::new(
+            Uuid::new_v4(),
+            "BTC-20271231-60000-C".to_string(),
+            Side::Buy,
+            CommonOrderType::Limit,
+            CommonTimeInForce::Gtc,
+            Some(150.0),
+            10,
+        );
+        let bid = manager.submit_order(bid, env).await.unwrap();
+
+        // The same user sells into their own bid, which cancels the bid
+        let (user_id, instrument_id) = (bid.user_id, bid.instrument_id.clone());
+        client
+            .runtime
+            .on_instrument(&bid.instrument_id, move |engine| {
+                let price = engine.get_book(&instrument_id).unwrap().best_bid().unwrap();
+                let taker = BookOrder::new(Uuid::new_v4(), user_id, OrderSide::Sell, price, 10, 0, MeTimeInForce::Gtc)
+                    .with_instrument_id(instrument_id.as_str())
+                    .with_stp(SelfTradePrevention::CancelMaker);
+                let result = engine.match_order(taker.clone());
+                assert_eq!(result.stp_cancelled, vec![bid.order_id]);
+                ((), MatchingEvent::from_match(&taker, &result))
+            })
+            .await
+            .unwrap();
+
+        for _ in 0..100 {
+            let status = manager.get_order(bid.order_id, env).await.unwrap().unwrap().status;
+            if status == oms::OrderStatus::Cancelled {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("self-trade prevention cancel never reached the OMS");
+    }
+}
@@ -126,6 +126,29 @@ impl std::fmt::Display for OrderType {
     }
 }
 
+/// Post-only handling for orders that must add liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PostOnly {
+    /// Not post-only - the order may take liquidity
+    #[default]
+    None,
+    /// Reject the order if it would take liquidity
+    Reject,
+    /// Reprice the order one tick behind the opposite best price
+    Reprice,
+}
+
+impl std::fmt::Display for PostOnly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostOnly::None => write!(f, "none"),
+            PostOnly::Reject => write!(f, "reject"),
+            PostOnly::Reprice => write!(f, "reprice"),
+        }
+    }
+}
+
 /// Time in force for orders
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "UPPERCASE")]
@@ -143,6 +166,9 @@ pub enum TimeInForce {
     /// Day order - expires at end of trading day
     #[serde(alias = "day")]
     Day,
+    /// Good till date - remains active until its expiry timestamp
+    #[serde(alias = "gtd")]
+    Gtd,
 }
 
 impl std::fmt::Display for TimeInForce {
@@ -152,6 +178,7 @@ impl std::fmt::Display for TimeInForce {
             TimeInForce::Ioc => write!(f, "IOC"),
             TimeInForce::Fok => write!(f, "FOK"),
             TimeInForce::Day => write!(f, "DAY"),
+            TimeInForce::Gtd => write!(f, "GTD"),
         }
     }
 }
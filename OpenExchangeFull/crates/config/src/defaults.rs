@@ -5,9 +5,14 @@ pub fn default_enabled() -> bool {
 pub fn default_trading_hours() -> super::TradingHours {
     super::TradingHours {
         hours_type: "24/7".to_string(),
+        session_end_utc: default_session_end_utc(),
     }
 }
 
+pub fn default_session_end_utc() -> String {
+    "00:00".to_string()
+}
+
 pub fn default_postgres_port() -> u16 {
     5432
 }
@@ -48,6 +53,26 @@ pub fn default_snapshot_interval() -> u64 {
     60
 }
 
+pub fn default_max_trades_per_instrument() -> u64 {
+    1000
+}
+
+pub fn default_event_stream_max_len() -> u64 {
+    1_000_000
+}
+
+pub fn default_fsync_policy() -> String {
+    "always".to_string()
+}
+
+pub fn default_fsync_batch_size() -> u64 {
+    100
+}
+
+pub fn default_segment_size_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
 pub fn default_atomic_trades() -> bool {
     true
 }
@@ -56,6 +81,42 @@ pub fn default_max_partial_fills() -> u64 {
     10
 }
 
+pub fn default_market_protection_percent() -> f64 {
+    5.0
+}
+
+pub fn default_off_tick_policy() -> String {
+    "reject".to_string()
+}
+
+pub fn default_pro_rata_min_allocation() -> u32 {
+    1
+}
+
+pub fn default_pro_rata_top_order_percent() -> u32 {
+    40
+}
+
+pub fn default_mmp_window_ms() -> u64 {
+    1000
+}
+
+pub fn default_mmp_volatility() -> f64 {
+    0.5
+}
+
+pub fn default_rfq_request_ttl_secs() -> u64 {
+    60
+}
+
+pub fn default_rfq_quote_ttl_secs() -> u64 {
+    10
+}
+
+pub fn default_rfq_min_block_size() -> u32 {
+    1
+}
+
 pub fn default_matching_frequency_ms() -> u64 {
     10
 }
@@ -64,6 +125,10 @@ pub fn default_batch_size() -> u64 {
     100
 }
 
+pub fn default_shard_count() -> u64 {
+    1
+}
+
 pub fn default_depth_levels() -> u32 {
     50
 }
@@ -35,6 +35,25 @@ pub enum ExchangeMode {
 pub struct TradingHours {
     #[serde(rename = "type")]
     pub hours_type: String,
+    /// End of the daily session (HH:MM, UTC); DAY orders expire here
+    #[serde(default = "default_session_end_utc")]
+    pub session_end_utc: String,
+}
+
+impl TradingHours {
+    /// First session end strictly after `now`
+    ///
+    /// Falls back to midnight UTC if `session_end_utc` does not parse.
+    pub fn next_session_end(&self, now: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+        let end = chrono::NaiveTime::parse_from_str(&self.session_end_utc, "%H:%M")
+            .unwrap_or(chrono::NaiveTime::MIN);
+        let today = now.date_naive().and_time(end).and_utc();
+        if today > now {
+            today
+        } else {
+            today + chrono::Duration::days(1)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -356,6 +375,46 @@ pub struct OmsConfig {
     pub limits: OmsLimits,
     pub orderbook: OrderbookConfig,
     pub storage: StorageConfig,
+    /// Request-for-quote workflow for block trades
+    #[serde(default)]
+    pub rfq: RfqConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RfqConfig {
+    /// How long a request stays open for quotes
+    #[serde(rename = "request_ttl_secs")]
+    #[serde(default = "default_rfq_request_ttl_secs")]
+    pub request_ttl_secs: u64,
+    /// Longest a maker's quote may stay firm
+    #[serde(rename = "quote_ttl_secs")]
+    #[serde(default = "default_rfq_quote_ttl_secs")]
+    pub quote_ttl_secs: u64,
+    /// Smallest block trade, in contracts (combo units for a combo)
+    #[serde(rename = "min_block_size")]
+    #[serde(default = "default_rfq_min_block_size")]
+    pub min_block_size: u32,
+    /// Per-underlying overrides of `min_block_size`, e.g. `BTC: 25`
+    #[serde(default)]
+    pub min_block_sizes: HashMap<String, u32>,
+}
+
+impl Default for RfqConfig {
+    fn default() -> Self {
+        Self {
+            request_ttl_secs: default_rfq_request_ttl_secs(),
+            quote_ttl_secs: default_rfq_quote_ttl_secs(),
+            min_block_size: default_rfq_min_block_size(),
+            min_block_sizes: HashMap::new(),
+        }
+    }
+}
+
+impl RfqConfig {
+    /// Minimum block size of an underlying
+    pub fn min_block_size_for(&self, underlying: &str) -> u32 {
+        self.min_block_sizes.get(underlying).copied().unwrap_or(self.min_block_size)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -478,13 +537,87 @@ pub struct OrderbookConfig {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MatchingEngineConfig {
+    /// Allocation at each price level: "price_time_priority" (FIFO),
+    /// "pro_rata" or "price_time_pro_rata" (top-order slice, then pro-rata)
     pub algorithm: String,
+    /// Per-underlying overrides of `algorithm`, e.g. `BTC: pro_rata`
+    #[serde(default)]
+    pub underlying_algorithms: HashMap<String, String>,
+    /// Parameters of the pro-rata algorithms
+    #[serde(default)]
+    pub pro_rata: ProRataConfig,
     pub performance: PerformanceConfig,
     #[serde(rename = "orderbook_store")]
     pub orderbook_store: OrderbookStoreConfig,
     pub execution: ExecutionConfig,
     #[serde(rename = "circuit_breakers")]
     pub circuit_breakers: CircuitBreakersConfig,
+    /// Market maker protection for mass quotes
+    #[serde(default)]
+    pub mmp: MmpConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MmpConfig {
+    /// Rolling window fills of a maker's quotes are summed over
+    #[serde(rename = "window_ms")]
+    #[serde(default = "default_mmp_window_ms")]
+    pub window_ms: u64,
+    /// Implied volatility delta and vega are computed at
+    #[serde(rename = "volatility")]
+    #[serde(default = "default_mmp_volatility")]
+    pub volatility: f64,
+    /// Limits per underlying, e.g. `BTC: { quantity: 500, delta: 50 }`;
+    /// underlyings not listed are not protected
+    #[serde(default)]
+    pub limits: HashMap<String, MmpLimitsConfig>,
+}
+
+impl Default for MmpConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: default_mmp_window_ms(),
+            volatility: default_mmp_volatility(),
+            limits: HashMap::new(),
+        }
+    }
+}
+
+/// MMP limits of one underlying (a limit left out is not checked)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MmpLimitsConfig {
+    /// Contracts traded in the window
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    /// Absolute net delta traded in the window
+    #[serde(default)]
+    pub delta: Option<f64>,
+    /// Absolute net vega (per volatility point) traded in the window
+    #[serde(default)]
+    pub vega: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProRataConfig {
+    /// Pro-rata shares below this quantity are dropped and go to the
+    /// time-priority pass instead
+    #[serde(rename = "min_allocation")]
+    #[serde(default = "default_pro_rata_min_allocation")]
+    pub min_allocation: u32,
+    /// Share of the incoming quantity given first to the oldest order at
+    /// the level ("price_time_pro_rata" only)
+    #[serde(rename = "top_order_percent")]
+    #[serde(default = "default_pro_rata_top_order_percent")]
+    pub top_order_percent: u32,
+}
+
+impl Default for ProRataConfig {
+    fn default() -> Self {
+        Self {
+            min_allocation: default_pro_rata_min_allocation(),
+            top_order_percent: default_pro_rata_top_order_percent(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -493,6 +626,9 @@ pub struct PerformanceConfig {
     pub matching_frequency_ms: u64,
     #[serde(rename = "batch_size")]
     pub batch_size: u64,
+    /// Number of matching shards underlyings are hashed onto
+    #[serde(default = "default_shard_count")]
+    pub shards: u64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -501,6 +637,8 @@ pub struct OrderbookStoreConfig {
     pub store_type: String,
     #[serde(default)]
     pub redis: Option<RedisConfig>,
+    #[serde(default)]
+    pub journal: Option<JournalConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -521,6 +659,39 @@ pub struct RedisConfig {
     #[serde(rename = "snapshot_interval_seconds")]
     #[serde(default = "default_snapshot_interval")]
     pub snapshot_interval_seconds: u64,
+    /// Trades kept per instrument; older ones are trimmed
+    #[serde(rename = "max_trades_per_instrument")]
+    #[serde(default = "default_max_trades_per_instrument")]
+    pub max_trades_per_instrument: u64,
+    /// Approximate number of entries kept in the event stream; must cover
+    /// the events between snapshots and the lag of any consumer group
+    #[serde(rename = "event_stream_max_len")]
+    #[serde(default = "default_event_stream_max_len")]
+    pub event_stream_max_len: u64,
+}
+
+/// On-disk write-ahead journal for the matching engine (store type `file`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JournalConfig {
+    /// Directory holding the journal segments
+    pub dir: String,
+    /// When to fsync: `always`, `batch` or `never`
+    #[serde(rename = "fsync_policy")]
+    #[serde(default = "default_fsync_policy")]
+    pub fsync_policy: String,
+    /// Number of records between fsyncs when `fsync_policy` is `batch`
+    #[serde(rename = "fsync_batch_size")]
+    #[serde(default = "default_fsync_batch_size")]
+    pub fsync_batch_size: u64,
+    /// Segment size after which a new segment file is started
+    #[serde(rename = "segment_size_bytes")]
+    #[serde(default = "default_segment_size_bytes")]
+    pub segment_size_bytes: u64,
+    /// Seconds between engine snapshots written next to the segments
+    /// (0 disables them)
+    #[serde(rename = "snapshot_interval_seconds")]
+    #[serde(default = "default_snapshot_interval")]
+    pub snapshot_interval_seconds: u64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -531,6 +702,16 @@ pub struct ExecutionConfig {
     #[serde(rename = "max_partial_fills")]
     #[serde(default = "default_max_partial_fills")]
     pub max_partial_fills: u64,
+    /// Market orders never trade further than this percentage from the
+    /// mark price (or best opposite price when no mark is known)
+    #[serde(rename = "market_protection_percent")]
+    #[serde(default = "default_market_protection_percent")]
+    pub market_protection_percent: f64,
+    /// What to do with order prices that are not a multiple of the
+    /// instrument tick size: "reject" or "round" (to the passive side)
+    #[serde(rename = "off_tick_policy")]
+    #[serde(default = "default_off_tick_policy")]
+    pub off_tick_policy: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -540,6 +721,10 @@ pub struct CircuitBreakersConfig {
     #[serde(rename = "price_movement")]
     pub price_movement: PriceMovementConfig,
     pub liquidity: LiquidityConfig,
+    /// Pre-trade limit-up/limit-down band around the mark price
+    #[serde(rename = "price_band")]
+    #[serde(default)]
+    pub price_band: Option<PriceBandConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -554,6 +739,15 @@ pub struct PriceMovementConfig {
     pub halt_duration_seconds: u64,
 }
 
+/// Orders priced further than `percent_threshold` from the mark price are rejected
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PriceBandConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(rename = "percent_threshold")]
+    pub percent_threshold: f64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LiquidityConfig {
     #[serde(default = "default_enabled")]
@@ -2037,6 +2231,24 @@ mod tests {
         assert!(config.market_data.is_none());
     }
 
+    #[test]
+    fn test_next_session_end() {
+        use chrono::TimeZone;
+
+        let hours = TradingHours {
+            hours_type: "24/7".to_string(),
+            session_end_utc: "16:00".to_string(),
+        };
+        let morning = chrono::Utc.with_ymd_and_hms(2026, 3, 15, 9, 30, 0).unwrap();
+        let close = chrono::Utc.with_ymd_and_hms(2026, 3, 15, 16, 0, 0).unwrap();
+        assert_eq!(hours.next_session_end(morning), close);
+        // At or after the close the session ends tomorrow
+        assert_eq!(hours.next_session_end(close), close + chrono::Duration::days(1));
+
+        let midnight = default_trading_hours().next_session_end(morning);
+        assert_eq!(midnight, chrono::Utc.with_ymd_and_hms(2026, 3, 16, 0, 0, 0).unwrap());
+    }
+
     #[test]
     fn test_chain_config_parsing() {
         let yaml = r#"
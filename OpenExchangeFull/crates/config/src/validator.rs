@@ -198,6 +198,14 @@ fn validate_exchange(exchange: &ExchangeConfig, report: &mut ValidationReport) {
             "Only 24/7 trading hours are supported in version 1.0.0",
         );
     }
+
+    let time_regex = Regex::new(r"^([0-1]?[0-9]|2[0-3]):[0-5][0-9]$").unwrap();
+    if !time_regex.is_match(&exchange.trading_hours.session_end_utc) {
+        report.add_error(ValidationError::InvalidTimeFormat {
+            time: exchange.trading_hours.session_end_utc.clone(),
+            message: "Session end must be in 24-hour HH:MM format".to_string(),
+        });
+    }
 }
 
 fn validate_instruments(instrument: &InstrumentConfig, report: &mut ValidationReport) {
@@ -645,22 +653,72 @@ fn validate_matching_engine(engine: &MatchingEngineConfig, report: &mut Validati
         });
     }
 
-    let valid_store_types = ["redis", "inmemory"];
+    if engine.performance.shards == 0 {
+        report.add_error(ValidationError::InvalidPositiveInteger {
+            field: "shards".to_string(),
+        });
+    }
+
+    let valid_store_types = ["redis", "inmemory", "file"];
     if !valid_store_types.contains(&engine.orderbook_store.store_type.as_str()) {
         report.add_error(ValidationError::InvalidMatchingEngine {
             message: format!(
-                "Invalid orderbook_store type '{}'. Must be one of: redis, inmemory",
+                "Invalid orderbook_store type '{}'. Must be one of: redis, inmemory, file",
                 engine.orderbook_store.store_type
             ),
         });
     }
 
+    // Validate journal config
+    if let Some(journal) = &engine.orderbook_store.journal {
+        let valid_fsync_policies = ["always", "batch", "never"];
+        if !valid_fsync_policies.contains(&journal.fsync_policy.as_str()) {
+            report.add_error(ValidationError::InvalidMatchingEngine {
+                message: format!(
+                    "Invalid journal fsync_policy '{}'. Must be one of: always, batch, never",
+                    journal.fsync_policy
+                ),
+            });
+        }
+
+        if journal.fsync_batch_size == 0 {
+            report.add_error(ValidationError::InvalidPositiveInteger {
+                field: "journal.fsync_batch_size".to_string(),
+            });
+        }
+
+        if journal.segment_size_bytes == 0 {
+            report.add_error(ValidationError::InvalidPositiveInteger {
+                field: "journal.segment_size_bytes".to_string(),
+            });
+        }
+    } else if engine.orderbook_store.store_type == "file" {
+        report.add_error(ValidationError::InvalidMatchingEngine {
+            message: "orderbook_store type 'file' requires a journal section".to_string(),
+        });
+    }
+
     // Validate execution config
     if engine.execution.max_partial_fills == 0 {
         report.add_error(ValidationError::InvalidPositiveInteger {
             field: "max_partial_fills".to_string(),
         });
     }
+    if engine.execution.market_protection_percent <= 0.0
+        || engine.execution.market_protection_percent > 100.0
+    {
+        report.add_error(ValidationError::InvalidPercentageRange {
+            field: "execution.market_protection_percent".to_string(),
+        });
+    }
+    if !matches!(engine.execution.off_tick_policy.as_str(), "reject" | "round") {
+        report.add_error(ValidationError::InvalidMatchingEngine {
+            message: format!(
+                "execution.off_tick_policy must be 'reject' or 'round', got: {}",
+                engine.execution.off_tick_policy
+            ),
+        });
+    }
 
     // Validate circuit breakers
     if engine.circuit_breakers.price_movement.percent_threshold <= 0.0
@@ -0,0 +1,209 @@
+//! Price level allocation
+//!
+//! Decides how an incoming order's quantity is shared between the resting
+//! orders of one price level. Price priority always holds: a level is only
+//! reached once every better level is exhausted.
+//!
+//! - [`MatchingAlgorithm::PriceTimePriority`] fills the oldest order first
+//!   (FIFO).
+//! - [`MatchingAlgorithm::ProRata`] shares the quantity in proportion to
+//!   each order's size, so many market makers quoting the same price all
+//!   trade.
+//! - [`MatchingAlgorithm::PriceTimeProRata`] first gives the oldest order a
+//!   priority slice, then shares the rest pro-rata.
+//!
+//! Pro-rata shares are rounded down and shares below the minimum allocation
+//! are dropped. Whatever is left over goes to a final time-priority pass,
+//! so the outcome depends only on the queue and is the same on replay.
+//! All-or-none orders are only ever filled whole.
+
+use serde::{Deserialize, Serialize};
+
+/// Allocation strategy at a price level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "algorithm")]
+pub enum MatchingAlgorithm {
+    /// Oldest order first (FIFO)
+    #[default]
+    PriceTimePriority,
+    /// Proportional to size
+    ProRata {
+        /// Shares below this quantity go to the time-priority pass
+        min_allocation: u32,
+    },
+    /// Priority slice for the oldest order, then proportional to size
+    PriceTimeProRata {
+        /// Percentage of the incoming quantity offered to the oldest order
+        top_order_percent: u32,
+        /// Shares below this quantity go to the time-priority pass
+        min_allocation: u32,
+    },
+}
+
+/// A resting order as seen by the allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelOrder {
+    /// Quantity the order can trade now (zero if it cannot trade)
+    pub quantity: u32,
+    /// The order may only be filled whole
+    pub all_or_none: bool,
+}
+
+impl MatchingAlgorithm {
+    /// Parse a configured algorithm name
+    ///
+    /// Accepts `"price_time_priority"` (or `"fifo"`), `"pro_rata"` and
+    /// `"price_time_pro_rata"`.
+    pub fn from_name(name: &str, min_allocation: u32, top_order_percent: u32) -> Option<Self> {
+        match name {
+            "price_time_priority" | "fifo" => Some(Self::PriceTimePriority),
+            "pro_rata" => Some(Self::ProRata { min_allocation }),
+            "price_time_pro_rata" => Some(Self::PriceTimeProRata {
+                top_order_percent,
+                min_allocation,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Check if the level is filled strictly in time priority
+    pub fn is_fifo(&self) -> bool {
+        matches!(self, Self::PriceTimePriority)
+    }
+
+    /// Share `incoming` between the orders of a level, in queue order
+    ///
+    /// Returns the fill of each order. Fills never exceed an order's
+    /// quantity and sum to at most `incoming`.
+    pub fn allocate(&self, incoming: u32, orders: &[LevelOrder]) -> Vec<u32> {
+        let mut fills = vec![0; orders.len()];
+        let mut remaining = incoming;
+
+        match *self {
+            Self::PriceTimePriority => {}
+            Self::ProRata { min_allocation } => {
+                pro_rata(&mut fills, &mut remaining, orders, min_allocation);
+            }
+            Self::PriceTimeProRata {
+                top_order_percent,
+                min_allocation,
+            } => {
+                if let Some(top) = orders.iter().position(|o| o.quantity > 0) {
+                    let percent = u64::from(top_order_percent.min(100));
+                    let slice = (u64::from(incoming) * percent / 100) as u32;
+                    let slice = slice.min(orders[top].quantity);
+                    if !orders[top].all_or_none || slice == orders[top].quantity {
+                        fills[top] = slice;
+                        remaining -= slice;
+                    }
+                }
+                pro_rata(&mut fills, &mut remaining, orders, min_allocation);
+            }
+        }
+
+        // Time-priority pass over whatever is left
+        for (fill, order) in fills.iter_mut().zip(orders) {
+            if remaining == 0 {
+                break;
+            }
+            let open = order.quantity - *fill;
+            let qty = if order.all_or_none {
+                if open <= remaining { open } else { 0 }
+            } else {
+                open.min(remaining)
+            };
+            *fill += qty;
+            remaining -= qty;
+        }
+
+        fills
+    }
+}
+
+/// Add proportional shares of `remaining` to `fills`
+fn pro_rata(fills: &mut [u32], remaining: &mut u32, orders: &[LevelOrder], min_allocation: u32) {
+    let open: Vec<u32> = orders
+        .iter()
+        .zip(fills.iter())
+        .map(|(order, fill)| order.quantity - fill)
+        .collect();
+    let total: u64 = open.iter().map(|&q| u64::from(q)).sum();
+    if total == 0 || *remaining == 0 {
+        return;
+    }
+
+    // Enough for everyone: fill the level
+    if u64::from(*remaining) >= total {
+        for (fill, qty) in fills.iter_mut().zip(&open) {
+            *fill += qty;
+        }
+        *remaining -= total as u32;
+        return;
+    }
+
+    let pool = u64::from(*remaining);
+    for ((fill, &qty), order) in fills.iter_mut().zip(&open).zip(orders) {
+        let share = (pool * u64::from(qty) / total) as u32;
+        if share == 0 || share < min_allocation || (order.all_or_none && share < qty) {
+            continue;
+        }
+        *fill += share;
+        *remaining -= share;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(quantities: &[u32]) -> Vec<LevelOrder> {
+        quantities
+            .iter()
+            .map(|&quantity| LevelOrder {
+                quantity,
+                all_or_none: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pro_rata_rounds_down_and_gives_remainder_by_time() {
+        let algorithm = MatchingAlgorithm::ProRata { min_allocation: 1 };
+
+        // 10 over 30/20/10: 5, 3, 1 (rounded down), the leftover 1 to the oldest
+        assert_eq!(algorithm.allocate(10, &level(&[30, 20, 10])), vec![6, 3, 1]);
+
+        // Shares under the minimum are dropped and go to the time pass
+        let algorithm = MatchingAlgorithm::ProRata { min_allocation: 2 };
+        assert_eq!(algorithm.allocate(10, &level(&[30, 20, 10])), vec![7, 3, 0]);
+
+        // More than the level holds fills everyone
+        assert_eq!(algorithm.allocate(100, &level(&[30, 20, 10])), vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn test_price_time_pro_rata_gives_top_order_a_slice() {
+        let algorithm = MatchingAlgorithm::PriceTimeProRata {
+            top_order_percent: 50,
+            min_allocation: 1,
+        };
+
+        // Top order takes 10 of 20, the other 10 is shared over the open
+        // 10/30/30 as 1/4/4 and the leftover 1 goes back to the top order
+        assert_eq!(algorithm.allocate(20, &level(&[20, 30, 30])), vec![12, 4, 4]);
+    }
+
+    #[test]
+    fn test_all_or_none_orders_fill_whole_or_not_at_all() {
+        let algorithm = MatchingAlgorithm::ProRata { min_allocation: 1 };
+        let orders = [
+            LevelOrder { quantity: 6, all_or_none: true },
+            LevelOrder { quantity: 8, all_or_none: false },
+        ];
+
+        // 10 over 6/8: the AON share (4) is dropped and 5 go to the other
+        // order; the leftover 5 cannot cover the AON order, so the other
+        // order takes its last 3
+        assert_eq!(algorithm.allocate(10, &orders), vec![0, 8]);
+    }
+}
@@ -1,28 +1,62 @@
 //! HTTP API handlers for the Matching Engine
 
 use axum::{
-    extract::{Path, State, Query},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State, Query,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::auction::AuctionReason;
+use crate::block::BlockTrade;
+use crate::circuit_breaker::CircuitBreakerStatus;
+use crate::combo::{ComboDefinition, ComboLeg};
 use crate::domain::{OrderBookSnapshot, Trade};
 use crate::store::{MatchingStore, StoreError};
 use crate::domain::BookOrder;
+use crate::domain::MassCancelFilter;
 use crate::domain::OrderSide;
+use crate::price::PriceScale;
+use crate::domain::OrderType;
+use crate::domain::PostOnly;
+use crate::domain::SelfTradePrevention;
 use crate::domain::TimeInForce;
+use crate::domain::TriggerSource;
+use crate::book_feed::{spawn_book_feed, BookFeedHandle, BookFeedSnapshot, BookUpdate};
+use crate::event::MatchingEvent;
+use crate::mmp::MmpStatus;
+use crate::feed::{EventSubscription, SubscriptionError};
+use tokio::sync::{broadcast, OnceCell};
 use uuid::Uuid;
 
 /// State for the matching API - uses Arc for Clone
 pub struct MatchingApiState<S: ?Sized> {
     pub store: Arc<S>,
+    /// Book feed over the store's events, started by the first subscriber
+    pub book_feed: Arc<OnceCell<BookFeedHandle>>,
+}
+
+impl<S: MatchingStore + ?Sized> MatchingApiState<S> {
+    /// Create API state over a store
+    pub fn new(store: Arc<S>) -> Self {
+        Self {
+            store,
+            book_feed: Arc::new(OnceCell::new()),
+        }
+    }
 }
 
 impl<S: MatchingStore + ?Sized> Clone for MatchingApiState<S> {
     fn clone(&self) -> Self {
         Self {
             store: Arc::clone(&self.store),
+            book_feed: Arc::clone(&self.book_feed),
         }
     }
 }
@@ -30,16 +64,250 @@ impl<S: MatchingStore + ?Sized> Clone for MatchingApiState<S> {
 /// Convenience type for dynamic dispatch
 pub type DynMatchingApiState = MatchingApiState<dyn MatchingStore + Send + Sync>;
 
+/// A trade with its price converted back to decimal
+#[derive(Debug, serde::Serialize)]
+pub struct TradeResponse {
+    pub trade_id: Uuid,
+    pub instrument_id: String,
+    pub taker_order_id: Uuid,
+    pub maker_order_id: Uuid,
+    pub buyer_id: Uuid,
+    pub seller_id: Uuid,
+    pub price: f64,
+    pub quantity: u32,
+    pub aggressor_side: OrderSide,
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    /// Combo whose fill this leg trade is part of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combo_id: Option<String>,
+    /// RFQ this block trade was agreed in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rfq_id: Option<Uuid>,
+}
+
+impl TradeResponse {
+    fn new(trade: &Trade, scale: &PriceScale) -> Self {
+        Self {
+            trade_id: trade.trade_id,
+            instrument_id: trade.instrument_id.clone(),
+            taker_order_id: trade.taker_order_id,
+            maker_order_id: trade.maker_order_id,
+            buyer_id: trade.buyer_id,
+            seller_id: trade.seller_id,
+            price: scale.to_price(trade.price),
+            quantity: trade.quantity,
+            aggressor_side: trade.aggressor_side,
+            sequence: trade.sequence,
+            timestamp: trade.timestamp,
+            combo_id: trade.combo_id.clone(),
+            rfq_id: trade.rfq_id,
+        }
+    }
+}
+
+/// Convert trades to their decimal form
+fn trade_responses<'a>(
+    trades: impl IntoIterator<Item = &'a Trade>,
+    scale: &PriceScale,
+) -> Vec<TradeResponse> {
+    trades.into_iter().map(|trade| TradeResponse::new(trade, scale)).collect()
+}
+
 /// Request to submit an order
+///
+/// Prices are decimal and converted to ticks with the instrument's price
+/// scale; off-tick prices are rejected or rounded per its policy.
 #[derive(Debug, Deserialize)]
 pub struct SubmitOrderRequest {
     pub instrument_id: String,
     pub order_id: Option<Uuid>,
     pub user_id: Uuid,
     pub side: String,
+    /// Limit price (omit for market orders)
+    #[serde(default)]
     pub price: f64,
     pub quantity: u32,
     pub time_in_force: Option<String>,
+    /// "limit" (default) or "market"
+    pub order_type: Option<String>,
+    /// Stop price; makes this a stop-limit or stop-market order
+    pub stop_price: Option<f64>,
+    /// Stop reference: "last_trade" (default), "mark_price" or "index_price"
+    pub trigger: Option<String>,
+    /// Self-trade prevention: "none" (default), "cancel_taker", "cancel_maker",
+    /// "cancel_both" or "decrement_and_cancel"
+    pub stp: Option<String>,
+    /// Post-only: "none" (default), "reject" or "reprice"
+    pub post_only: Option<String>,
+    /// Fill the whole quantity at once or not at all
+    #[serde(default)]
+    pub all_or_none: bool,
+    /// Minimum quantity that must fill on arrival
+    pub min_quantity: Option<u32>,
+    /// Iceberg slice size; only this much is shown in the book at a time
+    pub display_quantity: Option<u32>,
+    /// Expiry time; required for "gtd" and "day", rejected otherwise
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Legs of a combo order; `instrument_id` is then the combo ID, and
+    /// the combo is defined first if it does not exist yet
+    #[serde(default)]
+    pub legs: Vec<ComboLeg>,
+}
+
+/// Request to define a combo instrument
+#[derive(Debug, Deserialize)]
+pub struct DefineComboRequest {
+    pub combo_id: String,
+    pub legs: Vec<ComboLeg>,
+}
+
+/// Response listing combo instruments
+#[derive(Debug, serde::Serialize)]
+pub struct CombosResponse {
+    pub success: bool,
+    pub combos: Vec<ComboDefinition>,
+    pub message: Option<String>,
+}
+
+/// Request to book a block trade agreed in an RFQ
+///
+/// The price is decimal (the net price for a combo) and converted to ticks
+/// with the instrument's price scale.
+#[derive(Debug, Deserialize)]
+pub struct BlockTradeRequest {
+    pub rfq_id: Uuid,
+    pub instrument_id: String,
+    pub price: f64,
+    pub quantity: u32,
+    pub taker_order_id: Uuid,
+    pub taker_user_id: Uuid,
+    /// Side of the taker: "buy" or "sell"
+    pub taker_side: String,
+    pub maker_order_id: Uuid,
+    pub maker_user_id: Uuid,
+    /// Legs of a combo trade; `instrument_id` is then the combo ID, and
+    /// the combo is defined first if it does not exist yet
+    #[serde(default)]
+    pub legs: Vec<ComboLeg>,
+}
+
+/// Response to a block trade
+#[derive(Debug, serde::Serialize)]
+pub struct BlockTradeResponse {
+    pub success: bool,
+    /// Booked trades, one per leg for a combo
+    pub trades: Vec<TradeResponse>,
+    pub message: Option<String>,
+}
+
+/// One side of an instrument's quote
+///
+/// The price is decimal and converted to ticks with the instrument's price
+/// scale.
+#[derive(Debug, Deserialize)]
+pub struct QuoteLevel {
+    /// Order ID to rest the quote under (generated if omitted)
+    pub order_id: Option<Uuid>,
+    pub price: f64,
+    pub quantity: u32,
+}
+
+/// A maker's bid and/or ask on one instrument
+#[derive(Debug, Deserialize)]
+pub struct QuoteEntry {
+    pub instrument_id: String,
+    pub bid: Option<QuoteLevel>,
+    pub ask: Option<QuoteLevel>,
+}
+
+/// Request to replace a maker's quotes
+#[derive(Debug, Deserialize)]
+pub struct MassQuoteRequest {
+    pub user_id: Uuid,
+    pub quotes: Vec<QuoteEntry>,
+}
+
+/// Response to a mass quote
+#[derive(Debug, serde::Serialize)]
+pub struct MassQuoteResponse {
+    pub success: bool,
+    /// Previous quotes removed from the book
+    pub replaced_order_ids: Vec<Uuid>,
+    /// New quotes resting in the book
+    pub resting_order_ids: Vec<Uuid>,
+    /// New quotes rejected because they would have crossed
+    pub rejected_order_ids: Vec<Uuid>,
+    pub message: Option<String>,
+}
+
+/// Market maker protection status of a maker
+#[derive(Debug, serde::Serialize)]
+pub struct MmpStatusResponse {
+    pub success: bool,
+    pub user_id: Uuid,
+    /// Exposure and frozen state per underlying
+    pub underlyings: Vec<MmpStatus>,
+    pub message: Option<String>,
+}
+
+/// Response to an MMP reset
+#[derive(Debug, serde::Serialize)]
+pub struct MmpResetResponse {
+    pub success: bool,
+    /// Whether quoting was frozen and is now allowed again
+    pub reset: bool,
+    pub message: Option<String>,
+}
+
+/// Request to update a reference price
+#[derive(Debug, Deserialize)]
+pub struct ReferencePriceRequest {
+    /// "mark_price", "index_price" or "last_trade"
+    pub source: String,
+    pub price: f64,
+}
+
+/// Response for a reference price update
+#[derive(Debug, serde::Serialize)]
+pub struct ReferencePriceResponse {
+    pub success: bool,
+    /// Stop orders released by the update
+    pub triggered_orders: Vec<Uuid>,
+    pub trades: Vec<TradeResponse>,
+    pub message: Option<String>,
+}
+
+/// Parse a self-trade prevention mode name
+fn parse_stp(stp: &str) -> Option<SelfTradePrevention> {
+    match stp.to_lowercase().as_str() {
+        "none" => Some(SelfTradePrevention::None),
+        "cancel_taker" | "ct" => Some(SelfTradePrevention::CancelTaker),
+        "cancel_maker" | "cm" => Some(SelfTradePrevention::CancelMaker),
+        "cancel_both" | "cb" => Some(SelfTradePrevention::CancelBoth),
+        "decrement_and_cancel" | "dc" => Some(SelfTradePrevention::DecrementAndCancel),
+        _ => None,
+    }
+}
+
+/// Parse a post-only mode name
+fn parse_post_only(post_only: &str) -> Option<PostOnly> {
+    match post_only.to_lowercase().as_str() {
+        "none" => Some(PostOnly::None),
+        "reject" => Some(PostOnly::Reject),
+        "reprice" => Some(PostOnly::Reprice),
+        _ => None,
+    }
+}
+
+/// Parse a stop trigger source name
+fn parse_trigger_source(source: &str) -> Option<TriggerSource> {
+    match source.to_lowercase().as_str() {
+        "last_trade" | "last" => Some(TriggerSource::LastTrade),
+        "mark_price" | "mark" => Some(TriggerSource::MarkPrice),
+        "index_price" | "index" => Some(TriggerSource::IndexPrice),
+        _ => None,
+    }
 }
 
 /// Request to cancel an order
@@ -48,12 +316,58 @@ pub struct CancelOrderRequest {
     pub order_id: Uuid,
 }
 
+/// Response for a mass cancel
+#[derive(Debug, serde::Serialize)]
+pub struct MassCancelResponse {
+    pub success: bool,
+    pub cancelled_order_ids: Vec<Uuid>,
+    pub message: Option<String>,
+}
+
+/// Request to sweep expired orders
+#[derive(Debug, Default, Deserialize)]
+pub struct ExpireOrdersRequest {
+    /// Sweep time (defaults to now)
+    #[serde(default)]
+    pub now: Option<DateTime<Utc>>,
+}
+
+/// Response for an expiry sweep
+#[derive(Debug, serde::Serialize)]
+pub struct ExpireOrdersResponse {
+    pub success: bool,
+    pub expired_order_ids: Vec<Uuid>,
+    pub message: Option<String>,
+}
+
+/// Request to amend a resting order
+#[derive(Debug, Deserialize)]
+pub struct AmendOrderRequest {
+    pub price: Option<f64>,
+    pub quantity: Option<u32>,
+}
+
+/// Response for an amend
+#[derive(Debug, serde::Serialize)]
+pub struct AmendOrderResponse {
+    pub success: bool,
+    pub priority_retained: bool,
+    pub trades: Vec<TradeResponse>,
+    pub remaining_quantity: u32,
+    pub message: Option<String>,
+}
+
 /// Response for order submission
 #[derive(Debug, serde::Serialize)]
 pub struct SubmitOrderResponse {
     pub success: bool,
-    pub trades: Vec<Trade>,
+    pub trades: Vec<TradeResponse>,
     pub remaining_quantity: u32,
+    /// Orders cancelled by self-trade prevention
+    pub stp_cancelled: Vec<Uuid>,
+    /// Price a post-only order was repriced to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repriced_to: Option<f64>,
     pub message: Option<String>,
 }
 
@@ -73,12 +387,51 @@ pub struct PriceLevelResponse {
     pub quantity: u32,
 }
 
+/// Call auction state with its indicative uncross
+#[derive(Debug, serde::Serialize)]
+pub struct AuctionResponse {
+    pub success: bool,
+    pub instrument_id: String,
+    /// Why the auction runs (None if the instrument trades continuously)
+    pub reason: Option<AuctionReason>,
+    /// Price the auction would uncross at now
+    pub indicative_price: Option<f64>,
+    /// Quantity that would trade at the indicative price
+    pub indicative_volume: u32,
+    /// Buy minus sell quantity left over at the indicative price
+    pub imbalance: i64,
+    pub message: Option<String>,
+}
+
+/// Response for an uncross
+#[derive(Debug, serde::Serialize)]
+pub struct UncrossResponse {
+    pub success: bool,
+    pub instrument_id: String,
+    /// Equilibrium price (None if nothing traded)
+    pub price: Option<f64>,
+    pub volume: u32,
+    pub trades: Vec<TradeResponse>,
+    pub message: Option<String>,
+}
+
+/// Circuit breaker, halt and price band status
+#[derive(Debug, serde::Serialize)]
+pub struct CircuitBreakersResponse {
+    pub success: bool,
+    /// Instruments with breaker state (tripped, halted or banded)
+    pub instruments: Vec<CircuitBreakerStatus>,
+    /// Underlyings halted by an operator
+    pub halted_underlyings: Vec<String>,
+    pub message: Option<String>,
+}
+
 /// Response for trades
 #[derive(Debug, serde::Serialize)]
 pub struct TradesResponse {
     pub success: bool,
     pub instrument_id: String,
-    pub trades: Vec<Trade>,
+    pub trades: Vec<TradeResponse>,
 }
 
 /// Submit an order to the matching engine
@@ -94,6 +447,8 @@ pub async fn submit_order<S: MatchingStore + 'static + ?Sized>(
                 success: false,
                 trades: vec![],
                 remaining_quantity: 0,
+                stp_cancelled: vec![],
+                repriced_to: None,
                 message: Some("Invalid side. Use 'buy' or 'sell'".to_string()),
             });
         }
@@ -102,32 +457,158 @@ pub async fn submit_order<S: MatchingStore + 'static + ?Sized>(
     let tif = match req.time_in_force.as_deref().unwrap_or("gtc").to_lowercase().as_str() {
         "ioc" => TimeInForce::Ioc,
         "fok" => TimeInForce::Fok,
+        "gtd" => TimeInForce::Gtd,
+        "day" => TimeInForce::Day,
         _ => TimeInForce::Gtc,
     };
 
-    let order = BookOrder::new(
+    let expires = matches!(tif, TimeInForce::Gtd | TimeInForce::Day);
+    if expires != req.expires_at.is_some() {
+        return Json(SubmitOrderResponse {
+            success: false,
+            trades: vec![],
+            remaining_quantity: 0,
+            stp_cancelled: vec![],
+            repriced_to: None,
+            message: Some("expires_at is required for GTD and DAY orders and not allowed otherwise".to_string()),
+        });
+    }
+
+    if !req.legs.is_empty() {
+        let combo = ComboDefinition::new(req.instrument_id.clone(), req.legs.clone());
+        if let Err(e) = state.store.define_combo(combo).await {
+            return Json(SubmitOrderResponse {
+                success: false,
+                trades: vec![],
+                remaining_quantity: 0,
+                stp_cancelled: vec![],
+                repriced_to: None,
+                message: Some(e.to_string()),
+            });
+        }
+    }
+
+    let order_type = match req.order_type.as_deref().unwrap_or("limit").to_lowercase().as_str() {
+        "market" => OrderType::Market,
+        _ => OrderType::Limit,
+    };
+
+    let scale = match state.store.price_scale(&req.instrument_id).await {
+        Ok(scale) => scale,
+        Err(e) => {
+            return Json(SubmitOrderResponse {
+                success: false,
+                trades: vec![],
+                remaining_quantity: 0,
+                stp_cancelled: vec![],
+                repriced_to: None,
+                message: Some(e.to_string()),
+            });
+        }
+    };
+
+    // A market order without a price is left at zero for the engine to fill in
+    let prices = if order_type == OrderType::Market && req.price == 0.0 {
+        Ok(0)
+    } else {
+        scale.order_ticks(req.price, side)
+    }
+    .and_then(|price| {
+        let stop_price = req
+            .stop_price
+            .map(|stop_price| scale.order_ticks(stop_price, side))
+            .transpose()?;
+        Ok((price, stop_price))
+    });
+    let (price, stop_price) = match prices {
+        Ok(prices) => prices,
+        Err(e) => {
+            return Json(SubmitOrderResponse {
+                success: false,
+                trades: vec![],
+                remaining_quantity: 0,
+                stp_cancelled: vec![],
+                repriced_to: None,
+                message: Some(e.to_string()),
+            });
+        }
+    };
+
+    let mut order = BookOrder::new(
         req.order_id.unwrap_or_else(Uuid::new_v4),
         req.user_id,
         side,
-        req.price,
+        price,
         req.quantity,
         0, // Sequence will be assigned by store
         tif,
-    ).with_instrument_id(req.instrument_id);
+    )
+    .with_instrument_id(req.instrument_id)
+    .with_order_type(order_type);
+
+    let Some(stp) = parse_stp(req.stp.as_deref().unwrap_or("none")) else {
+        return Json(SubmitOrderResponse {
+            success: false,
+            trades: vec![],
+            remaining_quantity: 0,
+            stp_cancelled: vec![],
+            repriced_to: None,
+            message: Some(
+                "Invalid stp. Use 'none', 'cancel_taker', 'cancel_maker', 'cancel_both' or 'decrement_and_cancel'"
+                    .to_string(),
+            ),
+        });
+    };
+    order = order.with_stp(stp);
+
+    let Some(post_only) = parse_post_only(req.post_only.as_deref().unwrap_or("none")) else {
+        return Json(SubmitOrderResponse {
+            success: false,
+            trades: vec![],
+            remaining_quantity: 0,
+            stp_cancelled: vec![],
+            repriced_to: None,
+            message: Some("Invalid post_only. Use 'none', 'reject' or 'reprice'".to_string()),
+        });
+    };
+    order = order
+        .with_post_only(post_only)
+        .with_all_or_none(req.all_or_none)
+        .with_min_quantity(req.min_quantity)
+        .with_display_quantity(req.display_quantity)
+        .with_expires_at(req.expires_at);
+
+    if let Some(stop_price) = stop_price {
+        let Some(source) = parse_trigger_source(req.trigger.as_deref().unwrap_or("last_trade")) else {
+            return Json(SubmitOrderResponse {
+                success: false,
+                trades: vec![],
+                remaining_quantity: 0,
+                stp_cancelled: vec![],
+                repriced_to: None,
+                message: Some("Invalid trigger. Use 'last_trade', 'mark_price' or 'index_price'".to_string()),
+            });
+        };
+        order = order.with_stop(stop_price, source);
+    }
 
     match state.store.submit_order(order).await {
         Ok(result) => Json(SubmitOrderResponse {
             success: true,
-            trades: result.trades,
+            trades: trade_responses(&result.trades, &scale),
             remaining_quantity: result.remaining_order
                 .map(|o| o.quantity)
                 .unwrap_or(0),
+            stp_cancelled: result.stp_cancelled,
+            repriced_to: result.repriced_to.map(|ticks| scale.to_price(ticks)),
             message: None,
         }),
         Err(e) => Json(SubmitOrderResponse {
             success: false,
             trades: vec![],
             remaining_quantity: 0,
+            stp_cancelled: vec![],
+            repriced_to: None,
             message: Some(e.to_string()),
         }),
     }
@@ -154,19 +635,610 @@ pub async fn cancel_order<S: MatchingStore + 'static + ?Sized>(
     }
 }
 
+/// Cancel every order selected by the query filter
+///
+/// Filters on `user_id`, `instrument_id`, `underlying` and `side`; any
+/// combination may be given.
+pub async fn mass_cancel<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Query(filter): Query<MassCancelFilter>,
+) -> Json<MassCancelResponse> {
+    match state.store.mass_cancel(&filter).await {
+        Ok(result) => Json(MassCancelResponse {
+            success: true,
+            cancelled_order_ids: result.order_ids(),
+            message: None,
+        }),
+        Err(e) => Json(MassCancelResponse {
+            success: false,
+            cancelled_order_ids: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Remove every GTD and DAY order that has expired
+///
+/// Sweeps at the request's `now`, or the current time if omitted.
+pub async fn expire_orders<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Json(req): Json<ExpireOrdersRequest>,
+) -> Json<ExpireOrdersResponse> {
+    match state.store.expire_orders(req.now.unwrap_or_else(Utc::now)).await {
+        Ok(expired) => Json(ExpireOrdersResponse {
+            success: true,
+            expired_order_ids: expired.iter().map(|e| e.order.order_id).collect(),
+            message: None,
+        }),
+        Err(e) => Json(ExpireOrdersResponse {
+            success: false,
+            expired_order_ids: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Query for an event stream
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// First sequence to deliver; live events only if omitted
+    pub from: Option<u64>,
+}
+
+/// Frame sent on an event stream
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventFrame {
+    /// The next matching event
+    Event { event: MatchingEvent },
+    /// The subscriber fell behind; reconnect with `from = resume_from`
+    Lagged { skipped: u64, resume_from: u64 },
+}
+
+/// Stream matching events over a WebSocket
+///
+/// Events are sent as JSON frames in sequence order, starting at `from`.
+/// A subscriber that falls behind gets a `lagged` frame and the socket is
+/// closed.
+pub async fn stream_events<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Query(query): Query<EventStreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let subscription = match query.from {
+        Some(from) => state.store.subscribe(from).await,
+        None => match state.store.get_sequence().await {
+            Ok(sequence) => state.store.subscribe(sequence + 1).await,
+            Err(e) => Err(e),
+        },
+    };
+
+    match subscription {
+        Ok(subscription) => ws.on_upgrade(move |socket| forward_events(socket, subscription)),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    }
+}
+
+/// Forward a subscription to a socket until either side goes away
+async fn forward_events(mut socket: WebSocket, mut subscription: EventSubscription) {
+    loop {
+        let (frame, last) = match subscription.recv().await {
+            Ok(event) => (EventFrame::Event { event }, false),
+            Err(SubscriptionError::Lagged { skipped, resume_from }) => {
+                (EventFrame::Lagged { skipped, resume_from }, true)
+            }
+            Err(SubscriptionError::Closed) => break,
+        };
+        let Ok(text) = serde_json::to_string(&frame) else { break };
+        if socket.send(Message::Text(text)).await.is_err() || last {
+            break;
+        }
+    }
+    let _ = socket.close().await;
+}
+
+/// Frame sent on a book feed
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BookFeedFrame {
+    /// Full book; replaces the client's copy
+    Snapshot { snapshot: BookFeedSnapshot },
+    /// Next incremental update
+    Update { update: BookUpdate },
+}
+
+/// Stream an instrument's book over a WebSocket
+///
+/// Sends an L3 snapshot, then every update after it (L2 level deltas and
+/// L3 order changes with the book sequence and checksum). A subscriber
+/// that falls behind is sent a fresh snapshot and continues from there.
+pub async fn stream_book<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let store = Arc::clone(&state.store);
+    let feed = state
+        .book_feed
+        .get_or_try_init(|| async move {
+            // Mirror the whole log so every book is complete
+            let events = store.subscribe(0).await?;
+            Ok::<_, StoreError>(spawn_book_feed(events, move |from| {
+                let store = Arc::clone(&store);
+                async move { store.subscribe(from).await.ok() }
+            }))
+        })
+        .await;
+
+    match feed {
+        Ok(feed) => {
+            let feed = feed.clone();
+            ws.on_upgrade(move |socket| forward_book(socket, feed, instrument_id))
+        }
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    }
+}
+
+/// Forward one book's snapshot and updates to a socket
+async fn forward_book(mut socket: WebSocket, feed: BookFeedHandle, instrument_id: String) {
+    'resync: loop {
+        let Ok((snapshot, mut updates)) = feed.subscribe(&instrument_id).await else { break };
+        let mut sequence = snapshot.sequence;
+        if send_frame(&mut socket, &BookFeedFrame::Snapshot { snapshot }).await.is_err() {
+            break;
+        }
+
+        loop {
+            match updates.recv().await {
+                Ok(update) if update.instrument_id == instrument_id && update.sequence > sequence => {
+                    sequence = update.sequence;
+                    if send_frame(&mut socket, &BookFeedFrame::Update { update }).await.is_err() {
+                        break 'resync;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => continue 'resync,
+                Err(broadcast::error::RecvError::Closed) => break 'resync,
+            }
+        }
+    }
+    let _ = socket.close().await;
+}
+
+/// Send a JSON frame on a socket
+async fn send_frame<T: serde::Serialize>(socket: &mut WebSocket, frame: &T) -> Result<(), ()> {
+    let text = serde_json::to_string(frame).map_err(|_| ())?;
+    socket.send(Message::Text(text)).await.map_err(|_| ())
+}
+
+/// Amend a resting order
+pub async fn amend_order<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(order_id): Path<Uuid>,
+    Json(req): Json<AmendOrderRequest>,
+) -> Json<AmendOrderResponse> {
+    let resting = match state.store.get_order(order_id).await {
+        Ok(Some(order)) => order,
+        Ok(None) => return amend_failed(StoreError::OrderNotFound(order_id).to_string()),
+        Err(e) => return amend_failed(e.to_string()),
+    };
+    let scale = match state.store.price_scale(&resting.instrument_id).await {
+        Ok(scale) => scale,
+        Err(e) => return amend_failed(e.to_string()),
+    };
+    let new_price = match req.price.map(|price| scale.order_ticks(price, resting.side)).transpose() {
+        Ok(new_price) => new_price,
+        Err(e) => return amend_failed(e.to_string()),
+    };
+
+    match state.store.amend_order(order_id, new_price, req.quantity).await {
+        Ok(result) => Json(AmendOrderResponse {
+            success: true,
+            priority_retained: result.priority_retained,
+            remaining_quantity: result.resting.map(|o| o.quantity).unwrap_or(0),
+            trades: trade_responses(&result.trades, &scale),
+            message: None,
+        }),
+        Err(e) => amend_failed(e.to_string()),
+    }
+}
+
+/// Response for an amend that was not applied
+fn amend_failed(message: String) -> Json<AmendOrderResponse> {
+    Json(AmendOrderResponse {
+        success: false,
+        priority_retained: false,
+        trades: vec![],
+        remaining_quantity: 0,
+        message: Some(message),
+    })
+}
+
+/// Update a reference price, releasing any stop orders it triggers
+pub async fn update_reference_price<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+    Json(req): Json<ReferencePriceRequest>,
+) -> Json<ReferencePriceResponse> {
+    let Some(source) = parse_trigger_source(&req.source) else {
+        return Json(ReferencePriceResponse {
+            success: false,
+            triggered_orders: vec![],
+            trades: vec![],
+            message: Some("Invalid source. Use 'last_trade', 'mark_price' or 'index_price'".to_string()),
+        });
+    };
+
+    let scale = match state.store.price_scale(&instrument_id).await {
+        Ok(scale) => scale,
+        Err(e) => {
+            return Json(ReferencePriceResponse {
+                success: false,
+                triggered_orders: vec![],
+                trades: vec![],
+                message: Some(e.to_string()),
+            });
+        }
+    };
+    // Reference prices need not lie on a tick
+    let price = scale.nearest_ticks(req.price);
+
+    match state.store.update_reference_price(&instrument_id, source, price).await {
+        Ok(triggered) => Json(ReferencePriceResponse {
+            success: true,
+            triggered_orders: triggered.iter().map(|t| t.order.order_id).collect(),
+            trades: trade_responses(
+                triggered.iter().flat_map(|t| t.result.all_trades()),
+                &scale,
+            ),
+            message: None,
+        }),
+        Err(e) => Json(ReferencePriceResponse {
+            success: false,
+            triggered_orders: vec![],
+            trades: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Get the call auction state of an instrument
+pub async fn get_auction<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+) -> Json<AuctionResponse> {
+    let status = async {
+        let auction = state.store.get_auction(&instrument_id).await?;
+        let indicative = state.store.get_indicative_uncross(&instrument_id).await?;
+        let scale = state.store.price_scale(&instrument_id).await?;
+        Ok::<_, StoreError>((auction, indicative, scale))
+    };
+
+    match status.await {
+        Ok((auction, indicative, scale)) => Json(AuctionResponse {
+            success: true,
+            reason: auction.map(|a| a.reason),
+            indicative_price: indicative.map(|i| scale.to_price(i.price)),
+            indicative_volume: indicative.map_or(0, |i| i.volume),
+            imbalance: indicative.map_or(0, |i| i.imbalance),
+            instrument_id,
+            message: None,
+        }),
+        Err(e) => Json(AuctionResponse {
+            success: false,
+            instrument_id,
+            reason: None,
+            indicative_price: None,
+            indicative_volume: 0,
+            imbalance: 0,
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Start an opening auction for an instrument
+pub async fn start_auction<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+) -> Json<serde_json::Value> {
+    match state.store.start_auction(&instrument_id, AuctionReason::Opening).await {
+        Ok(auction) => Json(serde_json::json!({
+            "success": true,
+            "sequence": auction.started_at,
+            "message": "Call auction started"
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// Uncross an instrument's call auction
+pub async fn uncross_auction<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+) -> Json<UncrossResponse> {
+    let scale = state.store.price_scale(&instrument_id).await.unwrap_or_default();
+    match state.store.uncross(&instrument_id).await {
+        Ok(result) => Json(UncrossResponse {
+            success: true,
+            price: result.price.map(|ticks| scale.to_price(ticks)),
+            volume: result.volume(),
+            trades: trade_responses(&result.trades, &scale),
+            instrument_id,
+            message: None,
+        }),
+        Err(e) => Json(UncrossResponse {
+            success: false,
+            instrument_id,
+            price: None,
+            volume: 0,
+            trades: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// List every combo instrument
+pub async fn get_combos<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+) -> Json<CombosResponse> {
+    match state.store.get_combos().await {
+        Ok(combos) => Json(CombosResponse {
+            success: true,
+            combos,
+            message: None,
+        }),
+        Err(e) => Json(CombosResponse {
+            success: false,
+            combos: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Define a combo instrument
+pub async fn define_combo<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Json(req): Json<DefineComboRequest>,
+) -> Json<CombosResponse> {
+    match state.store.define_combo(ComboDefinition::new(req.combo_id, req.legs)).await {
+        Ok(combo) => Json(CombosResponse {
+            success: true,
+            combos: vec![combo],
+            message: None,
+        }),
+        Err(e) => Json(CombosResponse {
+            success: false,
+            combos: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Book a block trade outside the order book
+pub async fn book_block_trade<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Json(req): Json<BlockTradeRequest>,
+) -> Json<BlockTradeResponse> {
+    let taker_side = match req.taker_side.to_lowercase().as_str() {
+        "buy" => OrderSide::Buy,
+        "sell" => OrderSide::Sell,
+        _ => {
+            return Json(BlockTradeResponse {
+                success: false,
+                trades: vec![],
+                message: Some("Invalid taker_side. Use 'buy' or 'sell'".to_string()),
+            });
+        }
+    };
+
+    let booked = async {
+        if !req.legs.is_empty() {
+            let combo = ComboDefinition::new(req.instrument_id.clone(), req.legs.clone());
+            state.store.define_combo(combo).await?;
+        }
+        let scale = state.store.price_scale(&req.instrument_id).await?;
+        let block = BlockTrade {
+            rfq_id: req.rfq_id,
+            instrument_id: req.instrument_id.clone(),
+            price: scale.order_ticks(req.price, taker_side)?,
+            quantity: req.quantity,
+            taker_order_id: req.taker_order_id,
+            taker_user_id: req.taker_user_id,
+            taker_side,
+            maker_order_id: req.maker_order_id,
+            maker_user_id: req.maker_user_id,
+        };
+        let trades = state.store.book_block_trade(block).await?;
+        Ok::<_, StoreError>((trades, scale))
+    };
+
+    match booked.await {
+        Ok((trades, scale)) => Json(BlockTradeResponse {
+            success: true,
+            trades: trade_responses(&trades, &scale),
+            message: None,
+        }),
+        Err(e) => Json(BlockTradeResponse {
+            success: false,
+            trades: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Replace a maker's quotes on every instrument quoted
+pub async fn mass_quote<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Json(req): Json<MassQuoteRequest>,
+) -> Json<MassQuoteResponse> {
+    let quoted = async {
+        let mut quotes = Vec::new();
+        for entry in &req.quotes {
+            let scale = state.store.price_scale(&entry.instrument_id).await?;
+            for (side, level) in [(OrderSide::Buy, &entry.bid), (OrderSide::Sell, &entry.ask)] {
+                let Some(level) = level else { continue };
+                let order_id = level.order_id.unwrap_or_else(Uuid::new_v4);
+                let price = scale.order_ticks(level.price, side)?;
+                quotes.push(
+                    BookOrder::new(order_id, req.user_id, side, price, level.quantity, 0, TimeInForce::Gtc)
+                        .with_instrument_id(entry.instrument_id.clone()),
+                );
+            }
+        }
+        state.store.mass_quote(req.user_id, quotes).await
+    };
+
+    match quoted.await {
+        Ok(result) => Json(MassQuoteResponse {
+            success: true,
+            replaced_order_ids: result.replaced.order_ids(),
+            resting_order_ids: result.resting_order_ids(),
+            rejected_order_ids: result.rejected_order_ids(),
+            message: None,
+        }),
+        Err(e) => Json(MassQuoteResponse {
+            success: false,
+            replaced_order_ids: vec![],
+            resting_order_ids: vec![],
+            rejected_order_ids: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Get a maker's market maker protection status
+pub async fn get_mmp_status<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(user_id): Path<Uuid>,
+) -> Json<MmpStatusResponse> {
+    match state.store.get_mmp_status(user_id).await {
+        Ok(underlyings) => Json(MmpStatusResponse {
+            success: true,
+            user_id,
+            underlyings,
+            message: None,
+        }),
+        Err(e) => Json(MmpStatusResponse {
+            success: false,
+            user_id,
+            underlyings: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Let a maker quote again in an underlying after MMP froze it
+pub async fn reset_mmp<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path((user_id, underlying)): Path<(Uuid, String)>,
+) -> Json<MmpResetResponse> {
+    match state.store.reset_mmp(user_id, &underlying).await {
+        Ok(reset) => Json(MmpResetResponse {
+            success: true,
+            reset,
+            message: None,
+        }),
+        Err(e) => Json(MmpResetResponse {
+            success: false,
+            reset: false,
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Get circuit breaker status of every instrument
+pub async fn get_circuit_breakers<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+) -> Json<CircuitBreakersResponse> {
+    let status = async {
+        let mut instruments = state.store.get_circuit_breaker_status().await?;
+        instruments.sort_by(|a, b| a.instrument_id.cmp(&b.instrument_id));
+        let halted_underlyings = state.store.get_halted_underlyings().await?;
+        Ok::<_, StoreError>((instruments, halted_underlyings))
+    };
+
+    match status.await {
+        Ok((instruments, halted_underlyings)) => Json(CircuitBreakersResponse {
+            success: true,
+            instruments,
+            halted_underlyings,
+            message: None,
+        }),
+        Err(e) => Json(CircuitBreakersResponse {
+            success: false,
+            instruments: vec![],
+            halted_underlyings: vec![],
+            message: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Halt an instrument until resumed
+pub async fn halt_instrument<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+) -> Json<serde_json::Value> {
+    admin_response(state.store.halt_instrument(&instrument_id).await, "Instrument halted")
+}
+
+/// Lift every halt of an instrument
+///
+/// A running halt auction uncrosses on the next order, or through the
+/// auction uncross endpoint.
+pub async fn resume_instrument<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(instrument_id): Path<String>,
+) -> Json<serde_json::Value> {
+    admin_response(state.store.resume_instrument(&instrument_id).await, "Instrument resumed")
+}
+
+/// Halt every instrument of an underlying until resumed
+pub async fn halt_underlying<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(underlying): Path<String>,
+) -> Json<serde_json::Value> {
+    admin_response(state.store.halt_underlying(&underlying).await, "Underlying halted")
+}
+
+/// Lift a halt of an underlying
+pub async fn resume_underlying<S: MatchingStore + 'static + ?Sized>(
+    State(state): State<MatchingApiState<S>>,
+    Path(underlying): Path<String>,
+) -> Json<serde_json::Value> {
+    admin_response(state.store.resume_underlying(&underlying).await, "Underlying resumed")
+}
+
+/// Response for an admin action
+fn admin_response(result: Result<(), StoreError>, message: &str) -> Json<serde_json::Value> {
+    match result {
+        Ok(()) => Json(serde_json::json!({
+            "success": true,
+            "message": message
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "message": e.to_string()
+        })),
+    }
+}
+
 /// Get order book for an instrument
 pub async fn get_order_book<S: MatchingStore + 'static + ?Sized>(
     State(state): State<MatchingApiState<S>>,
     Path(instrument_id): Path<String>,
 ) -> Json<OrderBookResponse> {
+    let scale = state.store.price_scale(&instrument_id).await.unwrap_or_default();
     match state.store.get_book(&instrument_id).await {
         Ok(Some(book)) => {
             let bids: Vec<PriceLevelResponse> = book.bids
                 .iter()
                 .take(10)
                 .map(|(price, orders)| PriceLevelResponse {
-                    price: price.0 .0,
-                    quantity: orders.iter().map(|o| o.quantity).sum(),
+                    price: scale.to_price(price.0),
+                    quantity: orders.iter().map(|o| o.visible_quantity()).sum(),
                 })
                 .collect();
 
@@ -174,8 +1246,8 @@ pub async fn get_order_book<S: MatchingStore + 'static + ?Sized>(
                 .iter()
                 .take(10)
                 .map(|(price, orders)| PriceLevelResponse {
-                    price: price.0,
-                    quantity: orders.iter().map(|o| o.quantity).sum(),
+                    price: scale.to_price(*price),
+                    quantity: orders.iter().map(|o| o.visible_quantity()).sum(),
                 })
                 .collect();
 
@@ -184,7 +1256,7 @@ pub async fn get_order_book<S: MatchingStore + 'static + ?Sized>(
                 instrument_id,
                 bids,
                 asks,
-                spread: book.spread(),
+                spread: book.spread().map(|ticks| scale.to_price(ticks)),
             })
         }
         Ok(None) => Json(OrderBookResponse {
@@ -214,11 +1286,12 @@ pub async fn get_trades<S: MatchingStore + 'static + ?Sized>(
         .and_then(|v| v.as_u64())
         .unwrap_or(50) as u32;
 
+    let scale = state.store.price_scale(&instrument_id).await.unwrap_or_default();
     match state.store.get_trades(&instrument_id, limit).await {
         Ok(trades) => Json(TradesResponse {
             success: true,
+            trades: trade_responses(&trades, &scale),
             instrument_id,
-            trades,
         }),
         Err(e) => Json(TradesResponse {
             success: false,
@@ -1,7 +1,7 @@
 //! HTTP routes for the Matching Engine API
 
 use axum::{
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 use std::sync::Arc;
@@ -13,34 +13,131 @@ use super::handlers::*;
 /// 
 /// Routes:
 /// - POST   /api/v1/internal/orders              - Submit order
+/// - DELETE /api/v1/internal/orders              - Mass cancel (query: user_id, instrument_id, underlying, side)
 /// - DELETE /api/v1/internal/orders/:instrument_id/:order_id - Cancel order
+/// - PATCH  /api/v1/internal/orders/:order_id    - Amend order
+/// - POST   /api/v1/internal/expiry              - Sweep expired GTD/DAY orders
+/// - POST   /api/v1/internal/prices/:instrument_id - Update mark/index/last price
 /// - GET    /api/v1/internal/books/:instrument_id - Get order book snapshot
+/// - GET    /api/v1/internal/books/:instrument_id/feed - WebSocket L2/L3 book feed
 /// - GET    /api/v1/internal/trades/:instrument_id - Get recent trades
+/// - GET    /api/v1/internal/auctions/:instrument_id - Call auction state and indicative uncross
+/// - POST   /api/v1/internal/auctions/:instrument_id - Start an opening auction
+/// - POST   /api/v1/internal/auctions/:instrument_id/uncross - Uncross the auction
+/// - GET    /api/v1/internal/combos              - List combo instruments
+/// - POST   /api/v1/internal/combos              - Define a combo instrument
+/// - POST   /api/v1/internal/block-trades        - Book a block trade agreed in an RFQ
+/// - POST   /api/v1/internal/mass-quotes         - Replace a maker's quotes
+/// - GET    /api/v1/internal/mmp/:user_id        - Market maker protection status of a maker
+/// - POST   /api/v1/internal/mmp/:user_id/:underlying/reset - Let a frozen maker quote again
+/// - GET    /api/v1/internal/circuit-breakers    - Breaker, halt and price band status
+/// - POST   /api/v1/internal/circuit-breakers/instruments/:instrument_id/halt   - Halt an instrument
+/// - POST   /api/v1/internal/circuit-breakers/instruments/:instrument_id/resume - Resume an instrument
+/// - POST   /api/v1/internal/circuit-breakers/underlyings/:underlying/halt      - Halt an underlying
+/// - POST   /api/v1/internal/circuit-breakers/underlyings/:underlying/resume    - Resume an underlying
+/// - GET    /api/v1/internal/events              - WebSocket event stream (query: from)
 /// - GET    /api/v1/matching/health              - Health check (service-specific path)
 pub fn create_router<S: MatchingStore + 'static + ?Sized>(state: MatchingApiState<S>) -> Router {
     Router::new()
         // Health check with service-specific path to avoid conflicts
         .route("/api/v1/matching/health", get(health))
-        // Order submission
+        // Order submission and mass cancel
         .route(
             "/api/v1/internal/orders",
-            post(submit_order),
+            post(submit_order).delete(mass_cancel),
         )
         // Order cancellation
         .route(
             "/api/v1/internal/orders/:instrument_id/:order_id",
             delete(cancel_order),
         )
+        // Order amendment
+        .route(
+            "/api/v1/internal/orders/:order_id",
+            patch(amend_order),
+        )
+        // Expiry sweep
+        .route(
+            "/api/v1/internal/expiry",
+            post(expire_orders),
+        )
+        // Reference prices (stop triggers)
+        .route(
+            "/api/v1/internal/prices/:instrument_id",
+            post(update_reference_price),
+        )
         // Order book snapshot
         .route(
             "/api/v1/internal/books/:instrument_id",
             get(get_order_book),
         )
+        .route(
+            "/api/v1/internal/books/:instrument_id/feed",
+            get(stream_book),
+        )
         // Recent trades
         .route(
             "/api/v1/internal/trades/:instrument_id",
             get(get_trades),
         )
+        // Call auctions
+        .route(
+            "/api/v1/internal/auctions/:instrument_id",
+            get(get_auction).post(start_auction),
+        )
+        .route(
+            "/api/v1/internal/auctions/:instrument_id/uncross",
+            post(uncross_auction),
+        )
+        // Combo instruments
+        .route(
+            "/api/v1/internal/combos",
+            get(get_combos).post(define_combo),
+        )
+        // Block trades agreed off-book
+        .route(
+            "/api/v1/internal/block-trades",
+            post(book_block_trade),
+        )
+        // Mass quotes and market maker protection
+        .route(
+            "/api/v1/internal/mass-quotes",
+            post(mass_quote),
+        )
+        .route(
+            "/api/v1/internal/mmp/:user_id",
+            get(get_mmp_status),
+        )
+        .route(
+            "/api/v1/internal/mmp/:user_id/:underlying/reset",
+            post(reset_mmp),
+        )
+        // Circuit breakers and manual halts (admin)
+        .route(
+            "/api/v1/internal/circuit-breakers",
+            get(get_circuit_breakers),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/instruments/:instrument_id/halt",
+            post(halt_instrument),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/instruments/:instrument_id/resume",
+            post(resume_instrument),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/underlyings/:underlying/halt",
+            post(halt_underlying),
+        )
+        .route(
+            "/api/v1/internal/circuit-breakers/underlyings/:underlying/resume",
+            post(resume_underlying),
+        )
+        // Matching event stream
+        .route(
+            "/api/v1/internal/events",
+            get(stream_events),
+        )
         .with_state(state)
 }
 
@@ -48,6 +145,6 @@ pub fn create_router<S: MatchingStore + 'static + ?Sized>(state: MatchingApiStat
 /// 
 /// Use this when you have `Arc<dyn MatchingStore>` instead of a concrete type.
 pub fn create_dyn_router(store: Arc<dyn MatchingStore + Send + Sync>) -> Router {
-    let state = DynMatchingApiState { store };
+    let state = DynMatchingApiState::new(store);
     create_router(state)
 }
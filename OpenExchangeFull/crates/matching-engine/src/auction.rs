@@ -0,0 +1,261 @@
+//! Call auctions
+//!
+//! While an instrument is in a call auction, incoming orders are collected
+//! in its book without matching, so the book may cross. The auction ends
+//! with a single uncross: every order that can trade at the equilibrium
+//! price does, at that one price.
+//!
+//! The equilibrium is the price level that maximises executable volume.
+//! Ties are broken by the smallest imbalance, then by market pressure (the
+//! highest price if buyers are left over at every remaining candidate, the
+//! lowest if sellers are), then by proximity to the reference price.
+//!
+//! Auctions run after a circuit-breaker halt and as the opening auction of
+//! a newly listed instrument.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{BookOrder, OrderBook, OrderSide, PostOnly};
+use crate::price::Ticks;
+
+/// Why an instrument is in a call auction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionReason {
+    /// Collecting orders while a circuit breaker halt runs
+    Halt,
+    /// Opening auction of a newly listed instrument
+    Opening,
+}
+
+/// A running call auction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallAuction {
+    /// Why the auction runs
+    pub reason: AuctionReason,
+    /// Sequence at which the auction started
+    pub started_at: u64,
+}
+
+/// Price and volume the auction would uncross at right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndicativeUncross {
+    /// Equilibrium price in ticks
+    pub price: Ticks,
+    /// Quantity that would trade
+    pub volume: u32,
+    /// Buy quantity minus sell quantity eligible at the price
+    pub imbalance: i64,
+}
+
+/// One pairing of a buy and a sell order in the uncross
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionFill {
+    /// Buy order ID
+    pub buy_order_id: Uuid,
+    /// Sell order ID
+    pub sell_order_id: Uuid,
+    /// Quantity traded
+    pub quantity: u32,
+}
+
+/// Cumulative buy and sell quantity eligible at a candidate price
+fn eligible_volume(book: &OrderBook, price: Ticks) -> (u64, u64) {
+    let buys = book
+        .bids
+        .iter()
+        .take_while(|(level, _)| level.0 >= price)
+        .flat_map(|(_, orders)| orders.iter())
+        .map(|o| o.quantity as u64)
+        .sum();
+    let sells = book
+        .asks
+        .iter()
+        .take_while(|(level, _)| **level <= price)
+        .flat_map(|(_, orders)| orders.iter())
+        .map(|o| o.quantity as u64)
+        .sum();
+    (buys, sells)
+}
+
+/// Equilibrium price of a (possibly crossed) book
+///
+/// Hidden iceberg quantity takes part in full. None if the book does not
+/// cross. `reference` (normally the last trade price) breaks the final tie;
+/// without one the lower of the middle candidates is used.
+pub fn indicative_uncross(book: &OrderBook, reference: Option<Ticks>) -> Option<IndicativeUncross> {
+    let mut candidates: Vec<Ticks> = book
+        .bids
+        .keys()
+        .map(|level| level.0)
+        .chain(book.asks.keys().copied())
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let evaluated: Vec<IndicativeUncross> = candidates
+        .into_iter()
+        .map(|price| {
+            let (buys, sells) = eligible_volume(book, price);
+            IndicativeUncross {
+                price,
+                volume: buys.min(sells) as u32,
+                imbalance: buys as i64 - sells as i64,
+            }
+        })
+        .collect();
+
+    // 1. Maximum executable volume
+    let volume = evaluated.iter().map(|c| c.volume).max().filter(|v| *v > 0)?;
+    let best: Vec<&IndicativeUncross> = evaluated.iter().filter(|c| c.volume == volume).collect();
+
+    // 2. Minimum imbalance
+    let least = best.iter().map(|c| c.imbalance.abs()).min()?;
+    let best: Vec<&IndicativeUncross> = best.into_iter().filter(|c| c.imbalance.abs() == least).collect();
+
+    // 3. Market pressure
+    if best.iter().all(|c| c.imbalance > 0) {
+        return best.last().map(|c| **c);
+    }
+    if best.iter().all(|c| c.imbalance < 0) {
+        return best.first().map(|c| **c);
+    }
+
+    // 4. Closest to the reference price
+    match reference {
+        Some(reference) => best
+            .into_iter()
+            .min_by_key(|c| ((c.price - reference).abs(), c.price))
+            .copied(),
+        None => best.get((best.len() - 1) / 2).map(|c| **c),
+    }
+}
+
+/// Pair the orders that trade at `price`, in price-time priority
+///
+/// Buy orders at or above the price and sell orders at or below it are
+/// each taken best price first, FIFO within a level, and matched against
+/// each other until one side runs out.
+pub fn allocate(book: &OrderBook, price: Ticks) -> Vec<AuctionFill> {
+    let mut buys = book
+        .bids
+        .iter()
+        .take_while(|(level, _)| level.0 >= price)
+        .flat_map(|(_, orders)| orders.iter())
+        .map(|o| (o.order_id, o.quantity));
+    let mut sells = book
+        .asks
+        .iter()
+        .take_while(|(level, _)| **level <= price)
+        .flat_map(|(_, orders)| orders.iter())
+        .map(|o| (o.order_id, o.quantity));
+
+    let mut fills = Vec::new();
+    let mut buy = buys.next();
+    let mut sell = sells.next();
+    while let (Some((buy_order_id, buy_left)), Some((sell_order_id, sell_left))) = (buy, sell) {
+        let quantity = buy_left.min(sell_left);
+        fills.push(AuctionFill {
+            buy_order_id,
+            sell_order_id,
+            quantity,
+        });
+        buy = if buy_left > quantity {
+            Some((buy_order_id, buy_left - quantity))
+        } else {
+            buys.next()
+        };
+        sell = if sell_left > quantity {
+            Some((sell_order_id, sell_left - quantity))
+        } else {
+            sells.next()
+        };
+    }
+    fills
+}
+
+/// Whether an order may join a call auction
+///
+/// Only plain resting (GTC, GTD, DAY) orders are collected: market,
+/// IOC/FOK, all-or-none and minimum-quantity orders need an immediate
+/// match, and post-only orders would trade in the uncross, so all of them
+/// are rejected.
+pub fn accepts(order: &BookOrder) -> bool {
+    !order.is_market()
+        && order.time_in_force.rests()
+        && !order.all_or_none
+        && order.min_quantity.is_none()
+        && order.post_only == PostOnly::None
+}
+
+/// Side treated as the aggressor of an auction trade: the later order's
+pub fn aggressor(buy: &BookOrder, sell: &BookOrder) -> OrderSide {
+    if buy.sequence > sell.sequence {
+        OrderSide::Buy
+    } else {
+        OrderSide::Sell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TimeInForce;
+
+    fn book(orders: &[(OrderSide, Ticks, u32)]) -> OrderBook {
+        let mut book = OrderBook::new("test".to_string());
+        for (sequence, (side, price, quantity)) in orders.iter().enumerate() {
+            book.insert_order(BookOrder::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                *side,
+                *price,
+                *quantity,
+                sequence as u64 + 1,
+                TimeInForce::Gtc,
+            ));
+        }
+        book
+    }
+
+    #[test]
+    fn test_equilibrium_maximises_volume_then_breaks_ties() {
+        use OrderSide::{Buy, Sell};
+
+        // Volume: 100 -> 5, 101 -> 8, 102 -> 4
+        let crossed = book(&[(Buy, 102, 4), (Buy, 101, 4), (Buy, 100, 2), (Sell, 100, 5), (Sell, 101, 3)]);
+        let uncross = indicative_uncross(&crossed, None).unwrap();
+        assert_eq!((uncross.price, uncross.volume, uncross.imbalance), (101, 8, 0));
+
+        // Equal volume at 100 and 101; leftover buyers at both push the price up
+        let pressure = book(&[(Buy, 101, 6), (Sell, 100, 3)]);
+        assert_eq!(indicative_uncross(&pressure, None).unwrap().price, 101);
+        let pressure = book(&[(Buy, 101, 3), (Sell, 100, 6)]);
+        assert_eq!(indicative_uncross(&pressure, None).unwrap().price, 100);
+
+        // Balanced at 100 and 101: the reference decides
+        let balanced = book(&[(Buy, 101, 5), (Sell, 100, 5)]);
+        assert_eq!(indicative_uncross(&balanced, Some(104)).unwrap().price, 101);
+        assert_eq!(indicative_uncross(&balanced, Some(90)).unwrap().price, 100);
+        assert_eq!(indicative_uncross(&balanced, None).unwrap().price, 100);
+
+        assert!(indicative_uncross(&book(&[(Buy, 99, 5), (Sell, 100, 5)]), None).is_none());
+    }
+
+    #[test]
+    fn test_allocation_follows_price_time_priority() {
+        use OrderSide::{Buy, Sell};
+
+        let crossed = book(&[(Buy, 101, 4), (Buy, 102, 3), (Buy, 100, 9), (Sell, 99, 5), (Sell, 101, 5)]);
+        let fills = allocate(&crossed, 101);
+        let quantities: Vec<u32> = fills.iter().map(|f| f.quantity).collect();
+        assert_eq!(quantities, vec![3, 2, 2]);
+                // The 102 bid fills first, then the 101 bid against the rest of the
+        // 99 ask and the 101 ask; the 100 bid is out
+        assert_ne!(fills[0].buy_order_id, fills[1].buy_order_id);
+        assert_eq!(fills[1].buy_order_id, fills[2].buy_order_id);
+        assert_eq!(fills[0].sell_order_id, fills[1].sell_order_id);
+        assert_ne!(fills[1].sell_order_id, fills[2].sell_order_id);
+    }
+}
@@ -0,0 +1,54 @@
+//! Block trades
+//!
+//! A block trade is a large trade agreed away from the order book through a
+//! request for quote (RFQ): the taker asks selected makers for firm quotes
+//! and accepts one. The engine only books the agreed trade. It never touches
+//! a book, so it neither takes nor leaves liquidity and does not move the
+//! last trade price that stops and auctions use, but it is sequenced and
+//! logged as `TradeExecuted` events like any book trade, so fills and
+//! settlement pick it up unchanged.
+//!
+//! A block trade on a combo is booked as one trade per leg, priced like a
+//! fill between two combo orders (see
+//! [`ComboDefinition::leg_prices`](crate::combo::ComboDefinition::leg_prices)).
+//! Each underlying can require a minimum block size; see
+//! [`MatchingEngine::set_min_block_size`](crate::engine::MatchingEngine::set_min_block_size).
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::OrderSide;
+use crate::price::Ticks;
+
+/// A block trade agreed in an RFQ, ready to be booked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTrade {
+    /// Request for quote the trade was agreed in
+    pub rfq_id: Uuid,
+    /// Instrument or combo traded
+    pub instrument_id: String,
+    /// Price in ticks (the net price for a combo)
+    pub price: Ticks,
+    /// Number of contracts (combo units for a combo)
+    pub quantity: u32,
+    /// Order of the side that requested and accepted the quote
+    pub taker_order_id: Uuid,
+    /// User that requested and accepted the quote
+    pub taker_user_id: Uuid,
+    /// Side of the taker
+    pub taker_side: OrderSide,
+    /// Order of the side that quoted
+    pub maker_order_id: Uuid,
+    /// User that quoted
+    pub maker_user_id: Uuid,
+}
+
+impl BlockTrade {
+    /// Buyer and seller of the trade
+    pub fn parties(&self) -> (Uuid, Uuid) {
+        match self.taker_side {
+            OrderSide::Buy => (self.taker_user_id, self.maker_user_id),
+            OrderSide::Sell => (self.maker_user_id, self.taker_user_id),
+        }
+    }
+}
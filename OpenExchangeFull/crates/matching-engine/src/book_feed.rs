@@ -0,0 +1,738 @@
+//! Incremental order book feeds (L2 deltas and L3 order-by-order)
+//!
+//! A [`BookFeed`] mirrors a matching engine by following its event stream
+//! (see [`MatchingEngine::follow`]). After each event it diffs every book
+//! the event touched against the copy it last published and emits one
+//! [`BookUpdate`] per changed book, carrying both the changed price levels
+//! (L2) and the added, modified and deleted orders (L3).
+//!
+//! # Sequencing and checksums
+//!
+//! Each book has its own update sequence that grows by exactly one per
+//! update, so a client that sees anything but `last + 1` has missed an
+//! update. Every update and snapshot also carries a CRC32 of the top
+//! [`CHECKSUM_DEPTH`] levels of the book as published (see
+//! [`book_checksum`]), so clients can verify their local copy.
+//!
+//! # Recovery
+//!
+//! Snapshots carry the book sequence they were taken at. A client (see
+//! [`LocalBook`]) subscribes to updates first, then takes a snapshot,
+//! drops updates at or below the snapshot's sequence and applies the rest
+//! in order. On a gap or checksum mismatch it takes a new snapshot.
+//!
+//! [`spawn_book_feed`] runs a feed on a task and hands out snapshots and
+//! live updates together, so no update falls between the two.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::{BookOrder, OrderBook, OrderSide};
+use crate::engine::MatchingEngine;
+use crate::event::MatchingEvent;
+use crate::feed::{EventSubscription, SubscriptionError};
+use crate::price::Ticks;
+
+/// Levels per side covered by the book checksum
+pub const CHECKSUM_DEPTH: usize = 10;
+
+/// Book updates buffered per subscriber before it is considered lagging
+pub const DEFAULT_BOOK_FEED_CAPACITY: usize = 4096;
+
+// ============================================================================
+// Wire types
+// ============================================================================
+
+/// New state of one price level
+///
+/// A `quantity` of zero removes the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelDelta {
+    pub side: OrderSide,
+    /// Price (ticks)
+    pub price: Ticks,
+    /// Total visible quantity at this price
+    pub quantity: u32,
+    /// Number of orders at this price
+    pub order_count: usize,
+}
+
+/// Change to one resting order
+///
+/// Quantities are visible quantities: an iceberg shows only its current
+/// slice. An order that loses time priority is deleted and added again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum OrderDelta {
+    /// An order joined the back of its price level
+    Add {
+        order_id: Uuid,
+        side: OrderSide,
+        price: Ticks,
+        quantity: u32,
+    },
+    /// An order's visible quantity changed; it keeps its place
+    Modify {
+        order_id: Uuid,
+        side: OrderSide,
+        price: Ticks,
+        quantity: u32,
+    },
+    /// An order left the book
+    Delete {
+        order_id: Uuid,
+        side: OrderSide,
+        price: Ticks,
+    },
+}
+
+/// Incremental change to one book
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookUpdate {
+    pub instrument_id: String,
+    /// Book sequence: the previous update's plus one
+    pub sequence: u64,
+    /// Matching event sequence that caused the update
+    pub event_sequence: u64,
+    /// Changed price levels, bids then asks, best first
+    pub levels: Vec<LevelDelta>,
+    /// Changed orders: deletes, then modifies, then adds in queue order
+    pub orders: Vec<OrderDelta>,
+    /// Checksum of the book after the update
+    pub checksum: u32,
+}
+
+/// A resting order in an L3 snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotOrder {
+    pub order_id: Uuid,
+    /// Visible quantity
+    pub quantity: u32,
+}
+
+/// One price level of an L3 snapshot, orders in queue order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotLevel {
+    pub price: Ticks,
+    pub orders: Vec<SnapshotOrder>,
+}
+
+/// Full order-by-order book at a book sequence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookFeedSnapshot {
+    pub instrument_id: String,
+    /// Book sequence of the last update included
+    pub sequence: u64,
+    /// Matching event sequence of the last update included
+    pub event_sequence: u64,
+    /// Bid levels, best first
+    pub bids: Vec<SnapshotLevel>,
+    /// Ask levels, best first
+    pub asks: Vec<SnapshotLevel>,
+    pub checksum: u32,
+}
+
+// ============================================================================
+// Checksum
+// ============================================================================
+
+/// CRC32 over the top [`CHECKSUM_DEPTH`] levels of a book
+///
+/// Levels are `(price, visible quantity)`, best first. The checksummed
+/// text interleaves bid and ask levels by depth as `price:quantity`,
+/// joined with `:` and skipping a side once it runs out; e.g. bids
+/// `[(99, 5)]` and asks `[(101, 2), (102, 7)]` give `99:5:101:2:102:7`.
+pub fn book_checksum(bids: &[(Ticks, u32)], asks: &[(Ticks, u32)]) -> u32 {
+    let mut fields = Vec::with_capacity(CHECKSUM_DEPTH * 2);
+    for depth in 0..CHECKSUM_DEPTH {
+        for side in [bids, asks] {
+            if let Some((price, quantity)) = side.get(depth) {
+                fields.push(format!("{}:{}", price, quantity));
+            }
+        }
+    }
+    crc32fast::hash(fields.join(":").as_bytes())
+}
+
+/// Top levels of a book as `(price, visible quantity)`, best first
+fn top_levels<'a>(levels: impl Iterator<Item = (Ticks, &'a VecDeque<BookOrder>)>) -> Vec<(Ticks, u32)> {
+    levels
+        .take(CHECKSUM_DEPTH)
+        .map(|(price, orders)| (price, orders.iter().map(|o| o.visible_quantity()).sum()))
+        .collect()
+}
+
+fn checksum_of(book: &OrderBook) -> u32 {
+    book_checksum(
+        &top_levels(book.bids.iter().map(|(price, orders)| (price.0, orders))),
+        &top_levels(book.asks.iter().map(|(price, orders)| (*price, orders))),
+    )
+}
+
+// ============================================================================
+// Server side
+// ============================================================================
+
+/// A book as last published
+struct PublishedBook {
+    book: OrderBook,
+    sequence: u64,
+    event_sequence: u64,
+}
+
+/// Builds book updates by mirroring a matching engine's events
+#[derive(Default)]
+pub struct BookFeed {
+    engine: MatchingEngine,
+    published: HashMap<String, PublishedBook>,
+    last_event: u64,
+}
+
+impl BookFeed {
+    /// Create a feed for an engine that has not processed any event yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the next matching event, returning the book updates it caused
+    ///
+    /// Events at or below the last one applied are ignored, so a feed can
+    /// resubscribe from its resume point after lagging.
+    pub fn apply(&mut self, event: &MatchingEvent) -> Vec<BookUpdate> {
+        if self.last_event > 0 && event.sequence() <= self.last_event {
+            return Vec::new();
+        }
+        self.last_event = event.sequence();
+
+        let touched = self.touched_instruments(event);
+        self.engine.follow(event);
+
+        touched
+            .into_iter()
+            .filter_map(|instrument_id| self.publish(&instrument_id, event.sequence()))
+            .collect()
+    }
+
+    /// Matching event sequence of the last event applied
+    pub fn event_sequence(&self) -> u64 {
+        self.last_event
+    }
+
+    /// Snapshot of a book as last published
+    pub fn snapshot(&self, instrument_id: &str) -> BookFeedSnapshot {
+        let Some(published) = self.published.get(instrument_id) else {
+            return BookFeedSnapshot {
+                instrument_id: instrument_id.to_string(),
+                sequence: 0,
+                event_sequence: 0,
+                bids: Vec::new(),
+                asks: Vec::new(),
+                checksum: book_checksum(&[], &[]),
+            };
+        };
+
+        let level = |price: Ticks, orders: &VecDeque<BookOrder>| SnapshotLevel {
+            price,
+            orders: orders
+                .iter()
+                .map(|o| SnapshotOrder {
+                    order_id: o.order_id,
+                    quantity: o.visible_quantity(),
+                })
+                .collect(),
+        };
+        let book = &published.book;
+        BookFeedSnapshot {
+            instrument_id: instrument_id.to_string(),
+            sequence: published.sequence,
+            event_sequence: published.event_sequence,
+            bids: book.bids.iter().map(|(price, orders)| level(price.0, orders)).collect(),
+            asks: book.asks.iter().map(|(price, orders)| level(*price, orders)).collect(),
+            checksum: checksum_of(book),
+        }
+    }
+
+    /// Instruments whose books an event may change, in a stable order
+    fn touched_instruments(&self, event: &MatchingEvent) -> Vec<String> {
+        let instrument_id = match event {
+            MatchingEvent::OrderAccepted { order, .. } => &order.instrument_id,
+            MatchingEvent::OrderCancelled { instrument_id, .. }
+            | MatchingEvent::OrderExpired { instrument_id, .. }
+            | MatchingEvent::OrderAmended { instrument_id, .. }
+            | MatchingEvent::StopTriggered { instrument_id, .. }
+            | MatchingEvent::AuctionStarted { instrument_id, .. }
+            | MatchingEvent::AuctionUncrossed { instrument_id, .. } => instrument_id,
+            MatchingEvent::OrdersMassCancelled { order_ids, .. }
+            | MatchingEvent::MmpTriggered { order_ids, .. } => {
+                // Look the orders up before they are removed
+                let instruments: HashSet<String> = order_ids
+                    .iter()
+                    .filter_map(|id| self.engine.get_order(*id))
+                    .map(|order| order.instrument_id.clone())
+                    .collect();
+                let mut instruments: Vec<String> = instruments.into_iter().collect();
+                instruments.sort();
+                return instruments;
+            }
+            // Derived from the events above, which already changed the book
+            MatchingEvent::TradeExecuted { .. }
+            | MatchingEvent::SelfTradePrevented { .. }
+            | MatchingEvent::ComboDefined { .. }
+            | MatchingEvent::MmpReset { .. }
+            | MatchingEvent::SequenceReset { .. } => return Vec::new(),
+        };
+        // Combo fills also change the books of the legs and combos involved
+        self.engine.linked_instruments(instrument_id)
+    }
+
+    /// Diff a book against its published copy and publish it if it changed
+    fn publish(&mut self, instrument_id: &str, event_sequence: u64) -> Option<BookUpdate> {
+        let current = match self.engine.get_book(instrument_id) {
+            Some(book) => book.clone(),
+            None => OrderBook::new(instrument_id.to_string()),
+        };
+        let published = self
+            .published
+            .entry(instrument_id.to_string())
+            .or_insert_with(|| PublishedBook {
+                book: OrderBook::new(instrument_id.to_string()),
+                sequence: 0,
+                event_sequence: 0,
+            });
+
+        let levels = diff_levels(&published.book, &current);
+        let orders = diff_orders(&published.book, &current);
+        if levels.is_empty() && orders.is_empty() {
+            return None;
+        }
+
+        published.sequence += 1;
+        published.event_sequence = event_sequence;
+        let update = BookUpdate {
+            instrument_id: instrument_id.to_string(),
+            sequence: published.sequence,
+            event_sequence,
+            levels,
+            orders,
+            checksum: checksum_of(&current),
+        };
+        published.book = current;
+        Some(update)
+    }
+}
+
+/// Visible level totals of one side, keyed by price
+fn level_totals<'a>(
+    levels: impl Iterator<Item = (Ticks, &'a VecDeque<BookOrder>)>,
+) -> BTreeMap<Ticks, (u32, usize)> {
+    levels
+        .map(|(price, orders)| {
+            (price, (orders.iter().map(|o| o.visible_quantity()).sum(), orders.len()))
+        })
+        .collect()
+}
+
+/// Price levels that differ between two books, bids then asks, best first
+fn diff_levels(old: &OrderBook, new: &OrderBook) -> Vec<LevelDelta> {
+    let sides = [
+        (
+            OrderSide::Buy,
+            level_totals(old.bids.iter().map(|(p, o)| (p.0, o))),
+            level_totals(new.bids.iter().map(|(p, o)| (p.0, o))),
+        ),
+        (
+            OrderSide::Sell,
+            level_totals(old.asks.iter().map(|(p, o)| (*p, o))),
+            level_totals(new.asks.iter().map(|(p, o)| (*p, o))),
+        ),
+    ];
+
+    let mut deltas = Vec::new();
+    for (side, old, new) in sides {
+        let mut prices: Vec<Ticks> = old.keys().chain(new.keys()).copied().collect();
+        prices.sort_unstable();
+        prices.dedup();
+        if side == OrderSide::Buy {
+            prices.reverse();
+        }
+        for price in prices {
+            let (quantity, order_count) = new.get(&price).copied().unwrap_or((0, 0));
+            if old.get(&price) != new.get(&price) {
+                deltas.push(LevelDelta { side, price, quantity, order_count });
+            }
+        }
+    }
+    deltas
+}
+
+/// Orders that differ between two books
+///
+/// An order is kept if it rests at the same price with the same sequence
+/// (the engine re-sequences an order whenever it loses time priority);
+/// anything else is a delete of the old order and an add of the new one.
+fn diff_orders(old: &OrderBook, new: &OrderBook) -> Vec<OrderDelta> {
+    let old_orders: HashMap<Uuid, &BookOrder> = old.orders().map(|o| (o.order_id, o)).collect();
+    let new_orders: HashMap<Uuid, &BookOrder> = new.orders().map(|o| (o.order_id, o)).collect();
+    let kept = |order: &BookOrder| {
+        old_orders.get(&order.order_id).is_some_and(|previous| {
+            previous.side == order.side && previous.price == order.price && previous.sequence == order.sequence
+        })
+    };
+
+    let mut deletes = Vec::new();
+    for order in old.orders() {
+        let still_there = new_orders.get(&order.order_id).is_some_and(|current| kept(current));
+        if !still_there {
+            deletes.push(OrderDelta::Delete {
+                order_id: order.order_id,
+                side: order.side,
+                price: order.price,
+            });
+        }
+    }
+
+    let mut modifies = Vec::new();
+    let mut adds = Vec::new();
+    for order in new.orders() {
+        let (order_id, side, price, quantity) = (order.order_id, order.side, order.price, order.visible_quantity());
+        if !kept(order) {
+            adds.push(OrderDelta::Add { order_id, side, price, quantity });
+        } else if old_orders[&order.order_id].visible_quantity() != quantity {
+            modifies.push(OrderDelta::Modify { order_id, side, price, quantity });
+        }
+    }
+
+    deletes.extend(modifies);
+    deletes.extend(adds);
+    deletes
+}
+
+// ============================================================================
+// Feed task
+// ============================================================================
+
+/// Errors from a book feed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BookFeedError {
+    /// An update did not follow the local book's sequence
+    #[error("Book update gap: expected sequence {expected}, got {received}")]
+    Gap { expected: u64, received: u64 },
+
+    /// The local book no longer matches the published one
+    #[error("Book checksum mismatch at sequence {sequence}")]
+    ChecksumMismatch { sequence: u64 },
+
+    /// The update is for a different book
+    #[error("Update for {received} applied to book {expected}")]
+    WrongInstrument { expected: String, received: String },
+
+    /// The feed task has stopped
+    #[error("Book feed stopped")]
+    Stopped,
+}
+
+/// Request to a feed task for a snapshot and live updates
+struct SubscribeRequest {
+    instrument_id: String,
+    reply: oneshot::Sender<(BookFeedSnapshot, broadcast::Receiver<BookUpdate>)>,
+}
+
+/// Handle to a running book feed
+#[derive(Clone)]
+pub struct BookFeedHandle {
+    requests: mpsc::Sender<SubscribeRequest>,
+}
+
+impl BookFeedHandle {
+    /// Snapshot of a book together with the updates that follow it
+    ///
+    /// The receiver carries updates for every book; updates for other
+    /// instruments, and ones at or below the snapshot's sequence, are for
+    /// the caller to skip (as [`LocalBook::apply`] does).
+    pub async fn subscribe(
+        &self,
+        instrument_id: &str,
+    ) -> Result<(BookFeedSnapshot, broadcast::Receiver<BookUpdate>), BookFeedError> {
+        let (reply, rx) = oneshot::channel();
+        self.requests
+            .send(SubscribeRequest {
+                instrument_id: instrument_id.to_string(),
+                reply,
+            })
+            .await
+            .map_err(|_| BookFeedError::Stopped)?;
+        rx.await.map_err(|_| BookFeedError::Stopped)
+    }
+}
+
+/// Run a book feed over a matching event subscription
+///
+/// The subscription should start at the beginning of the event log so the
+/// mirrored books are complete. If the feed itself lags it resubscribes
+/// through `resubscribe` from its resume point.
+pub fn spawn_book_feed<F, Fut>(mut events: EventSubscription, resubscribe: F) -> BookFeedHandle
+where
+    F: Fn(u64) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Option<EventSubscription>> + Send,
+{
+    let (requests_tx, mut requests) = mpsc::channel::<SubscribeRequest>(64);
+    let (updates, _) = broadcast::channel(DEFAULT_BOOK_FEED_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut feed = BookFeed::new();
+        loop {
+            tokio::select! {
+                request = requests.recv() => {
+                    let Some(request) = request else { break };
+                    let snapshot = feed.snapshot(&request.instrument_id);
+                    let _ = request.reply.send((snapshot, updates.subscribe()));
+                }
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        for update in feed.apply(&event) {
+                            let _ = updates.send(update);
+                        }
+                    }
+                    Err(SubscriptionError::Lagged { skipped, resume_from }) => {
+                        warn!(skipped, resume_from, "Book feed lagged, resubscribing");
+                        match resubscribe(resume_from).await {
+                            Some(resumed) => events = resumed,
+                            None => break,
+                        }
+                    }
+                    Err(SubscriptionError::Closed) => break,
+                },
+            }
+        }
+        info!("Book feed stopped");
+    });
+
+    BookFeedHandle { requests: requests_tx }
+}
+
+// ============================================================================
+// Client side
+// ============================================================================
+
+/// A client's copy of one book, kept from a snapshot and updates
+#[derive(Debug, Clone)]
+pub struct LocalBook {
+    instrument_id: String,
+    sequence: u64,
+    bids: BTreeMap<std::cmp::Reverse<Ticks>, Vec<SnapshotOrder>>,
+    asks: BTreeMap<Ticks, Vec<SnapshotOrder>>,
+}
+
+impl LocalBook {
+    /// Start from a snapshot, verifying its checksum
+    pub fn from_snapshot(snapshot: &BookFeedSnapshot) -> Result<Self, BookFeedError> {
+        let book = Self {
+            instrument_id: snapshot.instrument_id.clone(),
+            sequence: snapshot.sequence,
+            bids: snapshot
+                .bids
+                .iter()
+                .map(|level| (std::cmp::Reverse(level.price), level.orders.clone()))
+                .collect(),
+            asks: snapshot
+                .asks
+                .iter()
+                .map(|level| (level.price, level.orders.clone()))
+                .collect(),
+        };
+        book.verify(snapshot.checksum)?;
+        Ok(book)
+    }
+
+    /// Apply the next update
+    ///
+    /// Updates at or below the book's sequence (buffered before the
+    /// snapshot was taken) are skipped. On an error the book must be
+    /// rebuilt from a new snapshot.
+    pub fn apply(&mut self, update: &BookUpdate) -> Result<(), BookFeedError> {
+        if update.instrument_id != self.instrument_id {
+            return Err(BookFeedError::WrongInstrument {
+                expected: self.instrument_id.clone(),
+                received: update.instrument_id.clone(),
+            });
+        }
+        if update.sequence <= self.sequence {
+            return Ok(());
+        }
+        if update.sequence != self.sequence + 1 {
+            return Err(BookFeedError::Gap {
+                expected: self.sequence + 1,
+                received: update.sequence,
+            });
+        }
+
+        for delta in &update.orders {
+            match *delta {
+                OrderDelta::Add { order_id, side, price, quantity } => {
+                    self.level_mut(side, price).push(SnapshotOrder { order_id, quantity });
+                }
+                OrderDelta::Modify { order_id, side, price, quantity } => {
+                    if let Some(order) = self.level_mut(side, price).iter_mut().find(|o| o.order_id == order_id) {
+                        order.quantity = quantity;
+                    }
+                }
+                OrderDelta::Delete { order_id, side, price } => {
+                    self.level_mut(side, price).retain(|o| o.order_id != order_id);
+                }
+            }
+        }
+        self.bids.retain(|_, orders| !orders.is_empty());
+        self.asks.retain(|_, orders| !orders.is_empty());
+
+        self.sequence = update.sequence;
+        self.verify(update.checksum)
+    }
+
+    /// Book sequence of the last update applied
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Best bid and ask
+    pub fn best_bid(&self) -> Option<Ticks> {
+        self.bids.keys().next().map(|price| price.0)
+    }
+
+    pub fn best_ask(&self) -> Option<Ticks> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Aggregated `(price, visible quantity)` levels, best first
+    pub fn levels(&self, side: OrderSide) -> Vec<(Ticks, u32)> {
+        let total = |orders: &Vec<SnapshotOrder>| orders.iter().map(|o| o.quantity).sum();
+        match side {
+            OrderSide::Buy => self.bids.iter().map(|(price, orders)| (price.0, total(orders))).collect(),
+            OrderSide::Sell => self.asks.iter().map(|(price, orders)| (*price, total(orders))).collect(),
+        }
+    }
+
+    fn level_mut(&mut self, side: OrderSide, price: Ticks) -> &mut Vec<SnapshotOrder> {
+        match side {
+            OrderSide::Buy => self.bids.entry(std::cmp::Reverse(price)).or_default(),
+            OrderSide::Sell => self.asks.entry(price).or_default(),
+        }
+    }
+
+    fn verify(&self, checksum: u32) -> Result<(), BookFeedError> {
+        let mut bids = self.levels(OrderSide::Buy);
+        let mut asks = self.levels(OrderSide::Sell);
+        bids.truncate(CHECKSUM_DEPTH);
+        asks.truncate(CHECKSUM_DEPTH);
+        if book_checksum(&bids, &asks) != checksum {
+            return Err(BookFeedError::ChecksumMismatch { sequence: self.sequence });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TimeInForce;
+
+    const INSTRUMENT: &str = "BTC-50000-C";
+
+    fn order(side: OrderSide, price: Ticks, quantity: u32) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), side, price, quantity, 0, TimeInForce::Gtc)
+            .with_instrument_id(INSTRUMENT)
+    }
+
+    /// Run orders through a live engine and feed its events to a book feed
+    fn submit(engine: &mut MatchingEngine, feed: &mut BookFeed, order: BookOrder) -> Vec<BookUpdate> {
+        let result = engine.match_order(order.clone());
+        MatchingEvent::from_match(&order, &result)
+            .iter()
+            .flat_map(|event| feed.apply(event))
+            .collect()
+    }
+
+    #[test]
+    fn test_checksum_interleaves_levels() {
+        let expected = crc32fast::hash(b"99:5:101:2:102:7");
+        assert_eq!(book_checksum(&[(99, 5)], &[(101, 2), (102, 7)]), expected);
+    }
+
+    #[test]
+    fn test_updates_carry_l2_and_l3_deltas() {
+        let mut engine = MatchingEngine::new();
+        let mut feed = BookFeed::new();
+
+        let ask = order(OrderSide::Sell, 101, 10);
+        let ask_id = ask.order_id;
+        let updates = submit(&mut engine, &mut feed, ask);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].sequence, 1);
+        assert_eq!(
+            updates[0].levels,
+            vec![LevelDelta { side: OrderSide::Sell, price: 101, quantity: 10, order_count: 1 }]
+        );
+
+        // A partial fill modifies the maker in place
+        let updates = submit(&mut engine, &mut feed, order(OrderSide::Buy, 101, 4));
+        assert_eq!(updates[0].sequence, 2);
+        assert_eq!(
+            updates[0].orders,
+            vec![OrderDelta::Modify { order_id: ask_id, side: OrderSide::Sell, price: 101, quantity: 6 }]
+        );
+
+        // A trade that empties the level deletes the order and the level
+        let updates = submit(&mut engine, &mut feed, order(OrderSide::Buy, 101, 6));
+        assert_eq!(updates[0].levels[0].quantity, 0);
+        assert_eq!(
+            updates[0].orders,
+            vec![OrderDelta::Delete { order_id: ask_id, side: OrderSide::Sell, price: 101 }]
+        );
+        assert_eq!(updates[0].checksum, book_checksum(&[], &[]));
+    }
+
+    #[test]
+    fn test_local_book_recovers_from_snapshot_and_detects_gaps() {
+        let mut engine = MatchingEngine::new();
+        let mut feed = BookFeed::new();
+
+        // Updates buffered before the snapshot are skipped by the local book
+        let mut buffered = Vec::new();
+        for (side, price, quantity) in [(OrderSide::Buy, 99, 5), (OrderSide::Sell, 102, 3)] {
+            buffered.extend(submit(&mut engine, &mut feed, order(side, price, quantity)));
+        }
+        let snapshot = feed.snapshot(INSTRUMENT);
+        assert_eq!(snapshot.sequence, 2);
+        buffered.extend(submit(&mut engine, &mut feed, order(OrderSide::Buy, 100, 2)));
+
+        let mut book = LocalBook::from_snapshot(&snapshot).unwrap();
+        for update in &buffered {
+            book.apply(update).unwrap();
+        }
+        assert_eq!(book.sequence(), 3);
+        assert_eq!(book.best_bid(), Some(100));
+        assert_eq!(book.best_ask(), Some(102));
+
+        // A missed update is reported as a gap
+        let next = submit(&mut engine, &mut feed, order(OrderSide::Sell, 103, 1));
+        let after = submit(&mut engine, &mut feed, order(OrderSide::Sell, 104, 1));
+        assert!(matches!(
+            book.apply(&after[0]),
+            Err(BookFeedError::Gap { expected: 4, received: 5 })
+        ));
+        book.apply(&next[0]).unwrap();
+        book.apply(&after[0]).unwrap();
+        assert_eq!(book.levels(OrderSide::Sell), vec![(102, 3), (103, 1), (104, 1)]);
+
+        // A corrupted local copy fails the checksum
+        let mut corrupted = feed.snapshot(INSTRUMENT);
+        corrupted.bids[0].orders[0].quantity += 1;
+        assert!(matches!(
+            LocalBook::from_snapshot(&corrupted),
+            Err(BookFeedError::ChecksumMismatch { sequence: 5 })
+        ));
+    }
+}
@@ -3,11 +3,18 @@
 //! Circuit breakers halt trading when market conditions become unsafe:
 //! - Price Movement: Detect large price swings
 //! - Liquidity: Detect thin order books
+//! - Price Band: Reject orders priced through a band around the mark
+//!   price (limit-up/limit-down), before they can trade
+//!
+//! Operators can also halt an instrument, or every instrument of an
+//! underlying, by hand. Manual halts last until resumed.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+use crate::domain::{underlying_of, OrderSide};
+
 /// Circuit breaker state for a single instrument
 #[derive(Debug, Clone)]
 pub struct InstrumentCircuitBreaker {
@@ -15,6 +22,10 @@ pub struct InstrumentCircuitBreaker {
     pub price_movement_triggered: bool,
     pub liquidity_triggered: bool,
     pub halted_until: Option<Instant>,
+    /// Halted by an operator until resumed
+    pub manually_halted: bool,
+    /// Band around the last mark price, if price bands are enabled
+    pub price_band: Option<PriceBand>,
     pub last_trade_price: Option<f64>,
     pub price_history: Vec<(Instant, f64)>,
 }
@@ -26,12 +37,17 @@ impl InstrumentCircuitBreaker {
             price_movement_triggered: false,
             liquidity_triggered: false,
             halted_until: None,
+            manually_halted: false,
+            price_band: None,
             last_trade_price: None,
             price_history: Vec::new(),
         }
     }
 
     pub fn is_halted(&self) -> bool {
+        if self.manually_halted {
+            return true;
+        }
         if let Some(until) = self.halted_until {
             if Instant::now() < until {
                 return true;
@@ -42,6 +58,7 @@ impl InstrumentCircuitBreaker {
 
     pub fn clear_halt(&mut self) {
         self.halted_until = None;
+        self.manually_halted = false;
         self.price_movement_triggered = false;
         self.liquidity_triggered = false;
     }
@@ -61,6 +78,37 @@ impl InstrumentCircuitBreaker {
     }
 }
 
+/// Limit-up/limit-down band around a mark price, in decimal prices
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct PriceBand {
+    pub mark_price: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl PriceBand {
+    /// Band of `percent` either side of a mark price
+    pub fn around(mark_price: f64, percent: f64) -> Self {
+        let width = mark_price * percent / 100.0;
+        Self {
+            mark_price,
+            lower: (mark_price - width).max(0.0),
+            upper: mark_price + width,
+        }
+    }
+
+    /// Whether an order may be priced at `price`
+    ///
+    /// Buys may not be priced above the band and sells not below it;
+    /// passive prices on the far side are always allowed.
+    pub fn allows(&self, side: OrderSide, price: f64) -> bool {
+        match side {
+            OrderSide::Buy => price <= self.upper,
+            OrderSide::Sell => price >= self.lower,
+        }
+    }
+}
+
 /// Circuit breaker configuration
 #[derive(Debug, Clone)]
 pub struct CircuitBreakerConfig {
@@ -73,6 +121,8 @@ pub struct CircuitBreakerConfig {
     pub min_bid_ask_orders: u64,
     pub max_spread_percent: f64,
     pub liquidity_halt_duration_seconds: u64,
+    pub price_band_enabled: bool,
+    pub price_band_percent: f64,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -87,6 +137,8 @@ impl Default for CircuitBreakerConfig {
             min_bid_ask_orders: 5,
             max_spread_percent: 5.0,
             liquidity_halt_duration_seconds: 60,
+            price_band_enabled: false,
+            price_band_percent: 20.0,
         }
     }
 }
@@ -103,6 +155,11 @@ impl From<&config::CircuitBreakersConfig> for CircuitBreakerConfig {
             min_bid_ask_orders: config.liquidity.min_bid_ask_orders as u64,
             max_spread_percent: config.liquidity.max_spread_percent,
             liquidity_halt_duration_seconds: config.liquidity.halt_duration_seconds,
+            price_band_enabled: config.price_band.as_ref().is_some_and(|b| b.enabled),
+            price_band_percent: config
+                .price_band
+                .as_ref()
+                .map_or(CircuitBreakerConfig::default().price_band_percent, |b| b.percent_threshold),
         }
     }
 }
@@ -112,6 +169,8 @@ impl From<&config::CircuitBreakersConfig> for CircuitBreakerConfig {
 pub struct CircuitBreakerManager {
     config: CircuitBreakerConfig,
     breakers: HashMap<String, InstrumentCircuitBreaker>,
+    /// Underlyings halted by an operator
+    halted_underlyings: HashSet<String>,
 }
 
 impl CircuitBreakerManager {
@@ -119,6 +178,7 @@ impl CircuitBreakerManager {
         Self {
             config,
             breakers: HashMap::new(),
+            halted_underlyings: HashSet::new(),
         }
     }
 
@@ -129,11 +189,65 @@ impl CircuitBreakerManager {
     }
 
     /// Check if trading is halted for an instrument
+    ///
+    /// Includes manual halts of the instrument's underlying (the part of
+    /// the ID before the first `-`).
     pub fn is_halted(&self, instrument_id: &str) -> bool {
         self.breakers
             .get(instrument_id)
             .map(|b| b.is_halted())
             .unwrap_or(false)
+            || self.halted_underlyings.contains(underlying_of(instrument_id))
+    }
+
+    /// Halt an instrument until resumed (admin action)
+    pub fn halt(&mut self, instrument_id: &str) {
+        self.get_or_create(instrument_id).manually_halted = true;
+        warn!(instrument = instrument_id, "Instrument halted by operator");
+    }
+
+    /// Halt every instrument of an underlying until resumed (admin action)
+    pub fn halt_underlying(&mut self, underlying: &str) {
+        self.halted_underlyings.insert(underlying.to_string());
+        warn!(underlying, "Underlying halted by operator");
+    }
+
+    /// Resume an underlying halted with [`Self::halt_underlying`] (admin action)
+    ///
+    /// Instruments halted on their own stay halted.
+    pub fn resume_underlying(&mut self, underlying: &str) {
+        if self.halted_underlyings.remove(underlying) {
+            info!(underlying, "Underlying resumed by operator");
+        }
+    }
+
+    /// Underlyings halted by an operator
+    pub fn halted_underlyings(&self) -> Vec<String> {
+        let mut underlyings: Vec<String> = self.halted_underlyings.iter().cloned().collect();
+        underlyings.sort();
+        underlyings
+    }
+
+    /// Recompute an instrument's price band around a new mark price
+    ///
+    /// Returns the new band, or None if price bands are disabled.
+    pub fn update_price_band(&mut self, instrument_id: &str, mark_price: f64) -> Option<PriceBand> {
+        if !self.config.enabled || !self.config.price_band_enabled {
+            return None;
+        }
+        let band = PriceBand::around(mark_price, self.config.price_band_percent);
+        self.get_or_create(instrument_id).price_band = Some(band);
+        Some(band)
+    }
+
+    /// Current price band of an instrument
+    ///
+    /// None if price bands are disabled or no mark price is known yet.
+    pub fn price_band(&self, instrument_id: &str) -> Option<PriceBand> {
+        if !self.config.enabled || !self.config.price_band_enabled {
+            return None;
+        }
+        self.breakers.get(instrument_id)?.price_band
     }
 
     /// Check price movement circuit breaker
@@ -254,7 +368,7 @@ impl CircuitBreakerManager {
         false
     }
 
-    /// Clear circuit breakers for an instrument (admin action)
+    /// Clear circuit breakers for an instrument, including a manual halt (admin action)
     pub fn clear(&mut self, instrument_id: &str) {
         if let Some(breaker) = self.breakers.get_mut(instrument_id) {
             breaker.clear_halt();
@@ -268,9 +382,12 @@ impl CircuitBreakerManager {
             .values()
             .map(|b| CircuitBreakerStatus {
                 instrument_id: b.instrument_id.clone(),
-                halted: b.is_halted(),
+                halted: self.is_halted(&b.instrument_id),
+                manually_halted: b.manually_halted,
+                underlying_halted: self.halted_underlyings.contains(underlying_of(&b.instrument_id)),
                 price_movement_triggered: b.price_movement_triggered,
                 liquidity_triggered: b.liquidity_triggered,
+                price_band: self.price_band(&b.instrument_id),
             })
             .collect()
     }
@@ -281,6 +398,13 @@ impl CircuitBreakerManager {
 pub struct CircuitBreakerStatus {
     pub instrument_id: String,
     pub halted: bool,
+    /// Halted by an operator
+    pub manually_halted: bool,
+    /// The instrument's underlying is halted by an operator
+    pub underlying_halted: bool,
     pub price_movement_triggered: bool,
     pub liquidity_triggered: bool,
+    /// Orders priced outside this band are rejected
+    pub price_band: Option<PriceBand>,
 }
+
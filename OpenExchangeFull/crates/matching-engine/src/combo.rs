@@ -0,0 +1,279 @@
+//! Combo (multi-leg) instruments
+//!
+//! A combo is an options strategy such as a spread, straddle or strangle
+//! traded as one instrument: buying one unit of the combo buys or sells
+//! `ratio` contracts of each leg, selling one does the opposite. Combo
+//! orders rest in a book of their own, keyed by the combo ID, and are
+//! priced at the net of their legs in ticks: legs the combo buys count
+//! positive, legs it sells negative, so a net-credit combo has a negative
+//! price. Every leg is on the combo's own underlying.
+//!
+//! Combo orders trade against opposite combo orders in the combo book,
+//! against the best prices of the leg books (implied-in), and, while
+//! resting, against outright orders on one leg together with the best
+//! prices of the other legs (implied-out). See
+//! [`MatchingEngine::define_combo`](crate::engine::MatchingEngine::define_combo).
+//!
+//! Every combo fill is recorded as one trade per leg on the leg
+//! instruments, tagged with the combo ID. The legs of a fill execute
+//! atomically: if any leg cannot fill in full, none of them does.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{underlying_of, OrderSide};
+use crate::error::MatchingError;
+use crate::price::Ticks;
+
+/// One leg of a combo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboLeg {
+    /// Outright instrument traded by the leg
+    pub instrument_id: String,
+    /// Side of the leg when the combo is bought
+    pub side: OrderSide,
+    /// Contracts of the leg per unit of combo
+    pub ratio: u32,
+}
+
+impl ComboLeg {
+    /// Create a leg
+    pub fn new(instrument_id: impl Into<String>, side: OrderSide, ratio: u32) -> Self {
+        Self {
+            instrument_id: instrument_id.into(),
+            side,
+            ratio,
+        }
+    }
+
+    /// Side the leg trades on for a combo order on `combo_side`
+    pub fn side_for(&self, combo_side: OrderSide) -> OrderSide {
+        match combo_side {
+            OrderSide::Buy => self.side,
+            OrderSide::Sell => self.side.opposite(),
+        }
+    }
+
+    /// Signed contribution of one leg price to the combo price
+    fn weight(&self) -> i64 {
+        let ratio = i64::from(self.ratio);
+        match self.side {
+            OrderSide::Buy => ratio,
+            OrderSide::Sell => -ratio,
+        }
+    }
+}
+
+/// A combo instrument: its ID and legs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboDefinition {
+    /// Combo instrument ID (its book is keyed by it)
+    pub combo_id: String,
+    /// Legs, in the order their trades are recorded
+    pub legs: Vec<ComboLeg>,
+    /// Sequence at which the engine accepted the definition
+    #[serde(default)]
+    pub sequence: u64,
+}
+
+impl ComboDefinition {
+    /// Create a combo from its legs
+    pub fn new(combo_id: impl Into<String>, legs: Vec<ComboLeg>) -> Self {
+        Self {
+            combo_id: combo_id.into(),
+            legs,
+            sequence: 0,
+        }
+    }
+
+    /// Long straddle: buy a call and a put of the same strike
+    pub fn straddle(combo_id: impl Into<String>, call: &str, put: &str) -> Self {
+        Self::new(
+            combo_id,
+            vec![
+                ComboLeg::new(call, OrderSide::Buy, 1),
+                ComboLeg::new(put, OrderSide::Buy, 1),
+            ],
+        )
+    }
+
+    /// Long strangle: buy an out-of-the-money call and put
+    pub fn strangle(combo_id: impl Into<String>, call: &str, put: &str) -> Self {
+        Self::straddle(combo_id, call, put)
+    }
+
+    /// Vertical spread: buy one option and sell another of the same expiry
+    pub fn vertical_spread(combo_id: impl Into<String>, long: &str, short: &str) -> Self {
+        Self::new(
+            combo_id,
+            vec![
+                ComboLeg::new(long, OrderSide::Buy, 1),
+                ComboLeg::new(short, OrderSide::Sell, 1),
+            ],
+        )
+    }
+
+    /// Check the combo can be traded
+    ///
+    /// A combo needs at least two legs on distinct instruments other than
+    /// itself, all of the combo's own underlying (so the sharded runtime
+    /// keeps them together), every ratio must be positive, and at least one
+    /// leg must have ratio 1 (it absorbs rounding when leg prices are
+    /// assigned).
+    pub fn validate(&self) -> Result<(), MatchingError> {
+        let invalid = |reason: &str| Err(MatchingError::InvalidCombo(format!("{}: {reason}", self.combo_id)));
+
+        if self.combo_id.is_empty() {
+            return Err(MatchingError::InvalidCombo("combo ID is empty".to_string()));
+        }
+        if self.legs.len() < 2 {
+            return invalid("a combo needs at least two legs");
+        }
+        if self.legs.iter().any(|leg| leg.ratio == 0) {
+            return invalid("leg ratios must be positive");
+        }
+        if !self.legs.iter().any(|leg| leg.ratio == 1) {
+            return invalid("at least one leg must have ratio 1");
+        }
+        for (i, leg) in self.legs.iter().enumerate() {
+            if leg.instrument_id.is_empty() || leg.instrument_id == self.combo_id {
+                return invalid("legs must be outright instruments");
+            }
+            if self.legs[..i].iter().any(|other| other.instrument_id == leg.instrument_id) {
+                return invalid("legs must be on distinct instruments");
+            }
+            if underlying_of(&leg.instrument_id) != underlying_of(&self.combo_id) {
+                return invalid("legs must be on the combo's underlying");
+            }
+        }
+        Ok(())
+    }
+
+    /// Index of the leg trading an instrument
+    pub fn leg_index(&self, instrument_id: &str) -> Option<usize> {
+        self.legs.iter().position(|leg| leg.instrument_id == instrument_id)
+    }
+
+    /// Combo price of one unit at the given leg prices (in leg order)
+    pub fn net_price(&self, leg_prices: &[Ticks]) -> Ticks {
+        self.legs
+            .iter()
+            .zip(leg_prices)
+            .map(|(leg, price)| leg.weight() * price)
+            .sum()
+    }
+
+    /// Price of leg `index` that makes the legs net to `combo_price`
+    ///
+    /// The other legs trade at `leg_prices` (the entry for `index` is
+    /// ignored). Rounded in favour of the combo order: down when the
+    /// result is a bid on the leg, up when it is an ask.
+    pub fn implied_leg_price(
+        &self,
+        index: usize,
+        combo_price: Ticks,
+        leg_prices: &[Ticks],
+        leg_side: OrderSide,
+    ) -> Ticks {
+        let others: Ticks = self
+            .legs
+            .iter()
+            .zip(leg_prices)
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, (leg, price))| leg.weight() * price)
+            .sum();
+
+        let (mut numerator, mut denominator) = (combo_price - others, self.legs[index].weight());
+        if denominator < 0 {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+        match leg_side {
+            OrderSide::Buy => numerator.div_euclid(denominator),
+            OrderSide::Sell => -(-numerator).div_euclid(denominator),
+        }
+    }
+
+    /// Leg prices for a fill at `combo_price` between two combo orders
+    ///
+    /// Every leg trades at its reference price except one leg with ratio
+    /// 1, which is moved so that the legs net to the combo price: the first
+    /// such leg that stays non-negative, else the first one.
+    pub fn leg_prices(&self, combo_price: Ticks, references: &[Ticks]) -> Vec<Ticks> {
+        let adjusted = |index: usize| {
+            let others: Ticks = self
+                .legs
+                .iter()
+                .zip(references)
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, (leg, price))| leg.weight() * price)
+                .sum();
+            (combo_price - others) * self.legs[index].weight()
+        };
+
+        let mut prices = references.to_vec();
+        let unit_legs: Vec<usize> = (0..self.legs.len()).filter(|&i| self.legs[i].ratio == 1).collect();
+        if let Some(&first) = unit_legs.first() {
+            let index = unit_legs.iter().copied().find(|&i| adjusted(i) >= 0).unwrap_or(first);
+            prices[index] = adjusted(index);
+        }
+        prices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_malformed_combos() {
+        assert!(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P").validate().is_ok());
+
+        let one_leg = ComboDefinition::new("X", vec![ComboLeg::new("BTC-C", OrderSide::Buy, 1)]);
+        assert!(one_leg.validate().is_err());
+
+        let duplicate = ComboDefinition::straddle("X", "BTC-C", "BTC-C");
+        assert!(duplicate.validate().is_err());
+
+        let no_unit_leg = ComboDefinition::new(
+            "X",
+            vec![
+                ComboLeg::new("BTC-C", OrderSide::Buy, 2),
+                ComboLeg::new("BTC-P", OrderSide::Sell, 3),
+            ],
+        );
+        assert!(no_unit_leg.validate().is_err());
+
+        let cross_underlying = ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "ETH-P");
+        assert!(cross_underlying.validate().is_err());
+    }
+
+    #[test]
+    fn test_net_implied_and_assigned_leg_prices() {
+        // 1x2 call spread: buy 1 low strike, sell 2 high strike
+        let combo = ComboDefinition::new(
+            "BTC-1X2",
+            vec![
+                ComboLeg::new("BTC-C1", OrderSide::Buy, 1),
+                ComboLeg::new("BTC-C2", OrderSide::Sell, 2),
+            ],
+        );
+        assert_eq!(combo.net_price(&[500, 200]), 100);
+
+        // A combo bid at 100 with C1 offered at 501 implies a C2 ask:
+        // 501 - 2 * p <= 100  =>  p >= 200.5, rounded up to 201
+        assert_eq!(combo.implied_leg_price(1, 100, &[501, 0], OrderSide::Sell), 201);
+        // ...and with C2 bid at 200 a C1 bid of 500
+        assert_eq!(combo.implied_leg_price(0, 100, &[0, 200], OrderSide::Buy), 500);
+
+        // Combo-vs-combo fill at 90 moves the ratio-1 leg off its reference
+        let prices = combo.leg_prices(90, &[500, 200]);
+        assert_eq!(prices, vec![490, 200]);
+        assert_eq!(combo.net_price(&prices), 90);
+
+        // A straddle moves the second leg when the first would go negative
+        let straddle = ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P");
+        assert_eq!(straddle.leg_prices(100, &[50, 200]), vec![50, 50]);
+    }
+}
@@ -4,11 +4,12 @@
 //! These types are shared across all implementations (in-memory, Redis).
 
 use chrono::{DateTime, Utc};
-use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, VecDeque};
 use uuid::Uuid;
 
+use crate::price::Ticks;
+
 // ============================================================================
 // Order Side
 // ============================================================================
@@ -61,6 +62,10 @@ pub enum TimeInForce {
     Ioc,
     /// Fill or Kill - entire order must fill or cancel completely
     Fok,
+    /// Good Till Date - rests like GTC until the order's `expires_at`
+    Gtd,
+    /// Day - rests like GTC until the session end carried in `expires_at`
+    Day,
 }
 
 impl Default for TimeInForce {
@@ -69,6 +74,116 @@ impl Default for TimeInForce {
     }
 }
 
+impl TimeInForce {
+    /// Whether an unfilled remainder rests in the book (GTC, GTD, DAY)
+    pub fn rests(&self) -> bool {
+        matches!(self, TimeInForce::Gtc | TimeInForce::Gtd | TimeInForce::Day)
+    }
+}
+
+// ============================================================================
+// Order Type
+// ============================================================================
+
+/// How the order's price is determined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderType {
+    /// Trades at the order's limit price or better
+    #[default]
+    Limit,
+    /// Sweeps the opposite side up to the protection band; never rests
+    Market,
+}
+
+// ============================================================================
+// Self-Trade Prevention
+// ============================================================================
+
+/// What happens when an order would trade against the same user's resting order
+///
+/// The incoming (taker) order's mode decides.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTradePrevention {
+    /// Self-trades are allowed
+    #[default]
+    None,
+    /// Cancel the incoming order's remainder
+    CancelTaker,
+    /// Cancel the resting order and keep matching
+    CancelMaker,
+    /// Cancel both orders
+    CancelBoth,
+    /// Reduce both by the smaller quantity without trading; cancel whichever reaches zero
+    DecrementAndCancel,
+}
+
+impl SelfTradePrevention {
+    /// Apply the mode to a taker meeting its own resting maker
+    ///
+    /// Returns `(maker_cancelled, taker_cancelled)`. Quantities are only
+    /// changed by `DecrementAndCancel`.
+    pub fn apply(self, taker: &mut BookOrder, maker: &mut BookOrder) -> (bool, bool) {
+        match self {
+            SelfTradePrevention::None => (false, false),
+            SelfTradePrevention::CancelTaker => (false, true),
+            SelfTradePrevention::CancelMaker => (true, false),
+            SelfTradePrevention::CancelBoth => (true, true),
+            SelfTradePrevention::DecrementAndCancel => {
+                let qty = taker.quantity.min(maker.quantity);
+                taker.fill(qty);
+                maker.fill(qty);
+                (maker.is_filled(), taker.is_filled())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Post-Only
+// ============================================================================
+
+/// What happens to a post-only order that would take liquidity on arrival
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostOnly {
+    /// Not post-only; the order may take liquidity
+    #[default]
+    None,
+    /// Reject the order
+    Reject,
+    /// Move the price one tick behind the opposite best price
+    Reprice,
+}
+
+// ============================================================================
+// Stop Triggers
+// ============================================================================
+
+/// Reference price a stop order watches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerSource {
+    /// Last trade price on the instrument
+    #[default]
+    LastTrade,
+    /// Mark price
+    MarkPrice,
+    /// Index (underlying) price
+    IndexPrice,
+}
+
+/// Stop condition attached to a stop-market or stop-limit order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StopTrigger {
+    /// Price (in ticks) at which the order is released
+    pub stop_price: Ticks,
+    /// Which reference price is compared against the stop price
+    #[serde(default)]
+    pub source: TriggerSource,
+}
+
 // ============================================================================
 // Book Order
 // ============================================================================
@@ -87,14 +202,48 @@ pub struct BookOrder {
     pub instrument_id: String,
     /// Buy or Sell
     pub side: OrderSide,
-    /// Price (for limit orders)
-    pub price: f64,
+    /// Price in ticks (limit price, or protection limit once a market order is accepted)
+    pub price: Ticks,
     /// Remaining quantity to fill
     pub quantity: u32,
     /// Sequence number (determines time priority)
     pub sequence: u64,
     /// Time-in-force
     pub time_in_force: TimeInForce,
+    /// Limit or market
+    #[serde(default)]
+    pub order_type: OrderType,
+    /// Stop condition (None for orders that go straight to the book)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopTrigger>,
+    /// Self-trade prevention mode applied when this order is the taker
+    #[serde(default)]
+    pub stp: SelfTradePrevention,
+    /// Post-only handling (the order must add liquidity)
+    #[serde(default)]
+    pub post_only: PostOnly,
+    /// Only ever fill the whole remaining quantity at once, as taker or maker
+    #[serde(default)]
+    pub all_or_none: bool,
+    /// Minimum quantity that must fill on arrival, else the order is rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_quantity: Option<u32>,
+    /// Iceberg slice size shown at a time (None = whole quantity visible)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_quantity: Option<u32>,
+    /// Iceberg quantity currently shown; the rest of `quantity` is hidden
+    #[serde(default)]
+    pub shown_quantity: u32,
+    /// When a GTD or DAY order leaves the book (None = never expires)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the order reached the engine, stamped on acceptance and used as
+    /// the timestamp of its trades
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<DateTime<Utc>>,
+    /// Market maker quote placed by a mass quote (see [`crate::mmp`])
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quote: bool,
 }
 
 impl BookOrder {
@@ -103,7 +252,7 @@ impl BookOrder {
         order_id: Uuid,
         user_id: Uuid,
         side: OrderSide,
-        price: f64,
+        price: Ticks,
         quantity: u32,
         sequence: u64,
         time_in_force: TimeInForce,
@@ -117,6 +266,17 @@ impl BookOrder {
             quantity,
             sequence,
             time_in_force,
+            order_type: OrderType::Limit,
+            stop: None,
+            stp: SelfTradePrevention::None,
+            post_only: PostOnly::None,
+            all_or_none: false,
+            min_quantity: None,
+            display_quantity: None,
+            shown_quantity: 0,
+            expires_at: None,
+            received_at: None,
+            quote: false,
         }
     }
 
@@ -126,9 +286,125 @@ impl BookOrder {
         self
     }
 
+    /// Set the order type
+    pub fn with_order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    /// Check if this is a market order
+    pub fn is_market(&self) -> bool {
+        self.order_type == OrderType::Market
+    }
+
+    /// Attach a stop condition, making this a stop-market or stop-limit order
+    pub fn with_stop(mut self, stop_price: Ticks, source: TriggerSource) -> Self {
+        self.stop = Some(StopTrigger { stop_price, source });
+        self
+    }
+
+    /// Set the self-trade prevention mode
+    pub fn with_stp(mut self, stp: SelfTradePrevention) -> Self {
+        self.stp = stp;
+        self
+    }
+
+    /// Set the post-only handling
+    pub fn with_post_only(mut self, post_only: PostOnly) -> Self {
+        self.post_only = post_only;
+        self
+    }
+
+    /// Mark the order all-or-none
+    pub fn with_all_or_none(mut self, all_or_none: bool) -> Self {
+        self.all_or_none = all_or_none;
+        self
+    }
+
+    /// Set the minimum quantity that must fill on arrival
+    pub fn with_min_quantity(mut self, min_quantity: Option<u32>) -> Self {
+        self.min_quantity = min_quantity;
+        self
+    }
+
+    /// Make this an iceberg order showing `display_quantity` at a time
+    pub fn with_display_quantity(mut self, display_quantity: Option<u32>) -> Self {
+        self.display_quantity = display_quantity;
+        self
+    }
+
+    /// Set the expiry of a GTD or DAY order
+    pub fn with_expires_at(mut self, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// Set the time the order reached the engine (stamped by the engine's clock if unset)
+    pub fn with_received_at(mut self, received_at: DateTime<Utc>) -> Self {
+        self.received_at = Some(received_at);
+        self
+    }
+
+    /// Mark the order as a market maker quote
+    pub fn with_quote(mut self, quote: bool) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Check if the order's expiry has passed at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Check if this is an iceberg (reserve) order
+    pub fn is_iceberg(&self) -> bool {
+        self.display_quantity.is_some()
+    }
+
+    /// Quantity other participants can see (and trade in one go)
+    pub fn visible_quantity(&self) -> u32 {
+        if self.is_iceberg() {
+            self.shown_quantity
+        } else {
+            self.quantity
+        }
+    }
+
+    /// Check if an iceberg has used up its shown slice but has quantity left
+    pub fn needs_refresh(&self) -> bool {
+        self.is_iceberg() && self.shown_quantity == 0 && self.quantity > 0
+    }
+
+    /// Show the next iceberg slice from the hidden quantity
+    pub fn show_next_slice(&mut self) {
+        if let Some(display) = self.display_quantity {
+            self.shown_quantity = display.min(self.quantity);
+        }
+    }
+
+    /// Check if this order, as a resting maker, can trade against `quantity`
+    ///
+    /// All-or-none makers only trade when the whole remainder fills.
+    pub fn can_fill_against(&self, quantity: u32) -> bool {
+        !self.all_or_none || self.quantity <= quantity
+    }
+
+    /// Check if matching `maker` would be a self-trade this order must prevent
+    pub fn prevents_self_trade_with(&self, maker: &BookOrder) -> bool {
+        self.stp != SelfTradePrevention::None && self.user_id == maker.user_id
+    }
+
+    /// Check if this order waits for a stop trigger
+    pub fn is_stop(&self) -> bool {
+        self.stop.is_some()
+    }
+
     /// Reduce quantity after partial fill
+    ///
+    /// Fills of an iceberg come out of its shown slice first.
     pub fn fill(&mut self, qty: u32) {
         self.quantity = self.quantity.saturating_sub(qty);
+        self.shown_quantity = self.shown_quantity.saturating_sub(qty);
     }
 
     /// Check if order is completely filled
@@ -142,6 +418,98 @@ impl BookOrder {
     }
 }
 
+/// Location of a resting order, as kept in the engine's order-id index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderLocation {
+    /// Instrument whose book holds the order
+    pub instrument_id: String,
+    /// Side of the book
+    pub side: OrderSide,
+    /// Price level (ticks)
+    pub price: Ticks,
+}
+
+impl OrderLocation {
+    /// Location of a book order
+    pub fn of(order: &BookOrder) -> Self {
+        Self {
+            instrument_id: order.instrument_id.clone(),
+            side: order.side,
+            price: order.price,
+        }
+    }
+}
+
+// ============================================================================
+// Mass Cancel Filter
+// ============================================================================
+
+/// Selects the orders removed by a mass cancel
+///
+/// Every criterion that is set must match; an empty filter matches every
+/// order. The underlying is the part of the instrument ID before the first
+/// `-` (e.g. `BTC` for `BTC-50000-C`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MassCancelFilter {
+    /// Only orders of this user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    /// Only orders on this instrument
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instrument_id: Option<String>,
+    /// Only orders on instruments of this underlying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+    /// Only orders on this side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<OrderSide>,
+}
+
+impl MassCancelFilter {
+    /// Filter matching every order
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to one user's orders
+    pub fn with_user_id(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Restrict to one instrument
+    pub fn with_instrument_id(mut self, instrument_id: impl Into<String>) -> Self {
+        self.instrument_id = Some(instrument_id.into());
+        self
+    }
+
+    /// Restrict to instruments of one underlying
+    pub fn with_underlying(mut self, underlying: impl Into<String>) -> Self {
+        self.underlying = Some(underlying.into());
+        self
+    }
+
+    /// Restrict to one side
+    pub fn with_side(mut self, side: OrderSide) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Whether an order is selected by the filter
+    pub fn matches(&self, order: &BookOrder) -> bool {
+        let underlying = underlying_of(&order.instrument_id);
+        (self.user_id.is_none() || self.user_id == Some(order.user_id))
+            && (self.instrument_id.is_none() || self.instrument_id.as_deref() == Some(order.instrument_id.as_str()))
+            && (self.underlying.is_none() || self.underlying.as_deref() == Some(underlying))
+            && (self.side.is_none() || self.side == Some(order.side))
+    }
+}
+
+/// Underlying of an instrument: the part of its ID before the first `-`
+pub fn underlying_of(instrument_id: &str) -> &str {
+    instrument_id.split('-').next().unwrap_or(instrument_id)
+}
+
 // ============================================================================
 // Order Book
 // ============================================================================
@@ -153,18 +521,22 @@ impl BookOrder {
 /// 2. Asks sorted ascending (lowest price first)
 /// 3. Each price level is FIFO queue
 /// 4. Deterministic iteration order
+///
+/// Serialized with each side as a flat list of resting orders in priority
+/// order (best price first, FIFO within a level), so a deserialized book
+/// has exactly the same queues as the original.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
     /// Instrument this book is for
     pub instrument_id: String,
     /// Buy orders (price → FIFO queue)
     /// BTreeMap ensures deterministic iteration (descending)
-    #[serde(skip)]
-    pub bids: BTreeMap<std::cmp::Reverse<OrderedFloat<f64>>, VecDeque<BookOrder>>,
+    #[serde(with = "book_side_serde", default)]
+    pub bids: BTreeMap<std::cmp::Reverse<Ticks>, VecDeque<BookOrder>>,
     /// Sell orders (price → FIFO queue)
     /// BTreeMap ensures deterministic iteration (ascending)
-    #[serde(skip)]
-    pub asks: BTreeMap<OrderedFloat<f64>, VecDeque<BookOrder>>,
+    #[serde(with = "book_side_serde", default)]
+    pub asks: BTreeMap<Ticks, VecDeque<BookOrder>>,
     /// Sequence counter for this book
     pub sequence: u64,
 }
@@ -181,17 +553,17 @@ impl OrderBook {
     }
 
     /// Get best bid price (highest buy)
-    pub fn best_bid(&self) -> Option<f64> {
-        self.bids.keys().next().map(|k| k.0 .0)
+    pub fn best_bid(&self) -> Option<Ticks> {
+        self.bids.keys().next().map(|k| k.0)
     }
 
     /// Get best ask price (lowest sell)
-    pub fn best_ask(&self) -> Option<f64> {
-        self.asks.keys().next().map(|k| k.0)
+    pub fn best_ask(&self) -> Option<Ticks> {
+        self.asks.keys().next().copied()
     }
 
     /// Get spread
-    pub fn spread(&self) -> Option<f64> {
+    pub fn spread(&self) -> Option<Ticks> {
         match (self.best_bid(), self.best_ask()) {
             (Some(bid), Some(ask)) => Some(ask - bid),
             _ => None,
@@ -199,17 +571,17 @@ impl OrderBook {
     }
 
     /// Get total quantity at bid price level
-    pub fn bid_quantity_at(&self, price: f64) -> u32 {
+    pub fn bid_quantity_at(&self, price: Ticks) -> u32 {
         self.bids
-            .get(&std::cmp::Reverse(OrderedFloat(price)))
+            .get(&std::cmp::Reverse(price))
             .map(|orders| orders.iter().map(|o| o.quantity).sum())
             .unwrap_or(0)
     }
 
     /// Get total quantity at ask price level
-    pub fn ask_quantity_at(&self, price: f64) -> u32 {
+    pub fn ask_quantity_at(&self, price: Ticks) -> u32 {
         self.asks
-            .get(&OrderedFloat(price))
+            .get(&price)
             .map(|orders| orders.iter().map(|o| o.quantity).sum())
             .unwrap_or(0)
     }
@@ -219,13 +591,13 @@ impl OrderBook {
         match order.side {
             OrderSide::Buy => {
                 self.bids
-                    .entry(std::cmp::Reverse(OrderedFloat(order.price)))
+                    .entry(std::cmp::Reverse(order.price))
                     .or_insert_with(VecDeque::new)
                     .push_back(order);
             }
             OrderSide::Sell => {
                 self.asks
-                    .entry(OrderedFloat(order.price))
+                    .entry(order.price)
                     .or_insert_with(VecDeque::new)
                     .push_back(order);
             }
@@ -233,24 +605,95 @@ impl OrderBook {
     }
 
     /// Remove order by ID
+    ///
+    /// Drops the price level if it becomes empty.
     pub fn remove_order(&mut self, order_id: Uuid) -> Option<BookOrder> {
         // Search bids
-        for (_, queue) in self.bids.iter_mut() {
-            if let Some(pos) = queue.iter().position(|o| o.order_id == order_id) {
-                return queue.remove(pos);
+        let bid_level = self
+            .bids
+            .iter()
+            .find(|(_, queue)| queue.iter().any(|o| o.order_id == order_id))
+            .map(|(price, _)| *price);
+        if let Some(price) = bid_level {
+            let queue = self.bids.get_mut(&price)?;
+            let pos = queue.iter().position(|o| o.order_id == order_id)?;
+            let removed = queue.remove(pos);
+            if queue.is_empty() {
+                self.bids.remove(&price);
             }
+            return removed;
         }
 
         // Search asks
-        for (_, queue) in self.asks.iter_mut() {
-            if let Some(pos) = queue.iter().position(|o| o.order_id == order_id) {
-                return queue.remove(pos);
+        let ask_level = self
+            .asks
+            .iter()
+            .find(|(_, queue)| queue.iter().any(|o| o.order_id == order_id))
+            .map(|(price, _)| *price);
+        if let Some(price) = ask_level {
+            let queue = self.asks.get_mut(&price)?;
+            let pos = queue.iter().position(|o| o.order_id == order_id)?;
+            let removed = queue.remove(pos);
+            if queue.is_empty() {
+                self.asks.remove(&price);
             }
+            return removed;
         }
 
         None
     }
 
+    /// Remove order by ID from a known price level
+    ///
+    /// Only the queue at `price` is searched. Drops the level if it becomes empty.
+    pub fn remove_order_at(&mut self, side: OrderSide, price: Ticks, order_id: Uuid) -> Option<BookOrder> {
+        match side {
+            OrderSide::Buy => {
+                let key = std::cmp::Reverse(price);
+                let queue = self.bids.get_mut(&key)?;
+                let pos = queue.iter().position(|o| o.order_id == order_id)?;
+                let removed = queue.remove(pos);
+                if queue.is_empty() {
+                    self.bids.remove(&key);
+                }
+                removed
+            }
+            OrderSide::Sell => {
+                let key = price;
+                let queue = self.asks.get_mut(&key)?;
+                let pos = queue.iter().position(|o| o.order_id == order_id)?;
+                let removed = queue.remove(pos);
+                if queue.is_empty() {
+                    self.asks.remove(&key);
+                }
+                removed
+            }
+        }
+    }
+
+    /// Look up an order by ID at a known price level
+    pub fn get_order_at(&self, side: OrderSide, price: Ticks, order_id: Uuid) -> Option<&BookOrder> {
+        let queue = match side {
+            OrderSide::Buy => self.bids.get(&std::cmp::Reverse(price))?,
+            OrderSide::Sell => self.asks.get(&price)?,
+        };
+        queue.iter().find(|o| o.order_id == order_id)
+    }
+
+    /// Mutable lookup of an order by ID at a known price level
+    pub fn get_order_at_mut(&mut self, side: OrderSide, price: Ticks, order_id: Uuid) -> Option<&mut BookOrder> {
+        let queue = match side {
+            OrderSide::Buy => self.bids.get_mut(&std::cmp::Reverse(price))?,
+            OrderSide::Sell => self.asks.get_mut(&price)?,
+        };
+        queue.iter_mut().find(|o| o.order_id == order_id)
+    }
+
+    /// Iterate over all resting orders (bids then asks, priority order)
+    pub fn orders(&self) -> impl Iterator<Item = &BookOrder> {
+        self.bids.values().flatten().chain(self.asks.values().flatten())
+    }
+
     /// Clean up empty price levels
     pub fn cleanup_empty_levels(&mut self) {
         self.bids.retain(|_, queue| !queue.is_empty());
@@ -270,10 +713,10 @@ impl OrderBook {
 
     /// Calculate total available ask quantity at or below a given price
     /// Used for FOK pre-check on buy orders
-    pub fn available_ask_quantity_at_or_below(&self, max_price: f64) -> u32 {
+    pub fn available_ask_quantity_at_or_below(&self, max_price: Ticks) -> u32 {
         self.asks
             .iter()
-            .take_while(|(price, _)| price.0 <= max_price)
+            .take_while(|(price, _)| **price <= max_price)
             .flat_map(|(_, orders)| orders.iter())
             .map(|o| o.quantity)
             .sum()
@@ -281,14 +724,112 @@ impl OrderBook {
 
     /// Calculate total available bid quantity at or above a given price
     /// Used for FOK pre-check on sell orders
-    pub fn available_bid_quantity_at_or_above(&self, min_price: f64) -> u32 {
+    pub fn available_bid_quantity_at_or_above(&self, min_price: Ticks) -> u32 {
         self.bids
             .iter()
-            .take_while(|(price, _)| price.0 .0 >= min_price)
+            .take_while(|(price, _)| price.0 >= min_price)
             .flat_map(|(_, orders)| orders.iter())
             .map(|o| o.quantity)
             .sum()
     }
+
+    /// Quantity an incoming order could actually trade right now
+    ///
+    /// Walks the opposite side in priority order up to the order's limit,
+    /// the same way matching does. The taker's own orders are skipped under
+    /// STP `CancelMaker`; under the other STP modes the first one ends the
+    /// walk, since matching stops there. All-or-none makers are passed over
+    /// unless the rest of the order covers them. Used for the FOK,
+    /// all-or-none and minimum-quantity pre-checks.
+    pub fn fillable_quantity(&self, taker: &BookOrder) -> u32 {
+        let makers: Box<dyn Iterator<Item = &BookOrder>> = match taker.side {
+            OrderSide::Buy => Box::new(
+                self.asks
+                    .iter()
+                    .take_while(|(price, _)| **price <= taker.price)
+                    .flat_map(|(_, orders)| orders.iter()),
+            ),
+            OrderSide::Sell => Box::new(
+                self.bids
+                    .iter()
+                    .take_while(|(price, _)| price.0 >= taker.price)
+                    .flat_map(|(_, orders)| orders.iter()),
+            ),
+        };
+
+        let mut remaining = taker.quantity;
+        for maker in makers {
+            if remaining == 0 {
+                break;
+            }
+            if !maker.can_fill_against(remaining) {
+                continue;
+            }
+            if taker.prevents_self_trade_with(maker) {
+                if taker.stp == SelfTradePrevention::CancelMaker {
+                    continue;
+                }
+                break;
+            }
+            remaining -= remaining.min(maker.quantity);
+        }
+        taker.quantity - remaining
+    }
+}
+
+/// Serde support for one side of an order book
+///
+/// A side is written as the sequence of its resting orders in priority
+/// order and rebuilt by appending each order to its price level, which
+/// preserves FIFO position.
+mod book_side_serde {
+    use super::BookOrder;
+    use crate::price::Ticks;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::cmp::Reverse;
+    use std::collections::{BTreeMap, VecDeque};
+
+    /// Price level key of a book side
+    pub trait PriceKey: Ord {
+        fn from_price(price: Ticks) -> Self;
+    }
+
+    impl PriceKey for Ticks {
+        fn from_price(price: Ticks) -> Self {
+            price
+        }
+    }
+
+    impl PriceKey for Reverse<Ticks> {
+        fn from_price(price: Ticks) -> Self {
+            Reverse(price)
+        }
+    }
+
+    pub fn serialize<K, S>(
+        side: &BTreeMap<K, VecDeque<BookOrder>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(side.values().flatten())
+    }
+
+    pub fn deserialize<'de, K, D>(
+        deserializer: D,
+    ) -> Result<BTreeMap<K, VecDeque<BookOrder>>, D::Error>
+    where
+        K: PriceKey,
+        D: Deserializer<'de>,
+    {
+        let orders = Vec::<BookOrder>::deserialize(deserializer)?;
+        let mut side: BTreeMap<K, VecDeque<BookOrder>> = BTreeMap::new();
+        for order in orders {
+            side.entry(K::from_price(order.price)).or_default().push_back(order);
+        }
+        Ok(side)
+    }
 }
 
 // ============================================================================
@@ -312,8 +853,8 @@ pub struct Trade {
     /// User IDs involved
     pub buyer_id: Uuid,
     pub seller_id: Uuid,
-    /// Execution price (ALWAYS the maker's price)
-    pub price: f64,
+    /// Execution price in ticks (ALWAYS the maker's price)
+    pub price: Ticks,
     /// Number of contracts traded
     pub quantity: u32,
     /// Which side was the aggressor
@@ -322,23 +863,31 @@ pub struct Trade {
     pub sequence: u64,
     /// When trade occurred
     pub timestamp: DateTime<Utc>,
+    /// Combo this trade is a leg of (None for outright trades)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combo_id: Option<String>,
+    /// Request for quote this block trade was agreed in (None for book trades)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rfq_id: Option<Uuid>,
 }
 
 impl Trade {
     /// Create a new trade
     pub fn new(
+        trade_id: Uuid,
         instrument_id: String,
         taker_order_id: Uuid,
         maker_order_id: Uuid,
         buyer_id: Uuid,
         seller_id: Uuid,
-        price: f64,
+        price: Ticks,
         quantity: u32,
         aggressor_side: OrderSide,
         sequence: u64,
+        timestamp: DateTime<Utc>,
     ) -> Self {
         Self {
-            trade_id: Uuid::new_v4(),
+            trade_id,
             instrument_id,
             taker_order_id,
             maker_order_id,
@@ -348,9 +897,23 @@ impl Trade {
             quantity,
             aggressor_side,
             sequence,
-            timestamp: Utc::now(),
+            timestamp,
+            combo_id: None,
+            rfq_id: None,
         }
     }
+
+    /// Mark the trade as a leg of a combo fill
+    pub fn with_combo_id(mut self, combo_id: impl Into<String>) -> Self {
+        self.combo_id = Some(combo_id.into());
+        self
+    }
+
+    /// Mark the trade as an off-book block trade agreed in an RFQ
+    pub fn with_rfq_id(mut self, rfq_id: Uuid) -> Self {
+        self.rfq_id = Some(rfq_id);
+        self
+    }
 }
 
 // ============================================================================
@@ -360,9 +923,9 @@ impl Trade {
 /// Price level for market data snapshots
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceLevel {
-    /// Price
-    pub price: f64,
-    /// Total quantity at this price
+    /// Price (ticks)
+    pub price: Ticks,
+    /// Total visible quantity at this price (hidden iceberg quantity excluded)
     pub quantity: u32,
     /// Number of orders at this price
     pub order_count: usize,
@@ -385,14 +948,16 @@ pub struct OrderBookSnapshot {
 
 impl OrderBookSnapshot {
     /// Create snapshot from order book
+    ///
+    /// Levels show only the visible slice of iceberg orders.
     pub fn from_book(book: &OrderBook, depth: usize) -> Self {
         let bids: Vec<PriceLevel> = book
             .bids
             .iter()
             .take(depth)
             .map(|(price, orders)| PriceLevel {
-                price: price.0 .0,
-                quantity: orders.iter().map(|o| o.quantity).sum(),
+                price: price.0,
+                quantity: orders.iter().map(|o| o.visible_quantity()).sum(),
                 order_count: orders.len(),
             })
             .collect();
@@ -402,8 +967,8 @@ impl OrderBookSnapshot {
             .iter()
             .take(depth)
             .map(|(price, orders)| PriceLevel {
-                price: price.0,
-                quantity: orders.iter().map(|o| o.quantity).sum(),
+                price: *price,
+                quantity: orders.iter().map(|o| o.visible_quantity()).sum(),
                 order_count: orders.len(),
             })
             .collect();
@@ -438,7 +1003,7 @@ mod tests {
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Buy,
-            100.0,
+            100,
             10,
             1,
             TimeInForce::Gtc,
@@ -464,7 +1029,7 @@ mod tests {
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Buy,
-            100.0,
+            100,
             10,
             1,
             TimeInForce::Gtc,
@@ -473,11 +1038,49 @@ mod tests {
         let order_id = order.order_id;
         book.insert_order(order);
 
-        assert_eq!(book.best_bid(), Some(100.0));
+        assert_eq!(book.best_bid(), Some(100));
         assert_eq!(book.remove_order(order_id).unwrap().quantity, 10);
         assert!(book.is_empty());
     }
 
+    #[test]
+    fn test_order_book_serde_preserves_queues() {
+        let mut book = OrderBook::new("BTC-50000-C".to_string());
+        for (seq, (side, price)) in [
+            (OrderSide::Buy, 99),
+            (OrderSide::Buy, 100),
+            (OrderSide::Buy, 99),
+            (OrderSide::Sell, 101),
+            (OrderSide::Sell, 101),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            book.insert_order(BookOrder::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                side,
+                price,
+                5,
+                seq as u64 + 1,
+                TimeInForce::Gtc,
+            ));
+        }
+
+        let json = serde_json::to_string(&book).unwrap();
+        let restored: OrderBook = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.order_count(), 5);
+        assert_eq!(restored.best_bid(), Some(100));
+        assert_eq!(restored.bid_quantity_at(99), 10);
+        let queue: Vec<u64> = restored.bids[&std::cmp::Reverse(99)]
+            .iter()
+            .map(|o| o.sequence)
+            .collect();
+        assert_eq!(queue, vec![1, 3]);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    }
+
     #[test]
     fn test_order_book_spread() {
         let mut book = OrderBook::new("BTC-50000-C".to_string());
@@ -490,7 +1093,7 @@ mod tests {
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Buy,
-            95.0,
+            95,
             10,
             1,
             TimeInForce::Gtc,
@@ -502,11 +1105,11 @@ mod tests {
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Sell,
-            105.0,
+            105,
             10,
             2,
             TimeInForce::Gtc,
         ));
-        assert_eq!(book.spread(), Some(10.0));
+        assert_eq!(book.spread(), Some(10));
     }
 }
@@ -1,33 +1,98 @@
 //! Core Matching Engine
 //!
 //! This module implements the deterministic price-time priority matching algorithm.
+//! Within a price level, incoming quantity is shared FIFO or pro-rata per the
+//! instrument's [`MatchingAlgorithm`].
 
-use crate::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerManager, CircuitBreakerStatus};
-use crate::domain::{BookOrder, OrderBook, OrderSide, TimeInForce, Trade};
+use crate::allocation::{LevelOrder, MatchingAlgorithm};
+use crate::auction::{self, AuctionReason, CallAuction, IndicativeUncross};
+use crate::block::BlockTrade;
+use crate::clock::{Clock, IdGenerator, SequenceIds, SystemClock};
+use crate::combo::{ComboDefinition, ComboLeg};
+use crate::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerManager, CircuitBreakerStatus, PriceBand};
+use crate::domain::{
+    BookOrder, MassCancelFilter, OrderBook, OrderLocation, OrderSide, PostOnly, TimeInForce, Trade,
+    TriggerSource, underlying_of,
+};
+use crate::error::MatchingError;
+use crate::event::MatchingEvent;
 use crate::metrics::{MatchingEngineMetrics, MetricsSnapshot};
-use crate::result::MatchResult;
-use std::collections::HashMap;
+use crate::mmp::{self, MmpBreach, MmpExposure, MmpFreeze, MmpLimits, MmpStatus, MmpWindow};
+use crate::price::{OffTickPolicy, PriceScale, Ticks};
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, MmpTrip, TriggeredStop, UncrossResult,
+};
+use crate::snapshot::{EngineSnapshot, SNAPSHOT_VERSION};
+use crate::trigger::{ReferencePrices, TriggerBook};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::ops::Bound::{Excluded, Unbounded};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Default market order protection band, in percent of the reference price
+pub const DEFAULT_MARKET_PROTECTION_PERCENT: f64 = 5.0;
+
 /// Matching Engine - The heart of the exchange
 ///
 /// CRITICAL PROPERTIES:
 /// 1. Deterministic (same inputs → same outputs, always)
 /// 2. Pure function (no external state, no side effects)
-/// 3. Price-time priority (strictly enforced)
+/// 3. Price priority (strictly enforced), then time or pro-rata within a level
 /// 4. Per-instrument isolation (books never interact)
 pub struct MatchingEngine {
     /// Order books per instrument
     books: HashMap<String, OrderBook>,
+    /// Index of every resting order: order_id -> (instrument, side, price)
+    order_index: HashMap<Uuid, OrderLocation>,
     /// Global sequence counter
     sequence: u64,
     /// Circuit breaker manager
     circuit_breakers: Option<CircuitBreakerManager>,
     /// Metrics collection
     metrics: Option<Arc<MatchingEngineMetrics>>,
+    /// Mark prices per instrument (reference for market order protection)
+    mark_prices: HashMap<String, Ticks>,
+    /// Index prices per instrument
+    index_prices: HashMap<String, Ticks>,
+    /// Last trade price per instrument
+    last_prices: HashMap<String, Ticks>,
+    /// How far (in percent) a market order may trade through its reference price
+    market_protection_percent: f64,
+    /// Price scales by instrument ID or underlying symbol
+    price_scales: HashMap<String, PriceScale>,
+    /// Level allocation algorithms by instrument ID or underlying symbol
+    algorithms: HashMap<String, MatchingAlgorithm>,
+    /// Combo instruments by combo ID (ordered, so implied matching is deterministic)
+    combos: BTreeMap<String, ComboDefinition>,
+    /// Minimum block trade sizes by instrument ID or underlying symbol
+    min_block_sizes: HashMap<String, u32>,
+    /// Market maker protection limits by underlying symbol
+    mmp_limits: HashMap<String, MmpLimits>,
+    /// Quotes resting, or filled by the operation in progress
+    quote_orders: HashSet<Uuid>,
+    /// Fills of each maker's quotes per underlying, in the rolling window
+    mmp_windows: HashMap<(Uuid, String), MmpWindow>,
+    /// Makers whose quoting is frozen, per underlying (ordered for snapshots)
+    mmp_frozen: BTreeMap<(Uuid, String), MmpFreeze>,
+    /// Stop orders waiting for their trigger, per instrument
+    trigger_books: HashMap<String, TriggerBook>,
+    /// Index of every held stop order: order_id -> instrument
+    stop_index: HashMap<Uuid, String>,
+    /// Instruments collecting orders in a call auction
+    auctions: HashMap<String, CallAuction>,
+    /// Set while replaying; stops are then released only by logged triggers
+    replaying: bool,
+    /// Stamps commands that arrive without a time
+    clock: Arc<dyn Clock>,
+    /// Source of trade IDs
+    ids: Arc<dyn IdGenerator>,
+    /// Time of the command being processed (timestamp of its trades)
+    now: DateTime<Utc>,
+    /// Levels and books changed under an open combo fill checkpoint
+    undo: UndoLog,
 }
 
 impl MatchingEngine {
@@ -35,40 +100,150 @@ impl MatchingEngine {
     pub fn new() -> Self {
         Self {
             books: HashMap::new(),
+            order_index: HashMap::new(),
             sequence: 0,
             circuit_breakers: None,
             metrics: None,
+            mark_prices: HashMap::new(),
+            index_prices: HashMap::new(),
+            last_prices: HashMap::new(),
+            market_protection_percent: DEFAULT_MARKET_PROTECTION_PERCENT,
+            price_scales: HashMap::new(),
+            algorithms: HashMap::new(),
+            combos: BTreeMap::new(),
+            min_block_sizes: HashMap::new(),
+            mmp_limits: HashMap::new(),
+            quote_orders: HashSet::new(),
+            mmp_windows: HashMap::new(),
+            mmp_frozen: BTreeMap::new(),
+            trigger_books: HashMap::new(),
+            stop_index: HashMap::new(),
+            auctions: HashMap::new(),
+            replaying: false,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(SequenceIds::default()),
+            now: DateTime::UNIX_EPOCH,
+            undo: UndoLog::default(),
         }
     }
 
     /// Create a new matching engine with circuit breakers
     pub fn new_with_circuit_breakers(config: CircuitBreakerConfig) -> Self {
         Self {
-            books: HashMap::new(),
-            sequence: 0,
             circuit_breakers: Some(CircuitBreakerManager::new(config)),
-            metrics: None,
+            ..Self::new()
         }
     }
 
     /// Create a new matching engine with metrics enabled
     pub fn new_with_metrics() -> Self {
         Self {
-            books: HashMap::new(),
-            sequence: 0,
-            circuit_breakers: None,
             metrics: Some(Arc::new(MatchingEngineMetrics::new())),
+            ..Self::new()
         }
     }
 
     /// Create a new matching engine with both circuit breakers and metrics
     pub fn new_with_all(config: CircuitBreakerConfig) -> Self {
         Self {
-            books: HashMap::new(),
-            sequence: 0,
             circuit_breakers: Some(CircuitBreakerManager::new(config)),
             metrics: Some(Arc::new(MatchingEngineMetrics::new())),
+            ..Self::new()
+        }
+    }
+
+    /// Create a matching engine with the circuit breakers, market
+    /// protection, price scales, level allocation, block sizes and market
+    /// maker protection of a configuration
+    ///
+    /// Metrics are always enabled. Without a `matching_engine` section the
+    /// engine only gets the assets' tick sizes.
+    pub fn from_config(config: &config::MasterConfig) -> Self {
+        let me_config = config.matching_engine.as_ref();
+        let mut engine = match me_config {
+            Some(me_config) if me_config.circuit_breakers.enabled => {
+                debug!("Circuit breakers enabled");
+                Self::new_with_all(CircuitBreakerConfig::from(&me_config.circuit_breakers))
+            }
+            _ => Self::new_with_metrics(),
+        };
+
+        let off_tick = match me_config {
+            Some(me_config) => {
+                engine.set_market_protection_percent(me_config.execution.market_protection_percent);
+                match me_config.execution.off_tick_policy.as_str() {
+                    "round" => OffTickPolicy::Round,
+                    _ => OffTickPolicy::Reject,
+                }
+            }
+            None => OffTickPolicy::Reject,
+        };
+
+        // Option instruments take the tick size of their underlying asset
+        for asset in &config.instrument.supported_assets {
+            engine.set_price_scale(&asset.symbol, PriceScale::new(asset.tick_size).with_off_tick(off_tick));
+        }
+
+        // Level allocation: the configured algorithm for every asset, then
+        // per-underlying overrides
+        if let Some(me_config) = me_config {
+            let parse = |name: &str| {
+                MatchingAlgorithm::from_name(
+                    name,
+                    me_config.pro_rata.min_allocation,
+                    me_config.pro_rata.top_order_percent,
+                )
+                .unwrap_or_else(|| {
+                    warn!(algorithm = name, "Unknown matching algorithm, using price_time_priority");
+                    MatchingAlgorithm::PriceTimePriority
+                })
+            };
+            let algorithm = parse(&me_config.algorithm);
+            for asset in &config.instrument.supported_assets {
+                engine.set_matching_algorithm(&asset.symbol, algorithm);
+            }
+            for (underlying, name) in &me_config.underlying_algorithms {
+                engine.set_matching_algorithm(underlying, parse(name));
+            }
+        }
+
+        // Minimum block trade sizes: the RFQ default for every asset, then
+        // per-underlying overrides
+        if let Some(oms_config) = &config.oms {
+            for asset in &config.instrument.supported_assets {
+                engine.set_min_block_size(&asset.symbol, oms_config.rfq.min_block_size);
+            }
+            for (underlying, quantity) in &oms_config.rfq.min_block_sizes {
+                engine.set_min_block_size(underlying, *quantity);
+            }
+        }
+
+        // Market maker protection: limits of every configured underlying
+        if let Some(me_config) = me_config {
+            let mmp = &me_config.mmp;
+            for (underlying, limits) in &mmp.limits {
+                let mut engine_limits = MmpLimits::new(mmp.window_ms).with_volatility(mmp.volatility);
+                engine_limits.quantity = limits.quantity;
+                engine_limits.delta = limits.delta;
+                engine_limits.vega = limits.vega;
+                engine.set_mmp_limits(underlying, engine_limits);
+            }
         }
+
+        engine
+    }
+
+    /// Set the clock used to stamp commands that arrive without a time
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Set the source of trade IDs
+    ///
+    /// Replaying an event log reproduces its trades only with the generator
+    /// that produced them.
+    pub fn set_id_generator(&mut self, ids: Arc<dyn IdGenerator>) {
+        self.ids = ids;
     }
 
     /// Enable metrics collection
@@ -118,155 +293,1829 @@ impl MatchingEngine {
         }
     }
 
-    /// Get or create order book for instrument
-    fn get_or_create_book(&mut self, instrument_id: &str) -> &mut OrderBook {
-        self.books
-            .entry(instrument_id.to_string())
-            .or_insert_with(|| OrderBook::new(instrument_id.to_string()))
+    /// Halt an instrument until [`Self::resume_instrument`] (admin action)
+    ///
+    /// Works whether or not circuit breakers are enabled. Like any halt,
+    /// orders reaching the instrument start a call auction. Manual halts are
+    /// not logged and do not survive a restart.
+    pub fn halt_instrument(&mut self, instrument_id: &str) {
+        self.circuit_breakers_mut().halt(instrument_id);
+        self.publish_book_metrics(instrument_id);
     }
 
-    /// Get next sequence number
-    fn next_sequence(&mut self) -> u64 {
-        self.sequence += 1;
-        self.sequence
+    /// Lift every halt of an instrument (admin action)
+    ///
+    /// A running halt auction uncrosses on the next order, or on
+    /// [`Self::uncross`].
+    pub fn resume_instrument(&mut self, instrument_id: &str) {
+        self.clear_circuit_breaker(instrument_id);
+        self.publish_book_metrics(instrument_id);
+    }
+
+    /// Halt every instrument of an underlying (e.g. `"BTC"`) until resumed
+    pub fn halt_underlying(&mut self, underlying: &str) {
+        self.circuit_breakers_mut().halt_underlying(underlying);
+        self.publish_underlying_metrics(underlying);
+    }
+
+    /// Lift a halt placed with [`Self::halt_underlying`]
+    pub fn resume_underlying(&mut self, underlying: &str) {
+        if let Some(cb) = &mut self.circuit_breakers {
+            cb.resume_underlying(underlying);
+        }
+        self.publish_underlying_metrics(underlying);
+    }
+
+    /// Publish the book metrics of every instrument of an underlying
+    fn publish_underlying_metrics(&self, underlying: &str) {
+        for instrument_id in self.books.keys() {
+            if underlying_of(instrument_id) == underlying {
+                self.publish_book_metrics(instrument_id);
+            }
+        }
+    }
+
+    /// Underlyings halted by an operator
+    pub fn halted_underlyings(&self) -> Vec<String> {
+        self.circuit_breakers
+            .as_ref()
+            .map(|cb| cb.halted_underlyings())
+            .unwrap_or_default()
+    }
+
+    /// Price band an instrument's orders are checked against, if any
+    pub fn price_band(&self, instrument_id: &str) -> Option<PriceBand> {
+        self.circuit_breakers.as_ref()?.price_band(instrument_id)
+    }
+
+    /// Circuit breakers, created disabled for manual halts if not enabled
+    fn circuit_breakers_mut(&mut self) -> &mut CircuitBreakerManager {
+        self.circuit_breakers
+            .get_or_insert_with(|| CircuitBreakerManager::new(CircuitBreakerConfig::default()))
+    }
+
+    /// Put an instrument into a call auction
+    ///
+    /// Orders are collected without matching until [`Self::uncross`].
+    /// Consumes a sequence number. Used for opening auctions; halt auctions
+    /// start by themselves when an order reaches a halted instrument.
+    pub fn start_auction(
+        &mut self,
+        instrument_id: &str,
+        reason: AuctionReason,
+    ) -> Result<CallAuction, MatchingError> {
+        if self.combos.contains_key(instrument_id) {
+            return Err(MatchingError::Auction(format!(
+                "Combo {} trades continuously only",
+                instrument_id
+            )));
+        }
+        if self.auctions.contains_key(instrument_id) {
+            return Err(MatchingError::Auction(format!(
+                "Instrument {} is already in a call auction",
+                instrument_id
+            )));
+        }
+
+        let auction = CallAuction {
+            reason,
+            started_at: self.next_sequence(),
+        };
+        self.auctions.insert(instrument_id.to_string(), auction);
+        info!(instrument = %instrument_id, ?reason, sequence = auction.started_at, "Call auction started");
+        Ok(auction)
+    }
+
+    /// Running call auction of an instrument
+    pub fn auction(&self, instrument_id: &str) -> Option<&CallAuction> {
+        self.auctions.get(instrument_id)
+    }
+
+    /// Price and volume a running auction would uncross at now
+    ///
+    /// None if the instrument is not in an auction or its book does not cross.
+    pub fn indicative_uncross(&self, instrument_id: &str) -> Option<IndicativeUncross> {
+        self.auctions.get(instrument_id)?;
+        self.equilibrium(instrument_id)
+    }
+
+    /// Uncross a call auction and resume continuous trading
+    ///
+    /// Everything executable at the equilibrium price trades in one step.
+    /// Ending a halt auction early this way also clears the circuit breaker.
+    pub fn uncross(&mut self, instrument_id: &str) -> Result<UncrossResult, MatchingError> {
+        let auction = self.auctions.remove(instrument_id).ok_or_else(|| {
+            MatchingError::Auction(format!("Instrument {} is not in a call auction", instrument_id))
+        })?;
+        let price = self.equilibrium(instrument_id).map(|u| u.price);
+        self.now = self.clock.now();
+        Ok(self.uncross_at(instrument_id, auction, price))
+    }
+
+    /// Equilibrium of an instrument's book, tie-broken on last trade or mark price
+    fn equilibrium(&self, instrument_id: &str) -> Option<IndicativeUncross> {
+        let reference = self
+            .last_prices
+            .get(instrument_id)
+            .or_else(|| self.mark_prices.get(instrument_id))
+            .copied();
+        auction::indicative_uncross(self.books.get(instrument_id)?, reference)
+    }
+
+    /// Execute an auction's uncross at a given price
+    fn uncross_at(
+        &mut self,
+        instrument_id: &str,
+        auction: CallAuction,
+        price: Option<Ticks>,
+    ) -> UncrossResult {
+        let sequence = self.next_sequence();
+        let fills = match (price, self.books.get(instrument_id)) {
+            (Some(price), Some(book)) => auction::allocate(book, price),
+            _ => Vec::new(),
+        };
+
+        let mut trades = Vec::with_capacity(fills.len());
+        for fill in fills {
+            let (Some(buy), Some(sell)) = (
+                self.get_order(fill.buy_order_id).cloned(),
+                self.get_order(fill.sell_order_id).cloned(),
+            ) else {
+                continue;
+            };
+            let aggressor = auction::aggressor(&buy, &sell);
+            let (taker, maker) = match aggressor {
+                OrderSide::Buy => (&buy, &sell),
+                OrderSide::Sell => (&sell, &buy),
+            };
+            let trade_sequence = self.next_sequence();
+            trades.push(Trade::new(
+                self.ids.trade_id(instrument_id, trade_sequence),
+                instrument_id.to_string(),
+                taker.order_id,
+                maker.order_id,
+                buy.user_id,
+                sell.user_id,
+                price.expect("fills only exist with a price"),
+                fill.quantity,
+                aggressor,
+                trade_sequence,
+                self.now,
+            ));
+            self.fill_resting(fill.buy_order_id, fill.quantity);
+            self.fill_resting(fill.sell_order_id, fill.quantity);
+        }
+
+        if let Some(last_trade) = trades.last() {
+            self.last_prices.insert(instrument_id.to_string(), last_trade.price);
+        }
+        if auction.reason == AuctionReason::Halt {
+            self.clear_circuit_breaker(instrument_id);
+        }
+        self.publish_book_metrics(instrument_id);
+
+        info!(
+            instrument = %instrument_id,
+            ?price,
+            trades = trades.len(),
+            sequence,
+            "Call auction uncrossed"
+        );
+
+        UncrossResult {
+            instrument_id: instrument_id.to_string(),
+            auction,
+            price,
+            trades,
+            sequence,
+            timestamp: self.now,
+            triggered: self.fire_triggers(instrument_id),
+        }
+    }
+
+    /// Fill part of a resting order in place
+    ///
+    /// A filled order leaves the book; an iceberg whose slice is used up
+    /// shows its next slice at the back of its level under a new sequence.
+    fn fill_resting(&mut self, order_id: Uuid, quantity: u32) {
+        let Some(location) = self.order_index.get(&order_id).cloned() else {
+            return;
+        };
+        let Some(book) = self.books.get_mut(&location.instrument_id) else {
+            return;
+        };
+        let Some(order) = book.get_order_at_mut(location.side, location.price, order_id) else {
+            return;
+        };
+        order.fill(quantity);
+
+        if order.is_filled() {
+            book.remove_order_at(location.side, location.price, order_id);
+            self.order_index.remove(&order_id);
+        } else if order.needs_refresh() {
+            let mut order = book
+                .remove_order_at(location.side, location.price, order_id)
+                .expect("order was just found");
+            order.sequence = advance_sequence(&mut self.sequence);
+            order.show_next_slice();
+            book.insert_order(order);
+        }
+    }
+
+    /// Collect an order into a running call auction without matching it
+    fn collect_auction_order(&mut self, mut order: BookOrder) -> MatchResult {
+        if !auction::accepts(&order) || order.display_quantity == Some(0) {
+            info!(
+                order_id = %order.order_id,
+                instrument = %order.instrument_id,
+                "Order rejected: not accepted during a call auction"
+            );
+            return MatchResult::cancelled(order);
+        }
+
+        let sequence = self.next_sequence();
+        order.sequence = sequence;
+        if order.needs_refresh() {
+            order.show_next_slice();
+        }
+        self.order_index.insert(order.order_id, OrderLocation::of(&order));
+        if order.quote {
+            self.quote_orders.insert(order.order_id);
+        }
+        self.get_or_create_book(&order.instrument_id).insert_order(order.clone());
+
+        info!(
+            order_id = %order.order_id,
+            instrument = %order.instrument_id,
+            sequence,
+            "Order collected in call auction"
+        );
+        self.publish_book_metrics(&order.instrument_id);
+
+        let mut result = MatchResult::no_match(order, true);
+        result.sequence = Some(sequence);
+        result
+    }
+
+    /// Set the market order protection band (percent of the reference price)
+    pub fn set_market_protection_percent(&mut self, percent: f64) {
+        self.market_protection_percent = percent;
+    }
+
+    /// Get the market order protection band
+    pub fn market_protection_percent(&self) -> f64 {
+        self.market_protection_percent
+    }
+
+    /// Set the price scale for an instrument, or for every instrument of an
+    /// underlying (e.g. `"BTC"` covers `"BTC-20260315-50000-C"`)
+    pub fn set_price_scale(&mut self, key: &str, scale: PriceScale) {
+        self.price_scales.insert(key.to_string(), scale);
+    }
+
+    /// Price scale used to convert an instrument's decimal prices to ticks
+    ///
+    /// Looks up the instrument ID, then its underlying (the part before the
+    /// first `-`), then falls back to the default scale.
+    pub fn price_scale(&self, instrument_id: &str) -> PriceScale {
+        let underlying = underlying_of(instrument_id);
+        self.price_scales
+            .get(instrument_id)
+            .or_else(|| self.price_scales.get(underlying))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Set the level allocation algorithm for an instrument, or for every
+    /// instrument of an underlying
+    pub fn set_matching_algorithm(&mut self, key: &str, algorithm: MatchingAlgorithm) {
+        self.algorithms.insert(key.to_string(), algorithm);
+    }
+
+    /// Algorithm sharing incoming quantity between the orders of a level
+    ///
+    /// Looked up like [`price_scale`](Self::price_scale); defaults to
+    /// price-time priority.
+    pub fn matching_algorithm(&self, instrument_id: &str) -> MatchingAlgorithm {
+        let underlying = underlying_of(instrument_id);
+        self.algorithms
+            .get(instrument_id)
+            .or_else(|| self.algorithms.get(underlying))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Define a combo instrument
+    ///
+    /// Orders on `combo.combo_id` then trade as combo orders (see
+    /// [`crate::combo`]). Defining a combo again with the same legs does
+    /// nothing and returns `None`; otherwise the definition consumes a
+    /// sequence number, which is returned so it can be logged as
+    /// `MatchingEvent::ComboDefined`.
+    pub fn define_combo(&mut self, mut combo: ComboDefinition) -> Result<Option<u64>, MatchingError> {
+        combo.validate()?;
+
+        let combo_id = combo.combo_id.clone();
+        if let Some(existing) = self.combos.get(&combo_id) {
+            if existing.legs == combo.legs {
+                return Ok(None);
+            }
+            return Err(MatchingError::InvalidCombo(format!(
+                "{} is already defined with other legs",
+                combo_id
+            )));
+        }
+        if self.books.get(&combo_id).is_some_and(|book| !book.is_empty())
+            || self.combos.values().any(|c| c.leg_index(&combo_id).is_some())
+        {
+            return Err(MatchingError::InvalidCombo(format!(
+                "{} already trades as an outright instrument",
+                combo_id
+            )));
+        }
+        if let Some(leg) = combo.legs.iter().find(|leg| self.combos.contains_key(&leg.instrument_id)) {
+            return Err(MatchingError::InvalidCombo(format!(
+                "{}: leg {} is itself a combo",
+                combo_id, leg.instrument_id
+            )));
+        }
+
+        let sequence = self.next_sequence();
+        combo.sequence = sequence;
+        info!(combo = %combo_id, legs = combo.legs.len(), sequence, "Combo defined");
+        self.combos.insert(combo_id, combo);
+        Ok(Some(sequence))
+    }
+
+    /// Definition of a combo instrument
+    pub fn combo(&self, combo_id: &str) -> Option<&ComboDefinition> {
+        self.combos.get(combo_id)
+    }
+
+    /// Every defined combo, by combo ID
+    pub fn combos(&self) -> Vec<ComboDefinition> {
+        self.combos.values().cloned().collect()
+    }
+
+    /// Instruments whose books an order on `instrument_id` can change
+    ///
+    /// The instrument itself and, through combo fills, every combo it is
+    /// a leg of or that it is, together with all of their legs. Sorted.
+    pub fn linked_instruments(&self, instrument_id: &str) -> Vec<String> {
+        let mut linked = BTreeSet::from([instrument_id.to_string()]);
+        for combo in self.combos.values() {
+            if combo.combo_id == instrument_id || combo.leg_index(instrument_id).is_some() {
+                linked.insert(combo.combo_id.clone());
+                linked.extend(combo.legs.iter().map(|leg| leg.instrument_id.clone()));
+            }
+        }
+        linked.into_iter().collect()
+    }
+
+    /// Set the minimum block trade size for an instrument, or for every
+    /// instrument of an underlying
+    pub fn set_min_block_size(&mut self, key: &str, quantity: u32) {
+        self.min_block_sizes.insert(key.to_string(), quantity);
+    }
+
+    /// Smallest quantity a block trade on an instrument may have
+    ///
+    /// Looked up like [`price_scale`](Self::price_scale); defaults to 1.
+    pub fn min_block_size(&self, instrument_id: &str) -> u32 {
+        let underlying = underlying_of(instrument_id);
+        self.min_block_sizes
+            .get(instrument_id)
+            .or_else(|| self.min_block_sizes.get(underlying))
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// Book a block trade agreed in an RFQ (see [`crate::block`])
+    ///
+    /// The trade must reach the instrument's minimum block size and be
+    /// between two users, and neither the instrument nor, for a combo, any
+    /// of its legs may be halted. No book is touched. Returns the trades
+    /// booked, each under its own sequence number: one for an outright
+    /// instrument, one per leg for a combo.
+    pub fn book_block_trade(&mut self, block: &BlockTrade) -> Result<Vec<Trade>, MatchingError> {
+        let invalid = |reason: String| Err(MatchingError::InvalidBlockTrade(reason));
+
+        let min_size = self.min_block_size(&block.instrument_id);
+        if block.quantity == 0 || block.quantity < min_size {
+            return invalid(format!(
+                "quantity {} is below the minimum block size {} of {}",
+                block.quantity, min_size, block.instrument_id
+            ));
+        }
+        if block.taker_user_id == block.maker_user_id {
+            return invalid("taker and maker are the same user".to_string());
+        }
+        let combo = self.combos.get(&block.instrument_id).cloned();
+        if combo.is_none() && block.price <= 0 {
+            return invalid(format!("price {} must be positive", block.price));
+        }
+        let instruments = match &combo {
+            Some(combo) => combo_instruments(combo),
+            None => vec![block.instrument_id.clone()],
+        };
+        if let Some(halted) = instruments.iter().find(|id| self.is_halted(id)) {
+            return Err(MatchingError::CircuitBreaker(format!("Instrument {} is halted", halted)));
+        }
+
+        // An outright trade is a single leg bought at the block price
+        let (legs, prices) = match &combo {
+            Some(combo) => {
+                let references: Vec<Ticks> = combo
+                    .legs
+                    .iter()
+                    .map(|leg| self.leg_reference_price(&leg.instrument_id))
+                    .collect();
+                (combo.legs.clone(), combo.leg_prices(block.price, &references))
+            }
+            None => (
+                vec![ComboLeg::new(block.instrument_id.clone(), OrderSide::Buy, 1)],
+                vec![block.price],
+            ),
+        };
+
+        self.now = self.clock.now();
+        let (buyer_id, seller_id) = block.parties();
+        let mut trades = Vec::with_capacity(legs.len());
+        for (leg, price) in legs.iter().zip(prices) {
+            let sequence = self.next_sequence();
+            let (buyer_id, seller_id) = match leg.side {
+                OrderSide::Buy => (buyer_id, seller_id),
+                OrderSide::Sell => (seller_id, buyer_id),
+            };
+            let mut trade = Trade::new(
+                self.ids.trade_id(&leg.instrument_id, sequence),
+                leg.instrument_id.clone(),
+                block.taker_order_id,
+                block.maker_order_id,
+                buyer_id,
+                seller_id,
+                price,
+                block.quantity * leg.ratio,
+                leg.side_for(block.taker_side),
+                sequence,
+                self.now,
+            )
+            .with_rfq_id(block.rfq_id);
+            if combo.is_some() {
+                trade = trade.with_combo_id(&block.instrument_id);
+            }
+            trades.push(trade);
+        }
+
+        if let Some(metrics) = self.metrics.as_ref().filter(|_| !self.replaying) {
+            for trade in &trades {
+                metrics.record_trade(&trade.instrument_id, trade.quantity);
+            }
+        }
+        info!(
+            rfq_id = %block.rfq_id,
+            instrument = %block.instrument_id,
+            quantity = block.quantity,
+            trades = trades.len(),
+            "Block trade booked"
+        );
+        Ok(trades)
+    }
+
+    /// Set the market maker protection limits of an underlying
+    pub fn set_mmp_limits(&mut self, underlying: &str, limits: MmpLimits) {
+        self.mmp_limits.insert(underlying.to_string(), limits);
+    }
+
+    /// MMP limits of an underlying (None if its quotes are unprotected)
+    pub fn mmp_limits(&self, underlying: &str) -> Option<MmpLimits> {
+        self.mmp_limits.get(underlying).copied()
+    }
+
+    /// Replace a maker's quotes on a set of instruments (see [`crate::mmp`])
+    ///
+    /// Every order must belong to `user_id` and be a limit order on an
+    /// outright instrument. The maker's resting quotes on the instruments
+    /// named are removed first, as one mass cancel, then each new quote is
+    /// matched in request order. Quotes are post-only: one that would cross
+    /// is rejected unless it asks to be repriced. Nothing changes if quoting
+    /// is frozen in any of the underlyings quoted.
+    pub fn mass_quote(
+        &mut self,
+        user_id: Uuid,
+        quotes: Vec<BookOrder>,
+    ) -> Result<MassQuoteResult, MatchingError> {
+        for quote in &quotes {
+            if quote.user_id != user_id {
+                return Err(MatchingError::InvalidOrder(format!(
+                    "Quote {} belongs to another user",
+                    quote.order_id
+                )));
+            }
+            if quote.is_stop() || quote.is_market() || self.combos.contains_key(&quote.instrument_id) {
+                return Err(MatchingError::InvalidOrder(format!(
+                    "Quote {} must be a limit order on an outright instrument",
+                    quote.order_id
+                )));
+            }
+            let underlying = underlying_of(&quote.instrument_id);
+            if let Some(freeze) = self.mmp_frozen.get(&(user_id, underlying.to_string())) {
+                return Err(MatchingError::MmpFrozen(format!(
+                    "{} limit tripped in {} for user {}",
+                    freeze.breach, underlying, user_id
+                )));
+            }
+        }
+
+        let instruments: HashSet<&str> = quotes.iter().map(|q| q.instrument_id.as_str()).collect();
+        let mut previous: Vec<(u64, Uuid)> = instruments
+            .iter()
+            .filter_map(|id| self.books.get(*id))
+            .flat_map(|book| book.orders())
+            .filter(|o| o.quote && o.user_id == user_id)
+            .map(|o| (o.sequence, o.order_id))
+            .collect();
+        previous.sort_unstable();
+        let previous: Vec<Uuid> = previous.into_iter().map(|(_, order_id)| order_id).collect();
+        let replaced = self.remove_orders(&previous);
+
+        let quotes = quotes
+            .into_iter()
+            .map(|quote| {
+                let mut quote = quote.with_quote(true);
+                if quote.post_only == PostOnly::None {
+                    quote.post_only = PostOnly::Reject;
+                }
+                let result = self.match_order(quote.clone());
+                (quote, result)
+            })
+            .collect();
+
+        Ok(MassQuoteResult { replaced, quotes })
+    }
+
+    /// Let a maker quote again in an underlying
+    ///
+    /// Clears the maker's window too. Returns the sequence of the reset, or
+    /// `None` if quoting was not frozen (nothing to log).
+    pub fn reset_mmp(&mut self, user_id: Uuid, underlying: &str) -> Option<u64> {
+        let key = (user_id, underlying.to_string());
+        self.mmp_windows.remove(&key);
+        self.mmp_frozen.remove(&key)?;
+        let sequence = self.next_sequence();
+        info!(user_id = %user_id, underlying, sequence, "Market maker protection reset");
+        Some(sequence)
+    }
+
+    /// A maker's MMP exposure and frozen state per underlying
+    ///
+    /// Lists every underlying the maker's quotes traded in or that is
+    /// frozen, by symbol.
+    pub fn mmp_status(&self, user_id: Uuid) -> Vec<MmpStatus> {
+        let underlyings: BTreeSet<&String> = self
+            .mmp_windows
+            .keys()
+            .chain(self.mmp_frozen.keys())
+            .filter(|(user, _)| *user == user_id)
+            .map(|(_, underlying)| underlying)
+            .collect();
+        let now = self.clock.now();
+
+        underlyings
+            .into_iter()
+            .map(|underlying| {
+                let key = (user_id, underlying.clone());
+                let window_ms = self.mmp_limits(underlying).map_or(0, |limits| limits.window_ms);
+                let since = now - chrono::Duration::milliseconds(window_ms as i64);
+                MmpStatus {
+                    underlying: underlying.clone(),
+                    exposure: self
+                        .mmp_windows
+                        .get(&key)
+                        .map(|window| window.exposure(since))
+                        .unwrap_or_default(),
+                    frozen: self.mmp_frozen.get(&key).cloned(),
+                }
+            })
+            .collect()
+    }
+
+    /// Count the fills of quotes against their makers' MMP limits
+    ///
+    /// Called after each matching operation; a maker trips at most once
+    /// per underlying, after all of the operation's fills are counted.
+    /// Only prunes filled quotes while replaying: logged trips are replayed
+    /// instead.
+    fn check_mmp(&mut self, trades: &[Trade]) -> Vec<MmpTrip> {
+        let mut touched: Vec<(Uuid, String)> = Vec::new();
+        for trade in trades {
+            if !self.quote_orders.contains(&trade.maker_order_id) {
+                continue;
+            }
+            if !self.order_index.contains_key(&trade.maker_order_id) {
+                self.quote_orders.remove(&trade.maker_order_id);
+            }
+            let underlying = underlying_of(&trade.instrument_id);
+            let Some(limits) = self.mmp_limits(underlying).filter(|_| !self.replaying) else {
+                continue;
+            };
+            let (maker_id, maker_side) = match trade.aggressor_side {
+                OrderSide::Buy => (trade.seller_id, OrderSide::Sell),
+                OrderSide::Sell => (trade.buyer_id, OrderSide::Buy),
+            };
+            let key = (maker_id, underlying.to_string());
+            if self.mmp_frozen.contains_key(&key) {
+                continue;
+            }
+
+            let fill = mmp::fill_exposure(
+                &trade.instrument_id,
+                maker_side,
+                trade.quantity,
+                self.index_spot(&trade.instrument_id),
+                limits.volatility,
+                trade.timestamp,
+            );
+            self.mmp_windows
+                .entry(key.clone())
+                .or_default()
+                .record(trade.timestamp, fill, limits.window_ms);
+            if !touched.contains(&key) {
+                touched.push(key);
+            }
+        }
+
+        let mut trips = Vec::new();
+        for (user_id, underlying) in touched {
+            let Some(limits) = self.mmp_limits(&underlying) else {
+                continue;
+            };
+            let since = self.now - chrono::Duration::milliseconds(limits.window_ms as i64);
+            let exposure = self.mmp_windows[&(user_id, underlying.clone())].exposure(since);
+            let Some(breach) = exposure.breach(&limits) else {
+                continue;
+            };
+
+            let mut quotes: Vec<(u64, Uuid)> = self
+                .books
+                .iter()
+                .filter(|(instrument_id, _)| underlying_of(instrument_id) == underlying)
+                .flat_map(|(_, book)| book.orders())
+                .filter(|o| o.quote && o.user_id == user_id)
+                .map(|o| (o.sequence, o.order_id))
+                .collect();
+            quotes.sort_unstable();
+            let quotes: Vec<Uuid> = quotes.into_iter().map(|(_, order_id)| order_id).collect();
+
+            let trip = self.trip_mmp(user_id, &underlying, breach, exposure, &quotes);
+            warn!(
+                user_id = %user_id,
+                underlying = %underlying,
+                breach = %breach,
+                quantity = exposure.quantity,
+                delta = exposure.delta,
+                vega = exposure.vega,
+                cancelled = trip.cancelled.len(),
+                sequence = trip.sequence,
+                "Market maker protection tripped: quotes cancelled, quoting frozen"
+            );
+            trips.push(trip);
+        }
+        trips
+    }
+
+    /// Cancel a maker's quotes and freeze quoting in an underlying
+    fn trip_mmp(
+        &mut self,
+        user_id: Uuid,
+        underlying: &str,
+        breach: MmpBreach,
+        exposure: MmpExposure,
+        order_ids: &[Uuid],
+    ) -> MmpTrip {
+        let cancelled: Vec<BookOrder> = order_ids
+            .iter()
+            .filter_map(|order_id| self.remove_order(*order_id))
+            .collect();
+        let sequence = self.next_sequence();
+        self.mmp_frozen.insert(
+            (user_id, underlying.to_string()),
+            MmpFreeze {
+                user_id,
+                underlying: underlying.to_string(),
+                breach,
+                sequence,
+            },
+        );
+        MmpTrip {
+            user_id,
+            underlying: underlying.to_string(),
+            breach,
+            exposure,
+            cancelled,
+            sequence,
+        }
+    }
+
+    /// Index price of an instrument, or else of its underlying, in decimal
+    fn index_spot(&self, instrument_id: &str) -> Option<f64> {
+        [instrument_id, underlying_of(instrument_id)]
+            .into_iter()
+            .find_map(|key| {
+                self.index_prices
+                    .get(key)
+                    .map(|ticks| self.price_scale(key).to_price(*ticks))
+            })
+    }
+
+    /// Update the mark price (in ticks) for an instrument
+    ///
+    /// Returns the stop orders released by the new price.
+    pub fn set_mark_price(&mut self, instrument_id: &str, price: Ticks) -> Vec<TriggeredStop> {
+        self.update_reference_price(instrument_id, TriggerSource::MarkPrice, price)
+    }
+
+    /// Get the last known mark price for an instrument
+    pub fn mark_price(&self, instrument_id: &str) -> Option<Ticks> {
+        self.mark_prices.get(instrument_id).copied()
     }
 
-    /// Set sequence number (for replay)
-    pub fn set_sequence(&mut self, seq: u64) {
-        self.sequence = seq;
+    /// Update the index price (in ticks) for an instrument
+    ///
+    /// Returns the stop orders released by the new price.
+    pub fn set_index_price(&mut self, instrument_id: &str, price: Ticks) -> Vec<TriggeredStop> {
+        self.update_reference_price(instrument_id, TriggerSource::IndexPrice, price)
+    }
+
+    /// Update any reference price and release the stops it triggers
+    ///
+    /// Last-trade prices normally come from the engine's own trades; setting
+    /// one here is meant for seeding a freshly started engine. A new mark
+    /// price also moves the instrument's price band.
+    pub fn update_reference_price(
+        &mut self,
+        instrument_id: &str,
+        source: TriggerSource,
+        price: Ticks,
+    ) -> Vec<TriggeredStop> {
+        if source == TriggerSource::MarkPrice {
+            let mark_price = self.price_scale(instrument_id).to_price(price);
+            if let Some(cb) = &mut self.circuit_breakers {
+                cb.update_price_band(instrument_id, mark_price);
+            }
+        }
+        let prices = match source {
+            TriggerSource::LastTrade => &mut self.last_prices,
+            TriggerSource::MarkPrice => &mut self.mark_prices,
+            TriggerSource::IndexPrice => &mut self.index_prices,
+        };
+        prices.insert(instrument_id.to_string(), price);
+        self.now = self.clock.now();
+        self.fire_triggers(instrument_id)
+    }
+
+    /// Current reference prices for an instrument
+    pub fn reference_prices(&self, instrument_id: &str) -> ReferencePrices {
+        ReferencePrices {
+            last_trade: self.last_prices.get(instrument_id).copied(),
+            mark: self.mark_prices.get(instrument_id).copied(),
+            index: self.index_prices.get(instrument_id).copied(),
+        }
+    }
+
+    /// Worst price a market order may trade at
+    ///
+    /// The band is taken around the mark price when one is known, otherwise
+    /// around the best opposite price. None if there is no reference at all.
+    /// The limit is rounded inward to a whole tick so it never exceeds the band.
+    fn market_protection_price(&self, order: &BookOrder) -> Option<Ticks> {
+        let book = self.books.get(&order.instrument_id);
+        let reference = self.mark_price(&order.instrument_id).or_else(|| match order.side {
+            OrderSide::Buy => book.and_then(|b| b.best_ask()),
+            OrderSide::Sell => book.and_then(|b| b.best_bid()),
+        })?;
+
+        let band = self.market_protection_percent / 100.0;
+        let reference = reference as f64;
+        Some(match order.side {
+            OrderSide::Buy => (reference * (1.0 + band)).floor() as Ticks,
+            OrderSide::Sell => ((reference * (1.0 - band)).ceil() as Ticks).max(0),
+        })
+    }
+
+    /// Get or create order book for instrument
+    fn get_or_create_book(&mut self, instrument_id: &str) -> &mut OrderBook {
+        if self.undo.is_recording() && !self.books.contains_key(instrument_id) {
+            self.undo.entries.push(Undo::Book(instrument_id.to_string()));
+        }
+        self.books
+            .entry(instrument_id.to_string())
+            .or_insert_with(|| OrderBook::new(instrument_id.to_string()))
+    }
+
+    /// Get next sequence number
+    fn next_sequence(&mut self) -> u64 {
+        advance_sequence(&mut self.sequence)
+    }
+
+    /// Set sequence number (for replay)
+    pub fn set_sequence(&mut self, seq: u64) {
+        self.sequence = seq;
+    }
+
+    /// Get current sequence number
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Match a new order against the book
+    ///
+    /// This is the core matching algorithm:
+    /// 1. Check FOK, all-or-none and minimum-quantity preconditions (liquidity check)
+    /// 2. Check if order crosses with opposite side
+    /// 3. Match greedily at best prices (FIFO within price level)
+    /// 4. Generate trades
+    /// 5. Update book
+    /// 6. Handle remainder based on time-in-force
+    ///
+    /// Market orders sweep the opposite side up to the protection band and
+    /// are always immediate: a GTC market order is treated as IOC, so the
+    /// remainder is cancelled and never rests. A market order that already
+    /// carries a price (e.g. replayed from the event log) uses it as its
+    /// protection limit.
+    ///
+    /// Post-only orders never take liquidity: a crossing one is rejected or,
+    /// with [`PostOnly::Reprice`], moved one tick behind the opposite best
+    /// price. All-or-none orders trade their whole quantity in one go or not
+    /// at all, both as taker and while resting.
+    ///
+    /// Iceberg orders rest showing one slice of `display_quantity` at a time.
+    /// When a slice is used up the next one is shown under a new sequence at
+    /// the back of the price level, so refreshes replay deterministically.
+    ///
+    /// Stop orders are not matched: they are sequenced and held in the
+    /// trigger book (see [`crate::trigger`]). Any stops released by this
+    /// order's trades are matched in turn and reported in `triggered`.
+    ///
+    /// Trades are stamped with the order's `received_at`; an order without
+    /// one is stamped from the engine's clock first.
+    pub fn match_order(&mut self, mut order: BookOrder) -> MatchResult {
+        let instrument_id = order.instrument_id.clone();
+        let received_at = *order.received_at.get_or_insert_with(|| self.clock.now());
+        self.now = received_at;
+
+        let mut result = if order.is_stop() {
+            self.accept_stop(order)
+        } else {
+            self.execute_order(order)
+        };
+
+        if result.sequence.is_some() {
+            result.triggered = self.fire_triggers(&instrument_id);
+            // Combo fills also move the prices of other instruments
+            for other in traded_instruments(&result.trades) {
+                if other != instrument_id {
+                    let triggered = self.fire_triggers(&other);
+                    result.triggered.extend(triggered);
+                }
+            }
+        }
+        result.received_at = Some(received_at);
+        result
+    }
+
+    /// Hold a stop order in its instrument's trigger book
+    fn accept_stop(&mut self, mut order: BookOrder) -> MatchResult {
+        let instrument_id = order.instrument_id.clone();
+
+        if self.is_halted(&instrument_id) {
+            warn!(
+                order_id = %order.order_id,
+                instrument = %instrument_id,
+                "Stop order rejected: circuit breaker halted"
+            );
+            return MatchResult::cancelled(order);
+        }
+
+        let stop_price = order.stop.map(|s| s.stop_price).unwrap_or_default();
+        if stop_price <= 0 {
+            info!(order_id = %order.order_id, stop_price, "Stop order rejected: invalid stop price");
+            return MatchResult::cancelled(order);
+        }
+
+        let sequence = self.next_sequence();
+        order.sequence = sequence;
+        self.stop_index.insert(order.order_id, instrument_id.clone());
+        self.trigger_books
+            .entry(instrument_id.clone())
+            .or_default()
+            .insert(order.clone());
+
+        info!(
+            order_id = %order.order_id,
+            instrument = %instrument_id,
+            stop_price,
+            sequence,
+            "Stop order accepted"
+        );
+
+        let mut result = MatchResult::no_match(order, false);
+        result.sequence = Some(sequence);
+        result
+    }
+
+    /// Release every stop whose condition now holds, one at a time
+    ///
+    /// Each release consumes a sequence number and is matched immediately;
+    /// its trades move the last price, so conditions are re-checked after
+    /// every release. Does nothing while replaying: logged trigger events
+    /// release stops instead.
+    fn fire_triggers(&mut self, instrument_id: &str) -> Vec<TriggeredStop> {
+        let mut fired = Vec::new();
+        if self.replaying {
+            return fired;
+        }
+
+        loop {
+            let prices = self.reference_prices(instrument_id);
+            let Some((mut order, trigger_price)) = self
+                .trigger_books
+                .get_mut(instrument_id)
+                .and_then(|book| book.pop_triggered(&prices))
+            else {
+                break;
+            };
+            self.stop_index.remove(&order.order_id);
+
+            let sequence = self.next_sequence();
+            order.stop = None;
+            order.received_at = Some(self.now);
+            info!(
+                order_id = %order.order_id,
+                instrument = %instrument_id,
+                trigger_price,
+                sequence,
+                "Stop order triggered"
+            );
+
+            let result = self.execute_order(order.clone());
+            fired.push(TriggeredStop {
+                order,
+                trigger_price,
+                sequence,
+                result,
+            });
+        }
+
+        fired
+    }
+
+    /// Release a held stop without matching it (replay of a logged trigger)
+    fn take_stop(&mut self, instrument_id: &str, order_id: Uuid) -> Option<BookOrder> {
+        let mut order = self.trigger_books.get_mut(instrument_id)?.remove(order_id)?;
+        self.stop_index.remove(&order_id);
+        order.stop = None;
+        Some(order)
+    }
+
+    /// Match a non-stop order, or collect it if its instrument is in a call auction
+    ///
+    /// A halt auction whose circuit breaker has expired is uncrossed first.
+    /// An order reaching a halted instrument outside an auction starts one.
+    /// Neither happens while replaying: logged auction events do that.
+    ///
+    /// Combo orders have no auctions: they are rejected while the combo or
+    /// any of its legs is halted or in an auction.
+    fn execute_order(&mut self, order: BookOrder) -> MatchResult {
+        let instrument_id = order.instrument_id.clone();
+
+        if let Some(combo) = self.combos.get(&instrument_id) {
+            let suspended = std::iter::once(instrument_id.as_str())
+                .chain(combo.legs.iter().map(|leg| leg.instrument_id.as_str()))
+                .any(|id| self.is_halted(id) || self.auctions.contains_key(id));
+            if suspended {
+                warn!(
+                    order_id = %order.order_id,
+                    instrument = %instrument_id,
+                    "Combo order rejected: combo or a leg is not trading continuously"
+                );
+                return MatchResult::cancelled(order);
+            }
+            return self.match_continuous(order);
+        }
+
+        let mut uncrossed = None;
+        if let Some(auction) = self.auctions.get(&instrument_id).copied() {
+            if auction.reason == AuctionReason::Halt && !self.replaying && !self.is_halted(&instrument_id) {
+                self.auctions.remove(&instrument_id);
+                let price = self.equilibrium(&instrument_id).map(|u| u.price);
+                uncrossed = Some(self.uncross_at(&instrument_id, auction, price));
+            }
+        }
+
+        let mut auction_started = None;
+        if self.is_halted(&instrument_id) && !self.auctions.contains_key(&instrument_id) {
+            warn!(instrument = %instrument_id, "Circuit breaker halted: starting call auction");
+            auction_started = self.start_auction(&instrument_id, AuctionReason::Halt).ok();
+        }
+
+        let mut result = if self.auctions.contains_key(&instrument_id) {
+            self.collect_auction_order(order)
+        } else {
+            self.match_continuous(order)
+        };
+        result.uncrossed = uncrossed;
+        result.auction_started = auction_started;
+        result
+    }
+
+    /// Match a non-stop order against the book in continuous trading
+    fn match_continuous(&mut self, mut order: BookOrder) -> MatchResult {
+        let start_time = Instant::now();
+        
+        // Record order received
+        if let Some(metrics) = self.metrics.as_ref().filter(|_| !self.replaying) {
+            metrics.record_order_received(&order.instrument_id);
+        }
+
+        info!(
+            order_id = %order.order_id,
+            instrument = %order.instrument_id,
+            side = ?order.side,
+            price = order.price,
+            quantity = order.quantity,
+            "Matching order"
+        );
+
+        let instrument_id = order.instrument_id.clone();
+
+        // Iceberg: needs a slice to show, and cannot be all-or-none
+        if order.display_quantity == Some(0) || (order.is_iceberg() && order.all_or_none) {
+            info!(
+                order_id = %order.order_id,
+                "Iceberg order rejected: invalid display quantity"
+            );
+            return MatchResult::cancelled(order);
+        }
+
+        // Market orders: fix the protection limit, then never rest
+        if order.is_market() {
+            if order.price <= 0 {
+                match self.market_protection_price(&order) {
+                    Some(limit) => order.price = limit,
+                    None => {
+                        info!(
+                            order_id = %order.order_id,
+                            instrument = %instrument_id,
+                            "Market order rejected: no reference price"
+                        );
+                        return MatchResult::cancelled(order);
+                    }
+                }
+            }
+            if order.time_in_force.rests() {
+                order.time_in_force = TimeInForce::Ioc;
+            }
+        }
+
+        // Price band: market orders are held inside it, limit orders
+        // priced through it are rejected
+        if let Some(band) = self.price_band(&instrument_id) {
+            let scale = self.price_scale(&instrument_id).with_off_tick(OffTickPolicy::Round);
+            if order.is_market() {
+                let edge = match order.side {
+                    OrderSide::Buy => band.upper,
+                    OrderSide::Sell => band.lower,
+                };
+                if let Ok(limit) = scale.order_ticks(edge, order.side) {
+                    order.price = match order.side {
+                        OrderSide::Buy => order.price.min(limit),
+                        OrderSide::Sell => order.price.max(limit),
+                    };
+                }
+            } else if !band.allows(order.side, scale.to_price(order.price)) {
+                info!(
+                    order_id = %order.order_id,
+                    instrument = %instrument_id,
+                    price = order.price,
+                    lower = band.lower,
+                    upper = band.upper,
+                    "Order rejected: priced outside the price band"
+                );
+                return MatchResult::cancelled(order);
+            }
+        }
+        let protection_price = order.is_market().then_some(order.price);
+
+        let (best_bid, best_ask) = self
+            .books
+            .get(&instrument_id)
+            .map_or((None, None), |book| (book.best_bid(), book.best_ask()));
+        let crosses = match order.side {
+            OrderSide::Buy => best_ask.is_some_and(|ask| ask <= order.price),
+            OrderSide::Sell => best_bid.is_some_and(|bid| bid >= order.price),
+        };
+
+        // Post-only: the order must rest without taking liquidity
+        let mut repriced_to = None;
+        if order.post_only != PostOnly::None {
+            if order.is_market() || !order.time_in_force.rests() {
+                info!(
+                    order_id = %order.order_id,
+                    "Post-only order rejected: must be a resting limit order"
+                );
+                return MatchResult::cancelled(order);
+            }
+            if crosses {
+                let price = match (order.post_only, order.side) {
+                    (PostOnly::Reprice, OrderSide::Buy) => best_ask.map(|ask| ask - 1),
+                    (PostOnly::Reprice, OrderSide::Sell) => best_bid.map(|bid| bid + 1),
+                    _ => None,
+                };
+                match price.filter(|p| *p > 0) {
+                    Some(price) => {
+                        order.price = price;
+                        repriced_to = Some(price);
+                    }
+                    None => {
+                        info!(
+                            order_id = %order.order_id,
+                            "Post-only order rejected: would take liquidity"
+                        );
+                        return MatchResult::cancelled(order);
+                    }
+                }
+            }
+        }
+
+        // Liquidity pre-check: validate BEFORE touching the book
+        // - FOK must fill in full
+        // - All-or-none must fill in full if it trades at all (otherwise it rests)
+        // - Minimum quantity must fill at least that much on arrival
+        let required = if order.time_in_force == TimeInForce::Fok
+            || (order.all_or_none && crosses)
+        {
+            order.quantity
+        } else {
+            order.min_quantity.unwrap_or(0).min(order.quantity)
+        };
+        // Combo orders are checked after matching instead (see `match_combo`)
+        let combo = self.combos.get(&instrument_id).cloned();
+        if required > 0 && combo.is_none() {
+            let available = self.get_or_create_book(&instrument_id).fillable_quantity(&order);
+
+            if available < required {
+                info!(
+                    order_id = %order.order_id,
+                    available = available,
+                    required = required,
+                    "Order rejected: insufficient liquidity"
+                );
+                return MatchResult::cancelled(order);
+            }
+        }
+
+        // Get sequence number first, then get book
+        let sequence = self.next_sequence();
+        order.sequence = sequence;
+        self.get_or_create_book(&instrument_id);
+
+        let mut result = match combo {
+            Some(combo) => self.match_combo(&combo, order),
+            None => self.match_outright(instrument_id.clone(), order),
+        };
+        result.sequence = Some(sequence);
+        result.protection_price = protection_price;
+        result.repriced_to = repriced_to;
+
+        // Rest the remainder (GTC) in the book
+        if result.should_insert {
+            if let Some(remaining) = result.remaining_order.as_mut() {
+                // Minimum quantity only applies on arrival
+                remaining.min_quantity = None;
+                // Icebergs rest showing their first slice (a replayed
+                // compacted order keeps the slice it had)
+                if remaining.needs_refresh() {
+                    remaining.show_next_slice();
+                }
+                self.order_index
+                    .insert(remaining.order_id, OrderLocation::of(remaining));
+                if remaining.quote {
+                    self.quote_orders.insert(remaining.order_id);
+                }
+                self.get_or_create_book(&instrument_id)
+                    .insert_order(remaining.clone());
+            }
+        }
+
+        for trade in &result.trades {
+            self.last_prices.insert(trade.instrument_id.clone(), trade.price);
+        }
+
+        // Check circuit breakers after trades
+        let traded = traded_instruments(&result.trades);
+        if traded.iter().all(|id| *id == instrument_id) {
+            self.check_circuit_breakers(&instrument_id, &result.trades);
+        } else {
+            for id in &traded {
+                let trades: Vec<Trade> = result
+                    .trades
+                    .iter()
+                    .filter(|t| t.instrument_id == *id)
+                    .cloned()
+                    .collect();
+                self.check_circuit_breakers(id, &trades);
+            }
+        }
+        result.mmp_triggered = self.check_mmp(&result.trades);
+
+        // Record metrics (replayed orders were counted when first matched)
+        if let Some(metrics) = self.metrics.as_ref().filter(|_| !self.replaying) {
+            // Record latency
+            let elapsed = start_time.elapsed();
+            metrics.record_latency(&instrument_id, elapsed);
+            
+            // Record trades
+            for trade in &result.trades {
+                metrics.record_trade(&trade.instrument_id, trade.quantity);
+            }
+            
+            // Record order status
+            if result.has_trades() {
+                metrics.record_order_matched(&instrument_id);
+            } else {
+                metrics.record_order_rejected(&instrument_id);
+            }
+        }
+        self.publish_book_metrics(&instrument_id);
+        for id in traded.iter().filter(|id| **id != instrument_id) {
+            self.publish_book_metrics(id);
+        }
+
+        result
+    }
+
+    /// Match an order against its own book only
+    fn match_side(&mut self, instrument_id: String, order: BookOrder) -> MatchResult {
+        match order.side {
+            OrderSide::Buy => self.match_buy(instrument_id, order),
+            OrderSide::Sell => self.match_sell(instrument_id, order),
+        }
+    }
+
+    /// Match an outright order, also against resting combo orders (implied-out)
+    ///
+    /// A resting combo order offers each of its legs at the price that,
+    /// with the other legs' best levels, nets to its combo price. Outright
+    /// orders at that price or better trade first. An implied fill trades
+    /// the incoming order with the combo order and fills the other legs
+    /// from their books, all legs or none.
+    ///
+    /// Post-only, all-or-none and FOK orders only trade outright, as their
+    /// pre-checks only count the outright book.
+    fn match_outright(&mut self, instrument_id: String, mut order: BookOrder) -> MatchResult {
+        let implied = order.post_only == PostOnly::None
+            && !order.all_or_none
+            && order.time_in_force != TimeInForce::Fok
+            && self.combos.values().any(|combo| combo.leg_index(&instrument_id).is_some());
+        if !implied {
+            return self.match_side(instrument_id, order);
+        }
+
+        let mut trades = Vec::new();
+        let mut stp_cancelled = Vec::new();
+        // Combo orders whose legs could not be filled
+        let mut blocked = HashSet::new();
+
+        while !order.is_filled() {
+            let side = order.side;
+            let Some(quote) = self
+                .implied_out(&order, &blocked)
+                .filter(|quote| crosses(side, quote.price, order.price))
+            else {
+                break;
+            };
+
+            let best_outright = self.books.get(&instrument_id).and_then(|book| match side {
+                OrderSide::Buy => book.best_ask(),
+                OrderSide::Sell => book.best_bid(),
+            });
+            if best_outright.is_some_and(|price| !improves(side, quote.price, price)) {
+                let mut capped = order.clone();
+                capped.price = quote.price;
+                capped.time_in_force = TimeInForce::Ioc;
+                let result = self.match_side(instrument_id.clone(), capped);
+                let progressed = result.has_trades() || !result.stp_cancelled.is_empty();
+                let taker_cancelled = result.stp_cancelled.contains(&order.order_id);
+                trades.extend(result.trades);
+                stp_cancelled.extend(result.stp_cancelled);
+                take_remainder(&mut order, result.remaining_order);
+                if taker_cancelled {
+                    let mut result = MatchResult::partial_match(trades, order, false);
+                    result.stp_cancelled = stp_cancelled;
+                    return result;
+                }
+                if progressed {
+                    continue;
+                }
+            }
+
+            match self.fill_implied_out(&order, &quote) {
+                Some((leg_trades, cancelled)) => {
+                    order.fill(quote.units * quote.combo.legs[quote.index].ratio);
+                    trades.extend(leg_trades);
+                    stp_cancelled.extend(cancelled);
+                }
+                None => {
+                    blocked.insert(quote.maker.order_id);
+                }
+            }
+        }
+
+        let mut result = if order.is_filled() {
+            MatchResult::fully_matched(Vec::new())
+        } else {
+            self.match_side(instrument_id, order)
+        };
+        trades.append(&mut result.trades);
+        result.trades = trades;
+        stp_cancelled.append(&mut result.stp_cancelled);
+        result.stp_cancelled = stp_cancelled;
+        result
+    }
+
+    /// Best resting combo order an outright order can trade against
+    fn implied_out(&self, order: &BookOrder, blocked: &HashSet<Uuid>) -> Option<ImpliedOut> {
+        let mut best: Option<ImpliedOut> = None;
+
+        for combo in self.combos.values() {
+            let Some(index) = combo.leg_index(&order.instrument_id) else {
+                continue;
+            };
+            if self.is_halted(&combo.combo_id) {
+                continue;
+            }
+            let Some(book) = self.books.get(&combo.combo_id) else {
+                continue;
+            };
+
+            // The combo order takes the other side of the incoming order's leg
+            let leg = &combo.legs[index];
+            let combo_side = if leg.side == order.side.opposite() {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            let mut makers: Box<dyn Iterator<Item = &BookOrder>> = match combo_side {
+                OrderSide::Buy => Box::new(book.bids.values().flatten()),
+                OrderSide::Sell => Box::new(book.asks.values().flatten()),
+            };
+            let Some(maker) = makers.find(|maker| {
+                !maker.all_or_none
+                    && !blocked.contains(&maker.order_id)
+                    && !order.prevents_self_trade_with(maker)
+            }) else {
+                continue;
+            };
+
+            let mut prices = vec![0; combo.legs.len()];
+            let mut units = maker.visible_quantity().min(order.quantity / leg.ratio);
+            let mut available = true;
+            for (i, other) in combo.legs.iter().enumerate().filter(|(i, _)| *i != index) {
+                match self.leg_top(&other.instrument_id, other.side_for(combo_side)) {
+                    Some((price, quantity)) => {
+                        prices[i] = price;
+                        units = units.min(quantity / other.ratio);
+                    }
+                    None => available = false,
+                }
+            }
+            if !available || units == 0 {
+                continue;
+            }
+
+            let price = combo.implied_leg_price(index, maker.price, &prices, order.side.opposite());
+            prices[index] = price;
+            if best.as_ref().is_none_or(|b| improves(order.side, price, b.price)) {
+                best = Some(ImpliedOut {
+                    price,
+                    units,
+                    combo: combo.clone(),
+                    index,
+                    maker: maker.clone(),
+                    prices,
+                });
+            }
+        }
+
+        best
+    }
+
+    /// Trade an outright order against a resting combo order
+    ///
+    /// The incoming order's leg trades with the combo order at the implied
+    /// price; the other legs are taken from their books on behalf of the
+    /// combo order. Returns the leg trades and self-trade cancellations, or
+    /// None (with every book restored) if a leg did not fill in full.
+    fn fill_implied_out(&mut self, order: &BookOrder, quote: &ImpliedOut) -> Option<(Vec<Trade>, Vec<Uuid>)> {
+        let combo = &quote.combo;
+        let checkpoint = self.checkpoint();
+        let mut trades = Vec::new();
+        let mut stp_cancelled = Vec::new();
+
+        for (i, leg) in combo.legs.iter().enumerate() {
+            let quantity = quote.units * leg.ratio;
+            if i == quote.index {
+                let sequence = self.next_sequence();
+                let (buyer_id, seller_id) = match order.side {
+                    OrderSide::Buy => (order.user_id, quote.maker.user_id),
+                    OrderSide::Sell => (quote.maker.user_id, order.user_id),
+                };
+                trades.push(
+                    Trade::new(
+                        self.ids.trade_id(&leg.instrument_id, sequence),
+                        leg.instrument_id.clone(),
+                        order.order_id,
+                        quote.maker.order_id,
+                        buyer_id,
+                        seller_id,
+                        quote.price,
+                        quantity,
+                        order.side,
+                        sequence,
+                        self.now,
+                    )
+                    .with_combo_id(&combo.combo_id),
+                );
+                continue;
+            }
+
+            let Some(result) = self.fill_leg(&quote.maker, leg, quote.prices[i], quantity) else {
+                debug!(combo = %combo.combo_id, leg = %leg.instrument_id, "Implied-out fill abandoned");
+                self.rollback(checkpoint);
+                return None;
+            };
+            trades.extend(result.trades.into_iter().map(|t| t.with_combo_id(&combo.combo_id)));
+            stp_cancelled.extend(result.stp_cancelled);
+        }
+        self.commit(checkpoint);
+
+        self.fill_resting(quote.maker.order_id, quote.units);
+        Some((trades, stp_cancelled))
+    }
+
+    /// Match a combo order against the combo book and, through the leg
+    /// books, against implied prices (implied-in)
+    ///
+    /// Each step trades at the better of the best opposite combo order and
+    /// the implied price of the legs' best levels; combo orders win ties.
+    /// A fill against a combo order is recorded as leg trades at the legs'
+    /// reference prices (see [`ComboDefinition::leg_prices`]); an implied
+    /// fill trades every leg at its best level, all legs or none.
+    ///
+    /// FOK, all-or-none and minimum-quantity conditions are checked after
+    /// matching, and everything is undone if they do not hold. Post-only
+    /// combo orders never take implied prices.
+    fn match_combo(&mut self, combo: &ComboDefinition, mut order: BookOrder) -> MatchResult {
+        let combo_id = combo.combo_id.clone();
+        let original = order.clone();
+        let conditional = order.time_in_force == TimeInForce::Fok
+            || order.all_or_none
+            || order.min_quantity.is_some();
+        let checkpoint = conditional.then(|| self.checkpoint());
+
+        let mut trades = Vec::new();
+        let mut stp_cancelled = Vec::new();
+        let mut filled = 0;
+        let mut taker_cancelled = false;
+        let mut direct = true;
+        let mut implied = order.post_only == PostOnly::None;
+
+        while !order.is_filled() {
+            let side = order.side;
+            let best_implied = self
+                .implied_in(combo, side)
+                .filter(|(price, ..)| implied && crosses(side, *price, order.price));
+            let best_direct = self
+                .books
+                .get(&combo_id)
+                .filter(|_| direct)
+                .and_then(|book| match side {
+                    OrderSide::Buy => book.best_ask(),
+                    OrderSide::Sell => book.best_bid(),
+                })
+                .filter(|price| crosses(side, *price, order.price))
+                .filter(|price| {
+                    best_implied
+                        .as_ref()
+                        .is_none_or(|(implied_price, ..)| !improves(side, *implied_price, *price))
+                });
+
+            if let Some(price) = best_direct {
+                let mut capped = order.clone();
+                capped.price = price;
+                capped.time_in_force = TimeInForce::Ioc;
+                let result = self.match_side(combo_id.clone(), capped);
+                let progressed = result.has_trades() || !result.stp_cancelled.is_empty();
+                taker_cancelled = result.stp_cancelled.contains(&order.order_id);
+                filled += result.filled_quantity();
+                for trade in &result.trades {
+                    let leg_trades = self.combo_leg_trades(combo, trade);
+                    trades.extend(leg_trades);
+                }
+                stp_cancelled.extend(result.stp_cancelled);
+                take_remainder(&mut order, result.remaining_order);
+                if taker_cancelled {
+                    break;
+                }
+                // Only all-or-none orders too large to fill rest there
+                if !progressed {
+                    direct = false;
+                }
+                continue;
+            }
+
+            let Some((_, units, prices)) = best_implied else {
+                break;
+            };
+            let units = units.min(order.quantity);
+            match self.fill_implied_in(combo, &order, units, &prices) {
+                Some((leg_trades, cancelled)) => {
+                    order.fill(units);
+                    filled += units;
+                    trades.extend(leg_trades);
+                    stp_cancelled.extend(cancelled);
+                }
+                None => implied = false,
+            }
+        }
+
+        if let Some(checkpoint) = checkpoint {
+            let required = if original.time_in_force == TimeInForce::Fok || (original.all_or_none && filled > 0) {
+                original.quantity
+            } else {
+                original.min_quantity.unwrap_or(0).min(original.quantity)
+            };
+            if filled < required {
+                self.rollback(checkpoint);
+                info!(
+                    order_id = %original.order_id,
+                    filled,
+                    required,
+                    "Combo order rejected: insufficient liquidity"
+                );
+                // An all-or-none order that cannot fill in full rests untouched
+                return if original.all_or_none && original.time_in_force.rests() && !taker_cancelled {
+                    MatchResult::no_match(original, true)
+                } else {
+                    MatchResult::cancelled(original)
+                };
+            }
+            self.commit(checkpoint);
+        }
+
+        let mut result = if taker_cancelled {
+            MatchResult::partial_match(trades, order, false)
+        } else if order.is_filled() {
+            MatchResult::fully_matched(trades)
+        } else {
+            let rests = order.time_in_force.rests();
+            MatchResult::partial_match(trades, order, rests)
+        };
+        result.stp_cancelled = stp_cancelled;
+        result
     }
 
-    /// Get current sequence number
-    pub fn sequence(&self) -> u64 {
-        self.sequence
+    /// Implied price of a combo from the best levels of its legs
+    ///
+    /// Returns the combo price, the units tradeable at it and the price of
+    /// each leg, or None if a leg has no liquidity to trade against.
+    fn implied_in(&self, combo: &ComboDefinition, side: OrderSide) -> Option<(Ticks, u32, Vec<Ticks>)> {
+        let mut units = u32::MAX;
+        let mut prices = Vec::with_capacity(combo.legs.len());
+        for leg in &combo.legs {
+            let (price, quantity) = self.leg_top(&leg.instrument_id, leg.side_for(side))?;
+            units = units.min(quantity / leg.ratio);
+            prices.push(price);
+        }
+        (units > 0).then(|| (combo.net_price(&prices), units, prices))
     }
 
-    /// Match a new order against the book
+    /// Trade `units` of a combo order against the best level of every leg
     ///
-    /// This is the core matching algorithm:
-    /// 1. Check FOK preconditions (liquidity check)
-    /// 2. Check if order crosses with opposite side
-    /// 3. Match greedily at best prices (FIFO within price level)
-    /// 4. Generate trades
-    /// 5. Update book
-    /// 6. Handle remainder based on time-in-force
-    pub fn match_order(&mut self, mut order: BookOrder) -> MatchResult {
-        let start_time = Instant::now();
-        
-        // Record order received
-        if let Some(ref metrics) = self.metrics {
-            metrics.record_order_received();
+    /// Returns the leg trades and self-trade cancellations, or None (with
+    /// the leg books restored) if a leg did not fill in full.
+    fn fill_implied_in(
+        &mut self,
+        combo: &ComboDefinition,
+        order: &BookOrder,
+        units: u32,
+        prices: &[Ticks],
+    ) -> Option<(Vec<Trade>, Vec<Uuid>)> {
+        let checkpoint = self.checkpoint();
+        let mut trades = Vec::new();
+        let mut stp_cancelled = Vec::new();
+
+        for (leg, price) in combo.legs.iter().zip(prices) {
+            let Some(result) = self.fill_leg(order, leg, *price, units * leg.ratio) else {
+                debug!(combo = %combo.combo_id, leg = %leg.instrument_id, "Implied-in fill abandoned");
+                self.rollback(checkpoint);
+                return None;
+            };
+            trades.extend(result.trades.into_iter().map(|t| t.with_combo_id(&combo.combo_id)));
+            stp_cancelled.extend(result.stp_cancelled);
         }
+        self.commit(checkpoint);
+        Some((trades, stp_cancelled))
+    }
 
-        info!(
-            order_id = %order.order_id,
-            instrument = %order.instrument_id,
-            side = ?order.side,
-            price = order.price,
-            quantity = order.quantity,
-            "Matching order"
-        );
+    /// Take one leg of a combo order from the leg's book at exactly `price`
+    ///
+    /// The leg trades as an IOC order under the combo order's ID and user.
+    /// Returns None unless the whole `quantity` filled.
+    fn fill_leg(&mut self, order: &BookOrder, leg: &ComboLeg, price: Ticks, quantity: u32) -> Option<MatchResult> {
+        let leg_order = BookOrder::new(
+            order.order_id,
+            order.user_id,
+            leg.side_for(order.side),
+            price,
+            quantity,
+            order.sequence,
+            TimeInForce::Ioc,
+        )
+        .with_instrument_id(leg.instrument_id.clone())
+        .with_stp(order.stp);
 
-        let instrument_id = order.instrument_id.clone();
+        self.get_or_create_book(&leg.instrument_id);
+        let result = self.match_side(leg.instrument_id.clone(), leg_order);
+        (result.filled_quantity() == quantity).then_some(result)
+    }
 
-        // Circuit breaker check - reject if halted
-        if let Some(ref mut cb) = self.circuit_breakers {
-            if cb.is_halted(&instrument_id) {
-                warn!(
-                    order_id = %order.order_id,
-                    instrument = %instrument_id,
-                    "Order rejected: circuit breaker halted"
-                );
-                return MatchResult::cancelled(order);
-            }
+    /// Best level of a leg book a combo can trade against on `leg_side`
+    ///
+    /// Returns its price and the quantity there that fills without
+    /// conditions (all-or-none orders are not counted), or None if there is
+    /// none or the leg is not trading continuously.
+    fn leg_top(&self, instrument_id: &str, leg_side: OrderSide) -> Option<(Ticks, u32)> {
+        if self.is_halted(instrument_id) || self.auctions.contains_key(instrument_id) {
+            return None;
         }
+        let book = self.books.get(instrument_id)?;
+        let levels: Box<dyn Iterator<Item = (Ticks, &VecDeque<BookOrder>)>> = match leg_side {
+            OrderSide::Buy => Box::new(book.asks.iter().map(|(price, queue)| (*price, queue))),
+            OrderSide::Sell => Box::new(book.bids.iter().map(|(price, queue)| (price.0, queue))),
+        };
+        levels
+            .map(|(price, queue)| {
+                let quantity = queue
+                    .iter()
+                    .filter(|order| !order.all_or_none)
+                    .map(|order| order.visible_quantity())
+                    .sum();
+                (price, quantity)
+            })
+            .find(|(_, quantity)| *quantity > 0)
+    }
 
-        // FOK pre-check: validate liquidity BEFORE touching the book
-        // Need to get book temporarily for FOK check
-        if order.time_in_force == TimeInForce::Fok {
-            let book = self.get_or_create_book(&instrument_id);
-            let available = match order.side {
-                OrderSide::Buy => book.available_ask_quantity_at_or_below(order.price),
-                OrderSide::Sell => book.available_bid_quantity_at_or_above(order.price),
-            };
+    /// Split a trade between two combo orders into its leg trades
+    ///
+    /// Every leg trade takes a fresh sequence, after any the match has
+    /// already used, so a combo trade's legs are numbered consecutively and
+    /// in the order they are published.
+    fn combo_leg_trades(&mut self, combo: &ComboDefinition, trade: &Trade) -> Vec<Trade> {
+        let references: Vec<Ticks> = combo
+            .legs
+            .iter()
+            .map(|leg| self.leg_reference_price(&leg.instrument_id))
+            .collect();
+        let prices = combo.leg_prices(trade.price, &references);
 
-            if available < order.quantity {
-                info!(
-                    order_id = %order.order_id,
-                    available = available,
-                    required = order.quantity,
-                    "FOK order rejected: insufficient liquidity"
-                );
-                return MatchResult::cancelled(order);
-            }
+        let mut trades = Vec::with_capacity(combo.legs.len());
+        for (leg, price) in combo.legs.iter().zip(prices) {
+            let sequence = self.next_sequence();
+            let (buyer_id, seller_id) = match leg.side {
+                OrderSide::Buy => (trade.buyer_id, trade.seller_id),
+                OrderSide::Sell => (trade.seller_id, trade.buyer_id),
+            };
+            trades.push(
+                Trade::new(
+                    self.ids.trade_id(&leg.instrument_id, sequence),
+                    leg.instrument_id.clone(),
+                    trade.taker_order_id,
+                    trade.maker_order_id,
+                    buyer_id,
+                    seller_id,
+                    price,
+                    trade.quantity * leg.ratio,
+                    leg.side_for(trade.aggressor_side),
+                    sequence,
+                    trade.timestamp,
+                )
+                .with_combo_id(&combo.combo_id),
+            );
         }
+        trades
+    }
 
-        // Get sequence number first, then get book
-        let sequence = self.next_sequence();
-        order.sequence = sequence;
+    /// Price a leg is recorded at in a fill between two combo orders,
+    /// before it is adjusted to the combo price
+    ///
+    /// The leg's last trade price, else the mid of its best bid and ask,
+    /// else whichever of them exists, else zero.
+    fn leg_reference_price(&self, instrument_id: &str) -> Ticks {
+        if let Some(&last) = self.last_prices.get(instrument_id) {
+            return last;
+        }
+        let book = self.books.get(instrument_id);
+        match (book.and_then(|b| b.best_bid()), book.and_then(|b| b.best_ask())) {
+            (Some(bid), Some(ask)) => (bid + ask) / 2,
+            (Some(price), None) | (None, Some(price)) => price,
+            (None, None) => 0,
+        }
+    }
 
-        let result = match order.side {
-            OrderSide::Buy => self.match_buy(instrument_id.clone(), order),
-            OrderSide::Sell => self.match_sell(instrument_id.clone(), order),
-        };
+    /// Start recording what a combo fill changes, to undo it if needed
+    ///
+    /// Checkpoints nest: each must be committed or rolled back, innermost
+    /// first.
+    fn checkpoint(&mut self) -> Checkpoint {
+        let mark = self.undo.entries.len();
+        self.undo.marks.push(mark);
+        Checkpoint {
+            mark,
+            sequence: self.sequence,
+        }
+    }
 
-        // Check circuit breakers after trades
-        self.check_circuit_breakers(&instrument_id, &result.trades);
+    /// Keep everything done since a checkpoint was taken
+    ///
+    /// An enclosing checkpoint can still undo it.
+    fn commit(&mut self, checkpoint: Checkpoint) {
+        let mark = self.undo.marks.pop();
+        debug_assert_eq!(mark, Some(checkpoint.mark), "checkpoints close innermost first");
+        if self.undo.marks.is_empty() {
+            self.undo.entries.clear();
+        }
+    }
 
-        // Record metrics
-        if let Some(ref metrics) = self.metrics {
-            // Record latency
-            let elapsed = start_time.elapsed();
-            metrics.record_latency(elapsed);
-            
-            // Record trades
-            for trade in &result.trades {
-                metrics.record_trade(trade.quantity);
-            }
-            
-            // Record order status
-            if result.has_trades() {
-                metrics.record_order_matched();
-            } else {
-                metrics.record_order_rejected();
-            }
-            
-            // Update book depth
-            if let Some(book) = self.books.get(&instrument_id) {
-                let depth = book.bids.values().map(|v| v.len()).sum::<usize>() 
-                    + book.asks.values().map(|v| v.len()).sum::<usize>();
-                metrics.set_order_book_depth(depth as u64);
-                
-                if let Some(spread) = book.spread() {
-                    // Convert spread to basis points
-                    let spread_bps = if spread > 0.0 && book.best_bid().is_some() {
-                        (spread / book.best_bid().unwrap() * 10000.0) as u64
-                    } else {
-                        0
+    /// Undo everything done to the books since a checkpoint was taken
+    ///
+    /// Combo fills only take makers out of their levels (leg orders never
+    /// rest), so putting back the levels they touched, re-indexing their
+    /// orders and dropping the books they created is enough.
+    fn rollback(&mut self, checkpoint: Checkpoint) {
+        let mark = self.undo.marks.pop();
+        debug_assert_eq!(mark, Some(checkpoint.mark), "checkpoints close innermost first");
+        for entry in self.undo.entries.drain(checkpoint.mark..).rev() {
+            match entry {
+                Undo::Level { instrument_id, side, price, queue } => {
+                    for order in &queue {
+                        self.order_index.insert(order.order_id, OrderLocation::of(order));
+                    }
+                    let book = self
+                        .books
+                        .entry(instrument_id.clone())
+                        .or_insert_with(|| OrderBook::new(instrument_id));
+                    match side {
+                        OrderSide::Buy => book.bids.insert(std::cmp::Reverse(price), queue),
+                        OrderSide::Sell => book.asks.insert(price, queue),
                     };
-                    metrics.set_spread(spread_bps);
+                }
+                Undo::Book(instrument_id) => {
+                    self.books.remove(&instrument_id);
                 }
             }
         }
-
-        result
+        self.sequence = checkpoint.sequence;
     }
 
     /// Match a buy order against asks
     fn match_buy(&mut self, instrument_id: String, mut order: BookOrder) -> MatchResult {
         let mut trades = Vec::new();
+        let algorithm = self.matching_algorithm(&instrument_id);
         
         // Collect match data first, then create trades to avoid borrow issues
         let mut matches = Vec::new();
+        let mut filled_makers = Vec::new();
+        let mut stp_cancelled = Vec::new();
+        let mut taker_stp_cancelled = false;
         {
             let book = self.books.get_mut(&instrument_id)
                 .expect("Book should exist after get_or_create_book");
 
+            // Last level passed over because only all-or-none orders too
+            // large to fill rest there
+            let mut skipped_level = None;
+
             // Match against asks (sell side)
             loop {
                 // Check if we have any asks
-                let best_ask_price = match book.asks.keys().next() {
-                    Some(price) => price.0,
+                let next_level = match skipped_level {
+                    Some(price) => book.asks.range((Excluded(price), Unbounded)).next(),
+                    None => book.asks.iter().next(),
+                };
+                let best_ask_price = match next_level {
+                    Some((price, _)) => *price,
                     None => break, // No sellers
                 };
 
@@ -277,16 +2126,66 @@ impl MatchingEngine {
                 }
 
                 // Get orders at this price level (FIFO)
-                let price_key = ordered_float::OrderedFloat(best_ask_price);
+                let price_key = best_ask_price;
+                self.undo.save_level(&instrument_id, book, OrderSide::Sell, price_key);
                 let ask_queue = match book.asks.get_mut(&price_key) {
                     Some(q) => q,
                     None => break,
                 };
 
-                // Match with first order in queue (FIFO = time priority)
-                if let Some(mut ask_order) = ask_queue.pop_front() {
-                    // Calculate trade quantity
-                    let trade_qty = order.quantity.min(ask_order.quantity);
+                // Match with first order in queue we can trade with (FIFO = time
+                // priority). Pro-rata levels take self-trade conflicts out the
+                // same way, then share the taker across the rest of the level.
+                let Some(index) = ask_queue.iter().position(|maker| {
+                    maker.can_fill_against(order.quantity)
+                        && (algorithm.is_fifo() || order.prevents_self_trade_with(maker))
+                }) else {
+                    if !algorithm.is_fifo()
+                        && ask_queue.iter().any(|maker| maker.can_fill_against(order.quantity))
+                    {
+                        allocate_level(
+                            algorithm,
+                            ask_queue,
+                            &mut order,
+                            &mut self.sequence,
+                            &mut matches,
+                            &mut filled_makers,
+                        );
+                        if ask_queue.is_empty() {
+                            book.asks.remove(&price_key);
+                        }
+                        if order.is_filled() {
+                            break;
+                        }
+                        continue;
+                    }
+                    skipped_level = Some(price_key);
+                    continue;
+                };
+                if let Some(mut ask_order) = ask_queue.remove(index) {
+                    // Self-trade prevention: never match the taker's own order
+                    if order.prevents_self_trade_with(&ask_order) {
+                        let (maker_cancelled, taker_cancelled) =
+                            order.stp.apply(&mut order, &mut ask_order);
+                        if maker_cancelled {
+                            stp_cancelled.push(ask_order.order_id);
+                            filled_makers.push(ask_order.order_id);
+                        } else {
+                            requeue_maker(ask_queue, index, ask_order, &mut self.sequence);
+                        }
+                        if ask_queue.is_empty() {
+                            book.asks.remove(&price_key);
+                        }
+                        if taker_cancelled {
+                            stp_cancelled.push(order.order_id);
+                            taker_stp_cancelled = true;
+                            break;
+                        }
+                        continue;
+                    }
+
+                    // Calculate trade quantity (icebergs trade their shown slice)
+                    let trade_qty = order.quantity.min(ask_order.visible_quantity());
 
                     // Store match data
                     matches.push((
@@ -300,9 +2199,17 @@ impl MatchingEngine {
                     order.fill(trade_qty);
                     ask_order.fill(trade_qty);
 
-                    // If ask order not fully filled, put it back at front
+                    // If ask order not fully filled, put it back in place
+                    // (or at the back with a fresh iceberg slice)
                     if !ask_order.is_filled() {
-                        ask_queue.push_front(ask_order);
+                        requeue_maker(ask_queue, index, ask_order, &mut self.sequence);
+                    } else {
+                        filled_makers.push(ask_order.order_id);
+                    }
+
+                    // Drop the level once exhausted so the next best price is visible
+                    if ask_queue.is_empty() {
+                        book.asks.remove(&price_key);
                     }
 
                     // If our order is fully filled, we're done
@@ -318,9 +2225,16 @@ impl MatchingEngine {
             book.cleanup_empty_levels();
         }
 
+        // Filled and STP-cancelled makers are no longer resting
+        for order_id in filled_makers {
+            self.order_index.remove(&order_id);
+        }
+
         // Now create trades with sequence numbers (no borrow conflict)
         for (maker_order_id, maker_user_id, price, qty) in matches {
+            let sequence = self.next_sequence();
             let trade = Trade::new(
+                self.ids.trade_id(&instrument_id, sequence),
                 instrument_id.clone(),
                 order.order_id,       // Taker (aggressor)
                 maker_order_id,       // Maker (resting)
@@ -329,7 +2243,8 @@ impl MatchingEngine {
                 price,                // MAKER PRICE (critical!)
                 qty,
                 OrderSide::Buy,       // Aggressor side
-                self.next_sequence(),
+                sequence,
+                self.now,
             );
 
             debug!(
@@ -343,12 +2258,15 @@ impl MatchingEngine {
         }
 
         // Handle remainder based on time-in-force
-        if order.is_filled() {
+        let mut result = if taker_stp_cancelled {
+            // Self-trade prevention cancelled the taker; nothing rests
+            MatchResult::partial_match(trades, order, false)
+        } else if order.is_filled() {
             MatchResult::fully_matched(trades)
         } else {
             match order.time_in_force {
-                TimeInForce::Gtc => {
-                    // Good Till Cancel - insert into book
+                TimeInForce::Gtc | TimeInForce::Gtd | TimeInForce::Day => {
+                    // Resting time-in-force - insert into book (GTD/DAY until expiry)
                     MatchResult::partial_match(trades, order, true)
                 }
                 TimeInForce::Ioc => {
@@ -361,24 +2279,38 @@ impl MatchingEngine {
                     MatchResult::cancelled(order)
                 }
             }
-        }
+        };
+        result.stp_cancelled = stp_cancelled;
+        result
     }
 
     /// Match a sell order against bids
     fn match_sell(&mut self, instrument_id: String, mut order: BookOrder) -> MatchResult {
         let mut trades = Vec::new();
+        let algorithm = self.matching_algorithm(&instrument_id);
         
         // Collect match data first, then create trades to avoid borrow issues
         let mut matches = Vec::new();
+        let mut filled_makers = Vec::new();
+        let mut stp_cancelled = Vec::new();
+        let mut taker_stp_cancelled = false;
         {
             let book = self.books.get_mut(&instrument_id)
                 .expect("Book should exist after get_or_create_book");
 
+            // Last level passed over because only all-or-none orders too
+            // large to fill rest there
+            let mut skipped_level = None;
+
             // Match against bids (buy side)
             loop {
                 // Check if we have any bids
-                let best_bid_price = match book.bids.keys().next() {
-                    Some(reverse_price) => reverse_price.0 .0,
+                let next_level = match skipped_level {
+                    Some(price) => book.bids.range((Excluded(price), Unbounded)).next(),
+                    None => book.bids.iter().next(),
+                };
+                let best_bid_price = match next_level {
+                    Some((reverse_price, _)) => reverse_price.0,
                     None => break, // No buyers
                 };
 
@@ -389,16 +2321,66 @@ impl MatchingEngine {
                 }
 
                 // Get orders at this price level (FIFO)
-                let price_key = std::cmp::Reverse(ordered_float::OrderedFloat(best_bid_price));
+                let price_key = std::cmp::Reverse(best_bid_price);
+                self.undo.save_level(&instrument_id, book, OrderSide::Buy, best_bid_price);
                 let bid_queue = match book.bids.get_mut(&price_key) {
                     Some(q) => q,
                     None => break,
                 };
 
-                // Match with first order in queue (FIFO = time priority)
-                if let Some(mut bid_order) = bid_queue.pop_front() {
-                    // Calculate trade quantity
-                    let trade_qty = order.quantity.min(bid_order.quantity);
+                // Match with first order in queue we can trade with (FIFO = time
+                // priority). Pro-rata levels take self-trade conflicts out the
+                // same way, then share the taker across the rest of the level.
+                let Some(index) = bid_queue.iter().position(|maker| {
+                    maker.can_fill_against(order.quantity)
+                        && (algorithm.is_fifo() || order.prevents_self_trade_with(maker))
+                }) else {
+                    if !algorithm.is_fifo()
+                        && bid_queue.iter().any(|maker| maker.can_fill_against(order.quantity))
+                    {
+                        allocate_level(
+                            algorithm,
+                            bid_queue,
+                            &mut order,
+                            &mut self.sequence,
+                            &mut matches,
+                            &mut filled_makers,
+                        );
+                        if bid_queue.is_empty() {
+                            book.bids.remove(&price_key);
+                        }
+                        if order.is_filled() {
+                            break;
+                        }
+                        continue;
+                    }
+                    skipped_level = Some(price_key);
+                    continue;
+                };
+                if let Some(mut bid_order) = bid_queue.remove(index) {
+                    // Self-trade prevention: never match the taker's own order
+                    if order.prevents_self_trade_with(&bid_order) {
+                        let (maker_cancelled, taker_cancelled) =
+                            order.stp.apply(&mut order, &mut bid_order);
+                        if maker_cancelled {
+                            stp_cancelled.push(bid_order.order_id);
+                            filled_makers.push(bid_order.order_id);
+                        } else {
+                            requeue_maker(bid_queue, index, bid_order, &mut self.sequence);
+                        }
+                        if bid_queue.is_empty() {
+                            book.bids.remove(&price_key);
+                        }
+                        if taker_cancelled {
+                            stp_cancelled.push(order.order_id);
+                            taker_stp_cancelled = true;
+                            break;
+                        }
+                        continue;
+                    }
+
+                    // Calculate trade quantity (icebergs trade their shown slice)
+                    let trade_qty = order.quantity.min(bid_order.visible_quantity());
 
                     // Store match data
                     matches.push((
@@ -412,9 +2394,17 @@ impl MatchingEngine {
                     order.fill(trade_qty);
                     bid_order.fill(trade_qty);
 
-                    // If bid order not fully filled, put it back at front
+                    // If bid order not fully filled, put it back in place
+                    // (or at the back with a fresh iceberg slice)
                     if !bid_order.is_filled() {
-                        bid_queue.push_front(bid_order);
+                        requeue_maker(bid_queue, index, bid_order, &mut self.sequence);
+                    } else {
+                        filled_makers.push(bid_order.order_id);
+                    }
+
+                    // Drop the level once exhausted so the next best price is visible
+                    if bid_queue.is_empty() {
+                        book.bids.remove(&price_key);
                     }
 
                     // If our order is fully filled, we're done
@@ -430,9 +2420,16 @@ impl MatchingEngine {
             book.cleanup_empty_levels();
         }
 
+        // Filled and STP-cancelled makers are no longer resting
+        for order_id in filled_makers {
+            self.order_index.remove(&order_id);
+        }
+
         // Now create trades with sequence numbers (no borrow conflict)
         for (maker_order_id, maker_user_id, price, qty) in matches {
+            let sequence = self.next_sequence();
             let trade = Trade::new(
+                self.ids.trade_id(&instrument_id, sequence),
                 instrument_id.clone(),
                 order.order_id,       // Taker (aggressor)
                 maker_order_id,       // Maker (resting)
@@ -441,7 +2438,8 @@ impl MatchingEngine {
                 price,                // MAKER PRICE (critical!)
                 qty,
                 OrderSide::Sell,      // Aggressor side
-                self.next_sequence(),
+                sequence,
+                self.now,
             );
 
             debug!(
@@ -455,38 +2453,613 @@ impl MatchingEngine {
         }
 
         // Handle remainder based on time-in-force
-        if order.is_filled() {
+        let mut result = if taker_stp_cancelled {
+            // Self-trade prevention cancelled the taker; nothing rests
+            MatchResult::partial_match(trades, order, false)
+        } else if order.is_filled() {
             MatchResult::fully_matched(trades)
         } else {
             match order.time_in_force {
-                TimeInForce::Gtc => {
-                    // Good Till Cancel - insert into book
+                TimeInForce::Gtc | TimeInForce::Gtd | TimeInForce::Day => {
+                    // Resting time-in-force - insert into book (GTD/DAY until expiry)
                     MatchResult::partial_match(trades, order, true)
                 }
                 TimeInForce::Ioc => {
                     // Immediate or Cancel - cancel remainder
                     MatchResult::partial_match(trades, order, false)
                 }
-                TimeInForce::Fok => {
-                    // FOK pre-check ensures we have enough liquidity
-                    // If we get here with remainder, reject (shouldn't happen in single-threaded)
-                    MatchResult::cancelled(order)
+                TimeInForce::Fok => {
+                    // FOK pre-check ensures we have enough liquidity
+                    // If we get here with remainder, reject (shouldn't happen in single-threaded)
+                    MatchResult::cancelled(order)
+                }
+            }
+        };
+        result.stp_cancelled = stp_cancelled;
+        result
+    }
+
+    /// Cancel an order from the book
+    ///
+    /// Returns `None` if the order is not resting in `instrument_id`'s book.
+    /// A successful cancel consumes a sequence number, so every book
+    /// mutation is ordered relative to snapshots and the event log.
+    pub fn cancel_order(&mut self, instrument_id: &str, order_id: Uuid) -> Option<BookOrder> {
+        let owned = match self.order_index.get(&order_id) {
+            Some(location) => location.instrument_id == instrument_id,
+            None => self.stop_index.get(&order_id).map(String::as_str) == Some(instrument_id),
+        };
+        if owned {
+            self.cancel_by_id(order_id)
+        } else {
+            None
+        }
+    }
+
+    /// Cancel a resting order by ID alone
+    ///
+    /// Uses the order-id index, so the cost does not depend on the number of
+    /// instruments or price levels. Held stop orders are cancelled too.
+    pub fn cancel_by_id(&mut self, order_id: Uuid) -> Option<BookOrder> {
+        let removed = self.remove_order(order_id)?;
+        let sequence = self.next_sequence();
+        if removed.is_stop() {
+            info!(order_id = %order_id, instrument = %removed.instrument_id, sequence, "Stop order cancelled");
+        } else {
+            info!(
+                order_id = %order_id,
+                instrument = %removed.instrument_id,
+                sequence,
+                "Order cancelled"
+            );
+        }
+        Some(removed)
+    }
+
+    /// Cancel every resting order and held stop selected by `filter`
+    ///
+    /// The orders are removed in sequence order as one operation that
+    /// consumes a single sequence number; nothing is consumed if no order
+    /// matches.
+    pub fn mass_cancel(&mut self, filter: &MassCancelFilter) -> MassCancelResult {
+        let mut selected: Vec<(u64, Uuid)> = self
+            .books
+            .values()
+            .flat_map(|book| book.orders())
+            .chain(self.trigger_books.values().flat_map(|book| book.orders()))
+            .filter(|o| filter.matches(o))
+            .map(|o| (o.sequence, o.order_id))
+            .collect();
+        selected.sort_unstable();
+
+        let order_ids: Vec<Uuid> = selected.into_iter().map(|(_, order_id)| order_id).collect();
+        self.remove_orders(&order_ids)
+    }
+
+    /// Remove a list of orders as one sequenced operation
+    fn remove_orders(&mut self, order_ids: &[Uuid]) -> MassCancelResult {
+        let cancelled: Vec<BookOrder> = order_ids
+            .iter()
+            .filter_map(|order_id| self.remove_order(*order_id))
+            .collect();
+        let sequence = (!cancelled.is_empty()).then(|| self.next_sequence());
+
+        if let Some(sequence) = sequence {
+            info!(orders = cancelled.len(), sequence, "Orders mass cancelled");
+        }
+
+        MassCancelResult { cancelled, sequence }
+    }
+
+    /// Remove every GTD or DAY order whose expiry is at or before `now`
+    ///
+    /// The engine keeps no clock of its own: the caller passes the sweep
+    /// time. Each expired order consumes its own sequence number, in the
+    /// order the orders were accepted.
+    pub fn expire_orders(&mut self, now: DateTime<Utc>) -> Vec<ExpiredOrder> {
+        let mut due: Vec<(u64, Uuid)> = self
+            .books
+            .values()
+            .flat_map(|book| book.orders())
+            .chain(self.trigger_books.values().flat_map(|book| book.orders()))
+            .filter(|o| o.is_expired(now))
+            .map(|o| (o.sequence, o.order_id))
+            .collect();
+        due.sort_unstable();
+
+        due.into_iter()
+            .filter_map(|(_, order_id)| self.expire_by_id(order_id))
+            .collect()
+    }
+
+    /// Remove a single expired order as one sequenced operation
+    fn expire_by_id(&mut self, order_id: Uuid) -> Option<ExpiredOrder> {
+        let order = self.remove_order(order_id)?;
+        let sequence = self.next_sequence();
+        info!(order_id = %order_id, instrument = %order.instrument_id, sequence, "Order expired");
+        Some(ExpiredOrder { order, sequence })
+    }
+
+    /// Take a resting order or held stop out of its book without sequencing
+    fn remove_order(&mut self, order_id: Uuid) -> Option<BookOrder> {
+        self.quote_orders.remove(&order_id);
+        if let Some(instrument_id) = self.stop_index.remove(&order_id) {
+            return self
+                .trigger_books
+                .get_mut(&instrument_id)
+                .and_then(|book| book.remove(order_id));
+        }
+
+        let location = self.order_index.remove(&order_id)?;
+        let removed = self
+            .books
+            .get_mut(&location.instrument_id)
+            .and_then(|book| book.remove_order_at(location.side, location.price, order_id));
+        self.publish_book_metrics(&location.instrument_id);
+        removed
+    }
+
+    /// Amend a resting order's price and/or open quantity
+    ///
+    /// Priority rules:
+    /// - Quantity decrease at the same price keeps the order's place in the queue
+    /// - Price change or quantity increase re-queues the order with a new
+    ///   sequence; a re-priced order that crosses the book matches first
+    ///
+    /// Unknown or already-filled orders are rejected. Only an applied amend
+    /// consumes a sequence number; a rejected amend leaves it unchanged.
+    ///
+    /// A re-queued post-only order must not cross, and a crossing
+    /// all-or-none order must be able to fill in full.
+    pub fn amend_order(
+        &mut self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+    ) -> Result<AmendResult, MatchingError> {
+        let now = self.clock.now();
+        self.amend_order_at(order_id, new_price, new_quantity, now)
+    }
+
+    /// Amend an order with the time the amend was received
+    fn amend_order_at(
+        &mut self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+        now: DateTime<Utc>,
+    ) -> Result<AmendResult, MatchingError> {
+        self.now = now;
+        let previous = self
+            .get_order(order_id)
+            .cloned()
+            .ok_or_else(|| MatchingError::OrderNotFound(order_id.to_string()))?;
+
+        let price = new_price.unwrap_or(previous.price);
+        let quantity = new_quantity.unwrap_or(previous.quantity);
+
+        if price <= 0 {
+            return Err(MatchingError::InvalidOrder("Price must be greater than 0".to_string()));
+        }
+        if quantity == 0 {
+            return Err(MatchingError::InvalidOrder(
+                "Amended quantity must be greater than 0 (cancel instead)".to_string(),
+            ));
+        }
+        if price == previous.price && quantity == previous.quantity {
+            return Err(MatchingError::InvalidOrder("Amend does not change the order".to_string()));
+        }
+
+        let instrument_id = previous.instrument_id.clone();
+
+        // Quantity decrease in place: keep time priority
+        if price == previous.price && quantity < previous.quantity {
+            let sequence = self.next_sequence();
+            let book = self.books.get_mut(&instrument_id).expect("indexed order has a book");
+            let order = book
+                .get_order_at_mut(previous.side, previous.price, order_id)
+                .expect("indexed order is in its level");
+            order.quantity = quantity;
+            order.shown_quantity = order.shown_quantity.min(quantity);
+            let resting = order.clone();
+
+            info!(order_id = %order_id, quantity, sequence, "Order amended in place");
+
+            return Ok(AmendResult {
+                previous,
+                resting: Some(resting),
+                priority_retained: true,
+                trades: Vec::new(),
+                stp_cancelled: Vec::new(),
+                sequence,
+                timestamp: now,
+                triggered: Vec::new(),
+                mmp_triggered: Vec::new(),
+            });
+        }
+
+        // A re-queued combo order would need implied matching again
+        if self.combos.contains_key(&instrument_id) {
+            return Err(MatchingError::InvalidOrder(
+                "Combo orders can only be amended down in quantity (cancel and resubmit to re-price)"
+                    .to_string(),
+            ));
+        }
+
+        // Re-queue: during a call auction the order is collected again
+        // without matching; otherwise a re-priced order may cross, so
+        // respect halts
+        let in_auction = self.auctions.contains_key(&instrument_id);
+        if !in_auction && self.is_halted(&instrument_id) {
+            return Err(MatchingError::CircuitBreaker(format!(
+                "Instrument {} is halted",
+                instrument_id
+            )));
+        }
+
+        if let Some(band) = self.price_band(&instrument_id).filter(|_| !in_auction) {
+            if !band.allows(previous.side, self.price_scale(&instrument_id).to_price(price)) {
+                return Err(MatchingError::CircuitBreaker(format!(
+                    "Price is outside the band {}-{} of instrument {}",
+                    band.lower, band.upper, instrument_id
+                )));
+            }
+        }
+
+        let mut amended = previous.clone();
+        amended.price = price;
+        amended.quantity = quantity;
+
+        // Execution instructions still hold for the re-queued order
+        if let Some(book) = self.books.get(&instrument_id).filter(|_| !in_auction) {
+            let crosses = match amended.side {
+                OrderSide::Buy => book.best_ask().is_some_and(|ask| ask <= price),
+                OrderSide::Sell => book.best_bid().is_some_and(|bid| bid >= price),
+            };
+            if crosses && amended.post_only != PostOnly::None {
+                return Err(MatchingError::InvalidOrder(
+                    "Post-only amend would take liquidity".to_string(),
+                ));
+            }
+            if crosses && amended.all_or_none && book.fillable_quantity(&amended) < quantity {
+                return Err(MatchingError::InvalidOrder(
+                    "All-or-none amend cannot fill in full".to_string(),
+                ));
+            }
+        }
+
+        self.order_index.remove(&order_id);
+        if let Some(book) = self.books.get_mut(&instrument_id) {
+            book.remove_order_at(previous.side, previous.price, order_id);
+        }
+
+        let sequence = self.next_sequence();
+        amended.sequence = sequence;
+        amended.shown_quantity = 0;
+
+        let result = match amended.side {
+            _ if in_auction => MatchResult::no_match(amended, true),
+            OrderSide::Buy => self.match_buy(instrument_id.clone(), amended),
+            OrderSide::Sell => self.match_sell(instrument_id.clone(), amended),
+        };
+
+        let mut resting = result.remaining_order.filter(|_| result.should_insert);
+        if let Some(order) = resting.as_mut() {
+            order.show_next_slice();
+            self.order_index.insert(order.order_id, OrderLocation::of(order));
+            self.get_or_create_book(&instrument_id).insert_order(order.clone());
+        }
+
+        if let Some(last_trade) = result.trades.last() {
+            self.last_prices.insert(instrument_id.clone(), last_trade.price);
+        }
+        self.check_circuit_breakers(&instrument_id, &result.trades);
+        let mmp_triggered = self.check_mmp(&result.trades);
+        self.publish_book_metrics(&instrument_id);
+
+        info!(
+            order_id = %order_id,
+            price,
+            quantity,
+            trades = result.trades.len(),
+            sequence,
+            "Order amended and re-queued"
+        );
+
+        Ok(AmendResult {
+            previous,
+            resting,
+            priority_retained: false,
+            trades: result.trades,
+            stp_cancelled: result.stp_cancelled,
+            sequence,
+            timestamp: now,
+            triggered: self.fire_triggers(&instrument_id),
+            mmp_triggered,
+        })
+    }
+
+    /// Look up a resting order by ID
+    pub fn get_order(&self, order_id: Uuid) -> Option<&BookOrder> {
+        let location = self.order_index.get(&order_id)?;
+        self.books
+            .get(&location.instrument_id)?
+            .get_order_at(location.side, location.price, order_id)
+    }
+
+    /// Number of resting orders across all books
+    pub fn resting_order_count(&self) -> usize {
+        self.order_index.len()
+    }
+
+    /// Look up a held stop order by ID
+    pub fn get_stop_order(&self, order_id: Uuid) -> Option<&BookOrder> {
+        let instrument_id = self.stop_index.get(&order_id)?;
+        self.trigger_books.get(instrument_id)?.get(order_id)
+    }
+
+    /// Number of stop orders waiting for their trigger
+    pub fn stop_order_count(&self) -> usize {
+        self.stop_index.len()
+    }
+
+    /// Rebuild engine state by replaying events from the event log
+    ///
+    /// Accepted orders are re-run through `match_order` at their original
+    /// sequence, cancellations are re-applied and sequence resets restore the
+    /// counter. Stops are held and released, and call auctions started and
+    /// uncrossed, and combos defined, exactly as logged. Trade and
+    /// self-trade prevention events are derived from the orders and are skipped
+    /// (block trades touch no book, so only their sequence numbers count).
+    /// Circuit breakers are suspended for the duration of the replay since
+    /// every logged order already passed them.
+    pub fn replay(&mut self, events: &[MatchingEvent]) {
+        let circuit_breakers = self.circuit_breakers.take();
+        self.replaying = true;
+
+        for event in events {
+            self.replay_event(event);
+        }
+
+        self.replaying = false;
+        self.circuit_breakers = circuit_breakers;
+
+        info!(
+            events = events.len(),
+            sequence = self.sequence,
+            books = self.books.len(),
+            "Matching engine state replayed"
+        );
+    }
+
+    /// Apply one logged event, as `replay` does, to mirror another engine
+    ///
+    /// Used by followers of a live event stream; see `book_feed`.
+    pub fn follow(&mut self, event: &MatchingEvent) {
+        let circuit_breakers = self.circuit_breakers.take();
+        self.replaying = true;
+        self.replay_event(event);
+        self.replaying = false;
+        self.circuit_breakers = circuit_breakers;
+    }
+
+    /// Apply one logged event (replay mode must be on)
+    fn replay_event(&mut self, event: &MatchingEvent) {
+        match event {
+            MatchingEvent::OrderAccepted { order, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                self.match_order(order.clone());
+            }
+            MatchingEvent::OrderCancelled { order_id, instrument_id, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                self.cancel_order(instrument_id, *order_id);
+            }
+            MatchingEvent::OrdersMassCancelled { order_ids, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                self.remove_orders(order_ids);
+            }
+            MatchingEvent::OrderExpired { order_id, sequence, .. } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                if self.expire_by_id(*order_id).is_none() {
+                    warn!(order_id = %order_id, "Logged expiry has no resting order on replay");
+                }
+            }
+            MatchingEvent::OrderAmended { order_id, new_price, new_quantity, sequence, timestamp, .. } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                let now = timestamp.unwrap_or(self.now);
+                if let Err(e) = self.amend_order_at(*order_id, *new_price, *new_quantity, now) {
+                    warn!(order_id = %order_id, error = %e, "Logged amend failed on replay");
+                }
+            }
+            MatchingEvent::StopTriggered { order_id, instrument_id, .. } => {
+                if self.take_stop(instrument_id, *order_id).is_none() {
+                    warn!(order_id = %order_id, "Logged stop trigger has no held stop on replay");
+                }
+            }
+            MatchingEvent::AuctionStarted { instrument_id, reason, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                if let Err(e) = self.start_auction(instrument_id, *reason) {
+                    warn!(instrument = %instrument_id, error = %e, "Logged auction start failed on replay");
+                }
+            }
+            MatchingEvent::AuctionUncrossed { instrument_id, price, sequence, timestamp } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                if let Some(timestamp) = timestamp {
+                    self.now = *timestamp;
+                }
+                match self.auctions.remove(instrument_id) {
+                    Some(auction) => {
+                        self.uncross_at(instrument_id, auction, *price);
+                    }
+                    None => warn!(instrument = %instrument_id, "Logged uncross has no auction on replay"),
+                }
+            }
+            MatchingEvent::ComboDefined { combo, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                if let Err(e) = self.define_combo(combo.clone()) {
+                    warn!(combo = %combo.combo_id, error = %e, "Logged combo definition failed on replay");
+                }
+            }
+            MatchingEvent::MmpTriggered { user_id, underlying, breach, order_ids, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                self.trip_mmp(*user_id, underlying, *breach, MmpExposure::default(), order_ids);
+            }
+            MatchingEvent::MmpReset { user_id, underlying, sequence } => {
+                self.set_sequence(sequence.saturating_sub(1));
+                if self.reset_mmp(*user_id, underlying).is_none() {
+                    warn!(user_id = %user_id, underlying = %underlying, "Logged MMP reset has no frozen quoting on replay");
                 }
             }
+            MatchingEvent::TradeExecuted { .. } | MatchingEvent::SelfTradePrevented { .. } => {}
+            MatchingEvent::SequenceReset { sequence } => {
+                self.set_sequence(*sequence);
+            }
+        }
+        if event.sequence() > self.sequence {
+            self.set_sequence(event.sequence());
         }
     }
 
-    /// Cancel an order from the book
-    pub fn cancel_order(&mut self, instrument_id: &str, order_id: Uuid) -> Option<BookOrder> {
-        let book = self.get_or_create_book(instrument_id);
-        let removed = book.remove_order(order_id);
-        
-        if removed.is_some() {
-            info!(order_id = %order_id, instrument = %instrument_id, "Order cancelled");
+    /// Minimal event sequence that reproduces the current book state
+    ///
+    /// Every combo definition is emitted as a `ComboDefined`, every resting
+    /// order and held stop as an `OrderAccepted` with its remaining
+    /// quantity, every running call auction as an `AuctionStarted` and
+    /// every frozen maker as an `MmpTriggered` without orders, in sequence
+    /// order, followed by a `SequenceReset` to the current counter. Used to
+    /// compact the event log.
+    pub fn compacted_events(&self) -> Vec<MatchingEvent> {
+        let mut resting: Vec<&BookOrder> = self
+            .books
+            .values()
+            .flat_map(|book| book.orders())
+            .chain(self.trigger_books.values().flat_map(|book| book.orders()))
+            .collect();
+        resting.sort_by_key(|o| o.sequence);
+
+        let mut events: Vec<MatchingEvent> = resting
+            .into_iter()
+            .map(|o| MatchingEvent::OrderAccepted {
+                order: o.clone(),
+                sequence: o.sequence,
+            })
+            .collect();
+        events.extend(
+            self.auctions
+                .iter()
+                .map(|(instrument_id, auction)| MatchingEvent::auction_started(instrument_id, auction)),
+        );
+        events.extend(self.combos.values().map(MatchingEvent::combo_defined));
+        events.extend(self.mmp_frozen.values().map(MatchingEvent::mmp_frozen));
+        events.sort_by_key(|e| e.sequence());
+        events.push(MatchingEvent::SequenceReset {
+            sequence: self.sequence,
+        });
+        events
+    }
+
+    /// Capture all resting orders and the sequence counter
+    pub fn snapshot(&self) -> EngineSnapshot {
+        let mut books: Vec<OrderBook> = self
+            .books
+            .values()
+            .filter(|book| !book.is_empty())
+            .cloned()
+            .collect();
+        books.sort_by(|a, b| a.instrument_id.cmp(&b.instrument_id));
+
+        let mut stops: Vec<BookOrder> = self
+            .trigger_books
+            .values()
+            .flat_map(|book| book.orders())
+            .cloned()
+            .collect();
+        stops.sort_by_key(|o| o.sequence);
+
+        EngineSnapshot {
+            version: SNAPSHOT_VERSION,
+            sequence: self.sequence,
+            taken_at: self.clock.now(),
+            books,
+            stops,
+            last_prices: self.last_prices.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            auctions: self.auctions.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            combos: self.combos(),
+            mmp_frozen: self.mmp_frozen.values().cloned().collect(),
         }
-        
-        book.cleanup_empty_levels();
-        removed
+    }
+
+    /// Restore state from a snapshot plus the event-log tail
+    ///
+    /// Replaces all books with the snapshot's, then replays every event in
+    /// `tail` with a sequence above the snapshot's. Events already covered by
+    /// the snapshot may be passed and are skipped.
+    pub fn restore(
+        &mut self,
+        snapshot: &EngineSnapshot,
+        tail: &[MatchingEvent],
+    ) -> Result<(), MatchingError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(MatchingError::UnsupportedSnapshot(snapshot.version));
+        }
+
+        self.books = snapshot
+            .books
+            .iter()
+            .map(|book| (book.instrument_id.clone(), book.clone()))
+            .collect();
+        self.order_index = self
+            .books
+            .values()
+            .flat_map(|book| book.orders())
+            .map(|order| (order.order_id, OrderLocation::of(order)))
+            .collect();
+        self.trigger_books.clear();
+        self.stop_index.clear();
+        for stop in &snapshot.stops {
+            self.stop_index.insert(stop.order_id, stop.instrument_id.clone());
+            self.trigger_books
+                .entry(stop.instrument_id.clone())
+                .or_default()
+                .insert(stop.clone());
+        }
+        self.last_prices = snapshot.last_prices.clone().into_iter().collect();
+        self.auctions = snapshot.auctions.clone().into_iter().collect();
+        self.combos = snapshot
+            .combos
+            .iter()
+            .map(|combo| (combo.combo_id.clone(), combo.clone()))
+            .collect();
+        self.quote_orders = self
+            .books
+            .values()
+            .flat_map(|book| book.orders())
+            .filter(|order| order.quote)
+            .map(|order| order.order_id)
+            .collect();
+        self.mmp_windows.clear();
+        self.mmp_frozen = snapshot
+            .mmp_frozen
+            .iter()
+            .map(|freeze| ((freeze.user_id, freeze.underlying.clone()), freeze.clone()))
+            .collect();
+        self.sequence = snapshot.sequence;
+
+        let tail: Vec<MatchingEvent> = tail
+            .iter()
+            .filter(|e| e.sequence() > snapshot.sequence)
+            .cloned()
+            .collect();
+        self.replay(&tail);
+
+        info!(
+            snapshot_sequence = snapshot.sequence,
+            orders = snapshot.order_count(),
+            stops = snapshot.stops.len(),
+            tail_events = tail.len(),
+            "Matching engine restored from snapshot"
+        );
+        Ok(())
     }
 
     /// Get order book for an instrument
@@ -495,6 +3068,9 @@ impl MatchingEngine {
     }
 
     /// Get mutable order book for an instrument
+    ///
+    /// Orders added or removed through this reference bypass the order-id
+    /// index; use `match_order` / `cancel_by_id` for book changes.
     pub fn get_book_mut(&mut self, instrument_id: &str) -> Option<&mut OrderBook> {
         self.books.get_mut(instrument_id)
     }
@@ -520,6 +3096,33 @@ impl MatchingEngine {
         false
     }
 
+    /// Publish an instrument's book depth, spread and halt state
+    fn publish_book_metrics(&self, instrument_id: &str) {
+        let Some(ref metrics) = self.metrics else {
+            return;
+        };
+
+        let (depth, spread_bps) = match self.books.get(instrument_id) {
+            Some(book) => {
+                let depth = book.bids.values().map(|v| v.len()).sum::<usize>()
+                    + book.asks.values().map(|v| v.len()).sum::<usize>();
+                // Spread in basis points of the best bid
+                let spread_bps = book.spread().zip(book.best_bid()).map(|(spread, bid)| {
+                    if spread > 0 && bid > 0 {
+                        (spread as f64 / bid as f64 * 10000.0) as u64
+                    } else {
+                        0
+                    }
+                });
+                (depth as u64, spread_bps)
+            }
+            None => (0, None),
+        };
+        metrics.set_order_book_depth(instrument_id, depth);
+        metrics.set_spread(instrument_id, spread_bps);
+        metrics.set_halted(instrument_id, self.is_halted(instrument_id));
+    }
+
     /// Check and trigger circuit breakers after trades
     /// Call this after match_order completes
     fn check_circuit_breakers(&mut self, instrument_id: &str, trades: &[Trade]) {
@@ -527,12 +3130,14 @@ impl MatchingEngine {
             return;
         }
 
+        // Breaker thresholds are configured in decimal prices
+        let scale = self.price_scale(instrument_id);
         if let Some(ref mut cb) = self.circuit_breakers {
             // Get book stats for liquidity check
             if let Some(book) = self.books.get(instrument_id) {
                 let bid_count = book.bids.values().map(|v| v.len()).sum();
                 let ask_count = book.asks.values().map(|v| v.len()).sum();
-                let spread = book.spread();
+                let spread = book.spread().map(|ticks| scale.to_price(ticks));
 
                 // Check liquidity breaker
                 cb.check_liquidity(instrument_id, bid_count, ask_count, spread);
@@ -540,10 +3145,213 @@ impl MatchingEngine {
 
             // Check price movement breaker using last trade price
             if let Some(last_trade) = trades.last() {
-                cb.check_price_movement(instrument_id, last_trade.price);
+                cb.check_price_movement(instrument_id, scale.to_price(last_trade.price));
+            }
+        }
+    }
+}
+
+/// A resting combo order offering one of its legs, with the other legs'
+/// best levels (see `MatchingEngine::implied_out`)
+struct ImpliedOut {
+    /// Price offered on the leg
+    price: Ticks,
+    /// Combo units that can trade
+    units: u32,
+    /// The combo
+    combo: ComboDefinition,
+    /// Index of the offered leg
+    index: usize,
+    /// The resting combo order
+    maker: BookOrder,
+    /// Price of every leg (the offered leg's entry is `price`)
+    prices: Vec<Ticks>,
+}
+
+/// Start of a combo fill in the undo log, with the sequence counter then
+struct Checkpoint {
+    mark: usize,
+    sequence: u64,
+}
+
+/// What combo fills under open checkpoints changed in the books
+///
+/// A price level is saved the first time it is matched against after the
+/// innermost open checkpoint, so undoing costs only the levels touched.
+#[derive(Default)]
+struct UndoLog {
+    /// Start of each open checkpoint in `entries`, innermost last
+    marks: Vec<usize>,
+    entries: Vec<Undo>,
+}
+
+impl UndoLog {
+    /// Check if a checkpoint is open
+    fn is_recording(&self) -> bool {
+        !self.marks.is_empty()
+    }
+
+    /// Save a price level before it is matched against
+    fn save_level(&mut self, instrument_id: &str, book: &OrderBook, side: OrderSide, price: Ticks) {
+        let Some(&mark) = self.marks.last() else {
+            return;
+        };
+        let saved = self.entries[mark..].iter().any(|entry| {
+            matches!(entry, Undo::Level { instrument_id: id, side: s, price: p, .. }
+                if *s == side && *p == price && id == instrument_id)
+        });
+        if saved {
+            return;
+        }
+        let queue = match side {
+            OrderSide::Buy => book.bids.get(&std::cmp::Reverse(price)),
+            OrderSide::Sell => book.asks.get(&price),
+        };
+        self.entries.push(Undo::Level {
+            instrument_id: instrument_id.to_string(),
+            side,
+            price,
+            queue: queue.cloned().unwrap_or_default(),
+        });
+    }
+}
+
+/// One change recorded in an [`UndoLog`]
+enum Undo {
+    /// A price level as it was before a combo fill matched against it
+    Level {
+        instrument_id: String,
+        side: OrderSide,
+        price: Ticks,
+        queue: VecDeque<BookOrder>,
+    },
+    /// A book a combo fill created
+    Book(String),
+}
+
+
+/// Check if a taker on `side` limited at `limit` can trade at `price`
+fn crosses(side: OrderSide, price: Ticks, limit: Ticks) -> bool {
+    match side {
+        OrderSide::Buy => price <= limit,
+        OrderSide::Sell => price >= limit,
+    }
+}
+
+/// Check if `price` is strictly better than `other` for a taker on `side`
+fn improves(side: OrderSide, price: Ticks, other: Ticks) -> bool {
+    match side {
+        OrderSide::Buy => price < other,
+        OrderSide::Sell => price > other,
+    }
+}
+
+/// The combo and its legs
+fn combo_instruments(combo: &ComboDefinition) -> Vec<String> {
+    std::iter::once(combo.combo_id.clone())
+        .chain(combo.legs.iter().map(|leg| leg.instrument_id.clone()))
+        .collect()
+}
+
+/// Distinct instruments of some trades, in order of first trade
+fn traded_instruments(trades: &[Trade]) -> Vec<String> {
+    let mut instruments: Vec<String> = Vec::new();
+    for trade in trades {
+        if !instruments.contains(&trade.instrument_id) {
+            instruments.push(trade.instrument_id.clone());
+        }
+    }
+    instruments
+}
+
+/// Carry on with what a capped match left of an order
+fn take_remainder(order: &mut BookOrder, remaining: Option<BookOrder>) {
+    match remaining {
+        Some(rest) => {
+            order.quantity = rest.quantity;
+            order.shown_quantity = rest.shown_quantity;
+        }
+        None => order.quantity = 0,
+    }
+}
+
+/// Take the next number from the engine's sequence counter
+///
+/// Every sequence the engine hands out comes from here. Takes the counter
+/// rather than the engine so it can be used while a book is borrowed.
+fn advance_sequence(sequence: &mut u64) -> u64 {
+    *sequence += 1;
+    *sequence
+}
+
+/// Put a maker that still has quantity back in its price level
+///
+/// An iceberg whose shown slice is used up shows its next slice under a new
+/// sequence at the back of the queue, losing time priority. Any other maker
+/// keeps its place.
+fn requeue_maker(
+    queue: &mut VecDeque<BookOrder>,
+    index: usize,
+    mut maker: BookOrder,
+    sequence: &mut u64,
+) {
+    if maker.needs_refresh() {
+        maker.sequence = advance_sequence(sequence);
+        maker.show_next_slice();
+        queue.push_back(maker);
+    } else {
+        queue.insert(index, maker);
+    }
+}
+
+/// Fill a taker against every maker it can trade with at one price level
+///
+/// Used by the pro-rata algorithms once the level has no self-trade
+/// conflicts left. Makers trade in queue order; like [`requeue_maker`], an
+/// iceberg whose shown slice is used up moves to the back with a new slice.
+fn allocate_level(
+    algorithm: MatchingAlgorithm,
+    queue: &mut VecDeque<BookOrder>,
+    order: &mut BookOrder,
+    sequence: &mut u64,
+    matches: &mut Vec<(Uuid, Uuid, Ticks, u32)>,
+    filled_makers: &mut Vec<Uuid>,
+) {
+    let level: Vec<LevelOrder> = queue
+        .iter()
+        .map(|maker| LevelOrder {
+            quantity: if maker.can_fill_against(order.quantity) {
+                maker.visible_quantity()
+            } else {
+                0
+            },
+            all_or_none: maker.all_or_none,
+        })
+        .collect();
+    let fills = algorithm.allocate(order.quantity, &level);
+
+    let mut kept = VecDeque::with_capacity(queue.len());
+    let mut refreshed = Vec::new();
+    for (mut maker, qty) in queue.drain(..).zip(fills) {
+        if qty > 0 {
+            matches.push((maker.order_id, maker.user_id, maker.price, qty));
+            order.fill(qty);
+            maker.fill(qty);
+            if maker.is_filled() {
+                filled_makers.push(maker.order_id);
+                continue;
+            }
+            if maker.needs_refresh() {
+                maker.sequence = advance_sequence(sequence);
+                maker.show_next_slice();
+                refreshed.push(maker);
+                continue;
             }
         }
+        kept.push_back(maker);
     }
+    kept.extend(refreshed);
+    *queue = kept;
 }
 
 impl Default for MatchingEngine {
@@ -559,11 +3367,12 @@ impl Default for MatchingEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{OrderBookSnapshot, SelfTradePrevention};
     use uuid::Uuid;
 
     fn create_test_order(
         side: OrderSide,
-        price: f64,
+        price: Ticks,
         quantity: u32,
         tif: TimeInForce,
     ) -> BookOrder {
@@ -576,6 +3385,7 @@ mod tests {
             0,
             tif,
         )
+        .with_instrument_id("test")
     }
 
     #[test]
@@ -583,7 +3393,7 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Sell order at 100
-        let sell = create_test_order(OrderSide::Sell, 100.0, 10, TimeInForce::Gtc);
+        let sell = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
         let result = engine.match_order(sell);
 
         // No match (no buyers)
@@ -591,13 +3401,13 @@ mod tests {
         assert!(result.should_insert);
 
         // Buy order at 100 (should match)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Gtc);
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Gtc);
         let result = engine.match_order(buy);
 
         // Should produce 1 trade
         assert_eq!(result.trades.len(), 1);
         assert_eq!(result.trades[0].quantity, 10);
-        assert_eq!(result.trades[0].price, 100.0);
+        assert_eq!(result.trades[0].price, 100);
         assert!(result.remaining_order.is_none());
     }
 
@@ -606,11 +3416,11 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Sell 5 @ 100
-        let sell = create_test_order(OrderSide::Sell, 100.0, 5, TimeInForce::Gtc);
+        let sell = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
         engine.match_order(sell);
 
         // Buy 10 @ 100 (only 5 available)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Gtc);
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Gtc);
         let result = engine.match_order(buy);
 
         // Should match 5, leave 5 remaining
@@ -629,47 +3439,53 @@ mod tests {
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Sell,
-            100.0,
+            100,
             10,
             1, // First
             TimeInForce::Gtc,
-        );
+        )
+        .with_instrument_id("test");
         let sell2 = BookOrder::new(
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Sell,
-            100.0,
+            100,
             10,
             2, // Second
             TimeInForce::Gtc,
-        );
+        )
+        .with_instrument_id("test");
         let sell3 = BookOrder::new(
             Uuid::new_v4(),
             Uuid::new_v4(),
             OrderSide::Sell,
-            100.0,
+            100,
             10,
             3, // Third
             TimeInForce::Gtc,
-        );
+        )
+        .with_instrument_id("test");
+
+        let sell1_id = sell1.order_id;
+        let sell2_id = sell2.order_id;
 
         engine.match_order(sell1);
         engine.match_order(sell2);
         engine.match_order(sell3);
 
         // Buy 15 @ 100 (should match sell1 completely, sell2 partially)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 15, TimeInForce::Gtc);
+        let buy = create_test_order(OrderSide::Buy, 100, 15, TimeInForce::Gtc);
         let result = engine.match_order(buy);
 
         // Should have 2 trades
         assert_eq!(result.trades.len(), 2);
 
         // First trade should be with sell1 (earliest)
-        assert_eq!(result.trades[0].maker_order_id.sequence, 1);
+        assert_eq!(result.trades[0].maker_order_id, sell1_id);
         assert_eq!(result.trades[0].quantity, 10);
 
         // Second trade should be with sell2
-        assert_eq!(result.trades[1].maker_order_id.sequence, 2);
+        assert_eq!(result.trades[1].maker_order_id, sell2_id);
         assert_eq!(result.trades[1].quantity, 5);
     }
 
@@ -677,18 +3493,494 @@ mod tests {
     fn test_ioc_order() {
         let mut engine = MatchingEngine::new();
 
-        // Sell 5 @ 100
-        let sell = create_test_order(OrderSide::Sell, 100.0, 5, TimeInForce::Gtc);
-        engine.match_order(sell);
+        // Sell 5 @ 100
+        let sell = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
+        engine.match_order(sell);
+
+        // IOC Buy 10 @ 100 (only 5 available)
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Ioc);
+        let result = engine.match_order(buy);
+
+        // Should match 5, cancel 5 (not inserted)
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].quantity, 5);
+        assert!(!result.should_insert);
+    }
+
+    fn market_order(side: OrderSide, quantity: u32) -> BookOrder {
+        create_test_order(side, 0, quantity, TimeInForce::Gtc)
+            .with_order_type(crate::domain::OrderType::Market)
+    }
+
+    #[test]
+    fn test_market_order_sweeps_within_protection_band() {
+        let mut engine = MatchingEngine::new();
+        engine.set_market_protection_percent(5.0);
+
+        engine.match_order(create_test_order(OrderSide::Sell, 100, 3, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Sell, 104, 3, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Sell, 106, 3, TimeInForce::Gtc));
+
+        // Band is 100 * 1.05 = 105: the 106 level is out of reach
+        let result = engine.match_order(market_order(OrderSide::Buy, 10));
+        assert_eq!(result.filled_quantity(), 6);
+        assert_eq!(result.trades[1].price, 104);
+        assert_eq!(result.protection_price, Some(105));
+
+        // The remainder is cancelled, never rested
+        assert!(!result.should_insert);
+        assert_eq!(result.remaining_order.unwrap().quantity, 4);
+        assert_eq!(engine.get_book("test").unwrap().best_bid(), None);
+        assert_eq!(engine.get_book("test").unwrap().best_ask(), Some(106));
+    }
+
+    #[test]
+    fn test_market_order_band_uses_mark_price() {
+        let mut engine = MatchingEngine::new();
+        engine.set_market_protection_percent(2.0);
+        engine.set_mark_price("test", 100);
+
+        engine.match_order(create_test_order(OrderSide::Buy, 99, 5, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Buy, 97, 5, TimeInForce::Gtc));
+
+        // Band is 100 * 0.98 = 98 around the mark, not the best bid
+        let result = engine.match_order(market_order(OrderSide::Sell, 8));
+        assert_eq!(result.filled_quantity(), 5);
+        assert_eq!(result.protection_price, Some(98));
+        assert_eq!(engine.get_book("test").unwrap().best_bid(), Some(97));
+    }
+
+    #[test]
+    fn test_market_order_without_reference_is_rejected() {
+        let mut engine = MatchingEngine::new();
+
+        let result = engine.match_order(market_order(OrderSide::Buy, 5));
+        assert!(result.sequence.is_none());
+        assert!(!result.should_insert);
+        assert_eq!(engine.sequence(), 0);
+        assert_eq!(engine.resting_order_count(), 0);
+    }
+
+    #[test]
+    fn test_market_order_replays_at_logged_protection_price() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+
+        for order in [
+            create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 103, 5, TimeInForce::Gtc),
+            market_order(OrderSide::Buy, 8),
+        ] {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+        }
+
+        // A different band on the replica must not change the outcome
+        let mut replayed = MatchingEngine::new();
+        replayed.set_market_protection_percent(1.0);
+        replayed.replay(&events);
+        assert_eq!(replayed.get_book("test").unwrap().ask_quantity_at(103), 2);
+        assert_eq!(replayed.sequence(), engine.sequence());
+    }
+
+    fn stop_order(side: OrderSide, stop_price: Ticks, limit: Option<Ticks>, quantity: u32, source: TriggerSource) -> BookOrder {
+        let order = match limit {
+            Some(price) => create_test_order(side, price, quantity, TimeInForce::Gtc),
+            None => market_order(side, quantity),
+        };
+        order.with_stop(stop_price, source)
+    }
+
+    #[test]
+    fn test_stop_market_triggers_on_last_trade() {
+        let mut engine = MatchingEngine::new();
+        engine.match_order(create_test_order(OrderSide::Sell, 101, 5, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Sell, 102, 5, TimeInForce::Gtc));
+
+        let stop = stop_order(OrderSide::Buy, 101, None, 4, TriggerSource::LastTrade);
+        let stop_id = stop.order_id;
+        let result = engine.match_order(stop);
+        assert!(result.sequence.is_some());
+        assert!(result.triggered.is_empty());
+        assert_eq!(engine.stop_order_count(), 1);
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(101), 5);
+
+        // A trade at 101 fires the stop, which sweeps the rest of 101 and into 102
+        let result = engine.match_order(create_test_order(OrderSide::Buy, 101, 2, TimeInForce::Gtc));
+        assert_eq!(result.triggered.len(), 1);
+        let triggered = &result.triggered[0];
+        assert_eq!(triggered.order.order_id, stop_id);
+        assert_eq!(triggered.trigger_price, 101);
+        assert_eq!(triggered.result.filled_quantity(), 4);
+        assert_eq!(result.all_trades().len(), 3);
+        assert_eq!(engine.stop_order_count(), 0);
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(102), 4);
+    }
+
+    #[test]
+    fn test_stop_limit_triggers_on_mark_price_and_rests() {
+        let mut engine = MatchingEngine::new();
+        let stop = stop_order(OrderSide::Sell, 95, Some(94), 3, TriggerSource::MarkPrice);
+        let stop_id = stop.order_id;
+        engine.match_order(stop);
+
+        // Last-trade and index moves do not fire a mark-price stop
+        assert!(engine.update_reference_price("test", TriggerSource::LastTrade, 90).is_empty());
+        assert!(engine.set_index_price("test", 90).is_empty());
+        assert!(engine.set_mark_price("test", 96).is_empty());
+
+        let triggered = engine.set_mark_price("test", 95);
+        assert_eq!(triggered.len(), 1);
+        assert!(triggered[0].result.should_insert);
+        assert!(triggered[0].sequence < engine.get_order(stop_id).unwrap().sequence);
+        assert_eq!(engine.get_book("test").unwrap().best_ask(), Some(94));
+    }
+
+    #[test]
+    fn test_stop_cancel_and_cascade_replay() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        let submit = |engine: &mut MatchingEngine, events: &mut Vec<MatchingEvent>, order: BookOrder| {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+            result
+        };
+
+        for price in [100, 99, 98, 97] {
+            submit(&mut engine, &mut events, create_test_order(OrderSide::Buy, price, 2, TimeInForce::Gtc));
+        }
+        // First stop sells through 99 and 98, its trade at 98 fires the second
+        submit(&mut engine, &mut events, stop_order(OrderSide::Sell, 100, None, 4, TriggerSource::LastTrade));
+        submit(&mut engine, &mut events, stop_order(OrderSide::Sell, 98, Some(97), 1, TriggerSource::LastTrade));
+        let cancelled = stop_order(OrderSide::Sell, 50, None, 1, TriggerSource::LastTrade);
+        let cancelled_id = cancelled.order_id;
+        submit(&mut engine, &mut events, cancelled);
+        assert!(engine.cancel_order("other", cancelled_id).is_none());
+        engine.cancel_order("test", cancelled_id).unwrap();
+        events.push(MatchingEvent::OrderCancelled {
+            order_id: cancelled_id,
+            instrument_id: "test".to_string(),
+            sequence: engine.sequence(),
+        });
+
+        let result = submit(&mut engine, &mut events, create_test_order(OrderSide::Sell, 100, 2, TimeInForce::Gtc));
+        assert_eq!(result.triggered.len(), 2);
+        assert_eq!(engine.stop_order_count(), 0);
+        assert_eq!(engine.get_book("test").unwrap().bid_quantity_at(97), 1);
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(replayed.stop_order_count(), 0);
+        assert_eq!(
+            replayed.get_book("test").unwrap().orders().map(|o| (o.order_id, o.quantity)).collect::<Vec<_>>(),
+            engine.get_book("test").unwrap().orders().map(|o| (o.order_id, o.quantity)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_keeps_held_stops() {
+        let mut engine = MatchingEngine::new();
+        engine.match_order(create_test_order(OrderSide::Sell, 100, 1, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc));
+        let stop = stop_order(OrderSide::Buy, 105, Some(106), 1, TriggerSource::LastTrade);
+        let stop_id = stop.order_id;
+        engine.match_order(stop);
+
+        let snapshot = EngineSnapshot::from_bytes(&engine.snapshot().to_bytes().unwrap()).unwrap();
+        let mut restored = MatchingEngine::new();
+        restored.restore(&snapshot, &[]).unwrap();
+        assert_eq!(restored.get_stop_order(stop_id).unwrap().sequence, engine.get_stop_order(stop_id).unwrap().sequence);
+        assert_eq!(restored.reference_prices("test"), engine.reference_prices("test"));
+    }
+
+    fn user_order(user_id: Uuid, side: OrderSide, price: Ticks, quantity: u32, stp: SelfTradePrevention) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), user_id, side, price, quantity, 0, TimeInForce::Gtc)
+            .with_instrument_id("test")
+            .with_stp(stp)
+    }
+
+    #[test]
+    fn test_stp_cancel_taker_and_cancel_both() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut engine = MatchingEngine::new();
+        let own = user_order(alice, OrderSide::Sell, 100, 5, SelfTradePrevention::None);
+        let own_id = own.order_id;
+        engine.match_order(own);
+        engine.match_order(user_order(bob, OrderSide::Sell, 100, 5, SelfTradePrevention::None));
+
+        let taker = user_order(alice, OrderSide::Buy, 100, 10, SelfTradePrevention::CancelTaker);
+        let taker_id = taker.order_id;
+        let result = engine.match_order(taker);
+        assert!(result.trades.is_empty());
+        assert!(!result.should_insert);
+        assert_eq!(result.stp_cancelled, vec![taker_id]);
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 10);
+
+        let taker = user_order(alice, OrderSide::Buy, 100, 10, SelfTradePrevention::CancelBoth);
+        let taker_id = taker.order_id;
+        let result = engine.match_order(taker);
+        assert!(result.trades.is_empty());
+        assert_eq!(result.stp_cancelled, vec![own_id, taker_id]);
+        assert!(engine.get_order(own_id).is_none());
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 5);
+        assert_eq!(engine.get_book("test").unwrap().best_bid(), None);
+    }
+
+    #[test]
+    fn test_stp_cancel_maker_keeps_matching() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut engine = MatchingEngine::new();
+        let own = user_order(alice, OrderSide::Sell, 100, 5, SelfTradePrevention::None);
+        let own_id = own.order_id;
+        engine.match_order(own);
+        engine.match_order(user_order(bob, OrderSide::Sell, 100, 5, SelfTradePrevention::None));
+
+        let result = engine.match_order(user_order(alice, OrderSide::Buy, 100, 8, SelfTradePrevention::CancelMaker));
+        assert_eq!(result.stp_cancelled, vec![own_id]);
+        assert_eq!(result.filled_quantity(), 5);
+        assert_eq!(result.trades[0].seller_id, bob);
+        assert!(result.should_insert);
+        assert_eq!(engine.get_book("test").unwrap().bid_quantity_at(100), 3);
+        assert_eq!(engine.resting_order_count(), 1);
+    }
+
+    #[test]
+    fn test_stp_decrement_and_cancel() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut engine = MatchingEngine::new();
+        let small = user_order(alice, OrderSide::Sell, 100, 3, SelfTradePrevention::None);
+        let small_id = small.order_id;
+        engine.match_order(small);
+        engine.match_order(user_order(bob, OrderSide::Sell, 101, 5, SelfTradePrevention::None));
+
+        // Own 3 decrements the taker to 2, which then trades with bob
+        let result = engine.match_order(user_order(alice, OrderSide::Buy, 101, 5, SelfTradePrevention::DecrementAndCancel));
+        assert_eq!(result.stp_cancelled, vec![small_id]);
+        assert_eq!(result.filled_quantity(), 2);
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(101), 3);
+
+        // A larger own maker survives with the difference; the taker is cancelled
+        let big = user_order(alice, OrderSide::Sell, 99, 10, SelfTradePrevention::None);
+        let big_id = big.order_id;
+        engine.match_order(big);
+        let taker = user_order(alice, OrderSide::Buy, 99, 4, SelfTradePrevention::DecrementAndCancel);
+        let taker_id = taker.order_id;
+        let result = engine.match_order(taker);
+        assert!(result.trades.is_empty());
+        assert_eq!(result.stp_cancelled, vec![taker_id]);
+        assert_eq!(engine.get_order(big_id).unwrap().quantity, 6);
+    }
+
+    #[test]
+    fn test_stp_fok_precheck_and_replay() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        let submit = |engine: &mut MatchingEngine, events: &mut Vec<MatchingEvent>, order: BookOrder| {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+            result
+        };
+
+        submit(&mut engine, &mut events, user_order(alice, OrderSide::Sell, 100, 5, SelfTradePrevention::None));
+        submit(&mut engine, &mut events, user_order(bob, OrderSide::Sell, 100, 5, SelfTradePrevention::None));
+
+        // Only bob's 5 is fillable when own orders are skipped
+        let mut fok = user_order(alice, OrderSide::Buy, 100, 6, SelfTradePrevention::CancelMaker);
+        fok.time_in_force = TimeInForce::Fok;
+        let result = submit(&mut engine, &mut events, fok);
+        assert!(result.sequence.is_none());
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 10);
+
+        // Matching stops at alice's own order, so nothing is fillable
+        let mut fok = user_order(alice, OrderSide::Buy, 100, 1, SelfTradePrevention::CancelTaker);
+        fok.time_in_force = TimeInForce::Fok;
+        assert!(submit(&mut engine, &mut events, fok).sequence.is_none());
+
+        let mut fok = user_order(alice, OrderSide::Buy, 100, 5, SelfTradePrevention::CancelMaker);
+        fok.time_in_force = TimeInForce::Fok;
+        let result = submit(&mut engine, &mut events, fok);
+        assert_eq!(result.filled_quantity(), 5);
+        assert_eq!(result.stp_cancelled.len(), 1);
+        assert!(events.iter().any(|e| matches!(e, MatchingEvent::SelfTradePrevented { .. })));
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(replayed.resting_order_count(), 0);
+        assert_eq!(engine.resting_order_count(), 0);
+    }
+
+    fn gtc(side: OrderSide, price: Ticks, quantity: u32) -> BookOrder {
+        create_test_order(side, price, quantity, TimeInForce::Gtc).with_instrument_id("test")
+    }
+
+    #[test]
+    fn test_post_only_reject_and_reprice() {
+        let mut engine = MatchingEngine::new();
+        engine.match_order(gtc(OrderSide::Sell, 101, 5));
+
+        // Resting below the ask is accepted as is
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 5).with_post_only(PostOnly::Reject));
+        assert!(result.should_insert);
+        assert_eq!(result.repriced_to, None);
+
+        let result = engine.match_order(gtc(OrderSide::Buy, 101, 5).with_post_only(PostOnly::Reject));
+        assert!(result.sequence.is_none());
+        assert!(result.trades.is_empty());
+
+        let order = gtc(OrderSide::Buy, 102, 5).with_post_only(PostOnly::Reprice);
+        let result = engine.match_order(order.clone());
+        assert!(result.trades.is_empty());
+        assert_eq!(result.repriced_to, Some(100));
+        assert_eq!(engine.get_order(order.order_id).unwrap().price, 100);
+        match &MatchingEvent::from_match(&order, &result)[0] {
+            MatchingEvent::OrderAccepted { order, .. } => assert_eq!(order.price, 100),
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // Post-only never applies to immediate orders
+        let mut ioc = gtc(OrderSide::Sell, 110, 1).with_post_only(PostOnly::Reject);
+        ioc.time_in_force = TimeInForce::Ioc;
+        assert!(engine.match_order(ioc).sequence.is_none());
+
+        // Amending into the spread crossing the ask is refused
+        let err = engine.amend_order(order.order_id, Some(101), None).unwrap_err();
+        assert!(matches!(err, MatchingError::InvalidOrder(_)));
+    }
+
+    #[test]
+    fn test_all_or_none_taker() {
+        let mut engine = MatchingEngine::new();
+        engine.match_order(gtc(OrderSide::Sell, 100, 5));
+
+        // Crosses but only 5 of 8 available: rejected, book untouched
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 8).with_all_or_none(true));
+        assert!(result.sequence.is_none());
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 5);
+
+        // Not crossing: rests
+        let result = engine.match_order(gtc(OrderSide::Buy, 99, 8).with_all_or_none(true));
+        assert!(result.should_insert);
+
+        engine.match_order(gtc(OrderSide::Sell, 100, 3));
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 8).with_all_or_none(true));
+        assert_eq!(result.filled_quantity(), 8);
+        assert!(result.remaining_order.is_none());
+    }
+
+    #[test]
+    fn test_all_or_none_maker_is_skipped_until_fillable() {
+        let mut engine = MatchingEngine::new();
+        let aon = gtc(OrderSide::Sell, 100, 10).with_all_or_none(true);
+        let aon_id = aon.order_id;
+        engine.match_order(aon);
+        let behind = gtc(OrderSide::Sell, 100, 2);
+        let behind_id = behind.order_id;
+        engine.match_order(behind);
+        engine.match_order(gtc(OrderSide::Sell, 101, 4));
+
+        // Too small for the AON maker: trades behind it and at the next level
+        let mut ioc = gtc(OrderSide::Buy, 101, 5);
+        ioc.time_in_force = TimeInForce::Ioc;
+        let result = engine.match_order(ioc);
+        assert_eq!(result.filled_quantity(), 5);
+        assert_eq!(result.trades[0].maker_order_id, behind_id);
+        assert_eq!(result.trades[1].price, 101);
+        assert_eq!(engine.get_order(aon_id).unwrap().quantity, 10);
+
+        // A FOK that could only fill by splitting the AON maker is refused
+        let mut fok = gtc(OrderSide::Buy, 100, 6);
+        fok.time_in_force = TimeInForce::Fok;
+        assert!(engine.match_order(fok).sequence.is_none());
+
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 12));
+        assert_eq!(result.trades[0].maker_order_id, aon_id);
+        assert_eq!(result.trades[0].quantity, 10);
+        assert!(engine.get_order(aon_id).is_none());
+        assert_eq!(engine.get_book("test").unwrap().bid_quantity_at(100), 2);
+    }
+
+    #[test]
+    fn test_min_quantity() {
+        let mut engine = MatchingEngine::new();
+        engine.match_order(gtc(OrderSide::Sell, 100, 3));
+
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 10).with_min_quantity(Some(4)));
+        assert!(result.sequence.is_none());
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 3);
+
+        // Fills 3 and rests the rest without the minimum
+        let order = gtc(OrderSide::Buy, 100, 10).with_min_quantity(Some(3));
+        let result = engine.match_order(order.clone());
+        assert_eq!(result.filled_quantity(), 3);
+        assert!(result.should_insert);
+        let resting = engine.get_order(order.order_id).unwrap();
+        assert_eq!(resting.quantity, 7);
+        assert_eq!(resting.min_quantity, None);
+    }
+
+    #[test]
+    fn test_iceberg_shows_slice_and_refreshes_to_back() {
+        let mut engine = MatchingEngine::new();
+        let iceberg = gtc(OrderSide::Sell, 100, 10).with_display_quantity(Some(3));
+        let iceberg_id = iceberg.order_id;
+        engine.match_order(iceberg);
+        let other = gtc(OrderSide::Sell, 100, 2);
+        let other_id = other.order_id;
+        engine.match_order(other);
+
+        let snapshot = OrderBookSnapshot::from_book(engine.get_book("test").unwrap(), 5);
+        assert_eq!(snapshot.asks[0].quantity, 5);
+        assert_eq!(engine.get_book("test").unwrap().ask_quantity_at(100), 12);
+
+        // Takes the shown 3, then the other order, then the refreshed slice
+        let result = engine.match_order(gtc(OrderSide::Buy, 100, 6));
+        let fills: Vec<_> = result.trades.iter().map(|t| (t.maker_order_id, t.quantity)).collect();
+        assert_eq!(fills, vec![(iceberg_id, 3), (other_id, 2), (iceberg_id, 1)]);
+
+        let resting = engine.get_order(iceberg_id).unwrap();
+        assert_eq!((resting.quantity, resting.shown_quantity), (6, 2));
+        let snapshot = OrderBookSnapshot::from_book(engine.get_book("test").unwrap(), 5);
+        assert_eq!(snapshot.asks[0].quantity, 2);
+        assert_eq!(snapshot.asks[0].order_count, 1);
+    }
+
+    #[test]
+    fn test_iceberg_refresh_replays_deterministically() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        let orders = vec![
+            gtc(OrderSide::Sell, 100, 10).with_display_quantity(Some(4)),
+            gtc(OrderSide::Sell, 100, 3),
+            gtc(OrderSide::Buy, 100, 5),
+            gtc(OrderSide::Sell, 100, 2).with_display_quantity(Some(1)),
+            gtc(OrderSide::Buy, 100, 7),
+        ];
+        for order in orders {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+        }
+
+        let queue = |engine: &MatchingEngine| {
+            engine
+                .get_book("test")
+                .unwrap()
+                .orders()
+                .map(|o| (o.order_id, o.quantity, o.shown_quantity, o.sequence))
+                .collect::<Vec<_>>()
+        };
 
-        // IOC Buy 10 @ 100 (only 5 available)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Ioc);
-        let result = engine.match_order(buy);
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(queue(&replayed), queue(&engine));
 
-        // Should match 5, cancel 5 (not inserted)
-        assert_eq!(result.trades.len(), 1);
-        assert_eq!(result.trades[0].quantity, 5);
-        assert!(!result.should_insert);
+        // Compaction keeps the partly shown slice and the refreshed queue order
+        let mut restored = MatchingEngine::new();
+        restored.replay(&engine.compacted_events());
+        assert_eq!(queue(&restored), queue(&engine));
     }
 
     #[test]
@@ -696,11 +3988,11 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Sell 10 @ 100
-        let sell = create_test_order(OrderSide::Sell, 100.0, 10, TimeInForce::Gtc);
+        let sell = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
         engine.match_order(sell);
 
         // FOK Buy 10 @ 100 (can fill completely)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Fok);
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Fok);
         let result = engine.match_order(buy);
 
         // Should fill completely
@@ -714,11 +4006,11 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Sell 4 @ 99, Sell 7 @ 100 (total 11 available)
-        engine.match_order(create_test_order(OrderSide::Sell, 99.0, 4, TimeInForce::Gtc));
-        engine.match_order(create_test_order(OrderSide::Sell, 100.0, 7, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Sell, 99, 4, TimeInForce::Gtc));
+        engine.match_order(create_test_order(OrderSide::Sell, 100, 7, TimeInForce::Gtc));
 
         // FOK Buy 11 @ 100 (exact liquidity across multiple levels)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 11, TimeInForce::Fok);
+        let buy = create_test_order(OrderSide::Buy, 100, 11, TimeInForce::Fok);
         let result = engine.match_order(buy);
 
         // Should fully fill
@@ -733,11 +4025,11 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Sell 5 @ 100
-        let sell = create_test_order(OrderSide::Sell, 100.0, 5, TimeInForce::Gtc);
+        let sell = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
         engine.match_order(sell);
 
         // FOK Buy 10 @ 100 (can't fill completely)
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Fok);
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Fok);
         let result = engine.match_order(buy);
 
         // Should cancel entire order (FOK semantics)
@@ -750,11 +4042,11 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Buy 5 @ 100
-        let buy = create_test_order(OrderSide::Buy, 100.0, 5, TimeInForce::Gtc);
+        let buy = create_test_order(OrderSide::Buy, 100, 5, TimeInForce::Gtc);
         engine.match_order(buy);
 
         // FOK Sell 10 @ 100 (not enough bids)
-        let sell = create_test_order(OrderSide::Sell, 100.0, 10, TimeInForce::Fok);
+        let sell = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Fok);
         let result = engine.match_order(sell);
 
         // Should cancel (insufficient liquidity)
@@ -762,7 +4054,7 @@ mod tests {
         assert!(!result.should_insert);
 
         // Original buy should still be in book (untouched)
-        assert_eq!(engine.get_book("test").unwrap().bid_quantity_at(100.0), 5);
+        assert_eq!(engine.get_book("test").unwrap().bid_quantity_at(100), 5);
     }
 
     #[test]
@@ -770,7 +4062,7 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // FOK Buy with no sellers -> cancelled
-        let buy = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Fok);
+        let buy = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Fok);
         let result = engine.match_order(buy);
 
         assert_eq!(result.trades.len(), 0);
@@ -781,9 +4073,9 @@ mod tests {
     fn test_determinism() {
         // Run same sequence twice, must get identical results
         let orders = vec![
-            create_test_order(OrderSide::Sell, 100.0, 10, TimeInForce::Gtc),
-            create_test_order(OrderSide::Sell, 99.0, 5, TimeInForce::Gtc),
-            create_test_order(OrderSide::Buy, 100.0, 12, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 99, 5, TimeInForce::Gtc),
+            create_test_order(OrderSide::Buy, 100, 12, TimeInForce::Gtc),
         ];
 
         // Run 1
@@ -811,12 +4103,375 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_replay_rebuilds_state() {
+        let orders = vec![
+            create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 101, 5, TimeInForce::Gtc),
+            create_test_order(OrderSide::Buy, 99, 7, TimeInForce::Gtc),
+            create_test_order(OrderSide::Buy, 100, 4, TimeInForce::Gtc),
+        ];
+
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        for order in orders {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+        }
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+
+        let book = replayed.get_book("test").unwrap();
+        assert_eq!(book.ask_quantity_at(100), 6);
+        assert_eq!(book.ask_quantity_at(101), 5);
+        assert_eq!(book.bid_quantity_at(99), 7);
+        assert_eq!(replayed.sequence(), engine.sequence());
+
+        // Compacted events reproduce the same state
+        let mut compacted = MatchingEngine::new();
+        compacted.replay(&engine.compacted_events());
+        assert_eq!(compacted.get_book("test").unwrap().ask_quantity_at(100), 6);
+        assert_eq!(compacted.sequence(), engine.sequence());
+    }
+
+    #[test]
+    fn test_pro_rata_level_shares_taker_and_replays() {
+        let pro_rata = MatchingAlgorithm::ProRata { min_allocation: 1 };
+        let mut engine = MatchingEngine::new();
+        engine.set_matching_algorithm("test", pro_rata);
+
+        let makers = [
+            create_test_order(OrderSide::Sell, 100, 30, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 100, 20, TimeInForce::Gtc),
+            create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc),
+        ];
+        let mut events = Vec::new();
+        for maker in &makers {
+            let result = engine.match_order(maker.clone());
+            events.extend(MatchingEvent::from_match(maker, &result));
+        }
+
+        // FIFO would give all 10 to the first maker; pro-rata shares it
+        let taker = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Ioc);
+        let result = engine.match_order(taker.clone());
+        events.extend(MatchingEvent::from_match(&taker, &result));
+        let fills: Vec<_> = result.trades.iter().map(|t| (t.maker_order_id, t.quantity)).collect();
+        assert_eq!(
+            fills,
+            vec![(makers[0].order_id, 6), (makers[1].order_id, 3), (makers[2].order_id, 1)]
+        );
+
+        // Other underlyings keep FIFO
+        assert!(engine.matching_algorithm("other").is_fifo());
+
+        // Replaying under the same algorithm rebuilds the same book
+        let mut replayed = MatchingEngine::new();
+        replayed.set_matching_algorithm("test", pro_rata);
+        replayed.replay(&events);
+        for maker in &makers {
+            assert_eq!(
+                replayed.get_order(maker.order_id).map(|o| o.quantity),
+                engine.get_order(maker.order_id).map(|o| o.quantity)
+            );
+        }
+        assert_eq!(replayed.sequence(), engine.sequence());
+    }
+
+    #[test]
+    fn test_pro_rata_cancels_self_trade_conflicts_first() {
+        let mut engine = MatchingEngine::new();
+        engine.set_matching_algorithm(
+            "test",
+            MatchingAlgorithm::PriceTimeProRata { top_order_percent: 50, min_allocation: 1 },
+        );
+
+        let own = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
+        let other = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
+        let (own_id, other_id) = (own.order_id, other.order_id);
+        engine.match_order(own.clone());
+        engine.match_order(other);
+
+        let mut taker = create_test_order(OrderSide::Buy, 100, 8, TimeInForce::Ioc)
+            .with_stp(SelfTradePrevention::CancelMaker);
+        taker.user_id = own.user_id;
+        let result = engine.match_order(taker);
+
+        assert_eq!(result.stp_cancelled, vec![own_id]);
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].maker_order_id, other_id);
+        assert_eq!(result.trades[0].quantity, 8);
+    }
+
+    #[test]
+    fn test_order_index_tracks_resting_orders() {
+        let mut engine = MatchingEngine::new();
+
+        let sell1 = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
+        let sell2 = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
+        let other = create_test_order(OrderSide::Buy, 50, 1, TimeInForce::Gtc)
+            .with_instrument_id("other");
+        let (sell1_id, sell2_id, other_id) = (sell1.order_id, sell2.order_id, other.order_id);
+        engine.match_order(sell1);
+        engine.match_order(sell2);
+        engine.match_order(other);
+        assert_eq!(engine.resting_order_count(), 3);
+
+        // Fully filled maker leaves the index, partially filled one stays
+        engine.match_order(create_test_order(OrderSide::Buy, 100, 7, TimeInForce::Gtc));
+        assert!(engine.get_order(sell1_id).is_none());
+        assert_eq!(engine.get_order(sell2_id).unwrap().quantity, 3);
+        assert_eq!(engine.resting_order_count(), 2);
+
+        // Cancel by ID finds the order without knowing its instrument
+        let cancelled = engine.cancel_by_id(other_id).unwrap();
+        assert_eq!(cancelled.instrument_id, "other");
+        assert!(engine.get_order(other_id).is_none());
+        assert!(engine.cancel_by_id(other_id).is_none());
+
+        // Cancel with the wrong instrument is a no-op
+        assert!(engine.cancel_order("other", sell2_id).is_none());
+        assert!(engine.cancel_order("test", sell2_id).is_some());
+        assert_eq!(engine.resting_order_count(), 0);
+    }
+
+    #[test]
+    fn test_amend_priority_rules() {
+        let mut engine = MatchingEngine::new();
+
+        let sell1 = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
+        let sell2 = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
+        let (sell1_id, sell2_id) = (sell1.order_id, sell2.order_id);
+        engine.match_order(sell1);
+        engine.match_order(sell2);
+
+        // Decrease keeps priority: sell1 still trades first
+        let result = engine.amend_order(sell1_id, None, Some(4)).unwrap();
+        assert!(result.priority_retained);
+        let trades = engine
+            .match_order(create_test_order(OrderSide::Buy, 100, 2, TimeInForce::Gtc))
+            .trades;
+        assert_eq!(trades[0].maker_order_id, sell1_id);
+
+        // Increase re-queues sell1 behind sell2
+        let result = engine.amend_order(sell1_id, None, Some(5)).unwrap();
+        assert!(!result.priority_retained);
+        let trades = engine
+            .match_order(create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc))
+            .trades;
+        assert_eq!(trades[0].maker_order_id, sell2_id);
+    }
+
+    #[test]
+    fn test_amend_reprice_crosses_book() {
+        let mut engine = MatchingEngine::new();
+
+        engine.match_order(create_test_order(OrderSide::Buy, 99, 3, TimeInForce::Gtc));
+        let sell = create_test_order(OrderSide::Sell, 101, 5, TimeInForce::Gtc);
+        let sell_id = sell.order_id;
+        engine.match_order(sell);
+
+        let result = engine.amend_order(sell_id, Some(99), None).unwrap();
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].quantity, 3);
+        assert_eq!(result.resting.unwrap().quantity, 2);
+
+        let book = engine.get_book("test").unwrap();
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.ask_quantity_at(99), 2);
+    }
+
+    #[test]
+    fn test_amend_rejects_unknown_and_filled_orders() {
+        let mut engine = MatchingEngine::new();
+        assert!(matches!(
+            engine.amend_order(Uuid::new_v4(), None, Some(1)),
+            Err(MatchingError::OrderNotFound(_))
+        ));
+
+        let sell = create_test_order(OrderSide::Sell, 100, 5, TimeInForce::Gtc);
+        let sell_id = sell.order_id;
+        engine.match_order(sell);
+        let sequence = engine.sequence();
+        assert!(matches!(
+            engine.amend_order(sell_id, None, Some(0)),
+            Err(MatchingError::InvalidOrder(_))
+        ));
+        // A rejected amend consumes no sequence number
+        assert_eq!(engine.sequence(), sequence);
+
+        engine.match_order(create_test_order(OrderSide::Buy, 100, 5, TimeInForce::Gtc));
+        assert!(matches!(
+            engine.amend_order(sell_id, None, Some(2)),
+            Err(MatchingError::OrderNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_amend_replays_from_events() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+
+        for order in [
+            create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc),
+            create_test_order(OrderSide::Buy, 98, 4, TimeInForce::Gtc),
+        ] {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+        }
+        let bid_id = engine.get_book("test").unwrap().bids.values().flatten().next().unwrap().order_id;
+        let result = engine.amend_order(bid_id, Some(100), Some(6)).unwrap();
+        events.extend(MatchingEvent::from_amend(bid_id, Some(100), Some(6), &result));
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.get_book("test").unwrap().ask_quantity_at(100), 4);
+        assert_eq!(replayed.sequence(), engine.sequence());
+    }
+
+    /// Trades as bytes, minus the fields that are random per run
+    fn trade_bytes(trades: &[Trade]) -> Vec<u8> {
+        serde_json::to_vec(trades).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_restore_matches_uninterrupted_engine() {
+        let order = |side, price, qty| create_test_order(side, price, qty, TimeInForce::Gtc);
+        let before_snapshot = vec![
+            order(OrderSide::Sell, 101, 5),
+            order(OrderSide::Sell, 100, 3),
+            order(OrderSide::Sell, 100, 4),
+            order(OrderSide::Buy, 98, 6),
+            order(OrderSide::Buy, 99, 2),
+        ];
+        let tail = vec![
+            order(OrderSide::Buy, 100, 2),
+            order(OrderSide::Sell, 99, 1),
+            order(OrderSide::Buy, 97, 8),
+        ];
+        let received_at = Utc::now();
+        let after_restart = vec![
+            order(OrderSide::Buy, 101, 9).with_received_at(received_at),
+            order(OrderSide::Sell, 97, 15).with_received_at(received_at),
+            order(OrderSide::Buy, 102, 2).with_received_at(received_at),
+        ];
+
+        let mut live = MatchingEngine::new();
+        for o in before_snapshot {
+            live.match_order(o);
+        }
+        let cancelled = live.get_book("test").unwrap().bids.values().flatten().next().unwrap().order_id;
+
+        let snapshot_bytes = live.snapshot().to_bytes().unwrap();
+
+        let mut events = Vec::new();
+        for o in tail {
+            let result = live.match_order(o.clone());
+            events.extend(MatchingEvent::from_match(&o, &result));
+        }
+        live.cancel_order("test", cancelled).unwrap();
+        events.push(MatchingEvent::OrderCancelled {
+            order_id: cancelled,
+            instrument_id: "test".to_string(),
+            sequence: live.sequence(),
+        });
+
+        let mut restored = MatchingEngine::new();
+        let snapshot = EngineSnapshot::from_bytes(&snapshot_bytes).unwrap();
+        restored.restore(&snapshot, &events).unwrap();
+
+        assert_eq!(restored.sequence(), live.sequence());
+        assert_eq!(
+            serde_json::to_vec(&restored.snapshot().books).unwrap(),
+            serde_json::to_vec(&live.snapshot().books).unwrap()
+        );
+
+        for o in after_restart {
+            let live_trades = live.match_order(o.clone()).trades;
+            let restored_trades = restored.match_order(o).trades;
+            assert!(!live_trades.is_empty());
+            assert_eq!(trade_bytes(&live_trades), trade_bytes(&restored_trades));
+        }
+    }
+
+    #[test]
+    fn test_logged_commands_reproduce_events_bit_exact() {
+        use crate::clock::ManualClock;
+
+        let start = DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::days(20_000);
+        let clock = Arc::new(ManualClock::new(start));
+        let mut live = MatchingEngine::new();
+        live.set_clock(clock.clone());
+
+        let order = |side, price, qty| create_test_order(side, price, qty, TimeInForce::Gtc);
+        let resting = order(OrderSide::Sell, 101, 5);
+        let submitted = vec![
+            order(OrderSide::Sell, 100, 3),
+            resting.clone(),
+            order(OrderSide::Buy, 98, 4),
+            stop_order(OrderSide::Buy, 100, Some(102), 2, TriggerSource::LastTrade),
+            order(OrderSide::Buy, 100, 1),
+        ];
+        let mut log = Vec::new();
+        for o in &submitted {
+            clock.advance(chrono::Duration::milliseconds(250));
+            let result = live.match_order(o.clone());
+            log.extend(MatchingEvent::from_match(o, &result));
+        }
+        clock.advance(chrono::Duration::seconds(1));
+        let amend = live.amend_order(resting.order_id, Some(98), None).unwrap();
+        assert!(amend.has_trades());
+        log.extend(MatchingEvent::from_amend(resting.order_id, Some(98), None, &amend));
+
+        // Re-run the logged commands on an engine whose clock is a day off
+        let mut rerun = MatchingEngine::new();
+        rerun.set_clock(Arc::new(ManualClock::new(start + chrono::Duration::days(1))));
+        let mut rerun_log = Vec::new();
+        for o in &submitted {
+            let logged = log
+                .iter()
+                .find_map(|e| match e {
+                    MatchingEvent::OrderAccepted { order, .. } if order.order_id == o.order_id => Some(order),
+                    _ => None,
+                })
+                .unwrap();
+            let result = rerun.match_order(logged.clone());
+            rerun_log.extend(MatchingEvent::from_match(logged, &result));
+        }
+        let Some(MatchingEvent::OrderAmended { timestamp: Some(amended_at), .. }) =
+            log.iter().find(|e| matches!(e, MatchingEvent::OrderAmended { .. }))
+        else {
+            panic!("amend is logged with its time");
+        };
+        let result = rerun.amend_order_at(resting.order_id, Some(98), None, *amended_at).unwrap();
+        rerun_log.extend(MatchingEvent::from_amend(resting.order_id, Some(98), None, &result));
+
+        let trades: Vec<&Trade> = log
+            .iter()
+            .filter_map(|e| match e {
+                MatchingEvent::TradeExecuted { trade, .. } => Some(trade),
+                _ => None,
+            })
+            .collect();
+        assert!(trades.iter().all(|t| t.timestamp > start && t.timestamp < start + chrono::Duration::days(1)));
+        assert!(log.iter().any(|e| matches!(e, MatchingEvent::StopTriggered { .. })));
+        assert_eq!(serde_json::to_vec(&rerun_log).unwrap(), serde_json::to_vec(&log).unwrap());
+
+        // Replaying the log rebuilds the same books, order times included
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&log);
+        assert_eq!(
+            serde_json::to_vec(&replayed.snapshot().books).unwrap(),
+            serde_json::to_vec(&live.snapshot().books).unwrap()
+        );
+    }
+
     #[test]
     fn test_cancel_order() {
         let mut engine = MatchingEngine::new();
 
         // Add an order
-        let order = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Gtc);
+        let order = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Gtc);
         let order_id = order.order_id;
         engine.match_order(order);
 
@@ -829,12 +4484,107 @@ mod tests {
         assert_eq!(cancelled.unwrap().quantity, 10);
     }
 
+    #[test]
+    fn test_mass_cancel_filters_and_replays() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        let user = Uuid::new_v4();
+        let mut submit = |engine: &mut MatchingEngine, mut order: BookOrder, instrument: &str, owned: bool| {
+            order.instrument_id = instrument.to_string();
+            if owned {
+                order.user_id = user;
+            }
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+            order.order_id
+        };
+
+        let call_bid = submit(&mut engine, create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc), "BTC-50000-C", true);
+        let call_ask = submit(&mut engine, create_test_order(OrderSide::Sell, 110, 1, TimeInForce::Gtc), "BTC-50000-C", true);
+        let put_bid = submit(&mut engine, create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc), "BTC-40000-P", true);
+        let stop = submit(&mut engine, stop_order(OrderSide::Sell, 90, None, 1, TriggerSource::LastTrade), "BTC-40000-P", true);
+        let eth_bid = submit(&mut engine, create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc), "ETH-3000-C", true);
+        let other = submit(&mut engine, create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc), "BTC-50000-C", false);
+
+        let sequence = engine.sequence();
+        let none = engine.mass_cancel(&MassCancelFilter::new().with_instrument_id("SOL-10-C"));
+        assert!(none.cancelled.is_empty());
+        assert_eq!((none.sequence, engine.sequence()), (None, sequence));
+
+        let bids = engine.mass_cancel(&MassCancelFilter::new().with_user_id(user).with_underlying("BTC").with_side(OrderSide::Buy));
+        assert_eq!(bids.order_ids(), vec![call_bid, put_bid]);
+        assert_eq!(bids.sequence, Some(sequence + 1));
+        events.extend(MatchingEvent::from_mass_cancel(&bids));
+
+        let instrument = engine.mass_cancel(&MassCancelFilter::new().with_instrument_id("BTC-40000-P"));
+        assert_eq!(instrument.order_ids(), vec![stop]);
+        events.extend(MatchingEvent::from_mass_cancel(&instrument));
+
+        assert!(engine.get_order(call_ask).is_some());
+        assert!(engine.get_order(eth_bid).is_some());
+        assert!(engine.get_order(other).is_some());
+        assert_eq!(engine.stop_order_count(), 0);
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(replayed.resting_order_count(), 3);
+        assert_eq!(replayed.stop_order_count(), 0);
+        assert!(replayed.get_order(call_bid).is_none());
+    }
+
+    #[test]
+    fn test_expire_orders_sweeps_due_orders_and_replays() {
+        let mut engine = MatchingEngine::new();
+        let mut events = Vec::new();
+        let now = Utc::now();
+        let mut submit = |engine: &mut MatchingEngine, order: BookOrder| {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+            order.order_id
+        };
+
+        let gtc = submit(&mut engine, create_test_order(OrderSide::Buy, 100, 1, TimeInForce::Gtc));
+        let day = submit(
+            &mut engine,
+            create_test_order(OrderSide::Buy, 99, 1, TimeInForce::Day).with_expires_at(Some(now)),
+        );
+        let later = submit(
+            &mut engine,
+            create_test_order(OrderSide::Sell, 110, 1, TimeInForce::Gtd)
+                .with_expires_at(Some(now + chrono::Duration::hours(1))),
+        );
+        let stop = submit(
+            &mut engine,
+            stop_order(OrderSide::Sell, 90, None, 1, TriggerSource::LastTrade)
+                .with_expires_at(Some(now - chrono::Duration::minutes(1))),
+        );
+
+        let sequence = engine.sequence();
+        let expired = engine.expire_orders(now);
+        let ids: Vec<Uuid> = expired.iter().map(|e| e.order.order_id).collect();
+        assert_eq!(ids, vec![day, stop]);
+        assert_eq!(expired[1].sequence, sequence + 2);
+        events.extend(MatchingEvent::from_expired(&expired));
+
+        assert!(engine.expire_orders(now).is_empty());
+        assert!(engine.get_order(gtc).is_some());
+        assert!(engine.get_order(later).is_some());
+        assert_eq!(engine.stop_order_count(), 0);
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(replayed.resting_order_count(), 2);
+        assert!(replayed.get_order(day).is_none());
+    }
+
     #[test]
     fn test_no_crossing() {
         let mut engine = MatchingEngine::new();
 
         // Bid at 95
-        let bid = create_test_order(OrderSide::Buy, 95.0, 10, TimeInForce::Gtc);
+        let bid = create_test_order(OrderSide::Buy, 95, 10, TimeInForce::Gtc);
         let result = engine.match_order(bid);
 
         // No match (ask is higher at 100)
@@ -842,7 +4592,7 @@ mod tests {
         assert!(result.should_insert);
 
         // Ask at 100
-        let ask = create_test_order(OrderSide::Sell, 100.0, 10, TimeInForce::Gtc);
+        let ask = create_test_order(OrderSide::Sell, 100, 10, TimeInForce::Gtc);
         let result = engine.match_order(ask);
 
         // No match (bid is lower at 95)
@@ -855,15 +4605,509 @@ mod tests {
         let mut engine = MatchingEngine::new();
 
         // Bid at 100
-        let bid = create_test_order(OrderSide::Buy, 100.0, 10, TimeInForce::Gtc);
+        let bid = create_test_order(OrderSide::Buy, 100, 10, TimeInForce::Gtc);
         engine.match_order(bid);
 
         // Ask at 95 (crosses!)
-        let ask = create_test_order(OrderSide::Sell, 95.0, 10, TimeInForce::Gtc);
+        let ask = create_test_order(OrderSide::Sell, 95, 10, TimeInForce::Gtc);
         let result = engine.match_order(ask);
 
         // Should match at 100 (maker's price)
         assert_eq!(result.trades.len(), 1);
-        assert_eq!(result.trades[0].price, 100.0);
+        assert_eq!(result.trades[0].price, 100);
+    }
+
+    #[test]
+    fn test_halt_collects_orders_and_uncrosses_when_halt_ends() {
+        let mut engine = MatchingEngine::new_with_circuit_breakers(CircuitBreakerConfig {
+            enabled: true,
+            price_movement_enabled: true,
+            ..CircuitBreakerConfig::default()
+        });
+        let mut events = Vec::new();
+        let mut submit = |engine: &mut MatchingEngine, order: BookOrder| {
+            let result = engine.match_order(order.clone());
+            events.extend(MatchingEvent::from_match(&order, &result));
+            result
+        };
+
+        // A 20% jump trips the price movement breaker
+        submit(&mut engine, gtc(OrderSide::Sell, 100, 1));
+        submit(&mut engine, gtc(OrderSide::Buy, 100, 1));
+        submit(&mut engine, gtc(OrderSide::Sell, 120, 1));
+        submit(&mut engine, gtc(OrderSide::Buy, 120, 1));
+        assert!(engine.is_halted("test"));
+
+        // Orders reaching the halted book start an auction and are collected
+        let result = submit(&mut engine, gtc(OrderSide::Buy, 105, 5));
+        assert_eq!(result.auction_started.map(|a| a.reason), Some(AuctionReason::Halt));
+        assert!(result.sequence.is_some());
+        let result = submit(&mut engine, gtc(OrderSide::Sell, 103, 3));
+        assert!(result.trades.is_empty());
+        assert!(result.auction_started.is_none());
+
+        let mut ioc = gtc(OrderSide::Sell, 103, 1);
+        ioc.time_in_force = TimeInForce::Ioc;
+        assert!(submit(&mut engine, ioc).sequence.is_none());
+
+        // Buyers are left over at both candidates, so the higher one wins
+        let indicative = engine.indicative_uncross("test").unwrap();
+        assert_eq!((indicative.price, indicative.volume, indicative.imbalance), (105, 3, 2));
+
+        // Once the halt is over the next order uncrosses the auction first
+        engine.clear_circuit_breaker("test");
+        let result = submit(&mut engine, gtc(OrderSide::Buy, 90, 1));
+        let uncross = result.uncrossed.as_ref().unwrap();
+        assert_eq!(uncross.price, Some(105));
+        assert_eq!(uncross.volume(), 3);
+        assert_eq!(uncross.trades[0].aggressor_side, OrderSide::Sell);
+        assert!(result.sequence.unwrap() > uncross.sequence);
+        assert!(engine.auction("test").is_none());
+        let book = engine.get_book("test").unwrap();
+        assert_eq!((book.best_bid(), book.best_ask()), (Some(105), None));
+        assert_eq!(book.bid_quantity_at(105), 2);
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), engine.sequence());
+        assert_eq!(
+            serde_json::to_vec(&replayed.snapshot().books).unwrap(),
+            serde_json::to_vec(&engine.snapshot().books).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_price_band_rejects_orders_priced_through_the_mark_band() {
+        let mut engine = MatchingEngine::new_with_circuit_breakers(CircuitBreakerConfig {
+            enabled: true,
+            price_band_enabled: true,
+            price_band_percent: 10.0,
+            ..CircuitBreakerConfig::default()
+        });
+        engine.set_market_protection_percent(50.0);
+
+        // No band until a mark price is known
+        assert!(engine.price_band("test").is_none());
+        engine.set_mark_price("test", 10000);
+        let band = engine.price_band("test").unwrap();
+        assert_eq!((band.lower, band.upper), (90.0, 110.0));
+
+        // Aggressive prices through the band are rejected, passive ones are not
+        assert!(engine.match_order(gtc(OrderSide::Buy, 11001, 1)).sequence.is_none());
+        assert!(engine.match_order(gtc(OrderSide::Sell, 8999, 1)).sequence.is_none());
+        assert!(engine.match_order(gtc(OrderSide::Buy, 8000, 1)).sequence.is_some());
+        let ask = gtc(OrderSide::Sell, 10500, 1);
+        let ask_id = ask.order_id;
+        engine.match_order(ask);
+        engine.match_order(gtc(OrderSide::Sell, 11000, 1));
+        engine.match_order(gtc(OrderSide::Sell, 11200, 1));
+
+        // A market order sweeps no further than the band edge
+        let market = gtc(OrderSide::Buy, 0, 3).with_order_type(crate::domain::OrderType::Market);
+        let result = engine.match_order(market);
+        let prices: Vec<Ticks> = result.trades.iter().map(|t| t.price).collect();
+        assert_eq!(prices, vec![10500, 11000]);
+
+        // Amends are held to the band too
+        engine.match_order(gtc(OrderSide::Sell, 10800, 1));
+        let resting = engine.get_book("test").unwrap().asks[&10800][0].order_id;
+        assert!(matches!(
+            engine.amend_order(resting, Some(8500), None),
+            Err(MatchingError::CircuitBreaker(_))
+        ));
+        assert!(engine.get_order(ask_id).is_none());
+
+        let status = engine.circuit_breaker_status();
+        assert_eq!(status[0].price_band, Some(band));
+        assert!(!status[0].halted);
+    }
+
+    #[test]
+    fn test_manual_halts_of_instruments_and_underlyings() {
+        let mut engine = MatchingEngine::new();
+        let order = |instrument: &str, side, price| gtc(side, price, 1).with_instrument_id(instrument);
+
+        // Halting an underlying halts each of its instruments, not others
+        engine.halt_underlying("BTC");
+        assert!(engine.is_halted("BTC-20260315-50000-C"));
+        assert!(!engine.is_halted("ETH-20260315-3000-C"));
+        let result = engine.match_order(order("BTC-20260315-50000-C", OrderSide::Sell, 100));
+        assert_eq!(result.auction_started.map(|a| a.reason), Some(AuctionReason::Halt));
+        engine.match_order(order("ETH-20260315-3000-C", OrderSide::Sell, 100));
+        let result = engine.match_order(order("ETH-20260315-3000-C", OrderSide::Buy, 100));
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(engine.halted_underlyings(), vec!["BTC".to_string()]);
+
+        // Resuming lets the next order uncross the halt auction
+        engine.resume_underlying("BTC");
+        let result = engine.match_order(order("BTC-20260315-50000-C", OrderSide::Buy, 100));
+        assert!(result.uncrossed.is_some());
+        assert!(engine.auction("BTC-20260315-50000-C").is_none());
+
+        // Manual instrument halts last until resumed
+        engine.halt_instrument("ETH-20260315-3000-C");
+        let status = engine.circuit_breaker_status();
+        assert!(status[0].halted && status[0].manually_halted);
+        engine.resume_instrument("ETH-20260315-3000-C");
+        assert!(!engine.is_halted("ETH-20260315-3000-C"));
+    }
+
+    #[test]
+    fn test_opening_auction_survives_compaction_and_snapshot() {
+        let mut engine = MatchingEngine::new();
+        engine.start_auction("test", AuctionReason::Opening).unwrap();
+        assert!(matches!(
+            engine.start_auction("test", AuctionReason::Opening),
+            Err(MatchingError::Auction(_))
+        ));
+        assert!(matches!(engine.uncross("other"), Err(MatchingError::Auction(_))));
+
+        engine.match_order(gtc(OrderSide::Buy, 101, 4));
+        engine.match_order(gtc(OrderSide::Sell, 100, 5));
+        assert!(engine.match_order(gtc(OrderSide::Sell, 99, 1).with_post_only(PostOnly::Reject)).sequence.is_none());
+        assert_eq!(engine.get_book("test").unwrap().spread(), Some(-1));
+
+        // The crossed book and the auction come back from either form
+        let mut compacted = MatchingEngine::new();
+        compacted.replay(&engine.compacted_events());
+        let mut restored = MatchingEngine::new();
+        restored.restore(&engine.snapshot(), &[]).unwrap();
+
+        for engine in [&mut engine, &mut compacted, &mut restored] {
+            assert_eq!(engine.auction("test").map(|a| a.reason), Some(AuctionReason::Opening));
+            // Sellers are left over: the lower candidate wins
+            let result = engine.uncross("test").unwrap();
+            assert_eq!(result.price, Some(100));
+            assert_eq!(result.volume(), 4);
+            let book = engine.get_book("test").unwrap();
+            assert_eq!((book.best_bid(), book.best_ask()), (None, Some(100)));
+            assert_eq!(book.ask_quantity_at(100), 1);
+        }
+    }
+
+    fn leg_order(instrument_id: &str, side: OrderSide, price: Ticks, quantity: u32, tif: TimeInForce) -> BookOrder {
+        create_test_order(side, price, quantity, tif).with_instrument_id(instrument_id)
+    }
+
+    #[test]
+    fn test_combo_trades_implied_in_and_implied_out() {
+        let mut engine = MatchingEngine::new();
+        let straddle = ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P");
+        assert!(engine.define_combo(straddle.clone()).unwrap().is_some());
+        assert_eq!(engine.define_combo(straddle.clone()).unwrap(), None);
+        assert!(matches!(
+            engine.define_combo(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-Q")),
+            Err(MatchingError::InvalidCombo(_))
+        ));
+
+        engine.match_order(leg_order("BTC-C", OrderSide::Sell, 500, 5, TimeInForce::Gtc));
+        engine.match_order(leg_order("BTC-P", OrderSide::Sell, 200, 3, TimeInForce::Gtc));
+
+        // Implied-in: the legs' asks net to 700, 3 units are there
+        let combo = leg_order("BTC-STRADDLE", OrderSide::Buy, 700, 4, TimeInForce::Gtc);
+        let result = engine.match_order(combo.clone());
+        let legs: Vec<_> = result.trades.iter().map(|t| (t.instrument_id.as_str(), t.price, t.quantity)).collect();
+        assert_eq!(legs, vec![("BTC-C", 500, 3), ("BTC-P", 200, 3)]);
+        assert!(result.trades.iter().all(|t| t.combo_id.as_deref() == Some("BTC-STRADDLE")));
+        assert_eq!(engine.get_book("BTC-STRADDLE").unwrap().bid_quantity_at(700), 1);
+
+        // Implied-out: the resting combo bid and the call ask imply a put bid at 200
+        let result = engine.match_order(leg_order("BTC-P", OrderSide::Sell, 200, 1, TimeInForce::Gtc));
+        let legs: Vec<_> = result.trades.iter().map(|t| (t.instrument_id.as_str(), t.price, t.quantity)).collect();
+        assert_eq!(legs, vec![("BTC-C", 500, 1), ("BTC-P", 200, 1)]);
+        assert!(result.trades.iter().all(|t| t.maker_order_id == combo.order_id || t.taker_order_id == combo.order_id));
+        assert!(engine.get_book("BTC-STRADDLE").unwrap().is_empty());
+        assert_eq!(engine.get_book("BTC-C").unwrap().ask_quantity_at(500), 1);
+
+        // Combos come back from either form
+        let mut compacted = MatchingEngine::new();
+        compacted.replay(&engine.compacted_events());
+        let mut restored = MatchingEngine::new();
+        restored.restore(&engine.snapshot(), &[]).unwrap();
+        for other in [&compacted, &restored] {
+            assert_eq!(other.combos(), engine.combos());
+            assert_eq!(other.sequence(), engine.sequence());
+        }
+    }
+
+    #[test]
+    fn test_combo_book_fills_sequence_legs_in_order() {
+        let mut engine = MatchingEngine::new();
+        engine
+            .define_combo(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P"))
+            .unwrap();
+        engine.match_order(leg_order("BTC-STRADDLE", OrderSide::Sell, 700, 1, TimeInForce::Gtc));
+        engine.match_order(leg_order("BTC-STRADDLE", OrderSide::Sell, 700, 1, TimeInForce::Gtc));
+
+        // Two combo fills in one match: four leg trades, published in sequence
+        let order = leg_order("BTC-STRADDLE", OrderSide::Buy, 700, 2, TimeInForce::Gtc);
+        let result = engine.match_order(order.clone());
+        assert_eq!(result.trades.len(), 4);
+        let sequences: Vec<u64> = MatchingEvent::from_match(&order, &result)
+            .iter()
+            .map(MatchingEvent::sequence)
+            .collect();
+        assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]), "{sequences:?}");
+
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&engine.compacted_events());
+        assert_eq!(replayed.sequence(), engine.sequence());
+    }
+
+    #[test]
+    fn test_fok_combo_rolls_back_all_legs() {
+        let mut engine = MatchingEngine::new();
+        engine
+            .define_combo(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P"))
+            .unwrap();
+        engine.match_order(leg_order("BTC-C", OrderSide::Sell, 500, 5, TimeInForce::Gtc));
+        engine.match_order(leg_order("BTC-P", OrderSide::Sell, 200, 2, TimeInForce::Gtc));
+
+        // Only 2 of 3 units are implied: nothing trades on either leg
+        let result = engine.match_order(leg_order("BTC-STRADDLE", OrderSide::Buy, 700, 3, TimeInForce::Fok));
+        assert!(result.trades.is_empty());
+        assert!(!result.should_insert);
+        assert_eq!(engine.get_book("BTC-C").unwrap().ask_quantity_at(500), 5);
+        assert_eq!(engine.get_book("BTC-P").unwrap().ask_quantity_at(200), 2);
+
+        // The outright put orders are still indexed and can be cancelled
+        let puts = engine.get_book("BTC-P").unwrap().orders().map(|o| o.order_id).collect::<Vec<_>>();
+        assert!(engine.cancel_order("BTC-P", puts[0]).is_some());
+    }
+
+    #[test]
+    fn test_fok_combo_restores_levels_in_queue_order() {
+        let mut engine = MatchingEngine::new();
+        engine
+            .define_combo(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P"))
+            .unwrap();
+        engine.match_order(leg_order("BTC-STRADDLE", OrderSide::Sell, 690, 1, TimeInForce::Gtc));
+        // An iceberg showing 1 of 2 ahead of a plain order
+        engine.match_order(leg_order("BTC-C", OrderSide::Sell, 500, 2, TimeInForce::Gtc).with_display_quantity(Some(1)));
+        engine.match_order(leg_order("BTC-C", OrderSide::Sell, 500, 1, TimeInForce::Gtc));
+        engine.match_order(leg_order("BTC-P", OrderSide::Sell, 200, 1, TimeInForce::Gtc));
+        let queue = |engine: &MatchingEngine, id: &str| -> Vec<(Uuid, u64, u32)> {
+            let book = engine.get_book(id).unwrap();
+            book.orders().map(|o| (o.order_id, o.sequence, o.quantity)).collect()
+        };
+        let before: Vec<_> = ["BTC-STRADDLE", "BTC-C", "BTC-P"].iter().map(|id| queue(&engine, id)).collect();
+        let sequence = engine.sequence();
+
+        // One unit from the combo book and one implied, of three
+        let result = engine.match_order(leg_order("BTC-STRADDLE", OrderSide::Buy, 700, 3, TimeInForce::Fok));
+        assert!(result.trades.is_empty());
+        let after: Vec<_> = ["BTC-STRADDLE", "BTC-C", "BTC-P"].iter().map(|id| queue(&engine, id)).collect();
+        assert_eq!(after, before);
+        // Only the rejected order's own sequence is used
+        assert_eq!(engine.sequence(), sequence + 1);
+        assert!(engine.undo.entries.is_empty() && !engine.undo.is_recording());
+    }
+
+    fn block(instrument_id: &str, price: Ticks, quantity: u32, taker_side: OrderSide) -> BlockTrade {
+        BlockTrade {
+            rfq_id: Uuid::new_v4(),
+            instrument_id: instrument_id.to_string(),
+            price,
+            quantity,
+            taker_order_id: Uuid::new_v4(),
+            taker_user_id: Uuid::new_v4(),
+            taker_side,
+            maker_order_id: Uuid::new_v4(),
+            maker_user_id: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn test_block_trade_is_booked_off_book() {
+        let mut engine = MatchingEngine::new();
+        engine.set_min_block_size("BTC", 25);
+        engine.set_min_block_size("BTC-20260315-50000-C", 50);
+        assert_eq!(engine.min_block_size("BTC-20260320-60000-P"), 25);
+        assert_eq!(engine.min_block_size("ETH-20260315-3000-C"), 1);
+        engine.match_order(leg_order("BTC-20260320-60000-P", OrderSide::Sell, 110, 5, TimeInForce::Gtc));
+
+        assert!(matches!(
+            engine.book_block_trade(&block("BTC-20260320-60000-P", 100, 24, OrderSide::Buy)),
+            Err(MatchingError::InvalidBlockTrade(_))
+        ));
+        assert!(matches!(
+            engine.book_block_trade(&block("BTC-20260315-50000-C", 100, 25, OrderSide::Buy)),
+            Err(MatchingError::InvalidBlockTrade(_))
+        ));
+
+        // The taker sells through the resting ask without touching it
+        let trade = block("BTC-20260320-60000-P", 100, 25, OrderSide::Sell);
+        let trades = engine.book_block_trade(&trade).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].rfq_id, Some(trade.rfq_id));
+        assert_eq!((trades[0].buyer_id, trades[0].seller_id), (trade.maker_user_id, trade.taker_user_id));
+        assert_eq!((trades[0].price, trades[0].quantity), (100, 25));
+        let book = engine.get_book("BTC-20260320-60000-P").unwrap();
+        assert_eq!((book.best_ask(), book.ask_quantity_at(110)), (Some(110), 5));
+        assert!(!engine.last_prices.contains_key("BTC-20260320-60000-P"));
+
+        // Replay keeps the block trade's sequence
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&MatchingEvent::from_block_trade(&trades));
+        assert_eq!(replayed.sequence(), engine.sequence());
+    }
+
+    #[test]
+    fn test_combo_block_trade_books_every_leg() {
+        let mut engine = MatchingEngine::new();
+        engine
+            .define_combo(ComboDefinition::straddle("BTC-STRADDLE", "BTC-C", "BTC-P"))
+            .unwrap();
+        engine.set_mark_price("BTC-C", 500);
+        engine.set_mark_price("BTC-P", 200);
+
+        let trade = block("BTC-STRADDLE", 650, 10, OrderSide::Buy);
+        let trades = engine.book_block_trade(&trade).unwrap();
+        let legs: Vec<_> = trades.iter().map(|t| (t.instrument_id.as_str(), t.quantity, t.buyer_id)).collect();
+        assert_eq!(legs, vec![("BTC-C", 10, trade.taker_user_id), ("BTC-P", 10, trade.taker_user_id)]);
+        assert_eq!(trades.iter().map(|t| t.price).sum::<Ticks>(), 650);
+        assert!(trades
+            .iter()
+            .all(|t| t.combo_id.as_deref() == Some("BTC-STRADDLE") && t.rfq_id == Some(trade.rfq_id)));
+
+        engine.halt_instrument("BTC-P");
+        assert!(matches!(
+            engine.book_block_trade(&block("BTC-STRADDLE", 650, 10, OrderSide::Buy)),
+            Err(MatchingError::CircuitBreaker(_))
+        ));
+    }
+
+    fn quote(user_id: Uuid, instrument_id: &str, side: OrderSide, price: Ticks, quantity: u32) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), user_id, side, price, quantity, 0, TimeInForce::Gtc)
+            .with_instrument_id(instrument_id)
+    }
+
+    #[test]
+    fn test_mass_quote_replaces_previous_quotes() {
+        let mut engine = MatchingEngine::new();
+        let maker = Uuid::new_v4();
+        let call = "BTC-20260315-50000-C";
+        engine.match_order(gtc(OrderSide::Sell, 120, 5).with_instrument_id(call));
+
+        let first = engine
+            .mass_quote(maker, vec![quote(maker, call, OrderSide::Buy, 100, 10), quote(maker, call, OrderSide::Sell, 110, 10)])
+            .unwrap();
+        assert!(first.replaced.cancelled.is_empty());
+        assert_eq!(first.resting_order_ids().len(), 2);
+        assert!(engine.get_order(first.quotes[0].0.order_id).unwrap().quote);
+
+        // The new bid would cross the resting ask at 120, so it is rejected
+        let crossing = quote(maker, call, OrderSide::Buy, 125, 10);
+        let second = engine
+            .mass_quote(maker, vec![crossing.clone(), quote(maker, call, OrderSide::Sell, 111, 10)])
+            .unwrap();
+        assert_eq!(second.replaced.order_ids(), first.resting_order_ids());
+        assert_eq!(second.rejected_order_ids(), vec![crossing.order_id]);
+        assert!(!second.quotes.iter().any(|(_, result)| result.has_trades()));
+        let book = engine.get_book(call).unwrap();
+        assert_eq!((book.best_bid(), book.best_ask()), (None, Some(111)));
+
+        let other = Uuid::new_v4();
+        assert!(matches!(
+            engine.mass_quote(maker, vec![quote(other, call, OrderSide::Buy, 100, 1)]),
+            Err(MatchingError::InvalidOrder(_))
+        ));
+    }
+
+    #[test]
+    fn test_mmp_trip_cancels_quotes_and_freezes_until_reset() {
+        let mut engine = MatchingEngine::new();
+        engine.set_mmp_limits("BTC", MmpLimits::new(60_000).with_quantity(8));
+        let maker = Uuid::new_v4();
+        let (call, put) = ("BTC-20260315-50000-C", "BTC-20260315-50000-P");
+
+        let quoted = engine
+            .mass_quote(
+                maker,
+                vec![
+                    quote(maker, call, OrderSide::Sell, 110, 10),
+                    quote(maker, put, OrderSide::Buy, 90, 10),
+                    quote(maker, put, OrderSide::Sell, 100, 10),
+                ],
+            )
+            .unwrap();
+        let mut events = MatchingEvent::from_mass_quote(&quoted);
+
+        let below_limit = gtc(OrderSide::Buy, 110, 5).with_instrument_id(call);
+        let result = engine.match_order(below_limit.clone());
+        assert!(result.mmp_triggered.is_empty());
+        events.extend(MatchingEvent::from_match(&below_limit, &result));
+
+        let taker = gtc(OrderSide::Sell, 90, 3).with_instrument_id(put);
+        let result = engine.match_order(taker.clone());
+        events.extend(MatchingEvent::from_match(&taker, &result));
+        let trip = &result.mmp_triggered[0];
+        assert_eq!((trip.breach, trip.exposure.quantity), (MmpBreach::Quantity, 8));
+        assert_eq!(trip.cancelled.len(), 3);
+        assert!(engine.get_book(call).unwrap().is_empty());
+        assert!(engine.get_book(put).unwrap().is_empty());
+        assert!(matches!(
+            engine.mass_quote(maker, vec![quote(maker, call, OrderSide::Sell, 110, 1)]),
+            Err(MatchingError::MmpFrozen(_))
+        ));
+
+        // Replay freezes the maker again without recomputing exposure
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.resting_order_count(), 0);
+        assert!(replayed.mmp_status(maker)[0].frozen.is_some());
+        let mut compacted = MatchingEngine::new();
+        compacted.replay(&replayed.compacted_events());
+        assert!(compacted.mass_quote(maker, vec![quote(maker, call, OrderSide::Sell, 110, 1)]).is_err());
+
+        let sequence = engine.reset_mmp(maker, "BTC");
+        assert!(sequence.is_some());
+        assert!(engine.mmp_status(maker).is_empty());
+        assert!(engine.mass_quote(maker, vec![quote(maker, call, OrderSide::Sell, 110, 1)]).is_ok());
+    }
+
+    #[test]
+    fn test_from_config_applies_engine_settings() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../master_config/master_config.yaml");
+        let mut config = config::load_config(path).unwrap();
+        let me_config = config.matching_engine.as_mut().unwrap();
+        me_config.underlying_algorithms.insert("ETH".to_string(), "pro_rata".to_string());
+        me_config.mmp.limits.insert(
+            "BTC".to_string(),
+            config::MmpLimitsConfig { quantity: Some(500), delta: None, vega: None },
+        );
+        config.oms.as_mut().unwrap().rfq.min_block_sizes.insert("BTC".to_string(), 25);
+
+        let mut engine = MatchingEngine::from_config(&config);
+        let (call, eth_call) = ("BTC-20260315-50000-C", "ETH-20260315-3000-C");
+        assert_eq!(engine.price_scale(call).tick_size, 0.5);
+        assert_eq!(engine.market_protection_percent(), 5.0);
+        assert_eq!(engine.matching_algorithm(call), MatchingAlgorithm::PriceTimePriority);
+        assert!(matches!(engine.matching_algorithm(eth_call), MatchingAlgorithm::ProRata { .. }));
+        assert_eq!((engine.min_block_size(call), engine.min_block_size(eth_call)), (25, 1));
+        assert_eq!(engine.mmp_limits("BTC").and_then(|limits| limits.quantity), Some(500));
+
+        // Circuit breakers are on, with a price band around the mark
+        engine.set_mark_price(call, 2000);
+        assert!(engine.price_band(call).is_some());
+    }
+
+    #[test]
+    fn test_price_scale_lookup_and_drift_free_levels() {
+        let mut engine = MatchingEngine::new();
+        engine.set_price_scale("BTC", PriceScale::new(0.1));
+        engine.set_price_scale("BTC-20260315-50000-C", PriceScale::new(5.0));
+        assert_eq!(engine.price_scale("BTC-20260320-60000-P").tick_size, 0.1);
+        assert_eq!(engine.price_scale("BTC-20260315-50000-C").tick_size, 5.0);
+        assert_eq!(engine.price_scale("ETH-20260315-3000-C"), PriceScale::default());
+
+        // 0.1 + 0.2 and 0.3 are the same level once converted to ticks
+        let scale = engine.price_scale("BTC-20260320-60000-P");
+        let bid = scale.order_ticks(0.1 + 0.2, OrderSide::Buy).unwrap();
+        let ask = scale.order_ticks(0.3, OrderSide::Sell).unwrap();
+        engine.match_order(gtc(OrderSide::Buy, bid, 1));
+        let result = engine.match_order(gtc(OrderSide::Sell, ask, 1));
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(scale.to_price(result.trades[0].price), 0.3);
     }
 }
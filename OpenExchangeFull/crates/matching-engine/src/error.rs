@@ -21,6 +21,26 @@ pub enum MatchingError {
     #[error("Circuit breaker triggered: {0}")]
     CircuitBreaker(String),
 
+    /// Call auction operation not possible in the instrument's state
+    #[error("Auction error: {0}")]
+    Auction(String),
+
+    /// Combo definition that cannot be traded, or conflicts with an existing one
+    #[error("Invalid combo: {0}")]
+    InvalidCombo(String),
+
+    /// Block trade that cannot be booked
+    #[error("Invalid block trade: {0}")]
+    InvalidBlockTrade(String),
+
+    /// Quoting frozen by market maker protection until the maker resets it
+    #[error("Quoting frozen by market maker protection: {0}")]
+    MmpFrozen(String),
+
+    /// Snapshot written by an incompatible format version
+    #[error("Unsupported snapshot version: {0}")]
+    UnsupportedSnapshot(u32),
+
     /// Internal error
     #[error("Internal error: {0}")]
     Internal(String),
@@ -2,10 +2,18 @@
 //!
 //! These events are used for the event log to ensure determinism.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::Trade;
+use crate::auction::{AuctionReason, CallAuction};
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, Trade};
+use crate::mmp::{MmpBreach, MmpFreeze};
+use crate::price::Ticks;
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, MmpTrip, TriggeredStop, UncrossResult,
+};
 
 /// Event in the matching engine
 ///
@@ -14,8 +22,18 @@ use crate::domain::Trade;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MatchingEvent {
-    /// An order was accepted into the book
+    /// An order was accepted and sequenced by the engine
+    ///
+    /// Carries the full order so the log can be replayed.
     OrderAccepted {
+        /// The order as submitted (with its assigned sequence)
+        order: BookOrder,
+        /// Sequence number
+        sequence: u64,
+    },
+    
+    /// An order was cancelled
+    OrderCancelled {
         /// Order ID
         order_id: Uuid,
         /// Instrument ID
@@ -24,8 +42,19 @@ pub enum MatchingEvent {
         sequence: u64,
     },
     
-    /// An order was cancelled
-    OrderCancelled {
+    /// Orders were removed together by a mass cancel
+    ///
+    /// Logs the IDs rather than the filter, so replay removes exactly the
+    /// orders that were cancelled.
+    OrdersMassCancelled {
+        /// Cancelled order IDs, in sequence order
+        order_ids: Vec<Uuid>,
+        /// Sequence number
+        sequence: u64,
+    },
+
+    /// A GTD or DAY order reached its expiry and was removed
+    OrderExpired {
         /// Order ID
         order_id: Uuid,
         /// Instrument ID
@@ -33,7 +62,122 @@ pub enum MatchingEvent {
         /// Sequence number
         sequence: u64,
     },
+
+    /// A resting order was amended
+    ///
+    /// Replayed through `MatchingEngine::amend_order`, which re-derives
+    /// whether priority was kept and any trades from a re-priced order.
+    OrderAmended {
+        /// Order ID
+        order_id: Uuid,
+        /// Instrument ID
+        instrument_id: String,
+        /// New limit price in ticks (None if unchanged)
+        new_price: Option<Ticks>,
+        /// New open quantity (None if unchanged)
+        new_quantity: Option<u32>,
+        /// Sequence number
+        sequence: u64,
+        /// When the amend was received (timestamp of any trades)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<DateTime<Utc>>,
+    },
     
+    /// A held stop order was released from the trigger book
+    ///
+    /// The released order follows as its own `OrderAccepted`. On replay
+    /// stops are released only by these events, never by re-derived prices.
+    StopTriggered {
+        /// Order ID
+        order_id: Uuid,
+        /// Instrument ID
+        instrument_id: String,
+        /// Reference price (ticks) that fired the stop
+        trigger_price: Ticks,
+        /// Sequence number
+        sequence: u64,
+    },
+
+    /// Self-trade prevention cancelled orders while matching an incoming order
+    ///
+    /// Derived from the accepted or amended order it belongs to (same
+    /// sequence), so it is skipped on replay like trades.
+    SelfTradePrevented {
+        /// Incoming (taker) order ID
+        order_id: Uuid,
+        /// Instrument ID
+        instrument_id: String,
+        /// Cancelled order IDs (resting makers and/or the taker)
+        cancelled_order_ids: Vec<Uuid>,
+        /// Sequence number of the incoming order
+        sequence: u64,
+    },
+
+    /// An instrument entered a call auction
+    ///
+    /// Orders accepted until the uncross are collected without matching.
+    AuctionStarted {
+        /// Instrument ID
+        instrument_id: String,
+        /// Why the auction runs
+        reason: AuctionReason,
+        /// Sequence number
+        sequence: u64,
+    },
+
+    /// A call auction was uncrossed and continuous trading resumed
+    ///
+    /// Replayed at the logged price; the uncross trades follow as
+    /// `TradeExecuted` and are skipped on replay.
+    AuctionUncrossed {
+        /// Instrument ID
+        instrument_id: String,
+        /// Equilibrium price in ticks (None if nothing traded)
+        price: Option<Ticks>,
+        /// Sequence number
+        sequence: u64,
+        /// When the uncross ran (timestamp of its trades)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<DateTime<Utc>>,
+    },
+
+    /// A combo instrument was defined
+    ///
+    /// Logged ahead of the first order on the combo so replay can match it.
+    ComboDefined {
+        /// The combo and its legs
+        combo: ComboDefinition,
+        /// Sequence number
+        sequence: u64,
+    },
+
+    /// Market maker protection cancelled a maker's quotes and froze quoting
+    ///
+    /// Replay removes exactly the logged orders and freezes the underlying
+    /// again, without recomputing exposure.
+    MmpTriggered {
+        /// Maker whose quotes were cancelled
+        user_id: Uuid,
+        /// Underlying the protection tripped in
+        underlying: String,
+        /// Limit that tripped
+        breach: MmpBreach,
+        /// Cancelled quote IDs, in sequence order
+        order_ids: Vec<Uuid>,
+        /// Sequence number
+        sequence: u64,
+    },
+
+    /// A maker reset market maker protection and may quote again
+    MmpReset {
+        /// Maker
+        user_id: Uuid,
+        /// Underlying quoting resumes in
+        underlying: String,
+        /// Sequence number
+        sequence: u64,
+    },
+
     /// A trade was executed
     TradeExecuted {
         /// Trade details
@@ -55,8 +199,224 @@ impl MatchingEvent {
         match self {
             MatchingEvent::OrderAccepted { sequence, .. } => *sequence,
             MatchingEvent::OrderCancelled { sequence, .. } => *sequence,
+            MatchingEvent::OrdersMassCancelled { sequence, .. } => *sequence,
+            MatchingEvent::OrderExpired { sequence, .. } => *sequence,
+            MatchingEvent::OrderAmended { sequence, .. } => *sequence,
+            MatchingEvent::StopTriggered { sequence, .. } => *sequence,
+            MatchingEvent::SelfTradePrevented { sequence, .. } => *sequence,
+            MatchingEvent::AuctionStarted { sequence, .. } => *sequence,
+            MatchingEvent::AuctionUncrossed { sequence, .. } => *sequence,
+            MatchingEvent::ComboDefined { sequence, .. } => *sequence,
+            MatchingEvent::MmpTriggered { sequence, .. } => *sequence,
+            MatchingEvent::MmpReset { sequence, .. } => *sequence,
             MatchingEvent::TradeExecuted { sequence, .. } => *sequence,
             MatchingEvent::SequenceReset { sequence, .. } => *sequence,
         }
     }
+
+    /// Build the events recording an order submission and its outcome
+    ///
+    /// Orders rejected before sequencing (circuit breaker, FOK pre-check)
+    /// never touched the book and produce no events. Market orders are logged
+    /// with the protection limit they were matched against, so replay does
+    /// not depend on the mark price at the time; repriced post-only orders
+    /// are logged at the price they rested at.
+    ///
+    /// An auction uncrossed or started on the order's arrival is logged
+    /// ahead of it, even if the order itself was then rejected. The order is
+    /// logged with the time the engine received it, which replay reuses.
+    pub fn from_match(order: &BookOrder, result: &MatchResult) -> Vec<MatchingEvent> {
+        let mut events = Vec::with_capacity(result.trades.len() + 1);
+        if let Some(uncross) = &result.uncrossed {
+            events.extend(Self::from_uncross(uncross));
+        }
+        if let Some(auction) = &result.auction_started {
+            events.push(Self::auction_started(&order.instrument_id, auction));
+        }
+
+        let Some(sequence) = result.sequence else {
+            return events;
+        };
+
+        let mut accepted = order.clone();
+        accepted.sequence = sequence;
+        accepted.received_at = result.received_at.or(order.received_at);
+        if let Some(price) = result.protection_price.or(result.repriced_to) {
+            accepted.price = price;
+        }
+
+        events.push(MatchingEvent::OrderAccepted {
+            order: accepted,
+            sequence,
+        });
+        events.extend(result.trades.iter().map(|trade| MatchingEvent::TradeExecuted {
+            trade: trade.clone(),
+            sequence: trade.sequence,
+        }));
+        events.extend(Self::self_trade_prevented(order, &result.stp_cancelled, sequence));
+        events.extend(result.mmp_triggered.iter().map(Self::mmp_triggered));
+        events.extend(Self::from_triggers(&result.triggered));
+        events
+    }
+
+    /// Build the events recording released stops and their outcomes
+    pub fn from_triggers(triggered: &[TriggeredStop]) -> Vec<MatchingEvent> {
+        let mut events = Vec::new();
+        for stop in triggered {
+            events.push(MatchingEvent::StopTriggered {
+                order_id: stop.order.order_id,
+                instrument_id: stop.order.instrument_id.clone(),
+                trigger_price: stop.trigger_price,
+                sequence: stop.sequence,
+            });
+            events.extend(Self::from_match(&stop.order, &stop.result));
+        }
+        events
+    }
+
+    /// Build the events recording an amend and any trades it caused
+    pub fn from_amend(
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+        result: &AmendResult,
+    ) -> Vec<MatchingEvent> {
+        let mut events = Vec::with_capacity(result.trades.len() + 1);
+        events.push(MatchingEvent::OrderAmended {
+            order_id,
+            instrument_id: result.previous.instrument_id.clone(),
+            new_price,
+            new_quantity,
+            sequence: result.sequence,
+            timestamp: Some(result.timestamp),
+        });
+        events.extend(result.trades.iter().map(|trade| MatchingEvent::TradeExecuted {
+            trade: trade.clone(),
+            sequence: trade.sequence,
+        }));
+        events.extend(Self::self_trade_prevented(
+            &result.previous,
+            &result.stp_cancelled,
+            result.sequence,
+        ));
+        events.extend(result.mmp_triggered.iter().map(Self::mmp_triggered));
+        events.extend(Self::from_triggers(&result.triggered));
+        events
+    }
+
+    /// Event recording a mass cancel, if it removed anything
+    pub fn from_mass_cancel(result: &MassCancelResult) -> Option<MatchingEvent> {
+        result.sequence.map(|sequence| MatchingEvent::OrdersMassCancelled {
+            order_ids: result.order_ids(),
+            sequence,
+        })
+    }
+
+    /// Build the events recording a mass quote
+    ///
+    /// The replaced quotes are logged as one mass cancel, followed by each
+    /// new quote as it was accepted or rejected.
+    pub fn from_mass_quote(result: &MassQuoteResult) -> Vec<MatchingEvent> {
+        let mut events: Vec<MatchingEvent> = Self::from_mass_cancel(&result.replaced).into_iter().collect();
+        for (order, outcome) in &result.quotes {
+            events.extend(Self::from_match(order, outcome));
+        }
+        events
+    }
+
+    /// Event recording a market maker protection trip
+    pub fn mmp_triggered(trip: &MmpTrip) -> MatchingEvent {
+        MatchingEvent::MmpTriggered {
+            user_id: trip.user_id,
+            underlying: trip.underlying.clone(),
+            breach: trip.breach,
+            order_ids: trip.order_ids(),
+            sequence: trip.sequence,
+        }
+    }
+
+    /// Event restoring a frozen underlying in a compacted log
+    pub fn mmp_frozen(freeze: &MmpFreeze) -> MatchingEvent {
+        MatchingEvent::MmpTriggered {
+            user_id: freeze.user_id,
+            underlying: freeze.underlying.clone(),
+            breach: freeze.breach,
+            order_ids: Vec::new(),
+            sequence: freeze.sequence,
+        }
+    }
+
+    /// Build the events recording an expiry sweep
+    pub fn from_expired(expired: &[ExpiredOrder]) -> Vec<MatchingEvent> {
+        expired
+            .iter()
+            .map(|e| MatchingEvent::OrderExpired {
+                order_id: e.order.order_id,
+                instrument_id: e.order.instrument_id.clone(),
+                sequence: e.sequence,
+            })
+            .collect()
+    }
+
+    /// Build the events recording a block trade
+    ///
+    /// A block trade is logged as its trades alone; it touches no book, so
+    /// replay only needs their sequence numbers.
+    pub fn from_block_trade(trades: &[Trade]) -> Vec<MatchingEvent> {
+        trades
+            .iter()
+            .map(|trade| MatchingEvent::TradeExecuted {
+                trade: trade.clone(),
+                sequence: trade.sequence,
+            })
+            .collect()
+    }
+
+    /// Event recording the start of a call auction
+    pub fn auction_started(instrument_id: &str, auction: &CallAuction) -> MatchingEvent {
+        MatchingEvent::AuctionStarted {
+            instrument_id: instrument_id.to_string(),
+            reason: auction.reason,
+            sequence: auction.started_at,
+        }
+    }
+
+    /// Event recording a combo definition
+    pub fn combo_defined(combo: &ComboDefinition) -> MatchingEvent {
+        MatchingEvent::ComboDefined {
+            combo: combo.clone(),
+            sequence: combo.sequence,
+        }
+    }
+
+    /// Build the events recording an uncross and its trades
+    pub fn from_uncross(result: &UncrossResult) -> Vec<MatchingEvent> {
+        let mut events = Vec::with_capacity(result.trades.len() + 1);
+        events.push(MatchingEvent::AuctionUncrossed {
+            instrument_id: result.instrument_id.clone(),
+            price: result.price,
+            sequence: result.sequence,
+            timestamp: Some(result.timestamp),
+        });
+        events.extend(result.trades.iter().map(|trade| MatchingEvent::TradeExecuted {
+            trade: trade.clone(),
+            sequence: trade.sequence,
+        }));
+        events.extend(Self::from_triggers(&result.triggered));
+        events
+    }
+
+    /// STP event for an order, if it cancelled anything
+    fn self_trade_prevented(
+        order: &BookOrder,
+        cancelled: &[Uuid],
+        sequence: u64,
+    ) -> Option<MatchingEvent> {
+        (!cancelled.is_empty()).then(|| MatchingEvent::SelfTradePrevented {
+            order_id: order.order_id,
+            instrument_id: order.instrument_id.clone(),
+            cancelled_order_ids: cancelled.to_vec(),
+            sequence,
+        })
+    }
 }
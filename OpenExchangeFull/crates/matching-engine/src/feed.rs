@@ -0,0 +1,217 @@
+//! Live subscriptions to matching events
+//!
+//! Every event a store appends to its log or journal is also published on
+//! an [`EventFeed`]. A subscriber first receives the logged backlog from
+//! the sequence it asks for and then the live events, in sequence order and
+//! without gaps: the backlog is read and the live receiver attached under
+//! the same lock that appends take.
+//!
+//! The live side is a bounded broadcast channel. A subscriber that falls
+//! more than the channel capacity behind is told it lagged, together with
+//! the sequence to resubscribe from, instead of silently missing events.
+
+use std::collections::VecDeque;
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+use crate::event::MatchingEvent;
+use crate::metrics::names;
+
+/// Live events buffered per subscriber before it is considered lagging
+pub const DEFAULT_FEED_CAPACITY: usize = 4096;
+
+/// Errors returned while receiving from a subscription
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SubscriptionError {
+    /// The subscriber fell behind and events were dropped
+    ///
+    /// Resubscribe from `resume_from`; events at that sequence that were
+    /// already received may be delivered again.
+    #[error("Subscriber lagged by {skipped} events; resume from sequence {resume_from}")]
+    Lagged { skipped: u64, resume_from: u64 },
+
+    /// The publishing store was dropped
+    #[error("Event feed closed")]
+    Closed,
+}
+
+/// Publishing side of the matching event stream
+///
+/// After each publish the number of events the slowest live subscriber has
+/// yet to receive is published as the `matching_event_log_lag` gauge,
+/// labelled with the feed's name.
+#[derive(Clone)]
+pub struct EventFeed {
+    sender: broadcast::Sender<MatchingEvent>,
+    lag: metrics::Gauge,
+}
+
+impl EventFeed {
+    /// Create a feed buffering up to `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        Self::named(capacity, "events")
+    }
+
+    /// Create a feed whose lag is published under `name`
+    pub fn named(capacity: usize, name: impl Into<String>) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        let lag = metrics::gauge!(names::EVENT_LOG_LAG, "feed" => name.into());
+        Self { sender, lag }
+    }
+
+    /// Publish an event to every live subscriber
+    ///
+    /// Events are dropped if nobody is subscribed.
+    pub fn publish(&self, event: &MatchingEvent) {
+        let _ = self.sender.send(event.clone());
+        self.lag.set(self.sender.len() as f64);
+    }
+
+    /// Publish events in order
+    pub fn publish_all(&self, events: &[MatchingEvent]) {
+        for event in events {
+            let _ = self.sender.send(event.clone());
+        }
+        self.lag.set(self.sender.len() as f64);
+    }
+
+    /// Start a subscription that first replays `backlog`
+    ///
+    /// The caller must hold whatever lock serializes appends while reading
+    /// the backlog and calling this, so no event falls between the two.
+    pub fn subscribe(&self, from_sequence: u64, backlog: Vec<MatchingEvent>) -> EventSubscription {
+        EventSubscription {
+            backlog: backlog.into(),
+            live: self.sender.subscribe(),
+            resume_from: from_sequence,
+        }
+    }
+
+    /// Number of live subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl std::fmt::Debug for EventFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventFeed")
+            .field("sender", &self.sender)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEED_CAPACITY)
+    }
+}
+
+/// A subscriber's view of the matching event stream
+#[derive(Debug)]
+pub struct EventSubscription {
+    backlog: VecDeque<MatchingEvent>,
+    live: broadcast::Receiver<MatchingEvent>,
+    resume_from: u64,
+}
+
+impl EventSubscription {
+    /// Receive the next event in sequence order
+    pub async fn recv(&mut self) -> Result<MatchingEvent, SubscriptionError> {
+        let event = match self.backlog.pop_front() {
+            Some(event) => event,
+            None => match self.live.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    return Err(SubscriptionError::Lagged {
+                        skipped,
+                        resume_from: self.resume_from,
+                    });
+                }
+                Err(broadcast::error::RecvError::Closed) => return Err(SubscriptionError::Closed),
+            },
+        };
+        self.resume_from = event.sequence();
+        Ok(event)
+    }
+
+    /// Receive the next event if one is already waiting
+    ///
+    /// Returns None once the subscriber has caught up with the feed.
+    pub fn try_recv(&mut self) -> Option<Result<MatchingEvent, SubscriptionError>> {
+        let event = match self.backlog.pop_front() {
+            Some(event) => event,
+            None => match self.live.try_recv() {
+                Ok(event) => event,
+                Err(broadcast::error::TryRecvError::Empty) => return None,
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    return Some(Err(SubscriptionError::Lagged {
+                        skipped,
+                        resume_from: self.resume_from,
+                    }));
+                }
+                Err(broadcast::error::TryRecvError::Closed) => return Some(Err(SubscriptionError::Closed)),
+            },
+        };
+        self.resume_from = event.sequence();
+        Some(Ok(event))
+    }
+
+    /// Sequence to resubscribe from to continue this stream
+    pub fn resume_from(&self) -> u64 {
+        self.resume_from
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reset(sequence: u64) -> MatchingEvent {
+        MatchingEvent::SequenceReset { sequence }
+    }
+
+    #[tokio::test]
+    async fn test_backlog_then_live() {
+        let feed = EventFeed::new(8);
+        let mut subscription = feed.subscribe(2, vec![reset(2), reset(3)]);
+        feed.publish_all(&[reset(4), reset(5)]);
+
+        for expected in 2..=5 {
+            assert_eq!(subscription.recv().await.unwrap().sequence(), expected);
+        }
+        assert_eq!(subscription.resume_from(), 5);
+    }
+
+    #[test]
+    fn test_try_recv_takes_only_what_is_waiting() {
+        let feed = EventFeed::new(8);
+        let mut subscription = feed.subscribe(2, vec![reset(2)]);
+        feed.publish(&reset(3));
+
+        assert_eq!(subscription.try_recv().unwrap().unwrap().sequence(), 2);
+        assert_eq!(subscription.try_recv().unwrap().unwrap().sequence(), 3);
+        assert!(subscription.try_recv().is_none());
+
+        drop(feed);
+        assert!(matches!(subscription.try_recv(), Some(Err(SubscriptionError::Closed))));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_is_told_where_to_resume() {
+        let feed = EventFeed::new(2);
+        let mut subscription = feed.subscribe(1, vec![reset(1)]);
+        feed.publish_all(&[reset(2), reset(3), reset(4)]);
+
+        assert_eq!(subscription.recv().await.unwrap().sequence(), 1);
+        assert!(matches!(
+            subscription.recv().await,
+            Err(SubscriptionError::Lagged { skipped: 1, resume_from: 1 })
+        ));
+        assert_eq!(subscription.recv().await.unwrap().sequence(), 3);
+
+        drop(feed);
+        assert_eq!(subscription.recv().await.unwrap().sequence(), 4);
+        assert!(matches!(subscription.recv().await, Err(SubscriptionError::Closed)));
+    }
+}
@@ -0,0 +1,145 @@
+//! gRPC client for the matching service
+//!
+//! Used by the OMS and other services that submit orders to a remote
+//! matching node.
+
+use tonic::codec::{ProstCodec, Streaming};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+
+use super::proto::{self, path};
+
+/// Client for the matching gRPC service
+///
+/// # Example
+///
+/// ```ignore
+/// let client = MatchingGrpcClient::connect("http://localhost:9082").await?;
+///
+/// let book = client.get_book_snapshot(GetBookSnapshotRequest {
+///     instrument_id: "BTC-20240329-50000-C".to_string(),
+///     depth: 5,
+/// }).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatchingGrpcClient {
+    inner: tonic::client::Grpc<Channel>,
+}
+
+impl MatchingGrpcClient {
+    /// Connect to a matching node (e.g. "http://localhost:9082")
+    pub async fn connect(endpoint: &str) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.to_string())?.connect().await?;
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Create a client that connects on first use
+    pub fn connect_lazy(endpoint: &str) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.to_string())?.connect_lazy();
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Create a client from an existing channel
+    pub fn from_channel(channel: Channel) -> Self {
+        Self {
+            inner: tonic::client::Grpc::new(channel),
+        }
+    }
+
+    /// Wait for the channel, returning a handle to send on
+    async fn ready(&self) -> Result<tonic::client::Grpc<Channel>, Status> {
+        let mut grpc = self.inner.clone();
+        grpc.ready()
+            .await
+            .map_err(|e| Status::unavailable(format!("Matching service not ready: {e}")))?;
+        Ok(grpc)
+    }
+
+    /// Send a unary request
+    async fn unary<Req, Resp>(&self, method: &'static str, request: Req) -> Result<Resp, Status>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        let response = self
+            .ready()
+            .await?
+            .unary(Request::new(request), PathAndQuery::from_static(method), ProstCodec::default())
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Submit an order
+    pub async fn submit_order(&self, request: proto::SubmitOrderRequest) -> Result<proto::SubmitOrderResponse, Status> {
+        self.unary(path::SUBMIT_ORDER, request).await
+    }
+
+    /// Cancel an order
+    pub async fn cancel_order(&self, request: proto::CancelOrderRequest) -> Result<proto::CancelOrderResponse, Status> {
+        self.unary(path::CANCEL_ORDER, request).await
+    }
+
+    /// Amend a resting order
+    pub async fn amend_order(&self, request: proto::AmendOrderRequest) -> Result<proto::AmendOrderResponse, Status> {
+        self.unary(path::AMEND_ORDER, request).await
+    }
+
+    /// Cancel every order selected by a filter
+    pub async fn mass_cancel(&self, request: proto::MassCancelRequest) -> Result<proto::MassCancelResponse, Status> {
+        self.unary(path::MASS_CANCEL, request).await
+    }
+
+    /// Remove GTD and DAY orders expired at a time
+    pub async fn expire_orders(&self, request: proto::ExpireOrdersRequest) -> Result<proto::ExpireOrdersResponse, Status> {
+        self.unary(path::EXPIRE_ORDERS, request).await
+    }
+
+    /// Aggregated price levels of a book
+    pub async fn get_book_snapshot(
+        &self,
+        request: proto::GetBookSnapshotRequest,
+    ) -> Result<proto::GetBookSnapshotResponse, Status> {
+        self.unary(path::GET_BOOK_SNAPSHOT, request).await
+    }
+
+    /// Most recent trades of an instrument
+    pub async fn get_trades(&self, request: proto::GetTradesRequest) -> Result<proto::GetTradesResponse, Status> {
+        self.unary(path::GET_TRADES, request).await
+    }
+
+    /// Book a block trade agreed in an RFQ
+    pub async fn book_block_trade(
+        &self,
+        request: proto::BookBlockTradeRequest,
+    ) -> Result<proto::BookBlockTradeResponse, Status> {
+        self.unary(path::BOOK_BLOCK_TRADE, request).await
+    }
+
+    /// Replace a maker's quotes
+    pub async fn mass_quote(&self, request: proto::MassQuoteRequest) -> Result<proto::MassQuoteResponse, Status> {
+        self.unary(path::MASS_QUOTE, request).await
+    }
+
+    /// Let a maker quote again in an underlying after MMP froze it
+    pub async fn reset_mmp(&self, request: proto::ResetMmpRequest) -> Result<proto::ResetMmpResponse, Status> {
+        self.unary(path::RESET_MMP, request).await
+    }
+
+    /// Stream matching events from `from_sequence` (live only if None)
+    ///
+    /// The stream ends after a `Lagged` frame; resubscribe from its
+    /// `resume_from` to continue.
+    pub async fn subscribe_events(&self, from_sequence: Option<u64>) -> Result<Streaming<proto::EventFrame>, Status> {
+        let response = self
+            .ready()
+            .await?
+            .server_streaming(
+                Request::new(proto::SubscribeEventsRequest { from_sequence }),
+                PathAndQuery::from_static(path::SUBSCRIBE_EVENTS),
+                ProstCodec::default(),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+}
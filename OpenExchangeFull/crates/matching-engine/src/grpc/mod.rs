@@ -0,0 +1,16 @@
+//! gRPC service for the matching engine
+//!
+//! Implements `proto/matching.proto`: order submission, cancel, mass cancel,
+//! amend and expiry, book and trade queries, and a server-streaming event
+//! subscription. Served on the shared `GrpcServer`; [`MatchingGrpcClient`]
+//! is the client used by remote services such as the OMS.
+
+// Allow large error types - tonic's Status is unavoidably large
+#![allow(clippy::result_large_err)]
+
+pub mod proto;
+pub mod server;
+pub mod client;
+
+pub use client::MatchingGrpcClient;
+pub use server::{MatchingGrpcService, MatchingService, MatchingServiceServer};
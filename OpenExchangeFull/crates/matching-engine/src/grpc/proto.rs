@@ -0,0 +1,540 @@
+//! Message types of `proto/matching.proto`
+//!
+//! Written by hand with prost derives, as for the instrument service, so the
+//! build needs no `protoc`. Keep tags in step with the `.proto` file.
+
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+
+/// Fully qualified service name
+pub const SERVICE_NAME: &str = "openexchange.matching.v1.MatchingService";
+
+/// Request paths of the service's methods
+pub mod path {
+    pub const SUBMIT_ORDER: &str = "/openexchange.matching.v1.MatchingService/SubmitOrder";
+    pub const CANCEL_ORDER: &str = "/openexchange.matching.v1.MatchingService/CancelOrder";
+    pub const AMEND_ORDER: &str = "/openexchange.matching.v1.MatchingService/AmendOrder";
+    pub const MASS_CANCEL: &str = "/openexchange.matching.v1.MatchingService/MassCancel";
+    pub const EXPIRE_ORDERS: &str = "/openexchange.matching.v1.MatchingService/ExpireOrders";
+    pub const GET_BOOK_SNAPSHOT: &str = "/openexchange.matching.v1.MatchingService/GetBookSnapshot";
+    pub const GET_TRADES: &str = "/openexchange.matching.v1.MatchingService/GetTrades";
+    pub const BOOK_BLOCK_TRADE: &str = "/openexchange.matching.v1.MatchingService/BookBlockTrade";
+    pub const MASS_QUOTE: &str = "/openexchange.matching.v1.MatchingService/MassQuote";
+    pub const RESET_MMP: &str = "/openexchange.matching.v1.MatchingService/ResetMmp";
+    pub const SUBSCRIBE_EVENTS: &str = "/openexchange.matching.v1.MatchingService/SubscribeEvents";
+}
+
+// Enums
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum Side {
+    Unspecified = 0,
+    Buy = 1,
+    Sell = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum TimeInForce {
+    Unspecified = 0,
+    Gtc = 1,
+    Ioc = 2,
+    Fok = 3,
+    Gtd = 4,
+    Day = 5,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderType {
+    Unspecified = 0,
+    Limit = 1,
+    Market = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum TriggerSource {
+    Unspecified = 0,
+    LastTrade = 1,
+    MarkPrice = 2,
+    IndexPrice = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum SelfTradePrevention {
+    Unspecified = 0,
+    None = 1,
+    CancelTaker = 2,
+    CancelMaker = 3,
+    CancelBoth = 4,
+    DecrementAndCancel = 5,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, prost::Enumeration)]
+#[repr(i32)]
+pub enum PostOnly {
+    Unspecified = 0,
+    None = 1,
+    Reject = 2,
+    Reprice = 3,
+}
+
+// Shared messages
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Trade {
+    #[prost(string, tag = "1")]
+    pub trade_id: String,
+    #[prost(string, tag = "2")]
+    pub instrument_id: String,
+    #[prost(string, tag = "3")]
+    pub taker_order_id: String,
+    #[prost(string, tag = "4")]
+    pub maker_order_id: String,
+    #[prost(string, tag = "5")]
+    pub buyer_id: String,
+    #[prost(string, tag = "6")]
+    pub seller_id: String,
+    #[prost(double, tag = "7")]
+    pub price: f64,
+    #[prost(uint32, tag = "8")]
+    pub quantity: u32,
+    #[prost(enumeration = "Side", tag = "9")]
+    pub aggressor_side: i32,
+    #[prost(uint64, tag = "10")]
+    pub sequence: u64,
+    #[prost(message, optional, tag = "11")]
+    pub timestamp: Option<Timestamp>,
+    #[prost(string, optional, tag = "12")]
+    pub combo_id: Option<String>,
+    #[prost(string, optional, tag = "13")]
+    pub rfq_id: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ComboLeg {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(enumeration = "Side", tag = "2")]
+    pub side: i32,
+    #[prost(uint32, tag = "3")]
+    pub ratio: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PriceLevel {
+    #[prost(double, tag = "1")]
+    pub price: f64,
+    #[prost(uint32, tag = "2")]
+    pub quantity: u32,
+    #[prost(uint32, tag = "3")]
+    pub order_count: u32,
+}
+
+// Submit
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitOrderRequest {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(string, tag = "2")]
+    pub order_id: String,
+    #[prost(string, tag = "3")]
+    pub user_id: String,
+    #[prost(enumeration = "Side", tag = "4")]
+    pub side: i32,
+    #[prost(double, tag = "5")]
+    pub price: f64,
+    #[prost(uint32, tag = "6")]
+    pub quantity: u32,
+    #[prost(enumeration = "TimeInForce", tag = "7")]
+    pub time_in_force: i32,
+    #[prost(enumeration = "OrderType", tag = "8")]
+    pub order_type: i32,
+    #[prost(double, optional, tag = "9")]
+    pub stop_price: Option<f64>,
+    #[prost(enumeration = "TriggerSource", tag = "10")]
+    pub trigger: i32,
+    #[prost(enumeration = "SelfTradePrevention", tag = "11")]
+    pub stp: i32,
+    #[prost(enumeration = "PostOnly", tag = "12")]
+    pub post_only: i32,
+    #[prost(bool, tag = "13")]
+    pub all_or_none: bool,
+    #[prost(uint32, optional, tag = "14")]
+    pub min_quantity: Option<u32>,
+    #[prost(uint32, optional, tag = "15")]
+    pub display_quantity: Option<u32>,
+    #[prost(message, optional, tag = "16")]
+    pub expires_at: Option<Timestamp>,
+    #[prost(message, repeated, tag = "17")]
+    pub legs: Vec<ComboLeg>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitOrderResponse {
+    #[prost(string, tag = "1")]
+    pub order_id: String,
+    #[prost(uint64, optional, tag = "2")]
+    pub sequence: Option<u64>,
+    #[prost(message, repeated, tag = "3")]
+    pub trades: Vec<Trade>,
+    #[prost(uint32, tag = "4")]
+    pub remaining_quantity: u32,
+    #[prost(bool, tag = "5")]
+    pub resting: bool,
+    #[prost(string, repeated, tag = "6")]
+    pub stp_cancelled: Vec<String>,
+    #[prost(double, optional, tag = "7")]
+    pub repriced_to: Option<f64>,
+}
+
+// Cancel
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CancelOrderRequest {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(string, tag = "2")]
+    pub order_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CancelOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub cancelled: bool,
+}
+
+// Amend
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AmendOrderRequest {
+    #[prost(string, tag = "1")]
+    pub order_id: String,
+    #[prost(double, optional, tag = "2")]
+    pub price: Option<f64>,
+    #[prost(uint32, optional, tag = "3")]
+    pub quantity: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AmendOrderResponse {
+    #[prost(bool, tag = "1")]
+    pub priority_retained: bool,
+    #[prost(message, repeated, tag = "2")]
+    pub trades: Vec<Trade>,
+    #[prost(uint32, tag = "3")]
+    pub remaining_quantity: u32,
+    #[prost(uint64, tag = "4")]
+    pub sequence: u64,
+}
+
+// Mass cancel
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MassCancelRequest {
+    #[prost(string, optional, tag = "1")]
+    pub user_id: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub instrument_id: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub underlying: Option<String>,
+    #[prost(enumeration = "Side", tag = "4")]
+    pub side: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MassCancelResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub cancelled_order_ids: Vec<String>,
+}
+
+// Expiry
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExpireOrdersRequest {
+    #[prost(message, optional, tag = "1")]
+    pub now: Option<Timestamp>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExpireOrdersResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub expired_order_ids: Vec<String>,
+}
+
+// Book
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBookSnapshotRequest {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(uint32, tag = "2")]
+    pub depth: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBookSnapshotResponse {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(message, repeated, tag = "2")]
+    pub bids: Vec<PriceLevel>,
+    #[prost(message, repeated, tag = "3")]
+    pub asks: Vec<PriceLevel>,
+    #[prost(double, optional, tag = "4")]
+    pub spread: Option<f64>,
+    #[prost(uint64, tag = "5")]
+    pub sequence: u64,
+}
+
+// Trades
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTradesRequest {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(uint32, tag = "2")]
+    pub limit: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTradesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub trades: Vec<Trade>,
+}
+
+// Block trades
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BookBlockTradeRequest {
+    #[prost(string, tag = "1")]
+    pub rfq_id: String,
+    #[prost(string, tag = "2")]
+    pub instrument_id: String,
+    #[prost(double, tag = "3")]
+    pub price: f64,
+    #[prost(uint32, tag = "4")]
+    pub quantity: u32,
+    #[prost(string, tag = "5")]
+    pub taker_order_id: String,
+    #[prost(string, tag = "6")]
+    pub taker_user_id: String,
+    #[prost(enumeration = "Side", tag = "7")]
+    pub taker_side: i32,
+    #[prost(string, tag = "8")]
+    pub maker_order_id: String,
+    #[prost(string, tag = "9")]
+    pub maker_user_id: String,
+    #[prost(message, repeated, tag = "10")]
+    pub legs: Vec<ComboLeg>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BookBlockTradeResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub trades: Vec<Trade>,
+}
+
+// Mass quotes
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Quote {
+    #[prost(string, tag = "1")]
+    pub instrument_id: String,
+    #[prost(enumeration = "Side", tag = "2")]
+    pub side: i32,
+    #[prost(double, tag = "3")]
+    pub price: f64,
+    #[prost(uint32, tag = "4")]
+    pub quantity: u32,
+    #[prost(string, optional, tag = "5")]
+    pub order_id: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MassQuoteRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: String,
+    #[prost(message, repeated, tag = "2")]
+    pub quotes: Vec<Quote>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MassQuoteResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub replaced_order_ids: Vec<String>,
+    #[prost(string, repeated, tag = "2")]
+    pub resting_order_ids: Vec<String>,
+    #[prost(string, repeated, tag = "3")]
+    pub rejected_order_ids: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResetMmpRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: String,
+    #[prost(string, tag = "2")]
+    pub underlying: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResetMmpResponse {
+    #[prost(bool, tag = "1")]
+    pub reset: bool,
+}
+
+// Events
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubscribeEventsRequest {
+    #[prost(uint64, optional, tag = "1")]
+    pub from_sequence: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MatchingEvent {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+    #[prost(string, tag = "2")]
+    pub kind: String,
+    #[prost(string, tag = "3")]
+    pub payload: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Lagged {
+    #[prost(uint64, tag = "1")]
+    pub skipped: u64,
+    #[prost(uint64, tag = "2")]
+    pub resume_from: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EventFrame {
+    #[prost(oneof = "event_frame::Frame", tags = "1, 2")]
+    pub frame: Option<event_frame::Frame>,
+}
+
+/// Nested types of [`EventFrame`]
+pub mod event_frame {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Frame {
+        #[prost(message, tag = "1")]
+        Event(super::MatchingEvent),
+        #[prost(message, tag = "2")]
+        Lagged(super::Lagged),
+    }
+}
+
+impl MatchingEvent {
+    /// Wrap an engine event, tagged with its kind
+    pub fn from_event(event: &crate::event::MatchingEvent) -> serde_json::Result<Self> {
+        let value = serde_json::to_value(event)?;
+        Ok(Self {
+            sequence: event.sequence(),
+            kind: value["type"].as_str().unwrap_or_default().to_string(),
+            payload: value.to_string(),
+        })
+    }
+
+    /// Decode the engine event carried in the payload
+    pub fn to_event(&self) -> serde_json::Result<crate::event::MatchingEvent> {
+        serde_json::from_str(&self.payload)
+    }
+}
+
+// Conversions to and from the engine's types
+
+impl From<crate::domain::OrderSide> for Side {
+    fn from(side: crate::domain::OrderSide) -> Self {
+        match side {
+            crate::domain::OrderSide::Buy => Side::Buy,
+            crate::domain::OrderSide::Sell => Side::Sell,
+        }
+    }
+}
+
+impl Side {
+    /// Engine side (None if unspecified)
+    pub fn to_domain(self) -> Option<crate::domain::OrderSide> {
+        match self {
+            Side::Unspecified => None,
+            Side::Buy => Some(crate::domain::OrderSide::Buy),
+            Side::Sell => Some(crate::domain::OrderSide::Sell),
+        }
+    }
+}
+
+impl TimeInForce {
+    /// Engine time in force (GTC if unspecified)
+    pub fn to_domain(self) -> crate::domain::TimeInForce {
+        match self {
+            TimeInForce::Unspecified | TimeInForce::Gtc => crate::domain::TimeInForce::Gtc,
+            TimeInForce::Ioc => crate::domain::TimeInForce::Ioc,
+            TimeInForce::Fok => crate::domain::TimeInForce::Fok,
+            TimeInForce::Gtd => crate::domain::TimeInForce::Gtd,
+            TimeInForce::Day => crate::domain::TimeInForce::Day,
+        }
+    }
+}
+
+impl OrderType {
+    /// Engine order type (limit if unspecified)
+    pub fn to_domain(self) -> crate::domain::OrderType {
+        match self {
+            OrderType::Unspecified | OrderType::Limit => crate::domain::OrderType::Limit,
+            OrderType::Market => crate::domain::OrderType::Market,
+        }
+    }
+}
+
+impl TriggerSource {
+    /// Engine trigger source (last trade if unspecified)
+    pub fn to_domain(self) -> crate::domain::TriggerSource {
+        match self {
+            TriggerSource::Unspecified | TriggerSource::LastTrade => crate::domain::TriggerSource::LastTrade,
+            TriggerSource::MarkPrice => crate::domain::TriggerSource::MarkPrice,
+            TriggerSource::IndexPrice => crate::domain::TriggerSource::IndexPrice,
+        }
+    }
+}
+
+impl SelfTradePrevention {
+    /// Engine self-trade prevention mode (none if unspecified)
+    pub fn to_domain(self) -> crate::domain::SelfTradePrevention {
+        match self {
+            SelfTradePrevention::Unspecified | SelfTradePrevention::None => crate::domain::SelfTradePrevention::None,
+            SelfTradePrevention::CancelTaker => crate::domain::SelfTradePrevention::CancelTaker,
+            SelfTradePrevention::CancelMaker => crate::domain::SelfTradePrevention::CancelMaker,
+            SelfTradePrevention::CancelBoth => crate::domain::SelfTradePrevention::CancelBoth,
+            SelfTradePrevention::DecrementAndCancel => crate::domain::SelfTradePrevention::DecrementAndCancel,
+        }
+    }
+}
+
+impl PostOnly {
+    /// Engine post-only instruction (none if unspecified)
+    pub fn to_domain(self) -> crate::domain::PostOnly {
+        match self {
+            PostOnly::Unspecified | PostOnly::None => crate::domain::PostOnly::None,
+            PostOnly::Reject => crate::domain::PostOnly::Reject,
+            PostOnly::Reprice => crate::domain::PostOnly::Reprice,
+        }
+    }
+}
+
+/// Convert a time to a protobuf timestamp
+pub fn timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Convert a protobuf timestamp to a time (None if out of range)
+pub fn from_timestamp(timestamp: &Timestamp) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(timestamp.seconds, u32::try_from(timestamp.nanos).ok()?)
+}
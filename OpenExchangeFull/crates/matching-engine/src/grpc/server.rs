@@ -0,0 +1,702 @@
+//! gRPC server for the matching service
+//!
+//! [`MatchingService`] is the service definition; [`MatchingGrpcService`]
+//! implements it over a [`MatchingStore`], and [`MatchingServiceServer`]
+//! routes tonic requests to it.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use chrono::Utc;
+use futures::stream;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, BoxFuture, BoxStream, Service, StdError};
+use tonic::server::NamedService;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use super::proto::{self, path};
+use crate::block::BlockTrade;
+use crate::combo::{ComboDefinition, ComboLeg};
+use crate::domain::{BookOrder, MassCancelFilter, OrderType, TimeInForce, Trade};
+use crate::error::MatchingError;
+use crate::feed::{EventSubscription, SubscriptionError};
+use crate::price::PriceScale;
+use crate::store::{MatchingStore, StoreError};
+
+/// The matching service's RPCs
+#[tonic::async_trait]
+pub trait MatchingService: Send + Sync + 'static {
+    /// Submit an order
+    async fn submit_order(
+        &self,
+        request: Request<proto::SubmitOrderRequest>,
+    ) -> Result<Response<proto::SubmitOrderResponse>, Status>;
+
+    /// Cancel a resting order or held stop
+    async fn cancel_order(
+        &self,
+        request: Request<proto::CancelOrderRequest>,
+    ) -> Result<Response<proto::CancelOrderResponse>, Status>;
+
+    /// Amend a resting order
+    async fn amend_order(
+        &self,
+        request: Request<proto::AmendOrderRequest>,
+    ) -> Result<Response<proto::AmendOrderResponse>, Status>;
+
+    /// Cancel every order selected by a filter
+    async fn mass_cancel(
+        &self,
+        request: Request<proto::MassCancelRequest>,
+    ) -> Result<Response<proto::MassCancelResponse>, Status>;
+
+    /// Remove GTD and DAY orders expired at a time
+    async fn expire_orders(
+        &self,
+        request: Request<proto::ExpireOrdersRequest>,
+    ) -> Result<Response<proto::ExpireOrdersResponse>, Status>;
+
+    /// Aggregated price levels of a book
+    async fn get_book_snapshot(
+        &self,
+        request: Request<proto::GetBookSnapshotRequest>,
+    ) -> Result<Response<proto::GetBookSnapshotResponse>, Status>;
+
+    /// Most recent trades of an instrument
+    async fn get_trades(
+        &self,
+        request: Request<proto::GetTradesRequest>,
+    ) -> Result<Response<proto::GetTradesResponse>, Status>;
+
+    /// Book a block trade agreed in an RFQ
+    async fn book_block_trade(
+        &self,
+        request: Request<proto::BookBlockTradeRequest>,
+    ) -> Result<Response<proto::BookBlockTradeResponse>, Status>;
+
+    /// Replace a maker's quotes
+    async fn mass_quote(
+        &self,
+        request: Request<proto::MassQuoteRequest>,
+    ) -> Result<Response<proto::MassQuoteResponse>, Status>;
+
+    /// Let a maker quote again in an underlying after MMP froze it
+    async fn reset_mmp(
+        &self,
+        request: Request<proto::ResetMmpRequest>,
+    ) -> Result<Response<proto::ResetMmpResponse>, Status>;
+
+    /// Matching events from a sequence, then live
+    async fn subscribe_events(
+        &self,
+        request: Request<proto::SubscribeEventsRequest>,
+    ) -> Result<Response<BoxStream<proto::EventFrame>>, Status>;
+}
+
+/// Matching service backed by a store
+pub struct MatchingGrpcService<S: ?Sized> {
+    store: Arc<S>,
+}
+
+impl<S: MatchingStore + ?Sized> MatchingGrpcService<S> {
+    /// Create the service over a store
+    pub fn new(store: Arc<S>) -> Self {
+        Self { store }
+    }
+
+    /// Wrap the service for a tonic router
+    pub fn into_server(self) -> MatchingServiceServer<Self>
+    where
+        S: 'static,
+    {
+        MatchingServiceServer::new(self)
+    }
+}
+
+/// Map a store error to the status documented in `matching.proto`
+fn store_status(err: StoreError) -> Status {
+    let message = err.to_string();
+    match err {
+        StoreError::OrderNotFound(_) | StoreError::InstrumentNotFound(_) => Status::not_found(message),
+        StoreError::Matching(err) => match err {
+            MatchingError::InvalidOrder(_)
+            | MatchingError::InvalidCombo(_)
+            | MatchingError::InvalidBlockTrade(_) => Status::invalid_argument(message),
+            MatchingError::OrderNotFound(_) => Status::not_found(message),
+            MatchingError::InsufficientLiquidity
+            | MatchingError::CircuitBreaker(_)
+            | MatchingError::Auction(_)
+            | MatchingError::MmpFrozen(_) => Status::failed_precondition(message),
+            MatchingError::UnsupportedSnapshot(_) | MatchingError::Internal(_) => Status::internal(message),
+        },
+        StoreError::RedisError(_) | StoreError::JournalError(_) => Status::unavailable(message),
+        StoreError::SerializationError(_) | StoreError::Other(_) => Status::internal(message),
+    }
+}
+
+/// Parse a UUID field
+fn parse_uuid(field: &str, value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value).map_err(|_| Status::invalid_argument(format!("Invalid {field}: {value:?}")))
+}
+
+/// Parse an enum field, rejecting values this version does not know
+fn parse_enum<E: TryFrom<i32>>(field: &str, value: i32) -> Result<E, Status> {
+    E::try_from(value).map_err(|_| Status::invalid_argument(format!("Invalid {field}: {value}")))
+}
+
+/// Parse the legs of a combo
+fn parse_legs(legs: &[proto::ComboLeg]) -> Result<Vec<ComboLeg>, Status> {
+    legs.iter()
+        .map(|leg| {
+            let side = parse_enum::<proto::Side>("legs.side", leg.side)?
+                .to_domain()
+                .ok_or_else(|| Status::invalid_argument("legs.side is required"))?;
+            Ok(ComboLeg::new(leg.instrument_id.clone(), side, leg.ratio))
+        })
+        .collect()
+}
+
+/// Convert a trade to its message, with the price in decimal
+fn trade_message(trade: &Trade, scale: &PriceScale) -> proto::Trade {
+    proto::Trade {
+        trade_id: trade.trade_id.to_string(),
+        instrument_id: trade.instrument_id.clone(),
+        taker_order_id: trade.taker_order_id.to_string(),
+        maker_order_id: trade.maker_order_id.to_string(),
+        buyer_id: trade.buyer_id.to_string(),
+        seller_id: trade.seller_id.to_string(),
+        price: scale.to_price(trade.price),
+        quantity: trade.quantity,
+        aggressor_side: proto::Side::from(trade.aggressor_side) as i32,
+        sequence: trade.sequence,
+        timestamp: Some(proto::timestamp(trade.timestamp)),
+        combo_id: trade.combo_id.clone(),
+        rfq_id: trade.rfq_id.map(|id| id.to_string()),
+    }
+}
+
+/// Frame for the next item of a subscription
+fn event_frame(item: Result<crate::event::MatchingEvent, SubscriptionError>) -> Option<Result<proto::EventFrame, Status>> {
+    let frame = match item {
+        Ok(event) => match proto::MatchingEvent::from_event(&event) {
+            Ok(event) => proto::event_frame::Frame::Event(event),
+            Err(e) => return Some(Err(Status::internal(e.to_string()))),
+        },
+        Err(SubscriptionError::Lagged { skipped, resume_from }) => {
+            proto::event_frame::Frame::Lagged(proto::Lagged { skipped, resume_from })
+        }
+        Err(SubscriptionError::Closed) => return None,
+    };
+    Some(Ok(proto::EventFrame { frame: Some(frame) }))
+}
+
+#[tonic::async_trait]
+impl<S: MatchingStore + ?Sized + 'static> MatchingService for MatchingGrpcService<S> {
+    async fn submit_order(
+        &self,
+        request: Request<proto::SubmitOrderRequest>,
+    ) -> Result<Response<proto::SubmitOrderResponse>, Status> {
+        let req = request.into_inner();
+        let side = parse_enum::<proto::Side>("side", req.side)?
+            .to_domain()
+            .ok_or_else(|| Status::invalid_argument("side is required"))?;
+        let tif = parse_enum::<proto::TimeInForce>("time_in_force", req.time_in_force)?.to_domain();
+        let order_type = parse_enum::<proto::OrderType>("order_type", req.order_type)?.to_domain();
+        let trigger = parse_enum::<proto::TriggerSource>("trigger", req.trigger)?.to_domain();
+        let stp = parse_enum::<proto::SelfTradePrevention>("stp", req.stp)?.to_domain();
+        let post_only = parse_enum::<proto::PostOnly>("post_only", req.post_only)?.to_domain();
+
+        let expires_at = match &req.expires_at {
+            Some(expires_at) => Some(
+                proto::from_timestamp(expires_at).ok_or_else(|| Status::invalid_argument("Invalid expires_at"))?,
+            ),
+            None => None,
+        };
+        let expires = matches!(tif, TimeInForce::Gtd | TimeInForce::Day);
+        if expires != expires_at.is_some() {
+            return Err(Status::invalid_argument(
+                "expires_at is required for GTD and DAY orders and not allowed otherwise",
+            ));
+        }
+
+        let order_id = if req.order_id.is_empty() {
+            Uuid::new_v4()
+        } else {
+            parse_uuid("order_id", &req.order_id)?
+        };
+        let user_id = parse_uuid("user_id", &req.user_id)?;
+
+        if !req.legs.is_empty() {
+            let legs = parse_legs(&req.legs)?;
+            self.store
+                .define_combo(ComboDefinition::new(req.instrument_id.clone(), legs))
+                .await
+                .map_err(store_status)?;
+        }
+
+        let scale = self.store.price_scale(&req.instrument_id).await.map_err(store_status)?;
+        // A market order without a price is left at zero for the engine to fill in
+        let price = if order_type == OrderType::Market && req.price == 0.0 {
+            0
+        } else {
+            scale
+                .order_ticks(req.price, side)
+                .map_err(|e| store_status(e.into()))?
+        };
+        let stop_price = req
+            .stop_price
+            .map(|stop_price| scale.order_ticks(stop_price, side))
+            .transpose()
+            .map_err(|e| store_status(e.into()))?;
+
+        let mut order = BookOrder::new(order_id, user_id, side, price, req.quantity, 0, tif)
+            .with_instrument_id(req.instrument_id)
+            .with_order_type(order_type)
+            .with_stp(stp)
+            .with_post_only(post_only)
+            .with_all_or_none(req.all_or_none)
+            .with_min_quantity(req.min_quantity)
+            .with_display_quantity(req.display_quantity)
+            .with_expires_at(expires_at);
+        if let Some(stop_price) = stop_price {
+            order = order.with_stop(stop_price, trigger);
+        }
+
+        let result = self.store.submit_order(order).await.map_err(store_status)?;
+        let resting = result.should_insert && result.remaining_order.is_some();
+        Ok(Response::new(proto::SubmitOrderResponse {
+            order_id: order_id.to_string(),
+            sequence: result.sequence,
+            trades: result.trades.iter().map(|trade| trade_message(trade, &scale)).collect(),
+            remaining_quantity: result.remaining_order.map(|o| o.quantity).unwrap_or(0),
+            resting,
+            stp_cancelled: result.stp_cancelled.iter().map(Uuid::to_string).collect(),
+            repriced_to: result.repriced_to.map(|ticks| scale.to_price(ticks)),
+        }))
+    }
+
+    async fn cancel_order(
+        &self,
+        request: Request<proto::CancelOrderRequest>,
+    ) -> Result<Response<proto::CancelOrderResponse>, Status> {
+        let req = request.into_inner();
+        let order_id = parse_uuid("order_id", &req.order_id)?;
+        let instrument_id = if req.instrument_id.is_empty() {
+            match self.store.get_order(order_id).await.map_err(store_status)? {
+                Some(order) => order.instrument_id,
+                None => return Ok(Response::new(proto::CancelOrderResponse { cancelled: false })),
+            }
+        } else {
+            req.instrument_id
+        };
+
+        let cancelled = self
+            .store
+            .cancel_order(&instrument_id, order_id)
+            .await
+            .map_err(store_status)?
+            .is_some();
+        Ok(Response::new(proto::CancelOrderResponse { cancelled }))
+    }
+
+    async fn amend_order(
+        &self,
+        request: Request<proto::AmendOrderRequest>,
+    ) -> Result<Response<proto::AmendOrderResponse>, Status> {
+        let req = request.into_inner();
+        let order_id = parse_uuid("order_id", &req.order_id)?;
+        let resting = self
+            .store
+            .get_order(order_id)
+            .await
+            .map_err(store_status)?
+            .ok_or_else(|| store_status(StoreError::OrderNotFound(order_id)))?;
+        let scale = self.store.price_scale(&resting.instrument_id).await.map_err(store_status)?;
+        let new_price = req
+            .price
+            .map(|price| scale.order_ticks(price, resting.side))
+            .transpose()
+            .map_err(|e| store_status(e.into()))?;
+
+        let result = self
+            .store
+            .amend_order(order_id, new_price, req.quantity)
+            .await
+            .map_err(store_status)?;
+        Ok(Response::new(proto::AmendOrderResponse {
+            priority_retained: result.priority_retained,
+            trades: result.trades.iter().map(|trade| trade_message(trade, &scale)).collect(),
+            remaining_quantity: result.resting.map(|o| o.quantity).unwrap_or(0),
+            sequence: result.sequence,
+        }))
+    }
+
+    async fn mass_cancel(
+        &self,
+        request: Request<proto::MassCancelRequest>,
+    ) -> Result<Response<proto::MassCancelResponse>, Status> {
+        let req = request.into_inner();
+        let filter = MassCancelFilter {
+            user_id: req.user_id.as_deref().map(|id| parse_uuid("user_id", id)).transpose()?,
+            instrument_id: req.instrument_id,
+            underlying: req.underlying,
+            side: parse_enum::<proto::Side>("side", req.side)?.to_domain(),
+        };
+
+        let result = self.store.mass_cancel(&filter).await.map_err(store_status)?;
+        Ok(Response::new(proto::MassCancelResponse {
+            cancelled_order_ids: result.order_ids().iter().map(Uuid::to_string).collect(),
+        }))
+    }
+
+    async fn expire_orders(
+        &self,
+        request: Request<proto::ExpireOrdersRequest>,
+    ) -> Result<Response<proto::ExpireOrdersResponse>, Status> {
+        let now = match request.into_inner().now {
+            Some(now) => proto::from_timestamp(&now).ok_or_else(|| Status::invalid_argument("Invalid now"))?,
+            None => Utc::now(),
+        };
+
+        let expired = self.store.expire_orders(now).await.map_err(store_status)?;
+        Ok(Response::new(proto::ExpireOrdersResponse {
+            expired_order_ids: expired.iter().map(|e| e.order.order_id.to_string()).collect(),
+        }))
+    }
+
+    async fn get_book_snapshot(
+        &self,
+        request: Request<proto::GetBookSnapshotRequest>,
+    ) -> Result<Response<proto::GetBookSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let depth = if req.depth == 0 { 10 } else { req.depth as usize };
+        let scale = self.store.price_scale(&req.instrument_id).await.unwrap_or_default();
+        let book = self
+            .store
+            .get_book(&req.instrument_id)
+            .await
+            .map_err(store_status)?
+            .ok_or_else(|| store_status(StoreError::InstrumentNotFound(req.instrument_id.clone())))?;
+
+        let level = |price, orders: &std::collections::VecDeque<BookOrder>| proto::PriceLevel {
+            price: scale.to_price(price),
+            quantity: orders.iter().map(|o| o.visible_quantity()).sum(),
+            order_count: orders.len() as u32,
+        };
+        Ok(Response::new(proto::GetBookSnapshotResponse {
+            bids: book.bids.iter().take(depth).map(|(price, orders)| level(price.0, orders)).collect(),
+            asks: book.asks.iter().take(depth).map(|(price, orders)| level(*price, orders)).collect(),
+            spread: book.spread().map(|ticks| scale.to_price(ticks)),
+            sequence: book.sequence,
+            instrument_id: req.instrument_id,
+        }))
+    }
+
+    async fn get_trades(
+        &self,
+        request: Request<proto::GetTradesRequest>,
+    ) -> Result<Response<proto::GetTradesResponse>, Status> {
+        let req = request.into_inner();
+        let limit = if req.limit == 0 { 50 } else { req.limit };
+        let scale = self.store.price_scale(&req.instrument_id).await.unwrap_or_default();
+        let trades = self
+            .store
+            .get_trades(&req.instrument_id, limit)
+            .await
+            .map_err(store_status)?;
+        Ok(Response::new(proto::GetTradesResponse {
+            trades: trades.iter().map(|trade| trade_message(trade, &scale)).collect(),
+        }))
+    }
+
+    async fn book_block_trade(
+        &self,
+        request: Request<proto::BookBlockTradeRequest>,
+    ) -> Result<Response<proto::BookBlockTradeResponse>, Status> {
+        let req = request.into_inner();
+        let taker_side = parse_enum::<proto::Side>("taker_side", req.taker_side)?
+            .to_domain()
+            .ok_or_else(|| Status::invalid_argument("taker_side is required"))?;
+
+        if !req.legs.is_empty() {
+            let legs = parse_legs(&req.legs)?;
+            self.store
+                .define_combo(ComboDefinition::new(req.instrument_id.clone(), legs))
+                .await
+                .map_err(store_status)?;
+        }
+
+        let scale = self.store.price_scale(&req.instrument_id).await.map_err(store_status)?;
+        let price = scale
+            .order_ticks(req.price, taker_side)
+            .map_err(|e| store_status(e.into()))?;
+        let block = BlockTrade {
+            rfq_id: parse_uuid("rfq_id", &req.rfq_id)?,
+            instrument_id: req.instrument_id,
+            price,
+            quantity: req.quantity,
+            taker_order_id: parse_uuid("taker_order_id", &req.taker_order_id)?,
+            taker_user_id: parse_uuid("taker_user_id", &req.taker_user_id)?,
+            taker_side,
+            maker_order_id: parse_uuid("maker_order_id", &req.maker_order_id)?,
+            maker_user_id: parse_uuid("maker_user_id", &req.maker_user_id)?,
+        };
+
+        let trades = self.store.book_block_trade(block).await.map_err(store_status)?;
+        Ok(Response::new(proto::BookBlockTradeResponse {
+            trades: trades.iter().map(|trade| trade_message(trade, &scale)).collect(),
+        }))
+    }
+
+    async fn mass_quote(
+        &self,
+        request: Request<proto::MassQuoteRequest>,
+    ) -> Result<Response<proto::MassQuoteResponse>, Status> {
+        let req = request.into_inner();
+        let user_id = parse_uuid("user_id", &req.user_id)?;
+
+        let mut quotes = Vec::with_capacity(req.quotes.len());
+        for quote in req.quotes {
+            let side = parse_enum::<proto::Side>("quotes.side", quote.side)?
+                .to_domain()
+                .ok_or_else(|| Status::invalid_argument("quotes.side is required"))?;
+            let order_id = match &quote.order_id {
+                Some(id) => parse_uuid("quotes.order_id", id)?,
+                None => Uuid::new_v4(),
+            };
+            let scale = self.store.price_scale(&quote.instrument_id).await.map_err(store_status)?;
+            let price = scale
+                .order_ticks(quote.price, side)
+                .map_err(|e| store_status(e.into()))?;
+            quotes.push(
+                BookOrder::new(order_id, user_id, side, price, quote.quantity, 0, TimeInForce::Gtc)
+                    .with_instrument_id(quote.instrument_id),
+            );
+        }
+
+        let result = self.store.mass_quote(user_id, quotes).await.map_err(store_status)?;
+        let ids = |ids: Vec<Uuid>| ids.iter().map(Uuid::to_string).collect();
+        Ok(Response::new(proto::MassQuoteResponse {
+            replaced_order_ids: ids(result.replaced.order_ids()),
+            resting_order_ids: ids(result.resting_order_ids()),
+            rejected_order_ids: ids(result.rejected_order_ids()),
+        }))
+    }
+
+    async fn reset_mmp(
+        &self,
+        request: Request<proto::ResetMmpRequest>,
+    ) -> Result<Response<proto::ResetMmpResponse>, Status> {
+        let req = request.into_inner();
+        let user_id = parse_uuid("user_id", &req.user_id)?;
+        let reset = self.store.reset_mmp(user_id, &req.underlying).await.map_err(store_status)?;
+        Ok(Response::new(proto::ResetMmpResponse { reset }))
+    }
+
+    async fn subscribe_events(
+        &self,
+        request: Request<proto::SubscribeEventsRequest>,
+    ) -> Result<Response<BoxStream<proto::EventFrame>>, Status> {
+        let from_sequence = match request.into_inner().from_sequence {
+            Some(from) => from,
+            None => self.store.get_sequence().await.map_err(store_status)? + 1,
+        };
+        let subscription = self.store.subscribe(from_sequence).await.map_err(store_status)?;
+
+        // Ends after a lagged frame, as the WebSocket stream does
+        let frames = stream::unfold(Some(subscription), |subscription: Option<EventSubscription>| async move {
+            let mut subscription = subscription?;
+            let item = subscription.recv().await;
+            let last = item.is_err();
+            let frame = event_frame(item)?;
+            Some((frame, (!last).then_some(subscription)))
+        });
+        Ok(Response::new(Box::pin(frames)))
+    }
+}
+
+/// Tonic service routing requests to a [`MatchingService`]
+#[derive(Debug)]
+pub struct MatchingServiceServer<T> {
+    inner: Arc<T>,
+}
+
+impl<T: MatchingService> MatchingServiceServer<T> {
+    /// Serve an implementation of the service
+    pub fn new(inner: T) -> Self {
+        Self::from_arc(Arc::new(inner))
+    }
+
+    /// Serve a shared implementation of the service
+    pub fn from_arc(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Clone for MatchingServiceServer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> NamedService for MatchingServiceServer<T> {
+    const NAME: &'static str = proto::SERVICE_NAME;
+}
+
+/// Serve a unary method
+macro_rules! unary {
+    ($inner:expr, $req:expr, $method:ident) => {{
+        let inner = Arc::clone(&$inner);
+        let service = tower::service_fn(move |request| {
+            let inner = Arc::clone(&inner);
+            async move { inner.$method(request).await }
+        });
+        Box::pin(async move { Ok(tonic::server::Grpc::new(ProstCodec::default()).unary(service, $req).await) })
+    }};
+}
+
+impl<T, B> Service<http::Request<B>> for MatchingServiceServer<T>
+where
+    T: MatchingService,
+    B: tonic::codegen::Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        match req.uri().path() {
+            path::SUBMIT_ORDER => unary!(self.inner, req, submit_order),
+            path::CANCEL_ORDER => unary!(self.inner, req, cancel_order),
+            path::AMEND_ORDER => unary!(self.inner, req, amend_order),
+            path::MASS_CANCEL => unary!(self.inner, req, mass_cancel),
+            path::EXPIRE_ORDERS => unary!(self.inner, req, expire_orders),
+            path::GET_BOOK_SNAPSHOT => unary!(self.inner, req, get_book_snapshot),
+            path::GET_TRADES => unary!(self.inner, req, get_trades),
+            path::BOOK_BLOCK_TRADE => unary!(self.inner, req, book_block_trade),
+            path::MASS_QUOTE => unary!(self.inner, req, mass_quote),
+            path::RESET_MMP => unary!(self.inner, req, reset_mmp),
+            path::SUBSCRIBE_EVENTS => {
+                let inner = Arc::clone(&self.inner);
+                let service = tower::service_fn(move |request| {
+                    let inner = Arc::clone(&inner);
+                    async move { inner.subscribe_events(request).await }
+                });
+                Box::pin(async move {
+                    Ok(tonic::server::Grpc::new(ProstCodec::default())
+                        .server_streaming(service, req)
+                        .await)
+                })
+            }
+            _ => Box::pin(async move { Ok(Status::unimplemented("Unknown method").into_http()) }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::MatchingGrpcClient;
+    use crate::store::InMemoryStore;
+    use tonic::transport::server::TcpIncoming;
+
+    const INSTRUMENT: &str = "BTC-20240329-50000-C";
+
+    fn order(side: proto::Side, price: f64, quantity: u32) -> proto::SubmitOrderRequest {
+        proto::SubmitOrderRequest {
+            instrument_id: INSTRUMENT.to_string(),
+            user_id: Uuid::new_v4().to_string(),
+            side: side as i32,
+            price,
+            quantity,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_over_grpc() {
+        let store = Arc::new(InMemoryStore::new());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        let service = MatchingGrpcService::new(store).into_server();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming),
+        );
+
+        let client = MatchingGrpcClient::connect(&format!("http://{addr}")).await.unwrap();
+        let mut events = client.subscribe_events(Some(1)).await.unwrap();
+
+        let maker = client.submit_order(order(proto::Side::Sell, 100.0, 10)).await.unwrap();
+        assert!(maker.resting);
+        let taker = client.submit_order(order(proto::Side::Buy, 100.0, 4)).await.unwrap();
+        assert_eq!(taker.trades.len(), 1);
+        assert_eq!(taker.trades[0].price, 100.0);
+        assert_eq!(taker.trades[0].maker_order_id, maker.order_id);
+
+        let book = client
+            .get_book_snapshot(proto::GetBookSnapshotRequest { instrument_id: INSTRUMENT.to_string(), depth: 0 })
+            .await
+            .unwrap();
+        assert_eq!(book.asks, vec![proto::PriceLevel { price: 100.0, quantity: 6, order_count: 1 }]);
+
+        let amended = client
+            .amend_order(proto::AmendOrderRequest { order_id: maker.order_id.clone(), price: None, quantity: Some(2) })
+            .await
+            .unwrap();
+        assert!(amended.priority_retained);
+        assert!(client
+            .cancel_order(proto::CancelOrderRequest { instrument_id: String::new(), order_id: maker.order_id.clone() })
+            .await
+            .unwrap()
+            .cancelled);
+
+        let trades = client
+            .get_trades(proto::GetTradesRequest { instrument_id: INSTRUMENT.to_string(), limit: 0 })
+            .await
+            .unwrap();
+        assert_eq!(trades.trades, taker.trades);
+
+        let mut kinds = Vec::new();
+        while kinds.len() < 5 {
+            let frame = events.message().await.unwrap().unwrap();
+            let Some(proto::event_frame::Frame::Event(event)) = frame.frame else {
+                panic!("unexpected frame {:?}", frame.frame);
+            };
+            assert_eq!(event.to_event().unwrap().sequence(), event.sequence);
+            kinds.push(event.kind);
+        }
+        assert_eq!(kinds[0], "order_accepted");
+        assert!(kinds.contains(&"trade_executed".to_string()));
+        assert!(kinds.contains(&"order_amended".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_errors_map_to_status_codes() {
+        let service = MatchingGrpcService::new(Arc::new(InMemoryStore::new()));
+
+        let mut off_tick = order(proto::Side::Buy, 100.005, 1);
+        let status = service.submit_order(Request::new(off_tick.clone())).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        off_tick.side = proto::Side::Unspecified as i32;
+        let status = service.submit_order(Request::new(off_tick)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let missing = proto::AmendOrderRequest { order_id: Uuid::new_v4().to_string(), price: Some(101.0), quantity: None };
+        let status = service.amend_order(Request::new(missing)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+}
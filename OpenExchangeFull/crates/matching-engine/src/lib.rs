@@ -5,9 +5,28 @@
 //! # Features
 //!
 //! - Price-time priority matching (FIFO)
-//! - Support for GTC, IOC, FOK time-in-force
-//! - In-memory and Redis storage backends
+//! - Pro-rata and price-time-pro-rata level allocation, per underlying
+//! - Support for GTC, GTD, DAY, IOC, FOK time-in-force with expiry sweeps
+//! - Market orders with a protection band
+//! - Stop-market and stop-limit orders on last trade, mark or index price
+//! - Per-order self-trade prevention
+//! - Post-only, all-or-none and minimum-quantity instructions
+//! - Iceberg (reserve) orders
+//! - Multi-leg combo orders with implied-in/implied-out matching and atomic legs
+//! - Off-book block trades agreed by request for quote, with minimum sizes
+//! - Mass quotes with per-underlying market maker protection (quantity, delta, vega)
+//! - Mass cancel by user, instrument, underlying or side
+//! - Call auctions after circuit-breaker halts and for opening
+//! - Integer tick prices with per-instrument tick size
+//! - In-memory, Redis and file-journal storage backends
 //! - Deterministic event log for crash recovery
+//! - Injectable clock and sequence-derived trade IDs for bit-exact replay
+//! - Event subscriptions with resume from a sequence and lag detection
+//! - L2 delta and L3 order-by-order book feeds with CRC32 checksums
+//! - Durable write-ahead journal with replay on restart
+//! - Sharded runtime: one matching task and journal per instrument bucket
+//! - Full book snapshots (snapshot + event-log tail restore)
+//! - gRPC service and client (`grpc` feature)
 //! - Atomic trade execution
 //!
 //! # Architecture
@@ -22,8 +41,21 @@
 //!
 //! - [`domain`] - Core types (Trade, BookOrder, OrderBook)
 //! - [`engine`] - Core matching algorithm
+//! - [`allocation`] - FIFO and pro-rata sharing within a price level
+//! - [`combo`] - Combo (multi-leg) instrument definitions
+//! - [`block`] - Block trades booked outside the order book
+//! - [`mmp`] - Market maker protection for mass quotes
 //! - [`store`] - Storage backends (in-memory, Redis)
 //! - [`event`] - Event types for the event log
+//! - [`feed`] - Live subscriptions to matching events
+//! - [`book_feed`] - Incremental L2/L3 book updates and snapshots
+//! - [`wal`] - Segmented on-disk write-ahead log
+//! - [`snapshot`] - Point-in-time engine snapshots
+//! - [`trigger`] - Stop order trigger book
+//! - [`auction`] - Call auction equilibrium and uncross allocation
+//! - [`shard`] - Per-shard matching tasks fed by bounded queues
+//! - [`price`] - Integer tick prices and decimal conversion
+//! - [`clock`] - Time and trade ID sources
 //!
 //! # Example
 //!
@@ -42,7 +74,7 @@
 //!         uuid::Uuid::new_v4(),
 //!         uuid::Uuid::new_v4(),
 //!         OrderSide::Buy,
-//!         100.0,
+//!         10000, // price in ticks
 //!         10,
 //!         1,
 //!         TimeInForce::Gtc,
@@ -55,27 +87,61 @@
 
 pub mod domain;
 pub mod engine;
+pub mod allocation;
+pub mod combo;
+pub mod block;
+pub mod mmp;
 pub mod result;
 pub mod event;
 pub mod log;
+pub mod feed;
+pub mod book_feed;
+pub mod wal;
+pub mod snapshot;
+pub mod trigger;
+pub mod auction;
+pub mod price;
+pub mod clock;
 pub mod store;
 pub mod error;
 pub mod circuit_breaker;
 pub mod metrics;
+pub mod shard;
 
 #[cfg(feature = "api")]
 pub mod api;
 
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
 pub use domain::{
-    BookOrder, OrderBook, OrderSide, PriceLevel, TimeInForce, Trade, OrderBookSnapshot,
+    BookOrder, MassCancelFilter, OrderBook, OrderLocation, OrderSide, OrderType, PostOnly, PriceLevel,
+    SelfTradePrevention, StopTrigger, TimeInForce, Trade, TriggerSource, OrderBookSnapshot,
 };
 pub use engine::MatchingEngine;
-pub use result::{CancelResult, MatchResult};
+pub use allocation::MatchingAlgorithm;
+pub use combo::{ComboDefinition, ComboLeg};
+pub use block::BlockTrade;
+pub use mmp::{MmpBreach, MmpExposure, MmpFreeze, MmpLimits, MmpStatus};
+pub use result::{
+    AmendResult, CancelResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, MmpTrip, TriggeredStop,
+    UncrossResult,
+};
 pub use event::MatchingEvent;
+pub use feed::{EventFeed, EventSubscription, SubscriptionError};
+pub use book_feed::{BookFeed, BookFeedError, BookFeedHandle, BookFeedSnapshot, BookUpdate, LevelDelta, LocalBook, OrderDelta};
 pub use store::{
-    create_store, create_store_from_config, InMemoryStore, MatchingStore, RedisStore, StoreError, StoreResult, StoreType,
+    create_store, create_store_from_config, snapshot_interval, spawn_snapshots, FileStore, InMemoryStore, MatchingStore, RedisStore, StoreError,
+    StoreResult, StoreType,
 };
-pub use circuit_breaker::{CircuitBreakerManager, CircuitBreakerConfig, CircuitBreakerStatus};
+pub use circuit_breaker::{CircuitBreakerManager, CircuitBreakerConfig, CircuitBreakerStatus, PriceBand};
 pub use metrics::{MatchingEngineMetrics, MetricsSnapshot};
+pub use snapshot::EngineSnapshot;
+pub use trigger::{ReferencePrices, TriggerBook};
+pub use auction::{AuctionReason, CallAuction, IndicativeUncross};
+pub use price::{OffTickPolicy, PriceScale, Ticks};
+pub use clock::{Clock, IdGenerator, ManualClock, RandomIds, SequenceIds, SystemClock};
+pub use wal::{FsyncPolicy, WalConfig, WalError, WriteAheadLog};
+pub use shard::{Shard, ShardConfig, ShardError, ShardResult, ShardedEngine};
 
 pub use error::MatchingError;
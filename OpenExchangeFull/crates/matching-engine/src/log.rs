@@ -8,6 +8,7 @@ use tokio::sync::RwLock;
 use tracing::debug;
 
 use crate::event::MatchingEvent;
+use crate::feed::{EventFeed, EventSubscription};
 
 /// In-memory event log
 pub struct EventLog {
@@ -15,6 +16,8 @@ pub struct EventLog {
     events: Vec<MatchingEvent>,
     /// Current sequence number
     sequence: u64,
+    /// Live subscribers to appended events
+    feed: EventFeed,
 }
 
 impl EventLog {
@@ -23,16 +26,25 @@ impl EventLog {
         Self {
             events: Vec::new(),
             sequence: 0,
+            feed: EventFeed::default(),
         }
     }
 
-    /// Append an event to the log
+    /// Append an event to the log and publish it to subscribers
     pub fn append(&mut self, event: MatchingEvent) {
         self.sequence = event.sequence();
+        self.feed.publish(&event);
         self.events.push(event);
         debug!(sequence = self.sequence, "Event appended to log");
     }
 
+    /// Subscribe to events from a sequence number onwards
+    ///
+    /// Logged events are replayed first, then appended ones follow live.
+    pub fn subscribe(&self, from_sequence: u64) -> EventSubscription {
+        self.feed.subscribe(from_sequence, self.get_from(from_sequence))
+    }
+
     /// Get events from a sequence number onwards
     pub fn get_from(&self, from_sequence: u64) -> Vec<MatchingEvent> {
         self.events
@@ -1,9 +1,43 @@
 //! Metrics for the Matching Engine
 //!
 //! This module provides metrics collection for monitoring the matching engine.
-
+//!
+//! Every metric is kept in process (see [`MatchingEngineMetrics::snapshot`])
+//! and also published through the `metrics` facade, labelled by
+//! `instrument` and `underlying`. With the `observability` crate's
+//! Prometheus exporter installed they are scraped from `/metrics`; without
+//! a recorder publishing is a no-op.
+
+use metrics::Label;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Names of the published metrics
+pub mod names {
+    /// Orders that reached continuous matching (counter)
+    pub const ORDERS_RECEIVED: &str = "matching_orders_received_total";
+    /// Orders that traded (counter)
+    pub const ORDERS_MATCHED: &str = "matching_orders_matched_total";
+    /// Orders that did not trade (counter)
+    pub const ORDERS_REJECTED: &str = "matching_orders_rejected_total";
+    /// Trades executed (counter)
+    pub const TRADES: &str = "matching_trades_total";
+    /// Quantity traded (counter)
+    pub const TRADED_QUANTITY: &str = "matching_traded_quantity_total";
+    /// Time to match one order, in seconds (histogram)
+    pub const ORDER_LATENCY: &str = "matching_order_latency_seconds";
+    /// Resting orders in the book (gauge)
+    pub const BOOK_DEPTH: &str = "matching_book_depth";
+    /// Best bid/ask spread in basis points of the bid (gauge)
+    pub const SPREAD_BPS: &str = "matching_spread_bps";
+    /// 1 while the instrument is halted, else 0 (gauge)
+    pub const HALTED: &str = "matching_halted";
+    /// Events published but not yet received by the slowest live
+    /// subscriber (gauge, labelled by `feed` instead)
+    pub const EVENT_LOG_LAG: &str = "matching_event_log_lag";
+}
 
 /// Simple atomic counter
 #[derive(Debug)]
@@ -73,8 +107,9 @@ impl Default for Gauge {
     }
 }
 
-/// Histogram for tracking latencies (simple implementation)
-/// For production, consider using the `metrics` crate
+/// Running count, sum, min and max of latencies
+///
+/// The bucketed distribution is published as a Prometheus histogram.
 #[derive(Debug)]
 pub struct Histogram {
     count: AtomicU64,
@@ -96,18 +131,8 @@ impl Histogram {
     pub fn record(&self, value_us: u64) {
         self.count.fetch_add(1, Ordering::Relaxed);
         self.sum.fetch_add(value_us, Ordering::Relaxed);
-
-        // Update min
-        let current_min = self.min.load(Ordering::Relaxed);
-        if value_us < current_min {
-            self.min.store(value_us, Ordering::Relaxed);
-        }
-
-        // Update max
-        let current_max = self.max.load(Ordering::Relaxed);
-        if value_us > current_max {
-            self.max.store(value_us, Ordering::Relaxed);
-        }
+        self.min.fetch_min(value_us, Ordering::Relaxed);
+        self.max.fetch_max(value_us, Ordering::Relaxed);
     }
 
     pub fn get_stats(&self) -> HistogramStats {
@@ -146,7 +171,65 @@ pub struct HistogramStats {
     pub max_us: u64,
 }
 
+/// Metric handles of one instrument, registered on first use
+struct InstrumentHandles {
+    orders_received: metrics::Counter,
+    orders_matched: metrics::Counter,
+    orders_rejected: metrics::Counter,
+    trades: metrics::Counter,
+    traded_quantity: metrics::Counter,
+    latency: metrics::Histogram,
+    book_depth: metrics::Gauge,
+    spread_bps: metrics::Gauge,
+    halted: metrics::Gauge,
+    /// Last published values, for the in-process snapshot
+    state: InstrumentMetrics,
+}
+
+impl InstrumentHandles {
+    fn new(instrument_id: &str) -> Self {
+        let underlying = instrument_id.split('-').next().unwrap_or(instrument_id);
+        let labels = vec![
+            Label::new("instrument", instrument_id.to_string()),
+            Label::new("underlying", underlying.to_string()),
+        ];
+        Self {
+            orders_received: metrics::counter!(names::ORDERS_RECEIVED, labels.clone()),
+            orders_matched: metrics::counter!(names::ORDERS_MATCHED, labels.clone()),
+            orders_rejected: metrics::counter!(names::ORDERS_REJECTED, labels.clone()),
+            trades: metrics::counter!(names::TRADES, labels.clone()),
+            traded_quantity: metrics::counter!(names::TRADED_QUANTITY, labels.clone()),
+            latency: metrics::histogram!(names::ORDER_LATENCY, labels.clone()),
+            book_depth: metrics::gauge!(names::BOOK_DEPTH, labels.clone()),
+            spread_bps: metrics::gauge!(names::SPREAD_BPS, labels.clone()),
+            halted: metrics::gauge!(names::HALTED, labels),
+            state: InstrumentMetrics::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for InstrumentHandles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentHandles")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Current gauges of one instrument
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct InstrumentMetrics {
+    /// Resting orders in the book
+    pub book_depth: u64,
+    /// Best bid/ask spread in basis points (None while one side is empty)
+    pub spread_bps: Option<u64>,
+    pub halted: bool,
+}
+
 /// Metrics for the matching engine
+///
+/// Counters and latency are kept both in total and per instrument; book
+/// depth, spread and halt state only per instrument.
 #[derive(Debug)]
 pub struct MatchingEngineMetrics {
     pub orders_received: Counter,
@@ -154,8 +237,7 @@ pub struct MatchingEngineMetrics {
     pub orders_rejected: Counter,
     pub trades_executed: Counter,
     pub order_processing_latency: Histogram,
-    pub order_book_depth: Gauge,
-    pub spread: Gauge,
+    instruments: Mutex<HashMap<String, InstrumentHandles>>,
 }
 
 impl MatchingEngineMetrics {
@@ -166,44 +248,73 @@ impl MatchingEngineMetrics {
             orders_rejected: Counter::new(),
             trades_executed: Counter::new(),
             order_processing_latency: Histogram::new(),
-            order_book_depth: Gauge::new(),
-            spread: Gauge::new(),
+            instruments: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `f` on an instrument's handles, registering them if needed
+    fn with_instrument<R>(&self, instrument_id: &str, f: impl FnOnce(&mut InstrumentHandles) -> R) -> R {
+        let mut instruments = self.instruments.lock().unwrap_or_else(|e| e.into_inner());
+        if !instruments.contains_key(instrument_id) {
+            instruments.insert(instrument_id.to_string(), InstrumentHandles::new(instrument_id));
         }
+        f(instruments.get_mut(instrument_id).expect("just inserted"))
     }
 
-    pub fn record_order_received(&self) {
+    pub fn record_order_received(&self, instrument_id: &str) {
         self.orders_received.increment();
+        self.with_instrument(instrument_id, |m| m.orders_received.increment(1));
     }
 
-    pub fn record_order_matched(&self) {
+    pub fn record_order_matched(&self, instrument_id: &str) {
         self.orders_matched.increment();
+        self.with_instrument(instrument_id, |m| m.orders_matched.increment(1));
     }
 
-    pub fn record_order_rejected(&self) {
+    pub fn record_order_rejected(&self, instrument_id: &str) {
         self.orders_rejected.increment();
+        self.with_instrument(instrument_id, |m| m.orders_rejected.increment(1));
     }
 
-    pub fn record_trade(&self, quantity: u32) {
-        for _ in 0..quantity {
-            self.trades_executed.increment();
-        }
+    /// Record one trade; the in-process counter adds its quantity
+    pub fn record_trade(&self, instrument_id: &str, quantity: u32) {
+        self.trades_executed.value.fetch_add(quantity as u64, Ordering::Relaxed);
+        self.with_instrument(instrument_id, |m| {
+            m.trades.increment(1);
+            m.traded_quantity.increment(quantity as u64);
+        });
+    }
+
+    pub fn record_latency(&self, instrument_id: &str, duration: Duration) {
+        self.order_processing_latency.record(duration.as_micros() as u64);
+        self.with_instrument(instrument_id, |m| m.latency.record(duration.as_secs_f64()));
     }
 
-    pub fn record_latency(&self, duration: Duration) {
-        let us = duration.as_micros() as u64;
-        self.order_processing_latency.record(us);
+    pub fn set_order_book_depth(&self, instrument_id: &str, depth: u64) {
+        self.with_instrument(instrument_id, |m| {
+            m.state.book_depth = depth;
+            m.book_depth.set(depth as f64);
+        });
     }
 
-    pub fn set_order_book_depth(&self, depth: u64) {
-        self.order_book_depth.set(depth);
+    /// Set the spread, or None when one side of the book is empty
+    pub fn set_spread(&self, instrument_id: &str, spread_bps: Option<u64>) {
+        self.with_instrument(instrument_id, |m| {
+            m.state.spread_bps = spread_bps;
+            m.spread_bps.set(spread_bps.map_or(f64::NAN, |bps| bps as f64));
+        });
     }
 
-    pub fn set_spread(&self, spread_bps: u64) {
-        self.spread.set(spread_bps);
+    pub fn set_halted(&self, instrument_id: &str, halted: bool) {
+        self.with_instrument(instrument_id, |m| {
+            m.state.halted = halted;
+            m.halted.set(if halted { 1.0 } else { 0.0 });
+        });
     }
 
     pub fn snapshot(&self) -> MetricsSnapshot {
         let latency_stats = self.order_processing_latency.get_stats();
+        let instruments = self.instruments.lock().unwrap_or_else(|e| e.into_inner());
         
         MetricsSnapshot {
             orders_received: self.orders_received.get(),
@@ -213,11 +324,14 @@ impl MatchingEngineMetrics {
             order_processing_latency_avg_us: latency_stats.avg_us,
             order_processing_latency_min_us: latency_stats.min_us,
             order_processing_latency_max_us: latency_stats.max_us,
-            order_book_depth: self.order_book_depth.get(),
-            spread_bps: self.spread.get(),
+            instruments: instruments
+                .iter()
+                .map(|(id, m)| (id.clone(), m.state.clone()))
+                .collect(),
         }
     }
 
+    /// Reset the in-process counters (published counters keep counting)
     pub fn reset(&self) {
         self.orders_received.reset();
         self.orders_matched.reset();
@@ -242,6 +356,72 @@ pub struct MetricsSnapshot {
     pub order_processing_latency_avg_us: u64,
     pub order_processing_latency_min_us: u64,
     pub order_processing_latency_max_us: u64,
-    pub order_book_depth: u64,
-    pub spread_bps: u64,
+    /// Book depth, spread and halt state per instrument
+    pub instruments: BTreeMap<String, InstrumentMetrics>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BookOrder, OrderSide, TimeInForce};
+    use crate::engine::MatchingEngine;
+    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+    use uuid::Uuid;
+
+    fn order(instrument_id: &str, side: OrderSide, price: i64) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), side, price, 1, 0, TimeInForce::Gtc)
+            .with_instrument_id(instrument_id)
+    }
+
+    #[test]
+    fn test_metrics_are_exported_per_instrument() {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), &[0.001, 1.0])
+            .unwrap()
+            .build_recorder();
+        let handle = recorder.handle();
+
+        let snapshot = metrics::with_local_recorder(&recorder, || {
+            let mut engine = MatchingEngine::new_with_metrics();
+            engine.match_order(order("BTC-20260315-50000-C", OrderSide::Buy, 99));
+            engine.match_order(order("BTC-20260315-50000-C", OrderSide::Sell, 101));
+            engine.match_order(order("ETH-20260315-3000-C", OrderSide::Sell, 100));
+            engine.match_order(order("ETH-20260315-3000-C", OrderSide::Buy, 100));
+            engine.halt_underlying("ETH");
+            engine.metrics().unwrap()
+        });
+
+        // Gauges are kept per instrument instead of overwriting each other
+        let btc = &snapshot.instruments["BTC-20260315-50000-C"];
+        assert_eq!((btc.book_depth, btc.spread_bps, btc.halted), (2, Some(202), false));
+        let eth = &snapshot.instruments["ETH-20260315-3000-C"];
+        assert_eq!((eth.book_depth, eth.spread_bps, eth.halted), (0, None, true));
+        assert_eq!(snapshot.orders_received, 4);
+
+        let rendered = handle.render();
+        let btc_labels = r#"instrument="BTC-20260315-50000-C",underlying="BTC""#;
+        let eth_labels = r#"instrument="ETH-20260315-3000-C",underlying="ETH""#;
+        assert!(rendered.contains(&format!("{}{{{}}} 2", names::BOOK_DEPTH, btc_labels)));
+        assert!(rendered.contains(&format!("{}{{{}}} 1", names::HALTED, eth_labels)));
+        assert!(rendered.contains(&format!("{}{{{}}} 1", names::TRADES, eth_labels)));
+        assert!(rendered.contains(&format!(
+            "{}_bucket{{{},le=\"+Inf\"}} 2",
+            names::ORDER_LATENCY,
+            btc_labels
+        )));
+    }
+
+    #[test]
+    fn test_amend_requeue_updates_book_metrics() {
+        let mut engine = MatchingEngine::new_with_metrics();
+        engine.match_order(order("BTC-20260315-50000-C", OrderSide::Buy, 99));
+        let sell = order("BTC-20260315-50000-C", OrderSide::Sell, 101);
+        let sell_id = sell.order_id;
+        engine.match_order(sell);
+
+        engine.amend_order(sell_id, Some(100), None).unwrap();
+
+        let btc = &engine.metrics().unwrap().instruments["BTC-20260315-50000-C"];
+        assert_eq!((btc.book_depth, btc.spread_bps), (2, Some(101)));
+    }
 }
@@ -0,0 +1,290 @@
+//! Market maker protection (MMP)
+//!
+//! Market makers quote many instruments at once with mass quotes (see
+//! [`MatchingEngine::mass_quote`](crate::engine::MatchingEngine::mass_quote)):
+//! a quote is a post-only order flagged as such, and each mass quote
+//! replaces the maker's previous quotes on the instruments it names.
+//!
+//! MMP limits are set per underlying. Every fill of a maker's quote adds to
+//! the maker's exposure in that underlying over a rolling window: contracts
+//! traded, and net delta and vega from the Black-Scholes Greeks of
+//! [`market_data::black_scholes`] at the underlying's index price. Once a
+//! limit is reached the engine cancels all of the maker's quotes in the
+//! underlying and freezes quoting there until the maker resets it.
+//!
+//! A trip is sequenced and logged as `MatchingEvent::MmpTriggered` with the
+//! orders it cancelled, so replay never recomputes Greeks. The rolling
+//! windows are not persisted and start empty after a restart; frozen
+//! underlyings survive snapshots and compaction. The sharded runtime keeps
+//! all of an underlying's instruments on one shard, so a maker's exposure
+//! in an underlying is tracked in one place.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use market_data::black_scholes::black_scholes_greeks;
+use market_data::{BSInputs, OptionType};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::domain::OrderSide;
+
+/// Implied volatility Greeks are computed at unless configured
+pub const DEFAULT_MMP_VOLATILITY: f64 = 0.5;
+
+/// Hour of the day (UTC) options expire at
+const EXPIRY_HOUR_UTC: u32 = 8;
+
+/// MMP limits of an underlying
+///
+/// A limit left at `None` is not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MmpLimits {
+    /// Length of the rolling window, in milliseconds
+    pub window_ms: u64,
+    /// Contracts traded in the window that trip the protection
+    pub quantity: Option<u64>,
+    /// Absolute net delta traded in the window that trips the protection
+    pub delta: Option<f64>,
+    /// Absolute net vega (per volatility point) that trips the protection
+    pub vega: Option<f64>,
+    /// Implied volatility the Greeks are computed at
+    pub volatility: f64,
+}
+
+impl MmpLimits {
+    /// Limits over a window, with nothing checked yet
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            quantity: None,
+            delta: None,
+            vega: None,
+            volatility: DEFAULT_MMP_VOLATILITY,
+        }
+    }
+
+    /// Trip on traded quantity
+    pub fn with_quantity(mut self, quantity: u64) -> Self {
+        self.quantity = Some(quantity);
+        self
+    }
+
+    /// Trip on absolute net delta
+    pub fn with_delta(mut self, delta: f64) -> Self {
+        self.delta = Some(delta);
+        self
+    }
+
+    /// Trip on absolute net vega
+    pub fn with_vega(mut self, vega: f64) -> Self {
+        self.vega = Some(vega);
+        self
+    }
+
+    /// Compute Greeks at this implied volatility
+    pub fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = volatility;
+        self
+    }
+}
+
+/// Limit that tripped the protection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MmpBreach {
+    /// Traded quantity
+    Quantity,
+    /// Net delta
+    Delta,
+    /// Net vega
+    Vega,
+}
+
+impl std::fmt::Display for MmpBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MmpBreach::Quantity => write!(f, "quantity"),
+            MmpBreach::Delta => write!(f, "delta"),
+            MmpBreach::Vega => write!(f, "vega"),
+        }
+    }
+}
+
+/// What a maker's quotes traded in an underlying within the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MmpExposure {
+    /// Contracts traded
+    pub quantity: u64,
+    /// Net delta (positive when the maker bought delta)
+    pub delta: f64,
+    /// Net vega per volatility point
+    pub vega: f64,
+}
+
+impl MmpExposure {
+    /// First limit the exposure has reached, if any
+    pub fn breach(&self, limits: &MmpLimits) -> Option<MmpBreach> {
+        if limits.quantity.is_some_and(|limit| self.quantity >= limit) {
+            Some(MmpBreach::Quantity)
+        } else if limits.delta.is_some_and(|limit| self.delta.abs() >= limit) {
+            Some(MmpBreach::Delta)
+        } else if limits.vega.is_some_and(|limit| self.vega.abs() >= limit) {
+            Some(MmpBreach::Vega)
+        } else {
+            None
+        }
+    }
+}
+
+/// Quoting frozen for a maker in an underlying
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MmpFreeze {
+    /// Maker whose quoting is frozen
+    pub user_id: Uuid,
+    /// Underlying the protection tripped in
+    pub underlying: String,
+    /// Limit that tripped
+    pub breach: MmpBreach,
+    /// Sequence of the trip
+    pub sequence: u64,
+}
+
+/// MMP state of a maker in one underlying
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MmpStatus {
+    /// Underlying
+    pub underlying: String,
+    /// Exposure built up in the current window
+    pub exposure: MmpExposure,
+    /// The trip that froze quoting (None while the maker may quote)
+    pub frozen: Option<MmpFreeze>,
+}
+
+/// Fills of a maker's quotes in one underlying, oldest first
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MmpWindow {
+    fills: VecDeque<(DateTime<Utc>, MmpExposure)>,
+}
+
+impl MmpWindow {
+    /// Add a fill and drop those that left the window
+    pub(crate) fn record(&mut self, at: DateTime<Utc>, fill: MmpExposure, window_ms: u64) {
+        self.fills.push_back((at, fill));
+        let since = at - chrono::Duration::milliseconds(window_ms as i64);
+        while self.fills.front().is_some_and(|(t, _)| *t <= since) {
+            self.fills.pop_front();
+        }
+    }
+
+    /// Exposure of the fills after `since`
+    pub(crate) fn exposure(&self, since: DateTime<Utc>) -> MmpExposure {
+        self.fills
+            .iter()
+            .filter(|(t, _)| *t > since)
+            .fold(MmpExposure::default(), |total, (_, fill)| MmpExposure {
+                quantity: total.quantity + fill.quantity,
+                delta: total.delta + fill.delta,
+                vega: total.vega + fill.vega,
+            })
+    }
+}
+
+/// Exposure a maker takes on by trading `quantity` contracts on `side`
+///
+/// Option IDs follow `UNDERLYING-YYYYMMDD-STRIKE-C|P` and expire at 08:00
+/// UTC; their Greeks use `spot`, or the strike when no index price is
+/// known. Any other instrument (future, perpetual) counts as delta one.
+pub fn fill_exposure(
+    instrument_id: &str,
+    side: OrderSide,
+    quantity: u32,
+    spot: Option<f64>,
+    volatility: f64,
+    now: DateTime<Utc>,
+) -> MmpExposure {
+    let (delta, vega) = match parse_option(instrument_id) {
+        Some((expiry, strike, option_type)) => {
+            let seconds = (expiry - now).num_seconds() as f64;
+            let greeks = black_scholes_greeks(BSInputs {
+                spot: spot.unwrap_or(strike),
+                strike,
+                time: seconds / (365.25 * 24.0 * 3600.0),
+                vol: volatility,
+                rate: 0.0,
+                option_type,
+            });
+            (greeks.delta, greeks.vega / 100.0)
+        }
+        None => (1.0, 0.0),
+    };
+    let signed = match side {
+        OrderSide::Buy => quantity as f64,
+        OrderSide::Sell => -(quantity as f64),
+    };
+    MmpExposure {
+        quantity: quantity as u64,
+        delta: delta * signed,
+        vega: vega * signed,
+    }
+}
+
+/// Expiry, strike and type of an option instrument ID
+///
+/// None for any ID not of the form `UNDERLYING-YYYYMMDD-STRIKE-C|P`.
+pub fn parse_option(instrument_id: &str) -> Option<(DateTime<Utc>, f64, OptionType)> {
+    let parts: Vec<&str> = instrument_id.split('-').collect();
+    let [_, expiry, strike, option_type] = parts.as_slice() else {
+        return None;
+    };
+    let expiry = NaiveDate::parse_from_str(expiry, "%Y%m%d")
+        .ok()?
+        .and_hms_opt(EXPIRY_HOUR_UTC, 0, 0)?
+        .and_utc();
+    let strike: f64 = strike.parse().ok().filter(|s: &f64| *s > 0.0)?;
+    let option_type = match *option_type {
+        "C" => OptionType::Call,
+        "P" => OptionType::Put,
+        _ => return None,
+    };
+    Some((expiry, strike, option_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_exposure_signs_greeks_by_side() {
+        let now = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap().and_hms_opt(8, 0, 0).unwrap().and_utc();
+        let bought = fill_exposure("BTC-20260315-50000-C", OrderSide::Buy, 10, Some(50000.0), 0.5, now);
+        let sold = fill_exposure("BTC-20260315-50000-P", OrderSide::Sell, 10, Some(50000.0), 0.5, now);
+
+        assert_eq!(bought.quantity, 10);
+        assert!(bought.delta > 4.0 && bought.delta < 6.0);
+        assert!(bought.vega > 0.0);
+        // Selling a put buys delta and sells vega
+        assert!(sold.delta > 0.0);
+        assert!(sold.vega < 0.0);
+
+        let perpetual = fill_exposure("BTC-PERPETUAL", OrderSide::Sell, 3, None, 0.5, now);
+        assert_eq!((perpetual.delta, perpetual.vega), (-3.0, 0.0));
+    }
+
+    #[test]
+    fn test_window_drops_old_fills() {
+        let start = Utc::now();
+        let fill = MmpExposure { quantity: 5, delta: 1.0, vega: 0.5 };
+        let mut window = MmpWindow::default();
+        window.record(start, fill, 1000);
+        window.record(start + chrono::Duration::milliseconds(600), fill, 1000);
+        assert_eq!(window.exposure(start - chrono::Duration::seconds(1)).quantity, 10);
+
+        window.record(start + chrono::Duration::milliseconds(1200), fill, 1000);
+        let since = start + chrono::Duration::milliseconds(200);
+        let exposure = window.exposure(since);
+        assert_eq!(exposure.quantity, 10);
+        assert_eq!(exposure.delta, 2.0);
+        assert!(exposure.breach(&MmpLimits::new(1000).with_quantity(10)).is_some());
+        assert!(exposure.breach(&MmpLimits::new(1000).with_delta(2.5)).is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Integer tick prices
+//!
+//! The engine never compares decimal prices: every price inside it (order
+//! limits, book levels, trades, reference prices) is a whole number of
+//! ticks. Decimal prices exist only at the API boundary, where a
+//! [`PriceScale`] converts them in and out using the instrument's tick size.
+//!
+//! Converting an order price that is not a whole number of ticks either
+//! fails or rounds it to the passive side (buys down, sells up), depending
+//! on the scale's [`OffTickPolicy`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::OrderSide;
+use crate::error::MatchingError;
+
+/// A price expressed as a whole number of ticks
+pub type Ticks = i64;
+
+/// Default tick size for instruments without a configured scale
+pub const DEFAULT_TICK_SIZE: f64 = 0.01;
+
+/// Relative tolerance when deciding whether a decimal lies on a tick
+///
+/// Absorbs binary representation error (e.g. `0.1 + 0.2`) without letting
+/// a genuinely off-tick price through.
+const TICK_EPSILON: f64 = 1e-9;
+
+/// What to do with an order price that is not on a tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffTickPolicy {
+    /// Reject the order
+    #[default]
+    Reject,
+    /// Round to the nearest tick on the passive side
+    Round,
+}
+
+/// Conversion between decimal prices and ticks for one instrument
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceScale {
+    /// Size of one tick as a decimal price
+    pub tick_size: f64,
+    /// Handling of off-tick order prices
+    #[serde(default)]
+    pub off_tick: OffTickPolicy,
+}
+
+impl Default for PriceScale {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICK_SIZE)
+    }
+}
+
+impl PriceScale {
+    /// Create a scale that rejects off-tick prices
+    pub fn new(tick_size: f64) -> Self {
+        Self {
+            tick_size,
+            off_tick: OffTickPolicy::Reject,
+        }
+    }
+
+    /// Set the off-tick handling
+    pub fn with_off_tick(mut self, off_tick: OffTickPolicy) -> Self {
+        self.off_tick = off_tick;
+        self
+    }
+
+    /// Convert ticks to a decimal price
+    ///
+    /// Divides by the ticks per unit when the tick size is a unit fraction
+    /// (0.01, 0.1, 0.25...), which gives the closest decimal where
+    /// multiplying would not (`3 * 0.1` is `0.30000000000000004`).
+    pub fn to_price(&self, ticks: Ticks) -> f64 {
+        let per_unit = (1.0 / self.tick_size).round();
+        if self.tick_size < 1.0 && (per_unit * self.tick_size - 1.0).abs() <= TICK_EPSILON {
+            ticks as f64 / per_unit
+        } else {
+            ticks as f64 * self.tick_size
+        }
+    }
+
+    /// Exact tick count of a decimal price, None if it is off-tick
+    pub fn to_ticks(&self, price: f64) -> Option<Ticks> {
+        let ticks = price / self.tick_size;
+        let nearest = ticks.round();
+        ((ticks - nearest).abs() <= TICK_EPSILON * nearest.abs().max(1.0)).then_some(nearest as Ticks)
+    }
+
+    /// Nearest tick to a decimal price
+    ///
+    /// For reference prices (mark, index), which need not lie on a tick.
+    pub fn nearest_ticks(&self, price: f64) -> Ticks {
+        (price / self.tick_size).round() as Ticks
+    }
+
+    /// Convert an order's limit price, applying the off-tick policy
+    pub fn order_ticks(&self, price: f64, side: OrderSide) -> Result<Ticks, MatchingError> {
+        if !price.is_finite() {
+            return Err(MatchingError::InvalidOrder(format!("Invalid price {}", price)));
+        }
+        if let Some(ticks) = self.to_ticks(price) {
+            return Ok(ticks);
+        }
+        match self.off_tick {
+            OffTickPolicy::Reject => Err(MatchingError::InvalidOrder(format!(
+                "Price {} is not a multiple of tick size {}",
+                price, self.tick_size
+            ))),
+            OffTickPolicy::Round => {
+                let ticks = price / self.tick_size;
+                Ok(match side {
+                    OrderSide::Buy => ticks.floor() as Ticks,
+                    OrderSide::Sell => ticks.ceil() as Ticks,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_conversion_and_off_tick_policy() {
+        let scale = PriceScale::new(0.1);
+        assert_eq!(scale.to_ticks(0.1 + 0.2), Some(3));
+        assert_eq!(scale.to_ticks(0.3), Some(3));
+        assert_eq!(scale.to_ticks(0.35), None);
+        assert_eq!(scale.to_price(3), 0.3);
+        assert_eq!(PriceScale::new(0.25).to_price(3), 0.75);
+        assert_eq!(PriceScale::new(5.0).to_price(3), 15.0);
+
+        assert!(scale.order_ticks(0.35, OrderSide::Buy).is_err());
+        let rounding = scale.with_off_tick(OffTickPolicy::Round);
+        assert_eq!(rounding.order_ticks(0.35, OrderSide::Buy).unwrap(), 3);
+        assert_eq!(rounding.order_ticks(0.35, OrderSide::Sell).unwrap(), 4);
+        assert_eq!(rounding.order_ticks(0.3, OrderSide::Sell).unwrap(), 3);
+        assert_eq!(scale.nearest_ticks(0.34), 3);
+
+        let coarse = PriceScale::new(0.5);
+        assert_eq!(coarse.to_ticks(50123.5), Some(100247));
+        assert_eq!(coarse.to_ticks(50123.3), None);
+    }
+}
@@ -1,6 +1,11 @@
 //! Result types for matching operations
 
 use super::domain::{BookOrder, Trade};
+use crate::auction::CallAuction;
+use crate::mmp::{MmpBreach, MmpExposure};
+use crate::price::Ticks;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 /// Result of a matching operation
 #[derive(Debug, Clone)]
@@ -11,6 +16,24 @@ pub struct MatchResult {
     pub remaining_order: Option<BookOrder>,
     /// Whether the remaining order should be inserted into the book
     pub should_insert: bool,
+    /// Sequence assigned to the incoming order (None if rejected before sequencing)
+    pub sequence: Option<u64>,
+    /// Protection limit a market order was matched against (None for limit orders)
+    pub protection_price: Option<Ticks>,
+    /// Price a post-only order was moved to so it would not cross (None if unchanged)
+    pub repriced_to: Option<Ticks>,
+    /// Orders cancelled by self-trade prevention (makers, then the taker if cancelled)
+    pub stp_cancelled: Vec<Uuid>,
+    /// Stop orders released by this operation's trades, in release order
+    pub triggered: Vec<TriggeredStop>,
+    /// Market maker protection tripped by this operation's trades
+    pub mmp_triggered: Vec<MmpTrip>,
+    /// Call auction started by a halt before the order was collected
+    pub auction_started: Option<CallAuction>,
+    /// Call auction uncrossed because its halt ended, before the order was matched
+    pub uncrossed: Option<UncrossResult>,
+    /// Time the order was received, as stamped by the engine (logged with it)
+    pub received_at: Option<DateTime<Utc>>,
 }
 
 impl MatchResult {
@@ -20,6 +43,15 @@ impl MatchResult {
             trades: vec![],
             remaining_order: Some(order),
             should_insert,
+            sequence: None,
+            protection_price: None,
+            repriced_to: None,
+            stp_cancelled: Vec::new(),
+            triggered: Vec::new(),
+            mmp_triggered: Vec::new(),
+            auction_started: None,
+            uncrossed: None,
+            received_at: None,
         }
     }
 
@@ -29,6 +61,15 @@ impl MatchResult {
             trades,
             remaining_order: None,
             should_insert: false,
+            sequence: None,
+            protection_price: None,
+            repriced_to: None,
+            stp_cancelled: Vec::new(),
+            triggered: Vec::new(),
+            mmp_triggered: Vec::new(),
+            auction_started: None,
+            uncrossed: None,
+            received_at: None,
         }
     }
 
@@ -38,6 +79,15 @@ impl MatchResult {
             trades,
             remaining_order: Some(remaining),
             should_insert,
+            sequence: None,
+            protection_price: None,
+            repriced_to: None,
+            stp_cancelled: Vec::new(),
+            triggered: Vec::new(),
+            mmp_triggered: Vec::new(),
+            auction_started: None,
+            uncrossed: None,
+            received_at: None,
         }
     }
 
@@ -47,6 +97,15 @@ impl MatchResult {
             trades: vec![],
             remaining_order: Some(order),
             should_insert: false,
+            sequence: None,
+            protection_price: None,
+            repriced_to: None,
+            stp_cancelled: Vec::new(),
+            triggered: Vec::new(),
+            mmp_triggered: Vec::new(),
+            auction_started: None,
+            uncrossed: None,
+            received_at: None,
         }
     }
 
@@ -59,6 +118,79 @@ impl MatchResult {
     pub fn filled_quantity(&self) -> u32 {
         self.trades.iter().map(|t| t.quantity).sum()
     }
+
+    /// Trades of this order followed by those of every stop it released
+    ///
+    /// Trades of an auction uncrossed before the order come first.
+    pub fn all_trades(&self) -> Vec<&Trade> {
+        let mut trades: Vec<&Trade> = self
+            .uncrossed
+            .iter()
+            .flat_map(|uncross| uncross.all_trades())
+            .collect();
+        trades.extend(self.trades.iter());
+        for stop in &self.triggered {
+            trades.extend(stop.result.all_trades());
+        }
+        trades
+    }
+
+    /// MMP trips of this order followed by those of every stop it released
+    pub fn all_mmp_trips(&self) -> Vec<&MmpTrip> {
+        let mut trips: Vec<&MmpTrip> = self.mmp_triggered.iter().collect();
+        for stop in &self.triggered {
+            trips.extend(stop.result.all_mmp_trips());
+        }
+        trips
+    }
+}
+
+/// A stop order released from the trigger book
+#[derive(Debug, Clone)]
+pub struct TriggeredStop {
+    /// The released order (stop cleared) as submitted to matching
+    pub order: BookOrder,
+    /// Reference price that fired the stop
+    pub trigger_price: Ticks,
+    /// Sequence assigned to the trigger itself
+    pub sequence: u64,
+    /// Outcome of matching the released order
+    pub result: MatchResult,
+}
+
+/// Result of uncrossing a call auction
+#[derive(Debug, Clone)]
+pub struct UncrossResult {
+    /// Instrument that left the auction
+    pub instrument_id: String,
+    /// The auction that ended
+    pub auction: CallAuction,
+    /// Equilibrium price (None if the book did not cross)
+    pub price: Option<Ticks>,
+    /// Trades executed at the equilibrium price
+    pub trades: Vec<Trade>,
+    /// Sequence assigned to the uncross
+    pub sequence: u64,
+    /// Time of the uncross (timestamp of its trades)
+    pub timestamp: DateTime<Utc>,
+    /// Stop orders released by the uncross trades, in release order
+    pub triggered: Vec<TriggeredStop>,
+}
+
+impl UncrossResult {
+    /// Total quantity traded in the uncross
+    pub fn volume(&self) -> u32 {
+        self.trades.iter().map(|t| t.quantity).sum()
+    }
+
+    /// Trades of the uncross followed by those of every stop it released
+    pub fn all_trades(&self) -> Vec<&Trade> {
+        let mut trades: Vec<&Trade> = self.trades.iter().collect();
+        for stop in &self.triggered {
+            trades.extend(stop.result.all_trades());
+        }
+        trades
+    }
 }
 
 /// Result of a cancel operation
@@ -87,3 +219,134 @@ impl CancelResult {
         }
     }
 }
+
+/// Result of a mass cancel
+#[derive(Debug, Clone)]
+pub struct MassCancelResult {
+    /// Orders removed (resting and held stops), in sequence order
+    pub cancelled: Vec<BookOrder>,
+    /// Sequence assigned to the mass cancel (None if nothing matched)
+    pub sequence: Option<u64>,
+}
+
+impl MassCancelResult {
+    /// IDs of the cancelled orders
+    pub fn order_ids(&self) -> Vec<Uuid> {
+        self.cancelled.iter().map(|o| o.order_id).collect()
+    }
+}
+
+/// Market maker protection tripped for a maker in an underlying
+#[derive(Debug, Clone)]
+pub struct MmpTrip {
+    /// Maker whose quotes were cancelled
+    pub user_id: Uuid,
+    /// Underlying the protection tripped in
+    pub underlying: String,
+    /// Limit that tripped
+    pub breach: MmpBreach,
+    /// Exposure in the window when it tripped
+    pub exposure: MmpExposure,
+    /// Quotes cancelled, in sequence order
+    pub cancelled: Vec<BookOrder>,
+    /// Sequence assigned to the trip
+    pub sequence: u64,
+}
+
+impl MmpTrip {
+    /// IDs of the cancelled quotes
+    pub fn order_ids(&self) -> Vec<Uuid> {
+        self.cancelled.iter().map(|o| o.order_id).collect()
+    }
+}
+
+/// Result of a mass quote
+#[derive(Debug, Clone)]
+pub struct MassQuoteResult {
+    /// The maker's previous quotes on the quoted instruments, removed first
+    pub replaced: MassCancelResult,
+    /// Each new quote with the outcome of matching it, in request order
+    pub quotes: Vec<(BookOrder, MatchResult)>,
+}
+
+impl MassQuoteResult {
+    /// IDs of the new quotes resting in the book
+    pub fn resting_order_ids(&self) -> Vec<Uuid> {
+        self.quotes
+            .iter()
+            .filter(|(_, result)| result.should_insert)
+            .map(|(order, _)| order.order_id)
+            .collect()
+    }
+
+    /// Trades of every quote (e.g. of an auction uncrossed on its arrival)
+    pub fn all_trades(&self) -> Vec<&Trade> {
+        self.quotes.iter().flat_map(|(_, result)| result.all_trades()).collect()
+    }
+
+    /// IDs of the new quotes rejected (e.g. a post-only quote that would cross)
+    pub fn rejected_order_ids(&self) -> Vec<Uuid> {
+        self.quotes
+            .iter()
+            .filter(|(_, result)| !result.should_insert)
+            .map(|(order, _)| order.order_id)
+            .collect()
+    }
+}
+
+/// An order removed from the book because its time-in-force expired
+#[derive(Debug, Clone)]
+pub struct ExpiredOrder {
+    /// The order as it was removed
+    pub order: BookOrder,
+    /// Sequence assigned to the expiry
+    pub sequence: u64,
+}
+
+/// Result of an amend operation
+#[derive(Debug, Clone)]
+pub struct AmendResult {
+    /// The order before the amend
+    pub previous: BookOrder,
+    /// The order as it rests after the amend (None if fully filled on re-entry)
+    pub resting: Option<BookOrder>,
+    /// Whether the order kept its place in the queue
+    pub priority_retained: bool,
+    /// Trades generated when a re-priced order crossed the book
+    pub trades: Vec<Trade>,
+    /// Orders cancelled by self-trade prevention while re-matching
+    pub stp_cancelled: Vec<Uuid>,
+    /// Sequence assigned to the amend
+    pub sequence: u64,
+    /// Time the amend was received (timestamp of its trades)
+    pub timestamp: DateTime<Utc>,
+    /// Stop orders released by the amend's trades, in release order
+    pub triggered: Vec<TriggeredStop>,
+    /// Market maker protection tripped by the amend's trades
+    pub mmp_triggered: Vec<MmpTrip>,
+}
+
+impl AmendResult {
+    /// Check if any trades were generated
+    pub fn has_trades(&self) -> bool {
+        !self.trades.is_empty()
+    }
+
+    /// Trades of the amend followed by those of every stop it released
+    pub fn all_trades(&self) -> Vec<&Trade> {
+        let mut trades: Vec<&Trade> = self.trades.iter().collect();
+        for stop in &self.triggered {
+            trades.extend(stop.result.all_trades());
+        }
+        trades
+    }
+
+    /// MMP trips of the amend followed by those of every stop it released
+    pub fn all_mmp_trips(&self) -> Vec<&MmpTrip> {
+        let mut trips: Vec<&MmpTrip> = self.mmp_triggered.iter().collect();
+        for stop in &self.triggered {
+            trips.extend(stop.result.all_mmp_trips());
+        }
+        trips
+    }
+}
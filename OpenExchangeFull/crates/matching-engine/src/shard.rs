@@ -0,0 +1,749 @@
+//! Sharded matching runtime
+//!
+//! Instruments are hashed onto a fixed number of shards by underlying. Each
+//! shard owns its own `MatchingEngine` (and optionally its own journal) on a
+//! dedicated task fed by a bounded queue, so books on different shards never
+//! contend for a lock. Everything that spans instruments of one underlying
+//! stays on one shard: a combo and its legs, and a maker's quotes under
+//! market maker protection.
+//!
+//! # Determinism
+//!
+//! A shard applies its jobs strictly in queue order and sequences them with
+//! its engine's own counter, so each shard's journal replays to the same
+//! state on its own. An instrument always hashes to the same shard for a
+//! given shard count; changing the shard count requires a fresh journal.
+//!
+//! # Batching
+//!
+//! A shard takes the next job together with whatever is already queued
+//! behind it, up to `batch_size` jobs, without waiting for more. It applies
+//! them in order and appends all their events to the journal in one write,
+//! so a lone job is applied at once and a busy shard amortises its writes. Callers get their results once the
+//! batch is journaled. If the write fails the batch's changes are dropped
+//! by rebuilding the engine from the journal; a shard that cannot do that
+//! stops.
+//!
+//! # Snapshots
+//!
+//! [`ShardedEngine::snapshot`] has every journaled shard write an
+//! [`EngineSnapshot`](crate::snapshot::EngineSnapshot) next to its journal
+//! between batches; on startup a shard restores it and replays only the
+//! journal after it (see [`WriteAheadLog::recover`]).
+//!
+//! # Subscriptions
+//!
+//! Each shard publishes its journaled events on its own [`EventFeed`]. A
+//! subscription is served by the shard task between batches, so its journal
+//! backlog and live events join without a gap. Shards without a journal
+//! have no backlog and only stream live events.
+
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::domain::underlying_of;
+use crate::engine::MatchingEngine;
+use crate::event::MatchingEvent;
+use crate::feed::{EventFeed, EventSubscription, DEFAULT_FEED_CAPACITY};
+use crate::wal::WriteAheadLog;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Sharded runtime configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardConfig {
+    /// Number of shards underlyings are hashed onto
+    pub shards: usize,
+    /// Most jobs applied (and journaled) together
+    pub batch_size: usize,
+    /// Capacity of each shard's job queue
+    pub queue_capacity: usize,
+}
+
+impl Default for ShardConfig {
+    fn default() -> Self {
+        Self {
+            shards: 1,
+            batch_size: 100,
+            queue_capacity: 1600,
+        }
+    }
+}
+
+impl From<&config::PerformanceConfig> for ShardConfig {
+    fn from(config: &config::PerformanceConfig) -> Self {
+        let batch_size = config.batch_size.max(1) as usize;
+        Self {
+            shards: config.shards.max(1) as usize,
+            batch_size,
+            // Room for a few batches so producers rarely wait on a busy shard
+            queue_capacity: batch_size * 16,
+        }
+    }
+}
+
+/// Shard an instrument belongs to
+///
+/// FNV-1a over the instrument's underlying: stable across processes and
+/// Rust versions, unlike `std`'s hasher, so journals stay routable.
+pub fn shard_for(instrument_id: &str, shards: usize) -> usize {
+    let hash = underlying_of(instrument_id)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    (hash % shards.max(1) as u64) as usize
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Errors returned by the sharded runtime
+#[derive(Debug, Clone, Error)]
+pub enum ShardError {
+    #[error("Matching shard {0} has stopped")]
+    Stopped(usize),
+
+    #[error("Journal write failed: {0}")]
+    Journal(String),
+}
+
+/// Result type for sharded runtime operations
+pub type ShardResult<T> = std::result::Result<T, ShardError>;
+
+// ============================================================================
+// Runtime
+// ============================================================================
+
+/// Delivers a job's result once its batch has been journaled
+type Reply = Box<dyn FnOnce(ShardResult<()>) + Send>;
+
+/// Work applied to a shard's engine, returning the events to journal
+type Job = Box<dyn FnOnce(&mut MatchingEngine) -> (Vec<MatchingEvent>, Reply) + Send>;
+
+/// Message on a shard's queue
+enum Command {
+    /// Apply a job in the next batch
+    Apply(Job),
+    /// Subscribe to the shard's events once everything queued before is published
+    Subscribe {
+        from_sequence: u64,
+        reply: oneshot::Sender<ShardResult<EventSubscription>>,
+    },
+    /// Snapshot the engine next to its journal once everything queued
+    /// before is journaled
+    Snapshot { reply: oneshot::Sender<ShardResult<()>> },
+}
+
+/// One shard: an engine, its journal and its event feed
+pub struct Shard {
+    engine: MatchingEngine,
+    journal: Option<WriteAheadLog>,
+    feed: EventFeed,
+}
+
+impl Shard {
+    /// Create a shard around an engine (already replayed, if journaled)
+    pub fn new(engine: MatchingEngine) -> Self {
+        Self {
+            engine,
+            journal: None,
+            feed: EventFeed::default(),
+        }
+    }
+
+    /// Append every event the shard produces to a journal
+    pub fn with_journal(mut self, journal: WriteAheadLog) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Subscribe from `from_sequence`, replaying the journal up to now
+    fn subscribe(&self, from_sequence: u64) -> ShardResult<EventSubscription> {
+        let backlog = match &self.journal {
+            Some(journal) => journal
+                .read_from(from_sequence)
+                .map_err(|e| ShardError::Journal(e.to_string()))?,
+            None => Vec::new(),
+        };
+        Ok(self.feed.subscribe(from_sequence, backlog))
+    }
+
+    /// Write a snapshot of the engine next to its journal
+    ///
+    /// A shard without a journal has nothing to snapshot.
+    fn snapshot(&mut self) -> ShardResult<()> {
+        match self.journal.as_mut() {
+            Some(journal) => journal
+                .write_snapshot(&self.engine.snapshot())
+                .map_err(|e| ShardError::Journal(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Apply a batch of jobs, journal and publish their events, then reply
+    ///
+    /// Returns false if the journal write failed and the engine could not
+    /// be rebuilt from the journal, so the shard must stop.
+    fn apply(&mut self, index: usize, batch: &mut Vec<Job>) -> bool {
+        if batch.is_empty() {
+            return true;
+        }
+
+        let mut events = Vec::new();
+        let mut replies = Vec::with_capacity(batch.len());
+        for job in batch.drain(..) {
+            let (job_events, reply) = job(&mut self.engine);
+            events.extend(job_events);
+            replies.push(reply);
+        }
+
+        let journaled = match self.journal.as_mut() {
+            Some(journal) if !events.is_empty() => journal
+                .append_batch(&events)
+                .map_err(|e| ShardError::Journal(e.to_string())),
+            _ => Ok(()),
+        };
+        let healthy = match &journaled {
+            Ok(()) => {
+                self.feed.publish_all(&events);
+                true
+            }
+            Err(e) => {
+                error!(shard = index, error = %e, "Shard journal write failed; rebuilding the engine from the journal");
+                match self.rebuild() {
+                    Ok(()) => true,
+                    Err(e) => {
+                        error!(shard = index, error = %e, "Shard rebuild failed; stopping the shard");
+                        false
+                    }
+                }
+            }
+        };
+
+        debug!(shard = index, jobs = replies.len(), events = events.len(), "Shard batch applied");
+        for reply in replies {
+            reply(journaled.clone());
+        }
+        healthy
+    }
+
+    /// Reopen the journal and replay it into the engine, dropping changes
+    /// the journal does not hold
+    fn rebuild(&mut self) -> ShardResult<()> {
+        let journal = self.journal.as_ref().expect("only a journaled shard fails a write");
+        let reopened = journal.reopen().map_err(|e| ShardError::Journal(e.to_string()))?;
+        reopened
+            .recover(&mut self.engine)
+            .map_err(|e| ShardError::Journal(e.to_string()))?;
+        self.journal = Some(reopened);
+        Ok(())
+    }
+}
+
+/// Handle to the shard tasks
+///
+/// Cheap operations are routed to the shard owning the instrument; ones
+/// that only know an order ID, or span instruments, are broadcast.
+pub struct ShardedEngine {
+    queues: Vec<mpsc::Sender<Command>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ShardedEngine {
+    /// Spawn one task per shard
+    ///
+    /// `shards.len()` is the shard count used for routing.
+    pub fn spawn(shards: Vec<Shard>, config: &ShardConfig) -> Self {
+        let mut queues = Vec::with_capacity(shards.len());
+        let mut tasks = Vec::with_capacity(shards.len());
+        for (index, mut shard) in shards.into_iter().enumerate() {
+            shard.feed = EventFeed::named(DEFAULT_FEED_CAPACITY, format!("shard-{}", index));
+            let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+            queues.push(tx);
+            tasks.push(tokio::spawn(run_shard(
+                index,
+                shard,
+                rx,
+                config.batch_size.max(1),
+            )));
+        }
+
+        info!(shards = queues.len(), batch_size = config.batch_size, "Sharded matching runtime started");
+
+        Self { queues, tasks }
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Shard owning an instrument
+    pub fn shard_of(&self, instrument_id: &str) -> usize {
+        shard_for(instrument_id, self.queues.len())
+    }
+
+    /// Run `f` on the shard owning `instrument_id`
+    pub async fn on_instrument<R, F>(&self, instrument_id: &str, f: F) -> ShardResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut MatchingEngine) -> (R, Vec<MatchingEvent>) + Send + 'static,
+    {
+        let shard = self.shard_of(instrument_id);
+        let rx = self.enqueue(shard, f).await?;
+        rx.await.map_err(|_| ShardError::Stopped(shard))?
+    }
+
+    /// Run `f` on every shard, returning the results in shard order
+    ///
+    /// The job is queued on all shards before any result is awaited, so
+    /// the shards work on it in parallel.
+    pub async fn broadcast<R, F>(&self, f: F) -> ShardResult<Vec<R>>
+    where
+        R: Send + 'static,
+        F: Fn(&mut MatchingEngine) -> (R, Vec<MatchingEvent>) + Clone + Send + 'static,
+    {
+        let mut pending = Vec::with_capacity(self.queues.len());
+        for shard in 0..self.queues.len() {
+            pending.push((shard, self.enqueue(shard, f.clone()).await?));
+        }
+
+        let mut results = Vec::with_capacity(pending.len());
+        for (shard, rx) in pending {
+            results.push(rx.await.map_err(|_| ShardError::Stopped(shard))??);
+        }
+        Ok(results)
+    }
+
+    /// Stream a shard's events from `from_sequence`
+    ///
+    /// Sequences are per shard, so a consumer of every instrument holds one
+    /// subscription per shard.
+    pub async fn subscribe(&self, shard: usize, from_sequence: u64) -> ShardResult<EventSubscription> {
+        let queue = self.queues.get(shard).ok_or(ShardError::Stopped(shard))?;
+        let (tx, rx) = oneshot::channel();
+        queue
+            .send(Command::Subscribe {
+                from_sequence,
+                reply: tx,
+            })
+            .await
+            .map_err(|_| ShardError::Stopped(shard))?;
+        rx.await.map_err(|_| ShardError::Stopped(shard))?
+    }
+
+    /// Snapshot every shard's engine next to its journal
+    ///
+    /// Each shard snapshots between batches, so a restart replays only
+    /// the events journaled after it.
+    pub async fn snapshot(&self) -> ShardResult<()> {
+        let mut pending = Vec::with_capacity(self.queues.len());
+        for (shard, queue) in self.queues.iter().enumerate() {
+            let (tx, rx) = oneshot::channel();
+            queue
+                .send(Command::Snapshot { reply: tx })
+                .await
+                .map_err(|_| ShardError::Stopped(shard))?;
+            pending.push((shard, rx));
+        }
+        for (shard, rx) in pending {
+            rx.await.map_err(|_| ShardError::Stopped(shard))??;
+        }
+        Ok(())
+    }
+
+    /// Stop accepting work and wait for every queued job to finish
+    pub async fn shutdown(self) {
+        drop(self.queues);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+    }
+
+    /// Queue a job on a shard, returning the receiver for its result
+    async fn enqueue<R, F>(&self, shard: usize, f: F) -> ShardResult<oneshot::Receiver<ShardResult<R>>>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut MatchingEngine) -> (R, Vec<MatchingEvent>) + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job: Job = Box::new(move |engine| {
+            let (result, events) = f(engine);
+            let reply: Reply = Box::new(move |journaled| {
+                let _ = tx.send(journaled.map(|()| result));
+            });
+            (events, reply)
+        });
+
+        self.queues[shard]
+            .send(Command::Apply(job))
+            .await
+            .map_err(|_| ShardError::Stopped(shard))?;
+        Ok(rx)
+    }
+}
+
+/// Shard task: apply jobs in batches until every sender is dropped, or
+/// until a failed journal write leaves the engine ahead of its journal
+async fn run_shard(
+    index: usize,
+    mut shard: Shard,
+    mut commands: mpsc::Receiver<Command>,
+    batch_size: usize,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    'shard: while let Some(command) = commands.recv().await {
+        let mut next = Some(command);
+        while let Some(command) = next.take() {
+            match command {
+                Command::Apply(job) => batch.push(job),
+                Command::Subscribe { from_sequence, reply } => {
+                    // Publish everything queued ahead of the subscriber first
+                    if !shard.apply(index, &mut batch) {
+                        break 'shard;
+                    }
+                    let _ = reply.send(shard.subscribe(from_sequence));
+                }
+                Command::Snapshot { reply } => {
+                    // Cover everything queued ahead of the snapshot
+                    if !shard.apply(index, &mut batch) {
+                        break 'shard;
+                    }
+                    let _ = reply.send(shard.snapshot());
+                }
+            }
+            // Batch only what is already queued
+            if batch.len() < batch_size {
+                next = commands.try_recv().ok();
+            }
+        }
+
+        if !shard.apply(index, &mut batch) {
+            break;
+        }
+    }
+
+    info!(shard = index, "Matching shard stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockTrade;
+    use crate::combo::ComboDefinition;
+    use crate::domain::{BookOrder, OrderSide, TimeInForce};
+    use crate::error::MatchingError;
+    use crate::mmp::MmpLimits;
+    use crate::wal::WalConfig;
+    use uuid::Uuid;
+
+    fn order(instrument_id: &str, side: OrderSide, price: i64) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), side, price, 1, 0, TimeInForce::Gtc)
+            .with_instrument_id(instrument_id)
+    }
+
+    fn config(shards: usize) -> ShardConfig {
+        ShardConfig {
+            shards,
+            batch_size: 8,
+            queue_capacity: 64,
+        }
+    }
+
+    fn submit(
+        order: BookOrder,
+    ) -> impl FnOnce(&mut MatchingEngine) -> (u64, Vec<MatchingEvent>) + Send + 'static {
+        move |engine| {
+            let result = engine.match_order(order.clone());
+            (engine.sequence(), MatchingEvent::from_match(&order, &result))
+        }
+    }
+
+    #[test]
+    fn test_shard_for_is_stable() {
+        assert_eq!(shard_for("BTC-50000-C", 1), 0);
+        assert_eq!(shard_for("BTC-50000-C", 8), shard_for("BTC-50000-C", 8));
+        // Every instrument of an underlying shares its shard
+        assert!((0..64).all(|strike| shard_for(&format!("BTC-{}-C", 40000 + strike * 500), 4) == shard_for("BTC", 4)));
+        let used: std::collections::HashSet<usize> = (0..64)
+            .map(|underlying| shard_for(&format!("U{}-50000-C", underlying), 4))
+            .collect();
+        assert_eq!(used.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_shards_sequence_independently_and_broadcast() {
+        let instruments = ["BTC-50000-C", "ETH-3000-C", "SOL-150-C", "XRP-1-C"];
+        let runtime = ShardedEngine::spawn(
+            (0..2).map(|_| Shard::new(MatchingEngine::new())).collect(),
+            &config(2),
+        );
+        let (a, b) = instruments
+            .iter()
+            .flat_map(|x| instruments.iter().map(move |y| (*x, *y)))
+            .find(|(x, y)| runtime.shard_of(x) != runtime.shard_of(y))
+            .expect("instruments spread over both shards");
+
+        // Each shard counts its own sequence
+        assert_eq!(runtime.on_instrument(a, submit(order(a, OrderSide::Buy, 100))).await.unwrap(), 1);
+        assert_eq!(runtime.on_instrument(b, submit(order(b, OrderSide::Buy, 100))).await.unwrap(), 1);
+        let resting = order(a, OrderSide::Sell, 110);
+        let resting_id = resting.order_id;
+        assert_eq!(runtime.on_instrument(a, submit(resting)).await.unwrap(), 2);
+
+        // Only the owning shard finds the order
+        let cancelled = runtime
+            .broadcast(move |engine| (engine.cancel_by_id(resting_id).is_some(), Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(cancelled.iter().filter(|c| **c).count(), 1);
+        assert!(cancelled[runtime.shard_of(a)]);
+
+        runtime.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_combo_and_legs_match_on_one_shard() {
+        let runtime = ShardedEngine::spawn(
+            (0..4).map(|_| Shard::new(MatchingEngine::new())).collect(),
+            &config(4),
+        );
+        let combo = ComboDefinition::straddle("BTC-STRADDLE", "BTC-20260315-50000-C", "BTC-20260315-50000-P");
+        runtime
+            .on_instrument("BTC-STRADDLE", move |engine| (engine.define_combo(combo).unwrap(), Vec::new()))
+            .await
+            .unwrap();
+        for (leg, price) in [("BTC-20260315-50000-C", 500), ("BTC-20260315-50000-P", 200)] {
+            runtime.on_instrument(leg, submit(order(leg, OrderSide::Sell, price))).await.unwrap();
+        }
+
+        // The combo sees the legs' asks (implied-in) on its own shard
+        let trades = runtime
+            .on_instrument("BTC-STRADDLE", |engine| {
+                let result = engine.match_order(order("BTC-STRADDLE", OrderSide::Buy, 700));
+                (result.trades.len(), Vec::new())
+            })
+            .await
+            .unwrap();
+        assert_eq!(trades, 2);
+
+        runtime.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_combo_block_trade_checks_legs_on_its_shard() {
+        let runtime = ShardedEngine::spawn(
+            (0..4).map(|_| Shard::new(MatchingEngine::new())).collect(),
+            &config(4),
+        );
+        let (call, put) = ("BTC-20260315-50000-C", "BTC-20260315-50000-P");
+        let combo = ComboDefinition::straddle("BTC-STRADDLE", call, put);
+        runtime
+            .on_instrument("BTC-STRADDLE", move |engine| (engine.define_combo(combo).unwrap(), Vec::new()))
+            .await
+            .unwrap();
+        runtime
+            .on_instrument(put, move |engine| (engine.halt_instrument(put), Vec::new()))
+            .await
+            .unwrap();
+
+        let block = BlockTrade {
+            rfq_id: Uuid::new_v4(),
+            instrument_id: "BTC-STRADDLE".to_string(),
+            price: 700,
+            quantity: 1,
+            taker_order_id: Uuid::new_v4(),
+            taker_user_id: Uuid::new_v4(),
+            taker_side: OrderSide::Buy,
+            maker_order_id: Uuid::new_v4(),
+            maker_user_id: Uuid::new_v4(),
+        };
+        let book = |block: BlockTrade| move |engine: &mut MatchingEngine| (engine.book_block_trade(&block), Vec::new());
+
+        // The halt placed through the put's shard stops the combo's block trade
+        let halted = runtime.on_instrument("BTC-STRADDLE", book(block.clone())).await.unwrap();
+        assert!(matches!(halted, Err(MatchingError::CircuitBreaker(_))));
+
+        runtime
+            .on_instrument(put, move |engine| (engine.resume_instrument(put), Vec::new()))
+            .await
+            .unwrap();
+        let trades = runtime.on_instrument("BTC-STRADDLE", book(block)).await.unwrap().unwrap();
+        let legs: Vec<&str> = trades.iter().map(|trade| trade.instrument_id.as_str()).collect();
+        assert_eq!(legs, vec![call, put]);
+
+        runtime.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_mmp_covers_every_strike_of_an_underlying() {
+        let runtime = ShardedEngine::spawn(
+            (0..4)
+                .map(|_| {
+                    let mut engine = MatchingEngine::new();
+                    engine.set_mmp_limits("BTC", MmpLimits::new(60_000).with_quantity(8));
+                    Shard::new(engine)
+                })
+                .collect(),
+            &config(4),
+        );
+        let maker = Uuid::new_v4();
+        let strikes = ["BTC-20260315-50000-C", "BTC-20260320-60000-P"];
+        let quotes: Vec<BookOrder> = strikes
+            .iter()
+            .map(|id| {
+                BookOrder::new(Uuid::new_v4(), maker, OrderSide::Sell, 110, 10, 0, TimeInForce::Gtc).with_instrument_id(*id)
+            })
+            .collect();
+        runtime
+            .on_instrument("BTC", move |engine| (engine.mass_quote(maker, quotes).unwrap(), Vec::new()))
+            .await
+            .unwrap();
+
+        // Fills on both strikes add up to one exposure: the second trips it
+        let mut trips = Vec::new();
+        for id in strikes {
+            let taker = BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), OrderSide::Buy, 110, 5, 0, TimeInForce::Ioc)
+                .with_instrument_id(id);
+            let result = runtime
+                .on_instrument(id, move |engine| (engine.match_order(taker), Vec::new()))
+                .await
+                .unwrap();
+            trips.extend(result.mmp_triggered);
+        }
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].cancelled.len(), 2);
+        let resting = runtime
+            .on_instrument("BTC", |engine| (engine.resting_order_count(), Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(resting, 0);
+
+        runtime.shutdown().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_lone_job_is_applied_without_waiting_for_a_batch() {
+        let runtime = ShardedEngine::spawn(vec![Shard::new(MatchingEngine::new())], &config(1));
+
+        // The paused clock only moves if the shard waits on a timer
+        let started = tokio::time::Instant::now();
+        runtime
+            .on_instrument("BTC-50000-C", submit(order("BTC-50000-C", OrderSide::Buy, 100)))
+            .await
+            .unwrap();
+        assert_eq!(started.elapsed(), std::time::Duration::ZERO);
+
+        runtime.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shard_journal_replays() {
+        let dir = std::env::temp_dir().join(format!("openx-shard-{}", Uuid::new_v4()));
+        let journal = WriteAheadLog::open(WalConfig::new(&dir)).unwrap();
+        let runtime = ShardedEngine::spawn(vec![Shard::new(MatchingEngine::new()).with_journal(journal)], &config(1));
+
+        let mut pending = Vec::new();
+        for price in [100, 101, 102] {
+            let order = order("BTC-50000-C", OrderSide::Buy, price);
+            pending.push(runtime.on_instrument("BTC-50000-C", submit(order)));
+        }
+        for result in pending {
+            result.await.unwrap();
+        }
+        runtime.shutdown().await;
+
+        let events = WriteAheadLog::open(WalConfig::new(&dir)).unwrap().read_from(0).unwrap();
+        let mut replayed = MatchingEngine::new();
+        replayed.replay(&events);
+        assert_eq!(replayed.sequence(), 3);
+        assert_eq!(replayed.resting_order_count(), 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_shard_snapshot_covers_the_queued_jobs() {
+        let dir = std::env::temp_dir().join(format!("openx-shard-{}", Uuid::new_v4()));
+        let journal = WriteAheadLog::open(WalConfig::new(&dir)).unwrap();
+        let runtime = ShardedEngine::spawn(vec![Shard::new(MatchingEngine::new()).with_journal(journal)], &config(1));
+
+        let first = runtime.on_instrument("BTC-50000-C", submit(order("BTC-50000-C", OrderSide::Buy, 100)));
+        let second = runtime.on_instrument("BTC-50000-C", submit(order("BTC-50000-C", OrderSide::Buy, 101)));
+        let (first, second, snapshot) = tokio::join!(first, second, runtime.snapshot());
+        first.unwrap();
+        second.unwrap();
+        snapshot.unwrap();
+        runtime
+            .on_instrument("BTC-50000-C", submit(order("BTC-50000-C", OrderSide::Buy, 102)))
+            .await
+            .unwrap();
+        runtime.shutdown().await;
+
+        let journal = WriteAheadLog::open(WalConfig::new(&dir)).unwrap();
+        assert_eq!(journal.read_snapshot().unwrap().unwrap().sequence, 2);
+        let mut recovered = MatchingEngine::new();
+        let tail = journal.recover(&mut recovered).unwrap();
+        assert_eq!(tail.len(), 3);
+        assert_eq!(recovered.sequence(), 3);
+        assert_eq!(recovered.resting_order_count(), 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_shard_stops_when_its_journal_fails() {
+        let dir = std::env::temp_dir().join(format!("openx-shard-{}", Uuid::new_v4()));
+        let mut wal_config = WalConfig::new(&dir);
+        // Every append after the first starts a new segment
+        wal_config.segment_size_bytes = 1;
+        let journal = WriteAheadLog::open(wal_config).unwrap();
+        let shard = Shard::new(MatchingEngine::new()).with_journal(journal);
+        let runtime = ShardedEngine::spawn(vec![shard], &config(1));
+        let bid = order("BTC-50000-C", OrderSide::Buy, 100);
+        runtime.on_instrument("BTC-50000-C", submit(bid)).await.unwrap();
+
+        // A file in place of the directory fails the write and the rebuild
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::write(&dir, b"").unwrap();
+        let sell = order("BTC-50000-C", OrderSide::Sell, 100);
+        let failed = runtime.on_instrument("BTC-50000-C", submit(sell)).await;
+        assert!(matches!(failed, Err(ShardError::Journal(_))));
+
+        // The unjournaled trade is never built on
+        let next = order("BTC-50000-C", OrderSide::Buy, 101);
+        let stopped = runtime.on_instrument("BTC-50000-C", submit(next)).await;
+        assert!(matches!(stopped, Err(ShardError::Stopped(0))));
+
+        runtime.shutdown().await;
+        let _ = std::fs::remove_file(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_replays_journal_then_streams() {
+        let dir = std::env::temp_dir().join(format!("openx-shard-{}", Uuid::new_v4()));
+        let journal = WriteAheadLog::open(WalConfig::new(&dir)).unwrap();
+        let runtime = ShardedEngine::spawn(vec![Shard::new(MatchingEngine::new()).with_journal(journal)], &config(1));
+
+        for price in [100, 101] {
+            let order = order("BTC-50000-C", OrderSide::Buy, price);
+            runtime.on_instrument("BTC-50000-C", submit(order)).await.unwrap();
+        }
+        let mut subscription = runtime.subscribe(0, 2).await.unwrap();
+        let order = order("BTC-50000-C", OrderSide::Buy, 102);
+        runtime.on_instrument("BTC-50000-C", submit(order)).await.unwrap();
+
+        assert_eq!(subscription.recv().await.unwrap().sequence(), 2);
+        assert_eq!(subscription.recv().await.unwrap().sequence(), 3);
+        assert!(matches!(runtime.subscribe(1, 0).await, Err(ShardError::Stopped(1))));
+
+        runtime.shutdown().await;
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,83 @@
+//! Point-in-time snapshots of matching engine state
+//!
+//! A snapshot holds every resting order of every book in priority order
+//! (best price first, FIFO within a level), the held stop orders, last
+//! trade prices, running call auctions, combo definitions and makers frozen
+//! by market maker protection, together with the global sequence counter.
+//! Restoring loads the snapshot and replays the event-log tail, i.e. every
+//! event with a sequence above the snapshot's.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::auction::CallAuction;
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, OrderBook};
+use crate::mmp::MmpFreeze;
+use crate::price::Ticks;
+
+/// Current snapshot format version
+///
+/// Version 2 stores prices as integer ticks.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Serialized state of the whole matching engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+    /// Engine sequence at the time of the snapshot
+    pub sequence: u64,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+    /// All non-empty books, ordered by instrument ID
+    pub books: Vec<OrderBook>,
+    /// Stop orders waiting for their trigger, in sequence order
+    #[serde(default)]
+    pub stops: Vec<BookOrder>,
+    /// Last trade price (ticks) per instrument (reference for last-trade stops)
+    #[serde(default)]
+    pub last_prices: BTreeMap<String, Ticks>,
+    /// Instruments in a call auction (their books may be crossed)
+    #[serde(default)]
+    pub auctions: BTreeMap<String, CallAuction>,
+    /// Combo instruments, ordered by combo ID
+    #[serde(default)]
+    pub combos: Vec<ComboDefinition>,
+    /// Makers whose quoting market maker protection froze, by maker and underlying
+    #[serde(default)]
+    pub mmp_frozen: Vec<MmpFreeze>,
+}
+
+impl EngineSnapshot {
+    /// Create an empty snapshot (fresh engine)
+    pub fn empty() -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            sequence: 0,
+            taken_at: Utc::now(),
+            books: Vec::new(),
+            stops: Vec::new(),
+            last_prices: BTreeMap::new(),
+            auctions: BTreeMap::new(),
+            combos: Vec::new(),
+            mmp_frozen: Vec::new(),
+        }
+    }
+
+    /// Total number of resting orders
+    pub fn order_count(&self) -> usize {
+        self.books.iter().map(|b| b.order_count()).sum()
+    }
+
+    /// Encode the snapshot as JSON bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+
+    /// Decode a snapshot from JSON bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
@@ -0,0 +1,678 @@
+//! File-journal store implementation for the Matching Engine
+//!
+//! Books live in memory like [`InMemoryStore`](super::InMemoryStore), but every
+//! accepted order, trade and cancellation is appended to a durable
+//! [`WriteAheadLog`] before the call returns. On open the latest snapshot
+//! is restored and the journal after it replayed to rebuild the books and
+//! sequence; the trade history is rebuilt from the whole journal.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::auction::{AuctionReason, CallAuction, IndicativeUncross};
+use crate::block::BlockTrade;
+use crate::circuit_breaker::CircuitBreakerStatus;
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, MassCancelFilter, OrderBook, Trade, TriggerSource};
+use crate::engine::MatchingEngine;
+use crate::event::MatchingEvent;
+use crate::feed::{EventFeed, EventSubscription};
+use crate::price::{PriceScale, Ticks};
+use crate::mmp::MmpStatus;
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, TriggeredStop, UncrossResult,
+};
+use crate::store::traits::{MatchingStore, StoreResult};
+use crate::wal::{WalConfig, WriteAheadLog};
+
+/// Journal-backed store for order matching
+///
+/// The engine write lock is held across the journal append, so journal
+/// order always equals engine order. If an append fails the call returns
+/// an error and the order must be treated as not accepted: the engine has
+/// already applied it, so it is rebuilt from the journal before the next
+/// write is accepted.
+pub struct FileStore {
+    /// The matching engine
+    engine: RwLock<MatchingEngine>,
+    /// Recent trades per instrument
+    trades: RwLock<HashMap<String, Vec<Trade>>>,
+    /// Durable event journal
+    journal: Mutex<WriteAheadLog>,
+    /// Live subscribers to journaled events
+    feed: EventFeed,
+    /// Max trades to keep per instrument
+    max_trades_per_instrument: usize,
+    /// Whether the engine holds changes the journal does not
+    needs_rebuild: AtomicBool,
+}
+
+impl FileStore {
+    /// Open the journal and replay it into a fresh engine
+    pub fn open(config: WalConfig) -> StoreResult<Self> {
+        Self::open_with_engine(config, MatchingEngine::new())
+    }
+
+    /// Open the journal and replay it into an engine that is already
+    /// configured
+    pub fn open_with_engine(config: WalConfig, mut engine: MatchingEngine) -> StoreResult<Self> {
+        let journal = WriteAheadLog::open(config)?;
+        let events = journal.recover(&mut engine)?;
+
+        let store = Self {
+            engine: RwLock::new(engine),
+            trades: RwLock::new(HashMap::new()),
+            journal: Mutex::new(journal),
+            feed: EventFeed::default(),
+            max_trades_per_instrument: 1000,
+            needs_rebuild: AtomicBool::new(false),
+        };
+
+        {
+            let mut trades = store.trades.try_write().expect("store not shared yet");
+            for event in &events {
+                if let MatchingEvent::TradeExecuted { trade, .. } = event {
+                    store.push_trade(&mut trades, trade.clone());
+                }
+            }
+        }
+
+        info!(events = events.len(), "File store recovered from journal");
+        Ok(store)
+    }
+
+    /// Rewrite the journal as the minimal event set for the current books
+    ///
+    /// Trade history is not carried over into the compacted journal. The
+    /// snapshot compaction invalidates is written again.
+    pub async fn compact(&self) -> StoreResult<()> {
+        let engine = self.engine.read().await;
+        let mut journal = self.journal.lock().await;
+        journal.compact(&engine.compacted_events())?;
+        journal.write_snapshot(&engine.snapshot())?;
+        Ok(())
+    }
+
+    /// Flush the journal to stable storage
+    pub async fn sync(&self) -> StoreResult<()> {
+        self.journal.lock().await.sync()?;
+        Ok(())
+    }
+
+    /// Get a read guard to the engine (for advanced operations)
+    pub async fn engine_read(&self) -> tokio::sync::RwLockReadGuard<'_, MatchingEngine> {
+        self.engine.read().await
+    }
+
+    /// Append a command's events to the journal, then publish them to
+    /// subscribers
+    ///
+    /// `engine` has already applied the command; if the append fails it is
+    /// rebuilt from the journal, or flagged for a rebuild before the next
+    /// write if the journal cannot be read.
+    async fn record(&self, engine: &mut MatchingEngine, events: &[MatchingEvent]) -> StoreResult<()> {
+        let mut journal = self.journal.lock().await;
+        if let Err(e) = journal.append_batch(events) {
+            error!(error = %e, "Journal append failed; rebuilding the engine from the journal");
+            if let Err(rebuild) = Self::rebuild(engine, &mut journal) {
+                error!(error = %rebuild, "Engine rebuild failed; refusing writes until it succeeds");
+                self.needs_rebuild.store(true, Ordering::SeqCst);
+            }
+            return Err(e.into());
+        }
+        self.feed.publish_all(events);
+        Ok(())
+    }
+
+    /// Rebuild the engine from the journal if a failed append left it ahead
+    ///
+    /// Must be called with the engine write lock held, before the command
+    /// changes the engine.
+    async fn ensure_writable(&self, engine: &mut MatchingEngine) -> StoreResult<()> {
+        if !self.needs_rebuild.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let mut journal = self.journal.lock().await;
+        Self::rebuild(engine, &mut journal)?;
+        self.needs_rebuild.store(false, Ordering::SeqCst);
+        info!(sequence = engine.sequence(), "Engine rebuilt from the journal");
+        Ok(())
+    }
+
+    /// Reopen the journal, dropping any torn record at its tail, and
+    /// recover the engine from it
+    fn rebuild(engine: &mut MatchingEngine, journal: &mut WriteAheadLog) -> StoreResult<()> {
+        let reopened = journal.reopen()?;
+        reopened.recover(engine)?;
+        *journal = reopened;
+        Ok(())
+    }
+
+    fn push_trade(&self, trades: &mut HashMap<String, Vec<Trade>>, trade: Trade) {
+        let instrument_trades = trades.entry(trade.instrument_id.clone()).or_default();
+        instrument_trades.push(trade);
+
+        // Trim to max size
+        if instrument_trades.len() > self.max_trades_per_instrument {
+            let excess = instrument_trades.len() - self.max_trades_per_instrument;
+            instrument_trades.drain(..excess);
+        }
+    }
+}
+
+#[async_trait]
+impl MatchingStore for FileStore {
+    async fn submit_order(&self, order: BookOrder) -> StoreResult<MatchResult> {
+        let instrument_id = order.instrument_id.clone();
+
+        let result = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let result = engine.match_order(order.clone());
+            let events = MatchingEvent::from_match(&order, &result);
+            self.record(&mut engine, &events).await?;
+            result
+        };
+
+        {
+            let mut trades = self.trades.write().await;
+            for trade in result.all_trades() {
+                self.push_trade(&mut trades, trade.clone());
+            }
+        }
+
+        debug!(
+            instrument_id = %instrument_id,
+            trades = result.trades.len(),
+            "Order matched"
+        );
+
+        Ok(result)
+    }
+
+    async fn cancel_order(
+        &self,
+        instrument_id: &str,
+        order_id: Uuid,
+    ) -> StoreResult<Option<BookOrder>> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let cancelled = engine.cancel_order(instrument_id, order_id);
+
+        if cancelled.is_some() {
+            let event = MatchingEvent::OrderCancelled {
+                order_id,
+                instrument_id: instrument_id.to_string(),
+                sequence: engine.sequence(),
+            };
+            self.record(&mut engine, &[event]).await?;
+
+            info!(order_id = %order_id, instrument_id = %instrument_id, "Order cancelled");
+        }
+
+        Ok(cancelled)
+    }
+
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> StoreResult<MassCancelResult> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let result = engine.mass_cancel(filter);
+
+        if let Some(event) = MatchingEvent::from_mass_cancel(&result) {
+            self.record(&mut engine, &[event]).await?;
+
+            info!(orders = result.cancelled.len(), "Orders mass cancelled");
+        }
+
+        Ok(result)
+    }
+
+    async fn expire_orders(&self, now: DateTime<Utc>) -> StoreResult<Vec<ExpiredOrder>> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let expired = engine.expire_orders(now);
+
+        if !expired.is_empty() {
+            self.record(&mut engine, &MatchingEvent::from_expired(&expired)).await?;
+
+            info!(orders = expired.len(), "Orders expired");
+        }
+
+        Ok(expired)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+    ) -> StoreResult<AmendResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let result = engine.amend_order(order_id, new_price, new_quantity)?;
+            let events = MatchingEvent::from_amend(order_id, new_price, new_quantity, &result);
+            self.record(&mut engine, &events).await?;
+            result
+        };
+
+        {
+            let mut trades = self.trades.write().await;
+            for trade in result.all_trades() {
+                self.push_trade(&mut trades, trade.clone());
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn update_reference_price(
+        &self,
+        instrument_id: &str,
+        source: TriggerSource,
+        price: Ticks,
+    ) -> StoreResult<Vec<TriggeredStop>> {
+        let triggered = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let triggered = engine.update_reference_price(instrument_id, source, price);
+            self.record(&mut engine, &MatchingEvent::from_triggers(&triggered)).await?;
+            triggered
+        };
+
+        let mut trades = self.trades.write().await;
+        for stop in &triggered {
+            for trade in stop.result.all_trades() {
+                self.push_trade(&mut trades, trade.clone());
+            }
+        }
+
+        Ok(triggered)
+    }
+
+    async fn start_auction(&self, instrument_id: &str, reason: AuctionReason) -> StoreResult<CallAuction> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let auction = engine.start_auction(instrument_id, reason)?;
+        self.record(&mut engine, &[MatchingEvent::auction_started(instrument_id, &auction)]).await?;
+        Ok(auction)
+    }
+
+    async fn uncross(&self, instrument_id: &str) -> StoreResult<UncrossResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let result = engine.uncross(instrument_id)?;
+            self.record(&mut engine, &MatchingEvent::from_uncross(&result)).await?;
+            result
+        };
+
+        let mut trades = self.trades.write().await;
+        for trade in result.all_trades() {
+            self.push_trade(&mut trades, trade.clone());
+        }
+
+        Ok(result)
+    }
+
+    async fn get_auction(&self, instrument_id: &str) -> StoreResult<Option<CallAuction>> {
+        let engine = self.engine.read().await;
+        Ok(engine.auction(instrument_id).copied())
+    }
+
+    async fn get_indicative_uncross(&self, instrument_id: &str) -> StoreResult<Option<IndicativeUncross>> {
+        let engine = self.engine.read().await;
+        Ok(engine.indicative_uncross(instrument_id))
+    }
+
+    async fn define_combo(&self, combo: ComboDefinition) -> StoreResult<ComboDefinition> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let combo_id = combo.combo_id.clone();
+        if engine.define_combo(combo)?.is_some() {
+            let defined = engine.combo(&combo_id).cloned().expect("combo was just defined");
+            self.record(&mut engine, &[MatchingEvent::combo_defined(&defined)]).await?;
+        }
+        Ok(engine.combo(&combo_id).cloned().expect("combo is defined"))
+    }
+
+    async fn get_combos(&self) -> StoreResult<Vec<ComboDefinition>> {
+        let engine = self.engine.read().await;
+        Ok(engine.combos())
+    }
+
+    async fn book_block_trade(&self, block: BlockTrade) -> StoreResult<Vec<Trade>> {
+        let trades = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let trades = engine.book_block_trade(&block)?;
+            self.record(&mut engine, &MatchingEvent::from_block_trade(&trades)).await?;
+            trades
+        };
+
+        let mut history = self.trades.write().await;
+        for trade in &trades {
+            self.push_trade(&mut history, trade.clone());
+        }
+        Ok(trades)
+    }
+
+    async fn mass_quote(&self, user_id: Uuid, quotes: Vec<BookOrder>) -> StoreResult<MassQuoteResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable(&mut engine).await?;
+            let result = engine.mass_quote(user_id, quotes)?;
+            self.record(&mut engine, &MatchingEvent::from_mass_quote(&result)).await?;
+            result
+        };
+
+        let mut history = self.trades.write().await;
+        for trade in result.all_trades() {
+            self.push_trade(&mut history, trade.clone());
+        }
+        Ok(result)
+    }
+
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> StoreResult<bool> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        let Some(sequence) = engine.reset_mmp(user_id, underlying) else {
+            return Ok(false);
+        };
+        self.record(&mut engine, &[MatchingEvent::MmpReset {
+            user_id,
+            underlying: underlying.to_string(),
+            sequence,
+        }])
+        .await?;
+        Ok(true)
+    }
+
+    async fn get_mmp_status(&self, user_id: Uuid) -> StoreResult<Vec<MmpStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.mmp_status(user_id))
+    }
+
+    async fn halt_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn resume_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn halt_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_underlying(underlying);
+        Ok(())
+    }
+
+    async fn resume_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_underlying(underlying);
+        Ok(())
+    }
+
+    async fn get_circuit_breaker_status(&self) -> StoreResult<Vec<CircuitBreakerStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.circuit_breaker_status())
+    }
+
+    async fn get_halted_underlyings(&self) -> StoreResult<Vec<String>> {
+        let engine = self.engine.read().await;
+        Ok(engine.halted_underlyings())
+    }
+
+    async fn get_book(&self, instrument_id: &str) -> StoreResult<Option<OrderBook>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_book(instrument_id).cloned())
+    }
+
+    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_book(instrument_id).and_then(|b| b.best_bid()))
+    }
+
+    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_book(instrument_id).and_then(|b| b.best_ask()))
+    }
+
+    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_book(instrument_id).and_then(|b| b.spread()))
+    }
+
+    async fn price_scale(&self, instrument_id: &str) -> StoreResult<PriceScale> {
+        let engine = self.engine.read().await;
+        Ok(engine.price_scale(instrument_id))
+    }
+
+    async fn get_order(&self, order_id: Uuid) -> StoreResult<Option<BookOrder>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_order(order_id).cloned())
+    }
+
+    async fn has_book(&self, instrument_id: &str) -> StoreResult<bool> {
+        let engine = self.engine.read().await;
+        Ok(engine.has_book(instrument_id))
+    }
+
+    async fn instruments(&self) -> StoreResult<Vec<String>> {
+        let engine = self.engine.read().await;
+        Ok(engine.instruments())
+    }
+
+    async fn get_trades(&self, instrument_id: &str, limit: u32) -> StoreResult<Vec<Trade>> {
+        let trades = self.trades.read().await;
+
+        match trades.get(instrument_id) {
+            Some(t) => {
+                let start = t.len().saturating_sub(limit as usize);
+                Ok(t[start..].to_vec())
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn append_event(&self, event: MatchingEvent) -> StoreResult<()> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        self.record(&mut engine, &[event]).await?;
+        Ok(())
+    }
+
+    async fn get_events(&self, from_sequence: u64) -> StoreResult<Vec<MatchingEvent>> {
+        Ok(self.journal.lock().await.read_from(from_sequence)?)
+    }
+
+    async fn get_sequence(&self) -> StoreResult<u64> {
+        Ok(self.journal.lock().await.sequence())
+    }
+
+    async fn subscribe(&self, from_sequence: u64) -> StoreResult<EventSubscription> {
+        let journal = self.journal.lock().await;
+        let backlog = journal.read_from(from_sequence)?;
+        Ok(self.feed.subscribe(from_sequence, backlog))
+    }
+
+    async fn snapshot(&self) -> StoreResult<()> {
+        // The engine write lock keeps commands out until the snapshot is written
+        let mut engine = self.engine.write().await;
+        self.ensure_writable(&mut engine).await?;
+        self.journal.lock().await.write_snapshot(&engine.snapshot())?;
+        Ok(())
+    }
+
+    fn engine(&self) -> MatchingEngine {
+        panic!("Use engine_read() for file store")
+    }
+
+    fn engine_ref(&self) -> &MatchingEngine {
+        panic!("Use get_book() and other query methods instead")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{OrderSide, TimeInForce};
+
+    fn order(side: OrderSide, price: Ticks, quantity: u32) -> BookOrder {
+        BookOrder::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            side,
+            price,
+            quantity,
+            0,
+            TimeInForce::Gtc,
+        )
+        .with_instrument_id("BTC-50000-C")
+    }
+
+    #[tokio::test]
+    async fn test_reopen_restores_books_and_trades() {
+        let dir = std::env::temp_dir().join(format!("openx-filestore-{}", Uuid::new_v4()));
+
+        let (bid_id, sequence) = {
+            let store = FileStore::open(WalConfig::new(&dir)).unwrap();
+            let bid = order(OrderSide::Buy, 99, 5);
+            let bid_id = bid.order_id;
+            store.submit_order(bid).await.unwrap();
+            store.submit_order(order(OrderSide::Sell, 101, 10)).await.unwrap();
+            let result = store.submit_order(order(OrderSide::Buy, 101, 4)).await.unwrap();
+            assert_eq!(result.trades.len(), 1);
+            let sequence = store.engine_read().await.sequence();
+            (bid_id, sequence)
+        };
+
+        let store = FileStore::open(WalConfig::new(&dir)).unwrap();
+        assert_eq!(store.get_best_bid("BTC-50000-C").await.unwrap(), Some(99));
+        assert_eq!(store.get_best_ask("BTC-50000-C").await.unwrap(), Some(101));
+        assert_eq!(store.get_trades("BTC-50000-C", 10).await.unwrap().len(), 1);
+        assert_eq!(store.engine_read().await.sequence(), sequence);
+
+        let book = store.get_book("BTC-50000-C").await.unwrap().unwrap();
+        assert_eq!(book.ask_quantity_at(101), 6);
+
+        // Cancellations survive a restart too
+        store.cancel_order("BTC-50000-C", bid_id).await.unwrap().unwrap();
+        store.compact().await.unwrap();
+        drop(store);
+
+        let store = FileStore::open(WalConfig::new(&dir)).unwrap();
+        assert_eq!(store.get_best_bid("BTC-50000-C").await.unwrap(), None);
+        assert_eq!(store.get_best_ask("BTC-50000-C").await.unwrap(), Some(101));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_store_from_config_replays_into_a_configured_engine() {
+        let dir = std::env::temp_dir().join(format!("openx-filestore-{}", Uuid::new_v4()));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../master_config/master_config.yaml");
+        let mut config = config::load_config(path).unwrap();
+        let store_config = &mut config.matching_engine.as_mut().unwrap().orderbook_store;
+        store_config.store_type = "file".to_string();
+        store_config.journal.as_mut().unwrap().dir = dir.display().to_string();
+
+        let call = "BTC-20260315-50000-C";
+        {
+            let store = crate::store::create_store_from_config(&config).await.unwrap();
+            store.submit_order(order(OrderSide::Buy, 99, 5).with_instrument_id(call)).await.unwrap();
+            store.update_reference_price(call, TriggerSource::MarkPrice, 200).await.unwrap();
+            let status = store.get_circuit_breaker_status().await.unwrap();
+            assert!(status.iter().any(|breaker| breaker.instrument_id == call && breaker.price_band.is_some()));
+        }
+
+        // The reopened store replays into an engine configured the same way
+        let store = crate::store::create_store_from_config(&config).await.unwrap();
+        assert_eq!(store.price_scale(call).await.unwrap().tick_size, 0.5);
+        assert_eq!(store.get_best_bid(call).await.unwrap(), Some(99));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_append_leaves_the_order_unaccepted() {
+        let dir = std::env::temp_dir().join(format!("openx-filestore-{}", Uuid::new_v4()));
+        let mut config = WalConfig::new(&dir);
+        // Every append after the first starts a new segment
+        config.segment_size_bytes = 1;
+        let store = FileStore::open(config).unwrap();
+        let bid = order(OrderSide::Buy, 99, 5);
+        let bid_id = bid.order_id;
+        store.submit_order(bid).await.unwrap();
+
+        // A file in place of the directory makes the next segment fail
+        let moved = dir.with_extension("moved");
+        std::fs::rename(&dir, &moved).unwrap();
+        std::fs::write(&dir, b"").unwrap();
+        let sell = order(OrderSide::Sell, 99, 5);
+        let sell_id = sell.order_id;
+        assert!(store.submit_order(sell).await.is_err());
+        assert!(store.cancel_order("BTC-50000-C", bid_id).await.is_err());
+
+        // Once the journal is back the next write rebuilds the engine first,
+        // so the failed sell never traded
+        std::fs::remove_file(&dir).unwrap();
+        std::fs::rename(&moved, &dir).unwrap();
+        assert!(store.cancel_order("BTC-50000-C", sell_id).await.unwrap().is_none());
+        assert!(store.get_order(sell_id).await.unwrap().is_none());
+        assert_eq!(store.get_book("BTC-50000-C").await.unwrap().unwrap().bid_quantity_at(99), 5);
+        assert!(store.get_trades("BTC-50000-C", 10).await.unwrap().is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_idle_store_is_snapshotted_on_a_timer() {
+        let dir = std::env::temp_dir().join(format!("openx-filestore-{}", Uuid::new_v4()));
+        let store: std::sync::Arc<dyn MatchingStore> = std::sync::Arc::new(FileStore::open(WalConfig::new(&dir)).unwrap());
+        store.submit_order(order(OrderSide::Buy, 99, 5)).await.unwrap();
+
+        // No write follows, so only the timer can take the snapshot
+        let task = crate::store::spawn_snapshots(store.clone(), std::time::Duration::from_millis(10));
+        let snapshot = loop {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            let journal = WriteAheadLog::open(WalConfig::new(&dir)).unwrap();
+            if let Some(snapshot) = journal.read_snapshot().unwrap() {
+                break snapshot;
+            }
+        };
+        task.abort();
+        assert_eq!(snapshot.sequence, 1);
+        drop(store);
+
+        let store = FileStore::open(WalConfig::new(&dir)).unwrap();
+        assert_eq!(store.get_best_bid("BTC-50000-C").await.unwrap(), Some(99));
+        assert_eq!(store.engine_read().await.sequence(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_resumes_from_journal() {
+        let dir = std::env::temp_dir().join(format!("openx-filestore-{}", Uuid::new_v4()));
+        let store = FileStore::open(WalConfig::new(&dir)).unwrap();
+        store.submit_order(order(OrderSide::Sell, 101, 10)).await.unwrap();
+        store.submit_order(order(OrderSide::Buy, 99, 5)).await.unwrap();
+
+        let mut subscription = store.subscribe(2).await.unwrap();
+        store.submit_order(order(OrderSide::Buy, 101, 4)).await.unwrap();
+
+        let backlog = subscription.recv().await.unwrap();
+        assert!(matches!(backlog, MatchingEvent::OrderAccepted { sequence: 2, .. }));
+        assert!(matches!(subscription.recv().await.unwrap(), MatchingEvent::OrderAccepted { sequence: 3, .. }));
+        assert!(matches!(subscription.recv().await.unwrap(), MatchingEvent::TradeExecuted { sequence: 4, .. }));
+        assert_eq!(subscription.resume_from(), 4);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
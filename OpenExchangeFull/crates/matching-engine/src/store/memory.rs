@@ -1,16 +1,26 @@
 //! In-memory store implementation for the Matching Engine
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 use uuid::Uuid;
 
-use crate::domain::{BookOrder, OrderBook, Trade};
+use crate::auction::{AuctionReason, CallAuction, IndicativeUncross};
+use crate::block::BlockTrade;
+use crate::circuit_breaker::CircuitBreakerStatus;
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, MassCancelFilter, OrderBook, Trade, TriggerSource};
 use crate::engine::MatchingEngine;
 use crate::event::MatchingEvent;
+use crate::feed::EventSubscription;
+use crate::price::{PriceScale, Ticks};
 use crate::log::create_event_log;
-use crate::result::MatchResult;
+use crate::mmp::MmpStatus;
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, TriggeredStop, UncrossResult,
+};
 use crate::store::traits::{MatchingStore, StoreError, StoreResult};
 
 /// In-memory store for order matching
@@ -31,8 +41,13 @@ pub struct InMemoryStore {
 impl InMemoryStore {
     /// Create a new in-memory store
     pub fn new() -> Self {
+        Self::with_engine(MatchingEngine::new())
+    }
+
+    /// Create a store around an engine that is already configured
+    pub fn with_engine(engine: MatchingEngine) -> Self {
         Self {
-            engine: RwLock::new(MatchingEngine::new()),
+            engine: RwLock::new(engine),
             trades: RwLock::new(std::collections::HashMap::new()),
             event_log: create_event_log(),
             max_trades_per_instrument: 1000,
@@ -74,39 +89,23 @@ impl Default for InMemoryStore {
 impl MatchingStore for InMemoryStore {
     async fn submit_order(&self, order: BookOrder) -> StoreResult<MatchResult> {
         let instrument_id = order.instrument_id.clone();
-        let sequence = {
-            let engine = self.engine.read().await;
-            engine.sequence()
-        };
         
-        // Log the order acceptance
-        {
-            let mut log = self.event_log.write().await;
-            log.append(MatchingEvent::OrderAccepted {
-                order_id: order.order_id,
-                instrument_id: instrument_id.clone(),
-                sequence,
-            });
-        }
-        
-        // Run matching
+        // Run matching and log the acceptance and any trades. The engine
+        // lock is held until the events are appended so the log (and its
+        // subscribers) see them in sequence order.
         let result = {
             let mut engine = self.engine.write().await;
-            engine.match_order(order)
+            let result = engine.match_order(order.clone());
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_match(&order, &result) {
+                log.append(event);
+            }
+            result
         };
         
-        // If trades were generated, log them and store
-        if result.has_trades() {
-            for trade in &result.trades {
-                self.add_trade(trade.clone()).await;
-                
-                // Log trade event
-                let mut log = self.event_log.write().await;
-                log.append(MatchingEvent::TradeExecuted {
-                    trade: trade.clone(),
-                    sequence: trade.sequence,
-                });
-            }
+        // Store trades in history (including those of released stops)
+        for trade in result.all_trades() {
+            self.add_trade(trade.clone()).await;
         }
         
         // If remaining order should be inserted, it's already in the engine
@@ -124,15 +123,8 @@ impl MatchingStore for InMemoryStore {
         instrument_id: &str,
         order_id: Uuid,
     ) -> StoreResult<Option<BookOrder>> {
-        let sequence = {
-            let engine = self.engine.read().await;
-            engine.sequence()
-        };
-        
-        let cancelled = {
-            let mut engine = self.engine.write().await;
-            engine.cancel_order(instrument_id, order_id)
-        };
+        let mut engine = self.engine.write().await;
+        let cancelled = engine.cancel_order(instrument_id, order_id);
         
         if cancelled.is_some() {
             // Log cancellation
@@ -140,7 +132,7 @@ impl MatchingStore for InMemoryStore {
             log.append(MatchingEvent::OrderCancelled {
                 order_id,
                 instrument_id: instrument_id.to_string(),
-                sequence,
+                sequence: engine.sequence(),
             });
             
             info!(order_id = %order_id, instrument_id = %instrument_id, "Order cancelled");
@@ -149,26 +141,251 @@ impl MatchingStore for InMemoryStore {
         Ok(cancelled)
     }
 
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> StoreResult<MassCancelResult> {
+        let mut engine = self.engine.write().await;
+        let result = engine.mass_cancel(filter);
+
+        if let Some(event) = MatchingEvent::from_mass_cancel(&result) {
+            let mut log = self.event_log.write().await;
+            log.append(event);
+
+            info!(orders = result.cancelled.len(), "Orders mass cancelled");
+        }
+
+        Ok(result)
+    }
+
+    async fn expire_orders(&self, now: DateTime<Utc>) -> StoreResult<Vec<ExpiredOrder>> {
+        let mut engine = self.engine.write().await;
+        let expired = engine.expire_orders(now);
+
+        if !expired.is_empty() {
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_expired(&expired) {
+                log.append(event);
+            }
+
+            info!(orders = expired.len(), "Orders expired");
+        }
+
+        Ok(expired)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+    ) -> StoreResult<AmendResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            let result = engine.amend_order(order_id, new_price, new_quantity)?;
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_amend(order_id, new_price, new_quantity, &result) {
+                log.append(event);
+            }
+            result
+        };
+        
+        for trade in result.all_trades() {
+            self.add_trade(trade.clone()).await;
+        }
+        
+        Ok(result)
+    }
+
+    async fn update_reference_price(
+        &self,
+        instrument_id: &str,
+        source: TriggerSource,
+        price: Ticks,
+    ) -> StoreResult<Vec<TriggeredStop>> {
+        let triggered = {
+            let mut engine = self.engine.write().await;
+            let triggered = engine.update_reference_price(instrument_id, source, price);
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_triggers(&triggered) {
+                log.append(event);
+            }
+            triggered
+        };
+
+        for stop in &triggered {
+            for trade in stop.result.all_trades() {
+                self.add_trade(trade.clone()).await;
+            }
+        }
+
+        Ok(triggered)
+    }
+
+    async fn start_auction(&self, instrument_id: &str, reason: AuctionReason) -> StoreResult<CallAuction> {
+        let mut engine = self.engine.write().await;
+        let auction = engine.start_auction(instrument_id, reason)?;
+
+        let mut log = self.event_log.write().await;
+        log.append(MatchingEvent::auction_started(instrument_id, &auction));
+
+        Ok(auction)
+    }
+
+    async fn uncross(&self, instrument_id: &str) -> StoreResult<UncrossResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            let result = engine.uncross(instrument_id)?;
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_uncross(&result) {
+                log.append(event);
+            }
+            result
+        };
+
+        for trade in result.all_trades() {
+            self.add_trade(trade.clone()).await;
+        }
+
+        Ok(result)
+    }
+
+    async fn get_auction(&self, instrument_id: &str) -> StoreResult<Option<CallAuction>> {
+        let engine = self.engine.read().await;
+        Ok(engine.auction(instrument_id).copied())
+    }
+
+    async fn get_indicative_uncross(&self, instrument_id: &str) -> StoreResult<Option<IndicativeUncross>> {
+        let engine = self.engine.read().await;
+        Ok(engine.indicative_uncross(instrument_id))
+    }
+
+    async fn define_combo(&self, combo: ComboDefinition) -> StoreResult<ComboDefinition> {
+        let mut engine = self.engine.write().await;
+        let combo_id = combo.combo_id.clone();
+        if engine.define_combo(combo)?.is_some() {
+            let defined = engine.combo(&combo_id).cloned().expect("combo was just defined");
+            let mut log = self.event_log.write().await;
+            log.append(MatchingEvent::combo_defined(&defined));
+        }
+        Ok(engine.combo(&combo_id).cloned().expect("combo is defined"))
+    }
+
+    async fn get_combos(&self) -> StoreResult<Vec<ComboDefinition>> {
+        let engine = self.engine.read().await;
+        Ok(engine.combos())
+    }
+
+    async fn book_block_trade(&self, block: BlockTrade) -> StoreResult<Vec<Trade>> {
+        let trades = {
+            let mut engine = self.engine.write().await;
+            let trades = engine.book_block_trade(&block)?;
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_block_trade(&trades) {
+                log.append(event);
+            }
+            trades
+        };
+
+        for trade in &trades {
+            self.add_trade(trade.clone()).await;
+        }
+        Ok(trades)
+    }
+
+    async fn mass_quote(&self, user_id: Uuid, quotes: Vec<BookOrder>) -> StoreResult<MassQuoteResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            let result = engine.mass_quote(user_id, quotes)?;
+            let mut log = self.event_log.write().await;
+            for event in MatchingEvent::from_mass_quote(&result) {
+                log.append(event);
+            }
+            result
+        };
+
+        for trade in result.all_trades() {
+            self.add_trade(trade.clone()).await;
+        }
+        Ok(result)
+    }
+
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> StoreResult<bool> {
+        let mut engine = self.engine.write().await;
+        let Some(sequence) = engine.reset_mmp(user_id, underlying) else {
+            return Ok(false);
+        };
+        let mut log = self.event_log.write().await;
+        log.append(MatchingEvent::MmpReset {
+            user_id,
+            underlying: underlying.to_string(),
+            sequence,
+        });
+        Ok(true)
+    }
+
+    async fn get_mmp_status(&self, user_id: Uuid) -> StoreResult<Vec<MmpStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.mmp_status(user_id))
+    }
+
+    async fn halt_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn resume_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn halt_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_underlying(underlying);
+        Ok(())
+    }
+
+    async fn resume_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_underlying(underlying);
+        Ok(())
+    }
+
+    async fn get_circuit_breaker_status(&self) -> StoreResult<Vec<CircuitBreakerStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.circuit_breaker_status())
+    }
+
+    async fn get_halted_underlyings(&self) -> StoreResult<Vec<String>> {
+        let engine = self.engine.read().await;
+        Ok(engine.halted_underlyings())
+    }
+
     async fn get_book(&self, instrument_id: &str) -> StoreResult<Option<OrderBook>> {
         let engine = self.engine.read().await;
         Ok(engine.get_book(instrument_id).cloned())
     }
 
-    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let engine = self.engine.read().await;
         Ok(engine.get_book(instrument_id).and_then(|b| b.best_bid()))
     }
 
-    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let engine = self.engine.read().await;
         Ok(engine.get_book(instrument_id).and_then(|b| b.best_ask()))
     }
 
-    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let engine = self.engine.read().await;
         Ok(engine.get_book(instrument_id).and_then(|b| b.spread()))
     }
 
+    async fn price_scale(&self, instrument_id: &str) -> StoreResult<PriceScale> {
+        let engine = self.engine.read().await;
+        Ok(engine.price_scale(instrument_id))
+    }
+
+    async fn get_order(&self, order_id: Uuid) -> StoreResult<Option<BookOrder>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_order(order_id).cloned())
+    }
+
     async fn has_book(&self, instrument_id: &str) -> StoreResult<bool> {
         let engine = self.engine.read().await;
         Ok(engine.has_book(instrument_id))
@@ -208,6 +425,11 @@ impl MatchingStore for InMemoryStore {
         Ok(log.sequence())
     }
 
+    async fn subscribe(&self, from_sequence: u64) -> StoreResult<EventSubscription> {
+        let log = self.event_log.read().await;
+        Ok(log.subscribe(from_sequence))
+    }
+
     fn engine(&self) -> MatchingEngine {
         // Note: This clones the engine state
         // For in-memory, this is acceptable since we're cloning the entire state
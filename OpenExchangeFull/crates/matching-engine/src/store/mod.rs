@@ -5,15 +5,21 @@
 mod traits;
 mod memory;
 mod redis;
+mod file;
 
 pub use traits::*;
 pub use memory::InMemoryStore;
 pub use redis::RedisStore;
+pub use file::FileStore;
 
 // Re-export for convenience
+use crate::engine::MatchingEngine;
 use crate::event::MatchingEvent;
 use crate::log::SharedEventLog;
-use tracing::info;
+use crate::wal::WalConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
 
 /// Store type selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,6 +28,8 @@ pub enum StoreType {
     InMemory,
     /// Redis store (persistent)
     Redis,
+    /// File journal store (persistent, write-ahead log on local disk)
+    File,
 }
 
 impl StoreType {
@@ -30,38 +38,114 @@ impl StoreType {
         match s.to_lowercase().as_str() {
             "inmemory" | "in_memory" | "memory" => Some(StoreType::InMemory),
             "redis" => Some(StoreType::Redis),
+            "file" | "wal" | "journal" => Some(StoreType::File),
             _ => None,
         }
     }
 }
 
 /// Create a store based on configuration
+///
+/// The store runs an unconfigured engine and the file store uses the
+/// default journal settings; use [`create_store_from_config`] for both.
 pub async fn create_store(
     store_type: StoreType,
     redis_config: Option<&config::RedisConfig>,
+) -> Result<Box<dyn MatchingStore>, String> {
+    create_store_with_journal(store_type, redis_config, WalConfig::default(), MatchingEngine::new()).await
+}
+
+async fn create_store_with_journal(
+    store_type: StoreType,
+    redis_config: Option<&config::RedisConfig>,
+    wal_config: WalConfig,
+    engine: MatchingEngine,
 ) -> Result<Box<dyn MatchingStore>, String> {
     match store_type {
         StoreType::InMemory => {
             info!("Creating in-memory store");
-            Ok(Box::new(InMemoryStore::new()))
+            Ok(Box::new(InMemoryStore::with_engine(engine)))
         }
         StoreType::Redis => {
             let config = redis_config.ok_or("Redis config required for Redis store")?;
             info!("Creating Redis store");
-            let store = RedisStore::new(config)
+            let store = RedisStore::with_engine(config, "matching", engine)
                 .await
                 .map_err(|e| format!("Failed to create Redis store: {}", e))?;
             Ok(Box::new(store))
         }
+        StoreType::File => {
+            info!(dir = %wal_config.dir.display(), "Creating file journal store");
+            let store = FileStore::open_with_engine(wal_config, engine)
+                .map_err(|e| format!("Failed to open file store: {}", e))?;
+            Ok(Box::new(store))
+        }
     }
 }
 
-/// Create store from OrderbookStoreConfig
+/// Create a store from the configuration
+///
+/// The store type and journal come from the matching engine's
+/// `orderbook_store` section (in-memory without one); the engine is built
+/// with [`MatchingEngine::from_config`].
 pub async fn create_store_from_config(
-    config: &config::OrderbookStoreConfig,
+    config: &config::MasterConfig,
 ) -> Result<Box<dyn MatchingStore>, String> {
-    let store_type = StoreType::from_str(&config.store_type)
+    let engine = MatchingEngine::from_config(config);
+    let Some(store_config) = config.matching_engine.as_ref().map(|m| &m.orderbook_store) else {
+        return create_store_with_journal(StoreType::InMemory, None, WalConfig::default(), engine).await;
+    };
+
+    let store_type = StoreType::from_str(&store_config.store_type)
         .unwrap_or(StoreType::InMemory);
     
-    create_store(store_type, config.redis.as_ref()).await
+    let wal_config = store_config
+        .journal
+        .as_ref()
+        .map(WalConfig::from)
+        .unwrap_or_default();
+    
+    create_store_with_journal(store_type, store_config.redis.as_ref(), wal_config, engine).await
+}
+
+/// How often the configured store is snapshotted
+///
+/// `snapshot_interval_seconds` of the Redis section (with persistence
+/// enabled) or of the journal, whichever the store type uses. None for the
+/// in-memory store or an interval of 0.
+pub fn snapshot_interval(config: &config::MasterConfig) -> Option<Duration> {
+    let store_config = &config.matching_engine.as_ref()?.orderbook_store;
+    let seconds = match StoreType::from_str(&store_config.store_type)? {
+        StoreType::InMemory => return None,
+        StoreType::Redis => store_config
+            .redis
+            .as_ref()
+            .filter(|redis| redis.persistence_enabled)?
+            .snapshot_interval_seconds,
+        StoreType::File => store_config
+            .journal
+            .as_ref()
+            .map_or_else(config::default_snapshot_interval, |journal| journal.snapshot_interval_seconds),
+    };
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// Periodically snapshot a store
+///
+/// Snapshots every `period` until the task is aborted, whether or not the
+/// store saw any writes; a failed snapshot is logged and retried on the
+/// next tick.
+pub fn spawn_snapshots(store: Arc<dyn MatchingStore>, period: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick is immediate, and the store was just restored
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = store.snapshot().await {
+                warn!(error = %e, "Periodic snapshot failed");
+            }
+        }
+    })
 }
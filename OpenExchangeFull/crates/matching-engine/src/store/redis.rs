@@ -1,46 +1,151 @@
 //! Redis store implementation for the Matching Engine
 //!
 //! This implementation stores order books and trades in Redis for persistence.
+//!
+//! Every command is written with one Lua script, so its events, the books
+//! it changed, its trades and the sequence land together or not at all.
+//! The script refuses a write unless the stored sequence is exactly the
+//! engine's sequence before the command, which fences off a second writer
+//! on the same keys. A command whose write fails has already changed the
+//! engine, so the store then refuses further writes until
+//! [`RedisStore::restore`] rebuilds the engine from Redis.
+//!
+//! Events go to a Redis Stream that downstream services read through
+//! consumer groups ([`RedisEventConsumer`]). Durability comes from an
+//! [`EngineSnapshot`] written on a timer by
+//! [`spawn_snapshots`](super::spawn_snapshots), stored with the ID of the last stream entry
+//! it covers; on startup the engine is restored from the snapshot and the
+//! stream tail is replayed on top.
 
 use async_trait::async_trait;
-use redis::{AsyncCommands, JsonAsyncCommands};
+use chrono::{DateTime, Utc};
+use redis::streams::{StreamId, StreamRangeReply, StreamReadOptions, StreamReadReply};
+use redis::AsyncCommands;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 
-use crate::domain::{BookOrder, OrderBook, Trade};
+use crate::auction::{AuctionReason, CallAuction, IndicativeUncross};
+use crate::block::BlockTrade;
+use crate::circuit_breaker::CircuitBreakerStatus;
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, MassCancelFilter, OrderBook, Trade, TriggerSource};
 use crate::engine::MatchingEngine;
 use crate::event::MatchingEvent;
+use crate::feed::EventSubscription;
+use crate::price::{PriceScale, Ticks};
 use crate::log::create_event_log;
-use crate::result::MatchResult;
+use crate::mmp::MmpStatus;
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, MmpTrip, TriggeredStop, UncrossResult,
+};
+use crate::snapshot::EngineSnapshot;
 use crate::store::traits::{MatchingStore, StoreError, StoreResult};
 use config::RedisConfig;
 
+/// Applies one command's writes atomically
+///
+/// KEYS: sequence, event stream, then `nb` book keys and `nt` trade keys.
+/// ARGV: previous sequence, new sequence, stream max length, trades kept,
+/// `ne`, `nb`, `nt`, then `ne` (sequence, event) pairs, `nb` book values
+/// (empty to delete) and, per trade key, a count followed by the trades.
+/// Returns the ID of the last stream entry added, if any.
+const APPLY_SCRIPT: &str = r#"
+local stored = tonumber(redis.call('GET', KEYS[1]) or '0')
+if stored ~= tonumber(ARGV[1]) then
+  return redis.error_reply('STALE stored sequence ' .. stored .. ' is not ' .. ARGV[1])
+end
+
+local ne, nb, nt = tonumber(ARGV[5]), tonumber(ARGV[6]), tonumber(ARGV[7])
+local arg = 8
+local last_id = false
+for _ = 1, ne do
+  last_id = redis.call('XADD', KEYS[2], 'MAXLEN', '~', ARGV[3], '*', 'seq', ARGV[arg], 'event', ARGV[arg + 1])
+  arg = arg + 2
+end
+for i = 1, nb do
+  if ARGV[arg] == '' then
+    redis.call('DEL', KEYS[2 + i])
+  else
+    redis.call('SET', KEYS[2 + i], ARGV[arg])
+  end
+  arg = arg + 1
+end
+for i = 1, nt do
+  local key = KEYS[2 + nb + i]
+  local count = tonumber(ARGV[arg])
+  for j = 1, count do
+    redis.call('RPUSH', key, ARGV[arg + j])
+  end
+  redis.call('LTRIM', key, -tonumber(ARGV[4]), -1)
+  arg = arg + count + 1
+end
+
+redis.call('SET', KEYS[1], ARGV[2])
+return last_id
+"#;
+
+/// Entries read per XRANGE call while restoring
+const RESTORE_BATCH: usize = 10_000;
+
 /// Redis store for order matching
 ///
 /// This implementation persists order books and trades to Redis.
 /// It also maintains an in-memory cache for fast access.
 pub struct RedisStore {
+    /// Client, for connections of event consumers
+    client: redis::Client,
     /// Redis connection pool (wrapped in Mutex for mutable access)
     redis: Arc<tokio::sync::Mutex<redis::aio::ConnectionManager>>,
     /// In-memory cache for fast reads
-    cache: RwLock<std::collections::HashMap<String, OrderBook>>,
-    /// In-memory cache for trades
-    trades: RwLock<std::collections::HashMap<String, Vec<Trade>>>,
-    /// Event log (in-memory for now, could be Redis streams)
+    cache: RwLock<HashMap<String, OrderBook>>,
+    /// Events since startup (or the restored snapshot), for subscribers
     event_log: crate::log::SharedEventLog,
     /// Engine for matching (in-memory, synced to Redis)
     engine: RwLock<MatchingEngine>,
+    /// Script applying a command's writes
+    apply_script: redis::Script,
     /// Redis key prefix
     key_prefix: String,
     /// Max trades per instrument
-    max_trades: usize,
+    max_trades: u64,
+    /// Approximate number of entries kept in the event stream
+    stream_max_len: u64,
+    /// ID of the last entry this store added to (or read from) the stream
+    last_event_id: tokio::sync::Mutex<Option<String>>,
+    /// Whether events and snapshots are persisted
+    persistence_enabled: bool,
+    /// Why writes are refused, once a command changed the engine but its
+    /// write failed
+    failed: tokio::sync::Mutex<Option<String>>,
 }
 
 impl RedisStore {
     /// Create a new Redis store
     pub async fn new(config: &RedisConfig) -> StoreResult<Self> {
+        Self::with_key_prefix(config, "matching").await
+    }
+
+    /// Create a Redis store whose keys start with `key_prefix`
+    ///
+    /// Stores with different prefixes share a database without seeing
+    /// each other's books, trades or events.
+    pub async fn with_key_prefix(config: &RedisConfig, key_prefix: impl Into<String>) -> StoreResult<Self> {
+        Self::with_engine(config, key_prefix, MatchingEngine::new()).await
+    }
+
+    /// Create a Redis store whose keys start with `key_prefix` around an
+    /// engine that is already configured
+    ///
+    /// The persisted books are restored into `engine`.
+    pub async fn with_engine(
+        config: &RedisConfig,
+        key_prefix: impl Into<String>,
+        engine: MatchingEngine,
+    ) -> StoreResult<Self> {
         let connection_string = format!(
             "redis://{}:{}@{}:{}/{}",
             if config.password.is_empty() { "" } else { &config.password },
@@ -60,14 +165,303 @@ impl RedisStore {
             .await
             .map_err(|e| StoreError::RedisError(e.to_string()))?;
 
-        Ok(Self {
+        let store = Self {
+            client,
             redis: Arc::new(tokio::sync::Mutex::new(connection_manager)),
-            cache: RwLock::new(std::collections::HashMap::new()),
-            trades: RwLock::new(std::collections::HashMap::new()),
+            cache: RwLock::new(HashMap::new()),
             event_log: create_event_log(),
-            engine: RwLock::new(MatchingEngine::new()),
-            key_prefix: "matching".to_string(),
-            max_trades: 1000,
+            engine: RwLock::new(engine),
+            apply_script: redis::Script::new(APPLY_SCRIPT),
+            key_prefix: key_prefix.into(),
+            max_trades: config.max_trades_per_instrument.max(1),
+            stream_max_len: config.event_stream_max_len,
+            last_event_id: tokio::sync::Mutex::new(None),
+            persistence_enabled: config.persistence_enabled,
+            failed: tokio::sync::Mutex::new(None),
+        };
+
+        if store.persistence_enabled {
+            store.restore().await?;
+        }
+
+        Ok(store)
+    }
+
+    /// Restore the engine from the latest snapshot plus the event tail
+    ///
+    /// The tail is every stream entry from the one the snapshot covers
+    /// onwards, after any events still in the list used by older versions.
+    /// Fails if the stream was trimmed past the snapshot. A store refusing
+    /// writes after a failed one accepts them again once restored.
+    pub async fn restore(&self) -> StoreResult<()> {
+        let (snapshot, offset, mut tail, entries) = {
+            let mut redis = self.redis.lock().await;
+            let snapshot: Option<Vec<u8>> = redis
+                .get(self.snapshot_key())
+                .await
+                .map_err(|e| StoreError::RedisError(e.to_string()))?;
+            let offset: Option<String> = redis
+                .get(self.snapshot_offset_key())
+                .await
+                .map_err(|e| StoreError::RedisError(e.to_string()))?;
+            let legacy: Vec<String> = redis
+                .lrange(self.legacy_events_key(), 0, -1)
+                .await
+                .map_err(|e| StoreError::RedisError(e.to_string()))?;
+            let entries = self.read_stream(&mut redis, offset.as_deref().unwrap_or("-")).await?;
+            (snapshot, offset, legacy, entries)
+        };
+
+        if let Some(offset) = &offset {
+            if entries.first().map(|entry| &entry.id) != Some(offset) {
+                return Err(StoreError::JournalError(format!(
+                    "Event stream trimmed past the snapshot at entry {}; raise event_stream_max_len",
+                    offset
+                )));
+            }
+        }
+
+        let snapshot = match snapshot {
+            Some(bytes) => EngineSnapshot::from_bytes(&bytes)
+                .map_err(|e| StoreError::SerializationError(e.to_string()))?,
+            None => EngineSnapshot::empty(),
+        };
+        for entry in &entries {
+            tail.push(entry.get("event").ok_or_else(|| {
+                StoreError::SerializationError(format!("Stream entry {} has no event", entry.id))
+            })?);
+        }
+        let tail = tail
+            .iter()
+            .map(|json| serde_json::from_str::<MatchingEvent>(json))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| StoreError::SerializationError(e.to_string()))?;
+
+        let mut engine = self.engine.write().await;
+        engine
+            .restore(&snapshot, &tail)
+            .map_err(|e| StoreError::Other(e.to_string()))?;
+        *self.last_event_id.lock().await = entries.last().map(|entry| entry.id.clone()).or(offset);
+
+        // Rebuild the read cache and in-memory log from the restored state
+        let mut cache = self.cache.write().await;
+        cache.clear();
+        for instrument_id in engine.instruments() {
+            if let Some(book) = engine.get_book(&instrument_id).filter(|b| !b.is_empty()) {
+                cache.insert(instrument_id, book.clone());
+            }
+        }
+        // Events already logged (when restoring a running store) are kept
+        let mut log = self.event_log.write().await;
+        let logged = snapshot.sequence.max(log.sequence());
+        for event in tail.into_iter().filter(|e| e.sequence() > logged) {
+            log.append(event);
+        }
+        *self.failed.lock().await = None;
+
+        info!(
+            sequence = engine.sequence(),
+            books = cache.len(),
+            "Redis store restored from snapshot"
+        );
+        Ok(())
+    }
+
+    /// Read the event stream from `start` (inclusive) to its end
+    async fn read_stream(
+        &self,
+        redis: &mut redis::aio::ConnectionManager,
+        start: &str,
+    ) -> StoreResult<Vec<StreamId>> {
+        let mut entries: Vec<StreamId> = Vec::new();
+        let mut start = start.to_string();
+        loop {
+            let reply: StreamRangeReply = redis
+                .xrange_count(self.events_key(), &start, "+", RESTORE_BATCH)
+                .await
+                .map_err(|e| StoreError::RedisError(e.to_string()))?;
+            let done = reply.ids.len() < RESTORE_BATCH;
+            entries.extend(reply.ids);
+            match entries.last() {
+                Some(last) if !done => start = next_stream_id(&last.id)?,
+                _ => return Ok(entries),
+            }
+        }
+    }
+
+    async fn write_snapshot(&self, engine: &MatchingEngine) -> StoreResult<()> {
+        let snapshot = engine.snapshot();
+        let bytes = snapshot
+            .to_bytes()
+            .map_err(|e| StoreError::SerializationError(e.to_string()))?;
+        let offset = self.last_event_id.lock().await.clone();
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().set(self.snapshot_key(), bytes).ignore();
+        match &offset {
+            Some(offset) => pipe.set(self.snapshot_offset_key(), offset).ignore(),
+            None => pipe.del(self.snapshot_offset_key()).ignore(),
+        };
+        // Events in the old list are covered by the snapshot too
+        pipe.del(self.legacy_events_key()).ignore();
+
+        let mut redis = self.redis.lock().await;
+        pipe.query_async::<_, ()>(&mut *redis)
+            .await
+            .map_err(|e| StoreError::RedisError(e.to_string()))?;
+
+        info!(
+            sequence = snapshot.sequence,
+            orders = snapshot.order_count(),
+            "Engine snapshot written"
+        );
+        Ok(())
+    }
+
+    /// Refuse writes while the engine holds changes Redis does not
+    ///
+    /// Must be called with the engine write lock held, before the command
+    /// changes the engine.
+    async fn ensure_writable(&self) -> StoreResult<()> {
+        match self.failed.lock().await.as_ref() {
+            Some(reason) => Err(StoreError::Other(format!(
+                "Store refuses writes after a failed write ({}); restore it first",
+                reason
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Write a command's events, changed books and trades atomically
+    ///
+    /// `previous_sequence` is the engine's sequence before the command.
+    /// Events reach the stream only when persistence is enabled; books
+    /// and trades are always written. On success the cache and in-memory
+    /// log are updated; on failure the store refuses further writes, as
+    /// the engine already holds the command's changes. Must be called with
+    /// the engine write lock held.
+    async fn apply(
+        &self,
+        engine: &MatchingEngine,
+        previous_sequence: u64,
+        events: Vec<MatchingEvent>,
+        instruments: &[String],
+    ) -> StoreResult<()> {
+        let written = self.write_command(engine, previous_sequence, events, instruments).await;
+        if let Err(e) = &written {
+            error!(error = %e, sequence = engine.sequence(), "Write failed; refusing writes until restored");
+            *self.failed.lock().await = Some(e.to_string());
+        }
+        written
+    }
+
+    async fn write_command(
+        &self,
+        engine: &MatchingEngine,
+        previous_sequence: u64,
+        events: Vec<MatchingEvent>,
+        instruments: &[String],
+    ) -> StoreResult<()> {
+        if events.is_empty() && engine.sequence() == previous_sequence {
+            return Ok(());
+        }
+
+        let books: Vec<(&String, Option<&OrderBook>)> = instruments
+            .iter()
+            .map(|id| (id, engine.get_book(id).filter(|book| !book.is_empty())))
+            .collect();
+        let mut trades: Vec<(&str, Vec<String>)> = Vec::new();
+        for event in &events {
+            if let MatchingEvent::TradeExecuted { trade, .. } = event {
+                let json = serde_json::to_string(trade).map_err(|e| StoreError::SerializationError(e.to_string()))?;
+                match trades.iter_mut().find(|(id, _)| *id == trade.instrument_id) {
+                    Some((_, list)) => list.push(json),
+                    None => trades.push((&trade.instrument_id, vec![json])),
+                }
+            }
+        }
+        let streamed: &[MatchingEvent] = if self.persistence_enabled { &events } else { &[] };
+
+        let mut invocation = self.apply_script.prepare_invoke();
+        invocation
+            .key(self.sequence_key())
+            .key(self.events_key())
+            .arg(previous_sequence)
+            .arg(engine.sequence())
+            .arg(self.stream_max_len)
+            .arg(self.max_trades)
+            .arg(streamed.len())
+            .arg(books.len())
+            .arg(trades.len());
+        for event in streamed {
+            let json = serde_json::to_string(event).map_err(|e| StoreError::SerializationError(e.to_string()))?;
+            invocation.arg(event.sequence()).arg(json);
+        }
+        for (instrument_id, book) in &books {
+            let json = match book {
+                Some(book) => serde_json::to_string(book).map_err(|e| StoreError::SerializationError(e.to_string()))?,
+                None => String::new(),
+            };
+            invocation.key(self.book_key(instrument_id)).arg(json);
+        }
+        for (instrument_id, list) in &trades {
+            invocation.key(self.trades_key(instrument_id)).arg(list.len()).arg(list);
+        }
+
+        let last_id: Option<String> = {
+            let mut redis = self.redis.lock().await;
+            invocation
+                .invoke_async(&mut *redis)
+                .await
+                .map_err(|e| StoreError::RedisError(e.to_string()))?
+        };
+        if last_id.is_some() {
+            *self.last_event_id.lock().await = last_id;
+        }
+
+        let mut cache = self.cache.write().await;
+        for (instrument_id, book) in books {
+            match book {
+                Some(book) => cache.insert(instrument_id.clone(), book.clone()),
+                None => cache.remove(instrument_id),
+            };
+        }
+        let mut log = self.event_log.write().await;
+        for event in events {
+            log.append(event);
+        }
+        Ok(())
+    }
+
+    /// Join a consumer group on the event stream
+    ///
+    /// The group is created at the start of the retained stream if it does
+    /// not exist yet. Each consumer gets its own connection, so blocking
+    /// reads do not hold up the store.
+    pub async fn consumer(
+        &self,
+        group: impl Into<String>,
+        consumer: impl Into<String>,
+    ) -> StoreResult<RedisEventConsumer> {
+        let mut redis = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| StoreError::RedisError(e.to_string()))?;
+        let group = group.into();
+
+        let created: redis::RedisResult<()> = redis.xgroup_create_mkstream(self.events_key(), &group, "0").await;
+        match created {
+            Ok(()) => info!(group = %group, "Created event stream consumer group"),
+            Err(e) if e.code() == Some("BUSYGROUP") => {}
+            Err(e) => return Err(StoreError::RedisError(e.to_string())),
+        }
+
+        Ok(RedisEventConsumer {
+            redis,
+            stream: self.events_key(),
+            group,
+            consumer: consumer.into(),
         })
     }
 
@@ -86,20 +480,24 @@ impl RedisStore {
         format!("{}:sequence", self.key_prefix)
     }
 
-    /// Sync engine state to Redis
-    async fn sync_book_to_redis(&self, instrument_id: &str, book: &OrderBook) -> StoreResult<()> {
-        let key = self.book_key(instrument_id);
-        
-        let json = serde_json::to_string(book)
-            .map_err(|e| StoreError::SerializationError(e.to_string()))?;
+    /// Generate Redis key for the latest engine snapshot
+    fn snapshot_key(&self) -> String {
+        format!("{}:snapshot", self.key_prefix)
+    }
 
-        let mut redis = self.redis.lock().await;
-        redis
-            .set::<_, _, ()>(&key, json)
-            .await
-            .map_err(|e| StoreError::RedisError(e.to_string()))?;
+    /// Generate Redis key for the last stream entry the snapshot covers
+    fn snapshot_offset_key(&self) -> String {
+        format!("{}:snapshot:offset", self.key_prefix)
+    }
 
-        Ok(())
+    /// Generate Redis key for the event stream
+    fn events_key(&self) -> String {
+        format!("{}:event_stream", self.key_prefix)
+    }
+
+    /// Generate Redis key for the event list written by older versions
+    fn legacy_events_key(&self) -> String {
+        format!("{}:events", self.key_prefix)
     }
 
     /// Load book from Redis into cache
@@ -121,18 +519,98 @@ impl RedisStore {
             None => Ok(None),
         }
     }
+}
 
-    /// Delete book from Redis
-    async fn delete_book_from_redis(&self, instrument_id: &str) -> StoreResult<()> {
-        let key = self.book_key(instrument_id);
-        
-        let mut redis = self.redis.lock().await;
-        redis
-            .del::<_, ()>(&key)
+/// Instruments whose quotes MMP trips cancelled
+fn mmp_instruments(trips: &[&MmpTrip]) -> Vec<String> {
+    trips
+        .iter()
+        .flat_map(|trip| trip.cancelled.iter().map(|order| order.instrument_id.clone()))
+        .collect()
+}
+
+/// Stream ID immediately after `id`
+fn next_stream_id(id: &str) -> StoreResult<String> {
+    let parsed = id
+        .split_once('-')
+        .and_then(|(ms, seq)| Some((ms.parse::<u64>().ok()?, seq.parse::<u64>().ok()?)));
+    match parsed {
+        Some((ms, seq)) => Ok(format!("{}-{}", ms, seq + 1)),
+        None => Err(StoreError::SerializationError(format!("Invalid stream entry ID: {}", id))),
+    }
+}
+
+/// An event read from the stream
+#[derive(Debug, Clone)]
+pub struct StreamEvent {
+    /// Stream entry ID, used to acknowledge the event
+    pub id: String,
+    /// The event
+    pub event: MatchingEvent,
+}
+
+/// A member of a consumer group on the Redis event stream
+///
+/// Events are delivered to one consumer of the group each and stay
+/// pending until acknowledged; after a restart a consumer re-reads its
+/// pending events with [`pending`](Self::pending) before new ones.
+pub struct RedisEventConsumer {
+    redis: redis::aio::MultiplexedConnection,
+    stream: String,
+    group: String,
+    consumer: String,
+}
+
+impl RedisEventConsumer {
+    /// Read up to `count` new events, waiting up to `block` if there are none
+    pub async fn read(&mut self, count: usize, block: Option<Duration>) -> StoreResult<Vec<StreamEvent>> {
+        let mut options = StreamReadOptions::default()
+            .group(&self.group, &self.consumer)
+            .count(count);
+        if let Some(block) = block {
+            options = options.block(block.as_millis() as usize);
+        }
+        self.read_from(">", options).await
+    }
+
+    /// Read up to `count` events delivered to this consumer but not yet acknowledged
+    pub async fn pending(&mut self, count: usize) -> StoreResult<Vec<StreamEvent>> {
+        let options = StreamReadOptions::default()
+            .group(&self.group, &self.consumer)
+            .count(count);
+        self.read_from("0", options).await
+    }
+
+    async fn read_from(&mut self, id: &str, options: StreamReadOptions) -> StoreResult<Vec<StreamEvent>> {
+        let reply: Option<StreamReadReply> = self
+            .redis
+            .xread_options(&[&self.stream], &[id], &options)
             .await
             .map_err(|e| StoreError::RedisError(e.to_string()))?;
 
-        Ok(())
+        reply
+            .into_iter()
+            .flat_map(|reply| reply.keys)
+            .flat_map(|key| key.ids)
+            .map(|entry| {
+                let json: String = entry.get("event").ok_or_else(|| {
+                    StoreError::SerializationError(format!("Stream entry {} has no event", entry.id))
+                })?;
+                let event = serde_json::from_str(&json).map_err(|e| StoreError::SerializationError(e.to_string()))?;
+                Ok(StreamEvent { id: entry.id, event })
+            })
+            .collect()
+    }
+
+    /// Acknowledge processed events
+    pub async fn ack(&mut self, ids: &[String]) -> StoreResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.redis
+            .xack::<_, _, _, ()>(&self.stream, &self.group, ids)
+            .await
+            .map_err(|e| StoreError::RedisError(e.to_string()))
     }
 }
 
@@ -141,69 +619,23 @@ impl MatchingStore for RedisStore {
     async fn submit_order(&self, order: BookOrder) -> StoreResult<MatchResult> {
         let instrument_id = order.instrument_id.clone();
         
-        // Load book from cache or Redis
-        {
-            let mut cache = self.cache.write().await;
-            if !cache.contains_key(&instrument_id) {
-                if let Some(book) = self.load_book_from_redis(&instrument_id).await? {
-                    cache.insert(instrument_id.clone(), book);
-                }
-            }
-        }
-        
-        // Run matching
+        // Run matching and write the results under the engine lock
         let result = {
             let mut engine = self.engine.write().await;
-            engine.match_order(order)
+            self.ensure_writable().await?;
+            let previous = engine.sequence();
+            let result = engine.match_order(order.clone());
+            let events = MatchingEvent::from_match(&order, &result);
+            // A combo fill can change the combo's and every leg's book, and
+            // an MMP trip the books of the maker's other quotes
+            let mut instruments = engine.linked_instruments(&instrument_id);
+            instruments.extend(mmp_instruments(&result.all_mmp_trips()));
+            instruments.sort();
+            instruments.dedup();
+            self.apply(&engine, previous, events, &instruments).await?;
+            result
         };
         
-        // Update cache with new book state
-        if result.should_insert {
-            if let Some(remaining) = &result.remaining_order {
-                let mut cache = self.cache.write().await;
-                let book = cache.entry(instrument_id.clone()).or_insert_with(|| {
-                    OrderBook::new(instrument_id.clone())
-                });
-                book.insert_order(remaining.clone());
-                
-                // Sync to Redis
-                if let Err(e) = self.sync_book_to_redis(&instrument_id, book).await {
-                    warn!(error = %e, "Failed to sync book to Redis");
-                }
-            }
-        } else if result.trades.is_empty() && result.remaining_order.is_none() {
-            // Order was fully filled, update cache
-            let mut cache = self.cache.write().await;
-            if let Some(book) = cache.get_mut(&instrument_id) {
-                book.cleanup_empty_levels();
-                if book.is_empty() {
-                    cache.remove(&instrument_id);
-                    let _ = self.delete_book_from_redis(&instrument_id).await;
-                } else {
-                    if let Err(e) = self.sync_book_to_redis(&instrument_id, book).await {
-                        warn!(error = %e, "Failed to sync book to Redis");
-                    }
-                }
-            }
-        }
-        
-        // Store trades
-        if result.has_trades() {
-            let mut trades = self.trades.write().await;
-            let instrument_trades = trades.entry(instrument_id.clone()).or_insert_with(Vec::new);
-            
-            for trade in &result.trades {
-                instrument_trades.push(trade.clone());
-                
-                // Trim to max
-                while instrument_trades.len() > self.max_trades {
-                    instrument_trades.remove(0);
-                }
-            }
-            
-            // TODO: Persist trades to Redis
-        }
-        
         debug!(
             instrument_id = %instrument_id,
             trades = result.trades.len(),
@@ -218,38 +650,21 @@ impl MatchingStore for RedisStore {
         instrument_id: &str,
         order_id: Uuid,
     ) -> StoreResult<Option<BookOrder>> {
-        // Load book from cache or Redis
-        {
-            let mut cache = self.cache.write().await;
-            if !cache.contains_key(instrument_id) {
-                if let Some(book) = self.load_book_from_redis(instrument_id).await? {
-                    cache.insert(instrument_id.to_string(), book);
-                }
-            }
-        }
-        
         let cancelled = {
             let mut engine = self.engine.write().await;
-            engine.cancel_order(instrument_id, order_id)
-        };
-        
-        // Update cache
-        if cancelled.is_some() {
-            let mut cache = self.cache.write().await;
-            if let Some(book) = cache.get_mut(instrument_id) {
-                book.remove_order(order_id);
-                book.cleanup_empty_levels();
-                
-                if book.is_empty() {
-                    cache.remove(instrument_id);
-                    let _ = self.delete_book_from_redis(instrument_id).await;
-                } else {
-                    if let Err(e) = self.sync_book_to_redis(instrument_id, book).await {
-                        warn!(error = %e, "Failed to sync book to Redis");
-                    }
-                }
+            self.ensure_writable().await?;
+            let previous = engine.sequence();
+            let cancelled = engine.cancel_order(instrument_id, order_id);
+            if cancelled.is_some() {
+                let event = MatchingEvent::OrderCancelled {
+                    order_id,
+                    instrument_id: instrument_id.to_string(),
+                    sequence: engine.sequence(),
+                };
+                self.apply(&engine, previous, vec![event], &[instrument_id.to_string()]).await?;
             }
-        }
+            cancelled
+        };
         
         if cancelled.is_some() {
             info!(order_id = %order_id, instrument_id = %instrument_id, "Order cancelled");
@@ -258,6 +673,211 @@ impl MatchingStore for RedisStore {
         Ok(cancelled)
     }
 
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> StoreResult<MassCancelResult> {
+        let result = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable().await?;
+            let previous = engine.sequence();
+            let result = engine.mass_cancel(filter);
+            if let Some(event) = MatchingEvent::from_mass_cancel(&result) {
+                let mut instruments: Vec<String> = result.cancelled.iter().map(|o| o.instrument_id.clone()).collect();
+                instruments.sort();
+                instruments.dedup();
+                self.apply(&engine, previous, vec![event], &instruments).await?;
+            }
+            result
+        };
+
+        if result.sequence.is_some() {
+            info!(orders = result.cancelled.len(), "Orders mass cancelled");
+        }
+
+        Ok(result)
+    }
+
+    async fn expire_orders(&self, now: DateTime<Utc>) -> StoreResult<Vec<ExpiredOrder>> {
+        let expired = {
+            let mut engine = self.engine.write().await;
+            self.ensure_writable().await?;
+            let previous = engine.sequence();
+            let expired = engine.expire_orders(now);
+            if !expired.is_empty() {
+                let mut instruments: Vec<String> = expired.iter().map(|e| e.order.instrument_id.clone()).collect();
+                instruments.sort();
+                instruments.dedup();
+                self.apply(&engine, previous, MatchingEvent::from_expired(&expired), &instruments).await?;
+            }
+            expired
+        };
+
+        if !expired.is_empty() {
+            info!(orders = expired.len(), "Orders expired");
+        }
+
+        Ok(expired)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+    ) -> StoreResult<AmendResult> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let result = engine.amend_order(order_id, new_price, new_quantity)?;
+        let events = MatchingEvent::from_amend(order_id, new_price, new_quantity, &result);
+        let mut instruments = mmp_instruments(&result.all_mmp_trips());
+        instruments.push(result.previous.instrument_id.clone());
+        instruments.sort();
+        instruments.dedup();
+        self.apply(&engine, previous, events, &instruments).await?;
+        Ok(result)
+    }
+
+    async fn update_reference_price(
+        &self,
+        instrument_id: &str,
+        source: TriggerSource,
+        price: Ticks,
+    ) -> StoreResult<Vec<TriggeredStop>> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let triggered = engine.update_reference_price(instrument_id, source, price);
+        let events = MatchingEvent::from_triggers(&triggered);
+        self.apply(&engine, previous, events, &[instrument_id.to_string()]).await?;
+        Ok(triggered)
+    }
+
+    async fn start_auction(&self, instrument_id: &str, reason: AuctionReason) -> StoreResult<CallAuction> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let auction = engine.start_auction(instrument_id, reason)?;
+        let event = MatchingEvent::auction_started(instrument_id, &auction);
+        self.apply(&engine, previous, vec![event], &[instrument_id.to_string()]).await?;
+        Ok(auction)
+    }
+
+    async fn uncross(&self, instrument_id: &str) -> StoreResult<UncrossResult> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let result = engine.uncross(instrument_id)?;
+        let events = MatchingEvent::from_uncross(&result);
+        self.apply(&engine, previous, events, &[instrument_id.to_string()]).await?;
+        Ok(result)
+    }
+
+    async fn get_auction(&self, instrument_id: &str) -> StoreResult<Option<CallAuction>> {
+        let engine = self.engine.read().await;
+        Ok(engine.auction(instrument_id).copied())
+    }
+
+    async fn get_indicative_uncross(&self, instrument_id: &str) -> StoreResult<Option<IndicativeUncross>> {
+        let engine = self.engine.read().await;
+        Ok(engine.indicative_uncross(instrument_id))
+    }
+
+    async fn define_combo(&self, combo: ComboDefinition) -> StoreResult<ComboDefinition> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let combo_id = combo.combo_id.clone();
+        if engine.define_combo(combo)?.is_some() {
+            let defined = engine.combo(&combo_id).cloned().expect("combo was just defined");
+            self.apply(&engine, previous, vec![MatchingEvent::combo_defined(&defined)], &[]).await?;
+        }
+        Ok(engine.combo(&combo_id).cloned().expect("combo is defined"))
+    }
+
+    async fn get_combos(&self) -> StoreResult<Vec<ComboDefinition>> {
+        let engine = self.engine.read().await;
+        Ok(engine.combos())
+    }
+
+    async fn book_block_trade(&self, block: BlockTrade) -> StoreResult<Vec<Trade>> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let trades = engine.book_block_trade(&block)?;
+        // Block trades never touch a book, so only the trades are written
+        self.apply(&engine, previous, MatchingEvent::from_block_trade(&trades), &[]).await?;
+        Ok(trades)
+    }
+
+    async fn mass_quote(&self, user_id: Uuid, quotes: Vec<BookOrder>) -> StoreResult<MassQuoteResult> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let result = engine.mass_quote(user_id, quotes)?;
+        let mut instruments: Vec<String> = result
+            .replaced
+            .cancelled
+            .iter()
+            .chain(result.quotes.iter().map(|(order, _)| order))
+            .map(|order| order.instrument_id.clone())
+            .collect();
+        instruments.sort();
+        instruments.dedup();
+        self.apply(&engine, previous, MatchingEvent::from_mass_quote(&result), &instruments).await?;
+        Ok(result)
+    }
+
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> StoreResult<bool> {
+        let mut engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        let previous = engine.sequence();
+        let Some(sequence) = engine.reset_mmp(user_id, underlying) else {
+            return Ok(false);
+        };
+        let event = MatchingEvent::MmpReset {
+            user_id,
+            underlying: underlying.to_string(),
+            sequence,
+        };
+        // A reset changes no book, so only the event is written
+        self.apply(&engine, previous, vec![event], &[]).await?;
+        Ok(true)
+    }
+
+    async fn get_mmp_status(&self, user_id: Uuid) -> StoreResult<Vec<MmpStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.mmp_status(user_id))
+    }
+
+    async fn halt_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn resume_instrument(&self, instrument_id: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_instrument(instrument_id);
+        Ok(())
+    }
+
+    async fn halt_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.halt_underlying(underlying);
+        Ok(())
+    }
+
+    async fn resume_underlying(&self, underlying: &str) -> StoreResult<()> {
+        self.engine.write().await.resume_underlying(underlying);
+        Ok(())
+    }
+
+    async fn get_circuit_breaker_status(&self) -> StoreResult<Vec<CircuitBreakerStatus>> {
+        let engine = self.engine.read().await;
+        Ok(engine.circuit_breaker_status())
+    }
+
+    async fn get_halted_underlyings(&self) -> StoreResult<Vec<String>> {
+        let engine = self.engine.read().await;
+        Ok(engine.halted_underlyings())
+    }
+
     async fn get_book(&self, instrument_id: &str) -> StoreResult<Option<OrderBook>> {
         // Check cache first
         {
@@ -278,21 +898,31 @@ impl MatchingStore for RedisStore {
         Ok(book)
     }
 
-    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let book = self.get_book(instrument_id).await?;
         Ok(book.and_then(|b| b.best_bid()))
     }
 
-    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let book = self.get_book(instrument_id).await?;
         Ok(book.and_then(|b| b.best_ask()))
     }
 
-    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<f64>> {
+    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<Ticks>> {
         let book = self.get_book(instrument_id).await?;
         Ok(book.and_then(|b| b.spread()))
     }
 
+    async fn price_scale(&self, instrument_id: &str) -> StoreResult<PriceScale> {
+        let engine = self.engine.read().await;
+        Ok(engine.price_scale(instrument_id))
+    }
+
+    async fn get_order(&self, order_id: Uuid) -> StoreResult<Option<BookOrder>> {
+        let engine = self.engine.read().await;
+        Ok(engine.get_order(order_id).cloned())
+    }
+
     async fn has_book(&self, instrument_id: &str) -> StoreResult<bool> {
         // Check cache
         {
@@ -308,27 +938,46 @@ impl MatchingStore for RedisStore {
     }
 
     async fn instruments(&self) -> StoreResult<Vec<String>> {
-        // For now, return from cache
-        // A production implementation would scan Redis keys
-        let cache = self.cache.read().await;
-        Ok(cache.keys().cloned().collect())
+        let prefix = self.book_key("");
+        let mut redis = self.redis.lock().await;
+        let mut keys = redis
+            .scan_match::<_, String>(format!("{}*", prefix))
+            .await
+            .map_err(|e| StoreError::RedisError(e.to_string()))?;
+
+        let mut instruments = Vec::new();
+        while let Some(key) = keys.next_item().await {
+            if let Some(instrument_id) = key.strip_prefix(&prefix) {
+                instruments.push(instrument_id.to_string());
+            }
+        }
+        // SCAN may return a key more than once
+        instruments.sort();
+        instruments.dedup();
+        Ok(instruments)
     }
 
     async fn get_trades(&self, instrument_id: &str, limit: u32) -> StoreResult<Vec<Trade>> {
-        let trades = self.trades.read().await;
-        
-        if let Some(instrument_trades) = trades.get(instrument_id) {
-            let start = instrument_trades.len().saturating_sub(limit as usize);
-            Ok(instrument_trades[start..].to_vec())
-        } else {
-            Ok(Vec::new())
+        if limit == 0 {
+            return Ok(Vec::new());
         }
+
+        let mut redis = self.redis.lock().await;
+        let trades: Vec<String> = redis
+            .lrange(self.trades_key(instrument_id), -(limit as isize), -1)
+            .await
+            .map_err(|e| StoreError::RedisError(e.to_string()))?;
+
+        trades
+            .iter()
+            .map(|json| serde_json::from_str(json).map_err(|e| StoreError::SerializationError(e.to_string())))
+            .collect()
     }
 
     async fn append_event(&self, event: MatchingEvent) -> StoreResult<()> {
-        let mut log = self.event_log.write().await;
-        log.append(event);
-        Ok(())
+        let engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        self.apply(&engine, engine.sequence(), vec![event], &[]).await
     }
 
     async fn get_events(&self, from_sequence: u64) -> StoreResult<Vec<MatchingEvent>> {
@@ -341,6 +990,22 @@ impl MatchingStore for RedisStore {
         Ok(log.sequence())
     }
 
+    /// Takes the engine write lock so no event can slip in between the
+    /// snapshot and the stream entry it is recorded as covering.
+    async fn snapshot(&self) -> StoreResult<()> {
+        if !self.persistence_enabled {
+            return Ok(());
+        }
+        let engine = self.engine.write().await;
+        self.ensure_writable().await?;
+        self.write_snapshot(&engine).await
+    }
+
+    async fn subscribe(&self, from_sequence: u64) -> StoreResult<EventSubscription> {
+        let log = self.event_log.read().await;
+        Ok(log.subscribe(from_sequence))
+    }
+
     fn engine(&self) -> MatchingEngine {
         panic!("Use query methods for Redis store")
     }
@@ -349,3 +1014,142 @@ impl MatchingStore for RedisStore {
         panic!("Use query methods for Redis store")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    //! These tests need a Redis server (`REDIS_HOST`/`REDIS_PORT`, default
+    //! 127.0.0.1:6379), so they are ignored by default; run them with
+    //! `cargo test -p matching-engine store::redis -- --ignored`.
+
+    use super::*;
+    use crate::domain::{OrderSide, TimeInForce};
+
+    fn redis_config() -> RedisConfig {
+        RedisConfig {
+            host: std::env::var("REDIS_HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
+            port: std::env::var("REDIS_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(6379),
+            password: String::new(),
+            cluster_mode: false,
+            db_index: 0,
+            persistence_enabled: true,
+            snapshot_interval_seconds: 3600,
+            max_trades_per_instrument: 2,
+            event_stream_max_len: 1000,
+        }
+    }
+
+    /// A fresh key prefix, so tests sharing a server do not see each other
+    fn test_prefix() -> String {
+        format!("test-{}", Uuid::new_v4())
+    }
+
+    async fn cleanup(config: &RedisConfig, prefix: &str) {
+        let client = redis::Client::open(format!("redis://{}:{}/", config.host, config.port)).unwrap();
+        let mut redis = client.get_multiplexed_async_connection().await.unwrap();
+        let keys: Vec<String> = {
+            let mut iter = redis.scan_match::<_, String>(format!("{}:*", prefix)).await.unwrap();
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+        if !keys.is_empty() {
+            redis.del::<_, ()>(keys).await.unwrap();
+        }
+    }
+
+    fn order(side: OrderSide, price: Ticks, quantity: u32) -> BookOrder {
+        BookOrder::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            side,
+            price,
+            quantity,
+            0,
+            TimeInForce::Gtc,
+        )
+        .with_instrument_id("BTC-50000-C")
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn test_restart_restores_books_trades_and_instruments() {
+        let config = redis_config();
+        let prefix = test_prefix();
+
+        {
+            let store = RedisStore::with_key_prefix(&config, &prefix).await.unwrap();
+            store.submit_order(order(OrderSide::Buy, 99, 5)).await.unwrap();
+            store.submit_order(order(OrderSide::Sell, 101, 10)).await.unwrap();
+            store.submit_order(order(OrderSide::Buy, 101, 1)).await.unwrap();
+            store.snapshot().await.unwrap();
+            // Written after the snapshot, so replayed from the stream
+            store.submit_order(order(OrderSide::Buy, 101, 1)).await.unwrap();
+            store.submit_order(order(OrderSide::Buy, 101, 1)).await.unwrap();
+        }
+
+        let store = RedisStore::with_key_prefix(&config, &prefix).await.unwrap();
+        assert_eq!(store.instruments().await.unwrap(), vec!["BTC-50000-C".to_string()]);
+        assert_eq!(store.get_best_bid("BTC-50000-C").await.unwrap(), Some(99));
+        let book = store.get_book("BTC-50000-C").await.unwrap().unwrap();
+        assert_eq!(book.ask_quantity_at(101), 7);
+
+        // Only the configured number of trades is kept
+        assert_eq!(store.get_trades("BTC-50000-C", 10).await.unwrap().len(), 2);
+
+        cleanup(&config, &prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn test_consumer_group_reads_and_acks_events() {
+        let config = redis_config();
+        let prefix = test_prefix();
+
+        let store = RedisStore::with_key_prefix(&config, &prefix).await.unwrap();
+        let mut consumer = store.consumer("settlement", "worker-1").await.unwrap();
+        store.submit_order(order(OrderSide::Sell, 101, 10)).await.unwrap();
+        store.submit_order(order(OrderSide::Buy, 101, 4)).await.unwrap();
+
+        let events = consumer.read(10, Some(Duration::from_millis(100))).await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2].event, MatchingEvent::TradeExecuted { .. }));
+
+        // Unacknowledged events are redelivered to the same consumer
+        assert_eq!(consumer.pending(10).await.unwrap().len(), 3);
+        let ids: Vec<String> = events.iter().map(|e| e.id.clone()).collect();
+        consumer.ack(&ids).await.unwrap();
+        assert!(consumer.pending(10).await.unwrap().is_empty());
+
+        cleanup(&config, &prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn test_stale_writer_is_fenced() {
+        let config = redis_config();
+        let prefix = test_prefix();
+
+        let first = RedisStore::with_key_prefix(&config, &prefix).await.unwrap();
+        let second = RedisStore::with_key_prefix(&config, &prefix).await.unwrap();
+        let bid = order(OrderSide::Buy, 99, 5);
+        let bid_id = bid.order_id;
+        first.submit_order(bid).await.unwrap();
+
+        let err = second.submit_order(order(OrderSide::Sell, 101, 5)).await.unwrap_err();
+        assert!(matches!(err, StoreError::RedisError(ref msg) if msg.contains("STALE")));
+        assert_eq!(first.get_best_ask("BTC-50000-C").await.unwrap(), None);
+
+        // The fenced sell is live in the second engine, so it refuses
+        // writes until rebuilt from Redis
+        let err = second.cancel_order("BTC-50000-C", Uuid::new_v4()).await.unwrap_err();
+        assert!(matches!(err, StoreError::Other(_)));
+        second.restore().await.unwrap();
+        assert!(second.get_order(bid_id).await.unwrap().is_some());
+        assert_eq!(second.get_best_ask("BTC-50000-C").await.unwrap(), None);
+        second.submit_order(order(OrderSide::Sell, 101, 5)).await.unwrap();
+
+        cleanup(&config, &prefix).await;
+    }
+}
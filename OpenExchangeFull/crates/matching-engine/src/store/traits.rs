@@ -3,12 +3,23 @@
 //! This module defines the trait that all store implementations must satisfy.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::domain::{BookOrder, OrderBook, Trade};
+use crate::auction::{AuctionReason, CallAuction, IndicativeUncross};
+use crate::block::BlockTrade;
+use crate::circuit_breaker::CircuitBreakerStatus;
+use crate::combo::ComboDefinition;
+use crate::domain::{BookOrder, MassCancelFilter, OrderBook, Trade, TriggerSource};
 use crate::engine::MatchingEngine;
 use crate::event::MatchingEvent;
-use crate::result::MatchResult;
+use crate::feed::EventSubscription;
+use crate::mmp::MmpStatus;
+use crate::price::{PriceScale, Ticks};
+use crate::result::{
+    AmendResult, ExpiredOrder, MassCancelResult, MassQuoteResult, MatchResult, TriggeredStop, UncrossResult,
+};
+use crate::wal::WalError;
 
 /// Errors that can occur in the store
 #[derive(Debug, thiserror::Error)]
@@ -25,12 +36,24 @@ pub enum StoreError {
     #[error("Serialization error: {0}")]
     SerializationError(String),
     
+    #[error("Journal error: {0}")]
+    JournalError(String),
+    
+    #[error("Matching error: {0}")]
+    Matching(#[from] crate::error::MatchingError),
+    
     #[error("Store error: {0}")]
     Other(String),
 }
 
 pub type StoreResult<T> = Result<T, StoreError>;
 
+impl From<WalError> for StoreError {
+    fn from(err: WalError) -> Self {
+        StoreError::JournalError(err.to_string())
+    }
+}
+
 /// Trait for order matching storage
 ///
 /// This trait defines the interface for storing order books and trades.
@@ -51,7 +74,111 @@ pub trait MatchingStore: Send + Sync {
     
     /// Cancel an order from the book
     async fn cancel_order(&self, instrument_id: &str, order_id: Uuid) -> StoreResult<Option<BookOrder>>;
+
+    /// Cancel every resting order and held stop selected by a filter
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> StoreResult<MassCancelResult>;
+
+    /// Remove every GTD or DAY order whose expiry is at or before `now`
+    async fn expire_orders(&self, now: DateTime<Utc>) -> StoreResult<Vec<ExpiredOrder>>;
+    
+    /// Amend a resting order's price and/or open quantity
+    ///
+    /// See `MatchingEngine::amend_order` for the priority rules.
+    async fn amend_order(
+        &self,
+        order_id: Uuid,
+        new_price: Option<Ticks>,
+        new_quantity: Option<u32>,
+    ) -> StoreResult<AmendResult>;
+
+    /// Update a mark, index or last-trade price for an instrument
+    ///
+    /// Every stop order the new price triggers is released and matched.
+    async fn update_reference_price(
+        &self,
+        instrument_id: &str,
+        source: TriggerSource,
+        price: Ticks,
+    ) -> StoreResult<Vec<TriggeredStop>>;
     
+    // ------------------------------------------------------------------------
+    // Call Auctions
+    // ------------------------------------------------------------------------
+
+    /// Put an instrument into a call auction (e.g. an opening auction)
+    async fn start_auction(&self, instrument_id: &str, reason: AuctionReason) -> StoreResult<CallAuction>;
+
+    /// Uncross an instrument's call auction and resume continuous trading
+    async fn uncross(&self, instrument_id: &str) -> StoreResult<UncrossResult>;
+
+    /// Get the running call auction of an instrument
+    async fn get_auction(&self, instrument_id: &str) -> StoreResult<Option<CallAuction>>;
+
+    /// Get the price and volume a running auction would uncross at now
+    async fn get_indicative_uncross(&self, instrument_id: &str) -> StoreResult<Option<IndicativeUncross>>;
+
+    // ------------------------------------------------------------------------
+    // Combos
+    // ------------------------------------------------------------------------
+
+    /// Define a combo instrument and return the definition in force
+    ///
+    /// Defining a combo again with the same legs is a no-op.
+    async fn define_combo(&self, combo: ComboDefinition) -> StoreResult<ComboDefinition>;
+
+    /// Get every defined combo
+    async fn get_combos(&self) -> StoreResult<Vec<ComboDefinition>>;
+
+    // ------------------------------------------------------------------------
+    // Block Trades
+    // ------------------------------------------------------------------------
+
+    /// Book a block trade agreed in an RFQ outside the order book
+    ///
+    /// Returns the booked trades: one for an outright, one per leg for a
+    /// combo.
+    async fn book_block_trade(&self, block: BlockTrade) -> StoreResult<Vec<Trade>>;
+
+    // ------------------------------------------------------------------------
+    // Mass Quotes
+    // ------------------------------------------------------------------------
+
+    /// Replace a maker's quotes on the instruments quoted
+    ///
+    /// See `MatchingEngine::mass_quote`; fails while market maker protection
+    /// has frozen quoting in any of the underlyings.
+    async fn mass_quote(&self, user_id: Uuid, quotes: Vec<BookOrder>) -> StoreResult<MassQuoteResult>;
+
+    /// Let a maker quote again in an underlying after MMP tripped
+    ///
+    /// Returns false if quoting was not frozen.
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> StoreResult<bool>;
+
+    /// A maker's MMP exposure and frozen state per underlying
+    async fn get_mmp_status(&self, user_id: Uuid) -> StoreResult<Vec<MmpStatus>>;
+
+    // ------------------------------------------------------------------------
+    // Circuit Breakers
+    // ------------------------------------------------------------------------
+
+    /// Halt an instrument until resumed (admin action)
+    async fn halt_instrument(&self, instrument_id: &str) -> StoreResult<()>;
+
+    /// Lift every halt of an instrument (admin action)
+    async fn resume_instrument(&self, instrument_id: &str) -> StoreResult<()>;
+
+    /// Halt every instrument of an underlying until resumed (admin action)
+    async fn halt_underlying(&self, underlying: &str) -> StoreResult<()>;
+
+    /// Lift a halt of an underlying (admin action)
+    async fn resume_underlying(&self, underlying: &str) -> StoreResult<()>;
+
+    /// Circuit breaker, halt and price band status per instrument
+    async fn get_circuit_breaker_status(&self) -> StoreResult<Vec<CircuitBreakerStatus>>;
+
+    /// Underlyings halted by an operator
+    async fn get_halted_underlyings(&self) -> StoreResult<Vec<String>>;
+
     // ------------------------------------------------------------------------
     // Book Queries
     // ------------------------------------------------------------------------
@@ -60,14 +187,20 @@ pub trait MatchingStore: Send + Sync {
     async fn get_book(&self, instrument_id: &str) -> StoreResult<Option<OrderBook>>;
     
     /// Get best bid price for an instrument
-    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<f64>>;
+    async fn get_best_bid(&self, instrument_id: &str) -> StoreResult<Option<Ticks>>;
     
     /// Get best ask price for an instrument
-    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<f64>>;
+    async fn get_best_ask(&self, instrument_id: &str) -> StoreResult<Option<Ticks>>;
     
     /// Get spread for an instrument
-    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<f64>>;
+    async fn get_spread(&self, instrument_id: &str) -> StoreResult<Option<Ticks>>;
     
+    /// Get the price scale used to convert decimal prices for an instrument
+    async fn price_scale(&self, instrument_id: &str) -> StoreResult<PriceScale>;
+
+    /// Get a resting order by ID
+    async fn get_order(&self, order_id: Uuid) -> StoreResult<Option<BookOrder>>;
+
     /// Check if an instrument has an order book
     async fn has_book(&self, instrument_id: &str) -> StoreResult<bool>;
     
@@ -93,6 +226,25 @@ pub trait MatchingStore: Send + Sync {
     
     /// Get current sequence number
     async fn get_sequence(&self) -> StoreResult<u64>;
+
+    /// Subscribe to events from a sequence number onwards
+    ///
+    /// Logged events are replayed first, then new events follow live in
+    /// sequence order. See [`crate::feed`] for lag handling.
+    async fn subscribe(&self, from_sequence: u64) -> StoreResult<EventSubscription>;
+
+    // ------------------------------------------------------------------------
+    // Snapshots
+    // ------------------------------------------------------------------------
+
+    /// Write a snapshot of the engine, so a restart replays only the events
+    /// after it
+    ///
+    /// Run on a timer by [`spawn_snapshots`](super::spawn_snapshots).
+    /// Stores without durable state have nothing to snapshot.
+    async fn snapshot(&self) -> StoreResult<()> {
+        Ok(())
+    }
     
     // ------------------------------------------------------------------------
     // Engine Access (for advanced operations)
@@ -0,0 +1,142 @@
+//! Stop order trigger book
+//!
+//! Stop orders are held outside the visible order book until their stop
+//! price is reached on the order's chosen reference price (last trade, mark
+//! or index). A triggered stop is released into the engine as a plain market
+//! (stop-market) or limit (stop-limit) order.
+//!
+//! Triggering is deterministic: among all stops whose condition holds, the
+//! one accepted first (lowest sequence) is released first, and conditions
+//! are re-evaluated after every release so cascades follow the same order.
+
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::domain::{BookOrder, OrderSide, TriggerSource};
+use crate::price::Ticks;
+
+/// Current reference prices for one instrument, in ticks
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReferencePrices {
+    /// Last trade price
+    pub last_trade: Option<Ticks>,
+    /// Mark price
+    pub mark: Option<Ticks>,
+    /// Index (underlying) price
+    pub index: Option<Ticks>,
+}
+
+impl ReferencePrices {
+    /// Price for a trigger source, if known
+    pub fn get(&self, source: TriggerSource) -> Option<Ticks> {
+        match source {
+            TriggerSource::LastTrade => self.last_trade,
+            TriggerSource::MarkPrice => self.mark,
+            TriggerSource::IndexPrice => self.index,
+        }
+    }
+}
+
+/// Stop orders waiting for their trigger, for one instrument
+///
+/// Keyed by acceptance sequence so iteration is in arrival order.
+#[derive(Debug, Clone, Default)]
+pub struct TriggerBook {
+    orders: BTreeMap<u64, BookOrder>,
+}
+
+impl TriggerBook {
+    /// Create an empty trigger book
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold a stop order until it triggers
+    pub fn insert(&mut self, order: BookOrder) {
+        self.orders.insert(order.sequence, order);
+    }
+
+    /// Remove a stop order by ID
+    pub fn remove(&mut self, order_id: Uuid) -> Option<BookOrder> {
+        let sequence = self
+            .orders
+            .iter()
+            .find(|(_, o)| o.order_id == order_id)
+            .map(|(seq, _)| *seq)?;
+        self.orders.remove(&sequence)
+    }
+
+    /// Look up a stop order by ID
+    pub fn get(&self, order_id: Uuid) -> Option<&BookOrder> {
+        self.orders.values().find(|o| o.order_id == order_id)
+    }
+
+    /// Remove and return the earliest stop whose condition holds
+    ///
+    /// Returns the order together with the reference price that fired it.
+    pub fn pop_triggered(&mut self, prices: &ReferencePrices) -> Option<(BookOrder, Ticks)> {
+        let (sequence, price) = self.orders.iter().find_map(|(seq, order)| {
+            let stop = order.stop.as_ref()?;
+            let price = prices.get(stop.source)?;
+            is_triggered(order.side, stop.stop_price, price).then_some((*seq, price))
+        })?;
+        self.orders.remove(&sequence).map(|order| (order, price))
+    }
+
+    /// All held stop orders in arrival order
+    pub fn orders(&self) -> impl Iterator<Item = &BookOrder> {
+        self.orders.values()
+    }
+
+    /// Number of held stop orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Check if no stop orders are held
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+}
+
+/// Buy stops fire at or above the stop price, sell stops at or below
+pub fn is_triggered(side: OrderSide, stop_price: Ticks, price: Ticks) -> bool {
+    match side {
+        OrderSide::Buy => price >= stop_price,
+        OrderSide::Sell => price <= stop_price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TimeInForce;
+
+    fn stop(side: OrderSide, stop_price: Ticks, source: TriggerSource, sequence: u64) -> BookOrder {
+        BookOrder::new(Uuid::new_v4(), Uuid::new_v4(), side, 0, 1, sequence, TimeInForce::Gtc)
+            .with_stop(stop_price, source)
+    }
+
+    #[test]
+    fn test_pop_triggered_in_arrival_order_per_source() {
+        let mut book = TriggerBook::new();
+        let late_buy = stop(OrderSide::Buy, 100, TriggerSource::LastTrade, 3);
+        let early_buy = stop(OrderSide::Buy, 105, TriggerSource::LastTrade, 1);
+        let sell = stop(OrderSide::Sell, 90, TriggerSource::LastTrade, 2);
+        let mark_buy = stop(OrderSide::Buy, 100, TriggerSource::MarkPrice, 4);
+        for order in [late_buy.clone(), early_buy.clone(), sell, mark_buy.clone()] {
+            book.insert(order);
+        }
+
+        let prices = ReferencePrices { last_trade: Some(106), ..Default::default() };
+        assert_eq!(book.pop_triggered(&prices).unwrap().0.order_id, early_buy.order_id);
+        assert_eq!(book.pop_triggered(&prices).unwrap().0.order_id, late_buy.order_id);
+        assert!(book.pop_triggered(&prices).is_none());
+
+        let prices = ReferencePrices { mark: Some(100), ..prices };
+        let (order, price) = book.pop_triggered(&prices).unwrap();
+        assert_eq!(order.order_id, mark_buy.order_id);
+        assert_eq!(price, 100);
+        assert_eq!(book.len(), 1);
+    }
+}
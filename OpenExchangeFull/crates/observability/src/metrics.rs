@@ -4,14 +4,23 @@
 //! and creating server-specific metric sets.
 
 use metrics::{counter, gauge, histogram, Counter, Gauge, Histogram};
-use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
 use std::net::SocketAddr;
 use std::time::Duration;
 
+/// Histogram buckets, in seconds, for every metric ending in `_seconds`
+///
+/// Spans matching latencies (microseconds) up to slow requests (seconds).
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.000_001, 0.000_005, 0.000_01, 0.000_025, 0.000_05, 0.000_1, 0.000_25, 0.000_5,
+    0.001, 0.002_5, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
 /// Initialize the Prometheus metrics exporter
 ///
 /// This starts an HTTP server on the specified port that exposes metrics
-/// at the `/metrics` endpoint.
+/// at the `/metrics` endpoint. Metrics ending in `_seconds` are exported
+/// as histograms with [`LATENCY_BUCKETS`]; other histograms as summaries.
 ///
 /// # Arguments
 ///
@@ -28,6 +37,7 @@ pub fn init_metrics(port: u16) -> anyhow::Result<()> {
 
     PrometheusBuilder::new()
         .with_http_listener(addr)
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS)?
         .install()?;
 
     tracing::info!(%addr, "Metrics server listening");
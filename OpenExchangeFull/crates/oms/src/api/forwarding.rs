@@ -11,6 +11,7 @@ use reqwest::Client;
 
 use common::addressbook::AddressBook;
 use crate::api::models::*;
+use crate::types::MassCancelFilter;
 
 pub struct OmsForwardingState {
     pub client: Client,
@@ -136,6 +137,58 @@ pub async fn forward_cancel_order(
     Ok(Json(result))
 }
 
+/// Forward mass cancel request
+pub async fn forward_mass_cancel(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path(env): Path<String>,
+    Query(filter): Query<MassCancelFilter>,
+) -> Result<Json<MassCancelResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/orders", oms_url, env);
+
+    let response = state.client
+        .delete(&url)
+        .query(&filter)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: MassCancelResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward amend order request
+pub async fn forward_amend_order(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, order_id)): Path<(String, String)>,
+    Json(req): Json<AmendOrderRequest>,
+) -> Result<Json<AmendOrderResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/orders/{}", oms_url, env, order_id);
+
+    let response = state.client
+        .patch(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: AmendOrderResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
 /// Forward get fills request
 pub async fn forward_get_fills(
     State(state): State<Arc<OmsForwardingState>>,
@@ -183,3 +236,209 @@ pub async fn forward_get_active_orders(
 
     Ok(Json(result))
 }
+
+/// Forward create RFQ request
+pub async fn forward_create_rfq(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path(env): Path<String>,
+    Json(req): Json<CreateRfqRequest>,
+) -> Result<Json<RfqResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs", oms_url, env);
+
+    let response = state.client
+        .post(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: RfqResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward list RFQs request
+pub async fn forward_list_rfqs(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path(env): Path<String>,
+    Query(params): Query<RfqUserParams>,
+) -> Result<Json<ListRfqsResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs", oms_url, env);
+
+    let response = state.client
+        .get(&url)
+        .query(&params)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: ListRfqsResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward get RFQ request
+pub async fn forward_get_rfq(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+) -> Result<Json<RfqResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs/{}", oms_url, env, rfq_id);
+
+    let response = state.client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: RfqResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward cancel RFQ request
+pub async fn forward_cancel_rfq(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Query(params): Query<RfqUserParams>,
+) -> Result<Json<RfqResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs/{}", oms_url, env, rfq_id);
+
+    let response = state.client
+        .delete(&url)
+        .query(&params)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: RfqResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward submit quote request
+pub async fn forward_submit_quote(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Json(req): Json<SubmitQuoteRequest>,
+) -> Result<Json<QuoteResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs/{}/quotes", oms_url, env, rfq_id);
+
+    let response = state.client
+        .post(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: QuoteResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward accept quote request
+pub async fn forward_accept_quote(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Json(req): Json<AcceptQuoteRequest>,
+) -> Result<Json<RfqResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/rfqs/{}/accept", oms_url, env, rfq_id);
+
+    let response = state.client
+        .post(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: RfqResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward mass quote request
+pub async fn forward_mass_quote(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path(env): Path<String>,
+    Json(req): Json<MassQuoteRequest>,
+) -> Result<Json<MassQuoteResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/mass-quotes", oms_url, env);
+
+    let response = state.client
+        .post(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: MassQuoteResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
+
+/// Forward reset market maker protection request
+pub async fn forward_reset_mmp(
+    State(state): State<Arc<OmsForwardingState>>,
+    Path((env, underlying)): Path<(String, String)>,
+    Json(req): Json<ResetMmpRequest>,
+) -> Result<Json<ResetMmpResponse>, String> {
+    let oms_url = state.address_book.get_oms_url()
+        .ok_or("OMS service not registered")?;
+
+    let url = format!("{}/api/v1/{}/mmp/{}/reset", oms_url, env, underlying);
+
+    let response = state.client
+        .post(&url)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result: ResetMmpResponse = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Json(result))
+}
@@ -9,7 +9,9 @@ pub fn oms_forwarding_routes(state: Arc<OmsForwardingState>) -> Router {
     Router::new()
         .route(
             "/api/v1/{env}/orders",
-            post(forward_create_order).get(forward_list_orders),
+            post(forward_create_order)
+                .get(forward_list_orders)
+                .delete(forward_mass_cancel),
         )
         .route(
             "/api/v1/{env}/orders/active/:user_id",
@@ -17,11 +19,37 @@ pub fn oms_forwarding_routes(state: Arc<OmsForwardingState>) -> Router {
         )
         .route(
             "/api/v1/{env}/orders/:order_id",
-            get(forward_get_order).delete(forward_cancel_order),
+            get(forward_get_order)
+                .delete(forward_cancel_order)
+                .patch(forward_amend_order),
         )
         .route(
             "/api/v1/{env}/orders/:order_id/fills",
             get(forward_get_fills),
         )
+        .route(
+            "/api/v1/{env}/rfqs",
+            post(forward_create_rfq).get(forward_list_rfqs),
+        )
+        .route(
+            "/api/v1/{env}/rfqs/:rfq_id",
+            get(forward_get_rfq).delete(forward_cancel_rfq),
+        )
+        .route(
+            "/api/v1/{env}/rfqs/:rfq_id/quotes",
+            post(forward_submit_quote),
+        )
+        .route(
+            "/api/v1/{env}/rfqs/:rfq_id/accept",
+            post(forward_accept_quote),
+        )
+        .route(
+            "/api/v1/{env}/mass-quotes",
+            post(forward_mass_quote),
+        )
+        .route(
+            "/api/v1/{env}/mmp/:underlying/reset",
+            post(forward_reset_mmp),
+        )
         .with_state(state)
 }
@@ -7,7 +7,9 @@ use axum::{
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::types::{Order, OrderStatus, Environment};
+use crate::types::{MassCancelFilter, Order, OrderStatus, Environment};
+use crate::rfq::Rfq;
+use common::types::{OrderType, PostOnly, Side, TimeInForce};
 use crate::manager::OrderManager;
 use crate::api::models::*;
 use crate::error::OmsError;
@@ -35,7 +37,7 @@ pub async fn create_order(
     // For now, use a default user ID (in production, get from auth)
     let user_id = Uuid::nil();
 
-    let order = Order::new(
+    let mut order = Order::new(
         user_id,
         req.instrument_id,
         req.side,
@@ -44,6 +46,11 @@ pub async fn create_order(
         req.price,
         req.quantity,
     );
+    order.post_only = req.post_only;
+    order.all_or_none = req.all_or_none;
+    order.min_quantity = req.min_quantity;
+    order.expires_at = req.expires_at;
+    order.legs = req.legs;
 
     match state.manager.submit_order(order, env).await {
         Ok(order) => Ok(Json(CreateOrderResponse::success(OrderResponse::from(order)))),
@@ -216,6 +223,95 @@ pub async fn cancel_order(
     }
 }
 
+/// Mass cancel handler
+///
+/// Cancels every open order selected by the `user_id`, `instrument_id`,
+/// `underlying` and `side` query parameters (at least one is required).
+pub async fn mass_cancel(
+    State(state): State<Arc<OmsApiState>>,
+    Path(env): Path<String>,
+    Query(filter): Query<MassCancelFilter>,
+) -> Result<Json<MassCancelResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+
+    match state.manager.mass_cancel(filter, env).await {
+        Ok(orders) => Ok(Json(MassCancelResponse {
+            success: true,
+            cancelled_count: orders.len() as u32,
+            cancelled_order_ids: orders.into_iter().map(|o| o.order_id).collect(),
+            error: None,
+        })),
+        Err(e) => {
+            let (status, code) = match e {
+                OmsError::ValidationError(_) => (axum::http::StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+                _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+            };
+            Err((
+                status,
+                Json(ErrorResponse {
+                    success: false,
+                    error: ErrorDetail {
+                        code: code.to_string(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                }),
+            ))
+        }
+    }
+}
+
+/// Amend order handler
+pub async fn amend_order(
+    State(state): State<Arc<OmsApiState>>,
+    Path((env, order_id)): Path<(String, String)>,
+    Json(req): Json<AmendOrderRequest>,
+) -> Result<Json<AmendOrderResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+    let order_id = Uuid::parse_str(&order_id)
+        .map_err(|_| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: ErrorDetail {
+                        code: "INVALID_ORDER_ID".to_string(),
+                        message: "Invalid order ID format".to_string(),
+                        details: None,
+                    },
+                }),
+            )
+        })?;
+
+    match state.manager.amend_order(order_id, req.price, req.quantity, env).await {
+        Ok(order) => Ok(Json(AmendOrderResponse {
+            success: true,
+            order: Some(OrderResponse::from(order)),
+            error: None,
+        })),
+        Err(e) => {
+            let (status, code) = match e {
+                OmsError::NotFound(_) => (axum::http::StatusCode::NOT_FOUND, "ORDER_NOT_FOUND"),
+                OmsError::OrderNotModifiable(_) => (axum::http::StatusCode::BAD_REQUEST, "INVALID_STATE"),
+                OmsError::ValidationError(_) => (axum::http::StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+                OmsError::RiskRejected(_) => (axum::http::StatusCode::BAD_REQUEST, "RISK_REJECTED"),
+                _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+            };
+            Err((
+                status,
+                Json(ErrorResponse {
+                    success: false,
+                    error: ErrorDetail {
+                        code: code.to_string(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                }),
+            ))
+        }
+    }
+}
+
 /// Get order fills handler
 pub async fn get_fills(
     State(state): State<Arc<OmsApiState>>,
@@ -306,3 +402,245 @@ pub async fn get_active_orders(
         )),
     }
 }
+
+/// Map an RFQ error to its status and code
+fn rfq_error(e: OmsError) -> (axum::http::StatusCode, Json<ErrorResponse>) {
+    let (status, code) = match e {
+        OmsError::RfqNotFound(_) => (axum::http::StatusCode::NOT_FOUND, "RFQ_NOT_FOUND"),
+        OmsError::InvalidState(_) => (axum::http::StatusCode::BAD_REQUEST, "INVALID_STATE"),
+        OmsError::ValidationError(_) => (axum::http::StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+        OmsError::RiskRejected(_) => (axum::http::StatusCode::BAD_REQUEST, "RISK_REJECTED"),
+        OmsError::InvalidOrder(_) | OmsError::InstrumentNotTradable(_) => {
+            (axum::http::StatusCode::BAD_REQUEST, "BLOCK_TRADE_REJECTED")
+        }
+        _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            success: false,
+            error: ErrorDetail {
+                code: code.to_string(),
+                message: e.to_string(),
+                details: None,
+            },
+        }),
+    )
+}
+
+/// Parse an RFQ ID path segment
+fn parse_rfq_id(rfq_id: &str) -> Result<Uuid, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    Uuid::parse_str(rfq_id).map_err(|_| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: ErrorDetail {
+                    code: "INVALID_RFQ_ID".to_string(),
+                    message: "Invalid RFQ ID format".to_string(),
+                    details: None,
+                },
+            }),
+        )
+    })
+}
+
+/// Create RFQ handler
+pub async fn create_rfq(
+    State(state): State<Arc<OmsApiState>>,
+    Path(env): Path<String>,
+    Json(req): Json<CreateRfqRequest>,
+) -> Result<Json<RfqResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+
+    let mut rfq = Rfq::new(req.requester_id, req.instrument_id, req.side, req.quantity, req.makers);
+    rfq.legs = req.legs;
+
+    match state.manager.request_quote(rfq, env).await {
+        Ok(rfq) => Ok(Json(RfqResponse {
+            success: true,
+            rfq: Some(rfq),
+            error: None,
+        })),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// List RFQs handler
+///
+/// Lists the RFQs the `user_id` query parameter requested or was asked to
+/// quote on.
+pub async fn list_rfqs(
+    State(state): State<Arc<OmsApiState>>,
+    Path(env): Path<String>,
+    Query(params): Query<RfqUserParams>,
+) -> Result<Json<ListRfqsResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+
+    match state.manager.list_rfqs(params.user_id, env).await {
+        Ok(rfqs) => Ok(Json(ListRfqsResponse { success: true, rfqs })),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// Get RFQ handler
+pub async fn get_rfq(
+    State(state): State<Arc<OmsApiState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+) -> Result<Json<RfqResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+    let rfq_id = parse_rfq_id(&rfq_id)?;
+
+    match state.manager.get_rfq(rfq_id, env).await {
+        Ok(Some(rfq)) => Ok(Json(RfqResponse {
+            success: true,
+            rfq: Some(rfq),
+            error: None,
+        })),
+        Ok(None) => Err(rfq_error(OmsError::RfqNotFound(rfq_id))),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// Cancel RFQ handler
+pub async fn cancel_rfq(
+    State(state): State<Arc<OmsApiState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Query(params): Query<RfqUserParams>,
+) -> Result<Json<RfqResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+    let rfq_id = parse_rfq_id(&rfq_id)?;
+
+    match state.manager.cancel_rfq(rfq_id, params.user_id, env).await {
+        Ok(rfq) => Ok(Json(RfqResponse {
+            success: true,
+            rfq: Some(rfq),
+            error: None,
+        })),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// Submit quote handler
+pub async fn submit_quote(
+    State(state): State<Arc<OmsApiState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Json(req): Json<SubmitQuoteRequest>,
+) -> Result<Json<QuoteResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+    let rfq_id = parse_rfq_id(&rfq_id)?;
+
+    match state.manager.submit_quote(rfq_id, req.maker_id, req.price, req.ttl_secs, env).await {
+        Ok(quote) => Ok(Json(QuoteResponse {
+            success: true,
+            quote: Some(quote),
+            error: None,
+        })),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// Accept quote handler
+pub async fn accept_quote(
+    State(state): State<Arc<OmsApiState>>,
+    Path((env, rfq_id)): Path<(String, String)>,
+    Json(req): Json<AcceptQuoteRequest>,
+) -> Result<Json<RfqResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+    let rfq_id = parse_rfq_id(&rfq_id)?;
+
+    match state.manager.accept_quote(rfq_id, req.quote_id, req.user_id, env).await {
+        Ok(rfq) => Ok(Json(RfqResponse {
+            success: true,
+            rfq: Some(rfq),
+            error: None,
+        })),
+        Err(e) => Err(rfq_error(e)),
+    }
+}
+
+/// Map a mass quote or MMP error to its status and code
+fn quote_error(e: OmsError) -> (axum::http::StatusCode, Json<ErrorResponse>) {
+    let (status, code) = match e {
+        OmsError::ValidationError(_) => (axum::http::StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+        OmsError::QuotingFrozen(_) => (axum::http::StatusCode::CONFLICT, "QUOTING_FROZEN"),
+        OmsError::InvalidOrder(_) | OmsError::InstrumentNotFound(_) => {
+            (axum::http::StatusCode::BAD_REQUEST, "QUOTE_REJECTED")
+        }
+        _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            success: false,
+            error: ErrorDetail {
+                code: code.to_string(),
+                message: e.to_string(),
+                details: None,
+            },
+        }),
+    )
+}
+
+/// Mass quote handler
+///
+/// Each bid and ask becomes a GTC post-only limit order of the maker;
+/// the maker's previous quotes on the instruments quoted are replaced.
+pub async fn mass_quote(
+    State(state): State<Arc<OmsApiState>>,
+    Path(env): Path<String>,
+    Json(req): Json<MassQuoteRequest>,
+) -> Result<Json<MassQuoteResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    let env = Environment::from(env.as_str());
+
+    let mut quotes = Vec::new();
+    for entry in req.quotes {
+        for (side, level) in [(Side::Buy, entry.bid), (Side::Sell, entry.ask)] {
+            let Some(level) = level else { continue };
+            let mut quote = Order::new(
+                req.user_id,
+                entry.instrument_id.clone(),
+                side,
+                OrderType::Limit,
+                TimeInForce::Gtc,
+                Some(level.price),
+                level.quantity,
+            );
+            quote.post_only = PostOnly::Reject;
+            quotes.push(quote);
+        }
+    }
+
+    match state.manager.mass_quote(req.user_id, quotes, env).await {
+        Ok(result) => {
+            let (resting, rejected): (Vec<Order>, Vec<Order>) = result
+                .quotes
+                .into_iter()
+                .partition(|quote| quote.status == OrderStatus::Open);
+            Ok(Json(MassQuoteResponse {
+                success: true,
+                resting_order_ids: resting.iter().map(|quote| quote.order_id).collect(),
+                rejected_order_ids: rejected.iter().map(|quote| quote.order_id).collect(),
+                replaced_order_ids: result.replaced.iter().map(|quote| quote.order_id).collect(),
+                error: None,
+            }))
+        }
+        Err(e) => Err(quote_error(e)),
+    }
+}
+
+/// Reset market maker protection handler
+pub async fn reset_mmp(
+    State(state): State<Arc<OmsApiState>>,
+    Path((_env, underlying)): Path<(String, String)>,
+    Json(req): Json<ResetMmpRequest>,
+) -> Result<Json<ResetMmpResponse>, (axum::http::StatusCode, Json<ErrorResponse>)> {
+    match state.manager.reset_mmp(req.user_id, &underlying).await {
+        Ok(reset) => Ok(Json(ResetMmpResponse {
+            success: true,
+            reset,
+            error: None,
+        })),
+        Err(e) => Err(quote_error(e)),
+    }
+}
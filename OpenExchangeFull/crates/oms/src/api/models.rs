@@ -2,8 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use common::types::{Side, OrderType, TimeInForce};
-use crate::types::{OrderStatus, Order};
+use common::types::{PostOnly, Side, OrderType, TimeInForce};
+use crate::types::{OrderLeg, OrderStatus, Order};
+use crate::rfq::{Rfq, RfqQuote};
 
 /// Request to create a new order
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,7 +17,20 @@ pub struct CreateOrderRequest {
     pub price: Option<f64>,
     pub quantity: u32,
     #[serde(default)]
+    pub post_only: PostOnly,
+    #[serde(default)]
+    pub all_or_none: bool,
+    #[serde(default)]
+    pub min_quantity: Option<u32>,
+    /// Expiry for GTD orders (DAY orders expire at the session end)
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
     pub client_order_id: Option<String>,
+    /// Legs of a combo order; `instrument_id` is then the combo ID and
+    /// `price` its net price
+    #[serde(default)]
+    pub legs: Vec<OrderLeg>,
 }
 
 fn default_time_in_force() -> TimeInForce {
@@ -65,6 +79,16 @@ pub struct OrderResponse {
     pub time_in_force: TimeInForce,
     pub price: Option<f64>,
     pub quantity: u32,
+    #[serde(default)]
+    pub post_only: PostOnly,
+    #[serde(default)]
+    pub all_or_none: bool,
+    #[serde(default)]
+    pub min_quantity: Option<u32>,
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub legs: Vec<OrderLeg>,
     pub filled_quantity: u32,
     pub remaining_quantity: u32,
     pub avg_fill_price: Option<f64>,
@@ -92,6 +116,11 @@ impl From<Order> for OrderResponse {
             time_in_force: order.time_in_force,
             price: order.price,
             quantity: order.quantity,
+            post_only: order.post_only,
+            all_or_none: order.all_or_none,
+            min_quantity: order.min_quantity,
+            expires_at: order.expires_at,
+            legs: order.legs,
             filled_quantity: order.filled_quantity,
             remaining_quantity: order.quantity - order.filled_quantity,
             avg_fill_price: order.avg_fill_price,
@@ -136,6 +165,9 @@ pub struct ListOrdersResponse {
 pub struct FillResponse {
     pub fill_id: Uuid,
     pub trade_id: Uuid,
+    /// Instrument traded (the leg, for combo orders)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instrument_id: Option<String>,
     pub quantity: u32,
     pub price: f64,
     pub fee: f64,
@@ -149,6 +181,7 @@ impl From<crate::types::OrderFill> for FillResponse {
         Self {
             fill_id: fill.fill_id,
             trade_id: fill.trade_id,
+            instrument_id: fill.instrument_id,
             quantity: fill.quantity,
             price: fill.price,
             fee: fill.fee,
@@ -168,6 +201,26 @@ pub struct GetFillsResponse {
     pub fills: Vec<FillResponse>,
 }
 
+/// Request to amend an open order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AmendOrderRequest {
+    /// New limit price
+    #[serde(default)]
+    pub price: Option<f64>,
+    /// New total quantity (including anything already filled)
+    #[serde(default)]
+    pub quantity: Option<u32>,
+}
+
+/// Amend order response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AmendOrderResponse {
+    pub success: bool,
+    pub order: Option<OrderResponse>,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
 /// Cancel order response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CancelOrderResponse {
@@ -177,6 +230,134 @@ pub struct CancelOrderResponse {
     pub error: Option<ErrorDetail>,
 }
 
+/// Mass cancel response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MassCancelResponse {
+    pub success: bool,
+    pub cancelled_count: u32,
+    pub cancelled_order_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
+/// One side of a maker's quote
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuoteLevel {
+    pub price: f64,
+    pub quantity: u32,
+}
+
+/// A maker's bid and/or ask on one instrument
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuoteEntry {
+    pub instrument_id: String,
+    #[serde(default)]
+    pub bid: Option<QuoteLevel>,
+    #[serde(default)]
+    pub ask: Option<QuoteLevel>,
+}
+
+/// Request to replace a maker's quotes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MassQuoteRequest {
+    pub user_id: Uuid,
+    pub quotes: Vec<QuoteEntry>,
+}
+
+/// Mass quote response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MassQuoteResponse {
+    pub success: bool,
+    /// New quotes resting in the book
+    pub resting_order_ids: Vec<Uuid>,
+    /// New quotes rejected by risk or for crossing the book
+    pub rejected_order_ids: Vec<Uuid>,
+    /// Previous quotes cancelled by the replace
+    pub replaced_order_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Request to let a maker quote again after market maker protection froze it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetMmpRequest {
+    pub user_id: Uuid,
+}
+
+/// MMP reset response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetMmpResponse {
+    pub success: bool,
+    /// Whether quoting was frozen and is now allowed again
+    pub reset: bool,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Request for quote on an instrument or combo
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRfqRequest {
+    pub requester_id: Uuid,
+    pub instrument_id: String,
+    pub side: Side,
+    pub quantity: u32,
+    /// Makers asked to quote
+    pub makers: Vec<Uuid>,
+    /// Legs of a combo; `instrument_id` is then the combo ID
+    #[serde(default)]
+    pub legs: Vec<OrderLeg>,
+}
+
+/// A maker's quote on an RFQ
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitQuoteRequest {
+    pub maker_id: Uuid,
+    /// Price for the full quantity (net price for a combo)
+    pub price: f64,
+    /// How long the quote stays firm (capped by the configured quote TTL)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+/// Request to accept a quote
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AcceptQuoteRequest {
+    /// Requester of the RFQ
+    pub user_id: Uuid,
+    pub quote_id: Uuid,
+}
+
+/// User whose RFQs are listed or cancelled
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RfqUserParams {
+    pub user_id: Uuid,
+}
+
+/// Single RFQ response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RfqResponse {
+    pub success: bool,
+    pub rfq: Option<Rfq>,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Quote response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuoteResponse {
+    pub success: bool,
+    pub quote: Option<RfqQuote>,
+    #[serde(default)]
+    pub error: Option<ErrorDetail>,
+}
+
+/// List RFQs response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListRfqsResponse {
+    pub success: bool,
+    pub rfqs: Vec<Rfq>,
+}
+
 /// Error detail
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorDetail {
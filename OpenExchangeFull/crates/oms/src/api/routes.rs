@@ -5,7 +5,9 @@ use axum::{
     Router,
 };
 use std::sync::Arc;
-use crate::api::handlers::{OmsApiState, health_handler, create_order, list_orders, get_active_orders, get_order, cancel_order, get_fills};
+use crate::api::handlers::{OmsApiState, health_handler, create_order, list_orders, get_active_orders, get_order, cancel_order, mass_cancel, amend_order, get_fills};
+use crate::api::handlers::{create_rfq, list_rfqs, get_rfq, cancel_rfq, submit_quote, accept_quote};
+use crate::api::handlers::{mass_quote, reset_mmp};
 
 /// Create the OMS router
 pub fn create_router(state: Arc<OmsApiState>) -> Router {
@@ -13,7 +15,7 @@ pub fn create_router(state: Arc<OmsApiState>) -> Router {
         .route("/api/v1/oms/health", get(health_handler))
         .route(
             "/api/v1/:env/orders",
-            post(create_order).get(list_orders),
+            post(create_order).get(list_orders).delete(mass_cancel),
         )
         .route(
             "/api/v1/:env/orders/active/:user_id",
@@ -21,12 +23,36 @@ pub fn create_router(state: Arc<OmsApiState>) -> Router {
         )
         .route(
             "/api/v1/:env/orders/:order_id",
-            get(get_order).delete(cancel_order),
+            get(get_order).delete(cancel_order).patch(amend_order),
         )
         .route(
             "/api/v1/:env/orders/:order_id/fills",
             get(get_fills),
         )
+        .route(
+            "/api/v1/:env/rfqs",
+            post(create_rfq).get(list_rfqs),
+        )
+        .route(
+            "/api/v1/:env/rfqs/:rfq_id",
+            get(get_rfq).delete(cancel_rfq),
+        )
+        .route(
+            "/api/v1/:env/rfqs/:rfq_id/quotes",
+            post(submit_quote),
+        )
+        .route(
+            "/api/v1/:env/rfqs/:rfq_id/accept",
+            post(accept_quote),
+        )
+        .route(
+            "/api/v1/:env/mass-quotes",
+            post(mass_quote),
+        )
+        .route(
+            "/api/v1/:env/mmp/:underlying/reset",
+            post(reset_mmp),
+        )
         .with_state(state)
 }
 
@@ -1,10 +1,20 @@
 //! Matching client - trait and implementations
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
-use crate::types::Order;
+use crate::types::{MassCancelFilter, Order};
 use crate::store::traits::OmsResult;
 
+/// What the matching engine did with a mass quote
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MassQuoteOutcome {
+    /// The maker's previous quotes removed from the books
+    pub replaced: Vec<Uuid>,
+    /// New quotes rejected because they would have crossed the book
+    pub rejected: Vec<Uuid>,
+}
+
 /// Client trait for Matching Engine - protocol agnostic
 #[async_trait]
 pub trait MatchingClient: Send + Sync {
@@ -19,9 +29,20 @@ pub trait MatchingClient: Send + Sync {
     
     /// Cancel an order in the matching engine
     ///
-    /// Removes the order from the order book.
+    /// Removes the order from the order book of `instrument_id`, the
+    /// order's instrument, so a sharded engine asks only the shard owning it.
     /// Returns Ok if order was removed or didn't exist.
-    async fn cancel_order(&self, order_id: Uuid) -> OmsResult<()>;
+    async fn cancel_order(&self, instrument_id: &str, order_id: Uuid) -> OmsResult<()>;
+    
+    /// Cancel every order selected by a filter in the matching engine
+    ///
+    /// Returns the IDs of the orders removed from the books.
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> OmsResult<Vec<Uuid>>;
+    
+    /// Remove every GTD and DAY order expired at `now` from the books
+    ///
+    /// Returns the IDs of the expired orders.
+    async fn expire_orders(&self, now: DateTime<Utc>) -> OmsResult<Vec<Uuid>>;
     
     /// Modify an order (cancel and replace)
     ///
@@ -31,6 +52,45 @@ pub trait MatchingClient: Send + Sync {
         old_order_id: Uuid,
         new_order: &Order,
     ) -> OmsResult<()>;
+    
+    /// Amend a resting order in place
+    ///
+    /// `instrument_id` is the order's instrument. `new_quantity` is the new
+    /// open (unfilled) quantity. A quantity decrease at the same price keeps
+    /// queue priority; a price change or quantity increase re-queues the
+    /// order.
+    async fn amend_order(
+        &self,
+        instrument_id: &str,
+        order_id: Uuid,
+        new_price: Option<f64>,
+        new_quantity: Option<u32>,
+    ) -> OmsResult<()>;
+    
+    /// Book a block trade agreed in an RFQ outside the order book
+    ///
+    /// The taker order carries the instrument (or combo legs), side,
+    /// agreed price and quantity; the maker order is on the other side.
+    /// Both should already be risk approved.
+    async fn book_block_trade(
+        &self,
+        rfq_id: Uuid,
+        taker: &Order,
+        maker: &Order,
+    ) -> OmsResult<()>;
+    
+    /// Replace a maker's quotes on every instrument quoted
+    ///
+    /// Each quote is a GTC post-only limit order of `user_id`, already
+    /// risk approved. Fails with `OmsError::QuotingFrozen` while market
+    /// maker protection has frozen the maker in an underlying quoted.
+    async fn mass_quote(&self, user_id: Uuid, quotes: &[Order]) -> OmsResult<MassQuoteOutcome>;
+    
+    /// Let a maker quote again in an underlying after market maker
+    /// protection froze it
+    ///
+    /// Returns false if quoting was not frozen.
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> OmsResult<bool>;
 }
 
 // ==================== Mock Implementation ====================
@@ -39,6 +99,12 @@ pub trait MatchingClient: Send + Sync {
 pub struct MockMatchingClient {
     submitted_orders: std::sync::Mutex<Vec<Uuid>>,
     cancelled_orders: std::sync::Mutex<Vec<Uuid>>,
+    amended_orders: std::sync::Mutex<Vec<Uuid>>,
+    mass_cancels: std::sync::Mutex<Vec<MassCancelFilter>>,
+    expiry_sweeps: std::sync::Mutex<Vec<DateTime<Utc>>>,
+    block_trades: std::sync::Mutex<Vec<Uuid>>,
+    mass_quotes: std::sync::Mutex<Vec<(Uuid, Vec<Uuid>)>>,
+    mmp_resets: std::sync::Mutex<Vec<(Uuid, String)>>,
 }
 
 impl MockMatchingClient {
@@ -47,6 +113,12 @@ impl MockMatchingClient {
         Self {
             submitted_orders: std::sync::Mutex::new(Vec::new()),
             cancelled_orders: std::sync::Mutex::new(Vec::new()),
+            amended_orders: std::sync::Mutex::new(Vec::new()),
+            mass_cancels: std::sync::Mutex::new(Vec::new()),
+            expiry_sweeps: std::sync::Mutex::new(Vec::new()),
+            block_trades: std::sync::Mutex::new(Vec::new()),
+            mass_quotes: std::sync::Mutex::new(Vec::new()),
+            mmp_resets: std::sync::Mutex::new(Vec::new()),
         }
     }
 
@@ -60,10 +132,46 @@ impl MockMatchingClient {
         self.cancelled_orders.lock().unwrap().clone()
     }
 
+    /// Get list of amended order IDs
+    pub fn get_amended_orders(&self) -> Vec<Uuid> {
+        self.amended_orders.lock().unwrap().clone()
+    }
+
+    /// Get list of mass cancel filters received
+    pub fn get_mass_cancels(&self) -> Vec<MassCancelFilter> {
+        self.mass_cancels.lock().unwrap().clone()
+    }
+
+    /// Get list of expiry sweep times received
+    pub fn get_expiry_sweeps(&self) -> Vec<DateTime<Utc>> {
+        self.expiry_sweeps.lock().unwrap().clone()
+    }
+
+    /// Get list of RFQ IDs whose block trades were booked
+    pub fn get_block_trades(&self) -> Vec<Uuid> {
+        self.block_trades.lock().unwrap().clone()
+    }
+
+    /// Get list of mass quotes received, as user and quote order IDs
+    pub fn get_mass_quotes(&self) -> Vec<(Uuid, Vec<Uuid>)> {
+        self.mass_quotes.lock().unwrap().clone()
+    }
+
+    /// Get list of MMP resets received
+    pub fn get_mmp_resets(&self) -> Vec<(Uuid, String)> {
+        self.mmp_resets.lock().unwrap().clone()
+    }
+
     /// Clear all tracked orders
     pub fn clear(&self) {
         self.submitted_orders.lock().unwrap().clear();
         self.cancelled_orders.lock().unwrap().clear();
+        self.amended_orders.lock().unwrap().clear();
+        self.mass_cancels.lock().unwrap().clear();
+        self.expiry_sweeps.lock().unwrap().clear();
+        self.block_trades.lock().unwrap().clear();
+        self.mass_quotes.lock().unwrap().clear();
+        self.mmp_resets.lock().unwrap().clear();
     }
 }
 
@@ -86,7 +194,7 @@ impl MatchingClient for MockMatchingClient {
         Ok(())
     }
 
-    async fn cancel_order(&self, order_id: Uuid) -> OmsResult<()> {
+    async fn cancel_order(&self, _instrument_id: &str, order_id: Uuid) -> OmsResult<()> {
         // Simulate some async delay
         tokio::time::sleep(std::time::Duration::from_millis(5)).await;
         
@@ -97,15 +205,93 @@ impl MatchingClient for MockMatchingClient {
         Ok(())
     }
 
+    async fn mass_cancel(&self, filter: &MassCancelFilter) -> OmsResult<Vec<Uuid>> {
+        // Simulate some async delay
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        
+        self.mass_cancels.lock().unwrap().push(filter.clone());
+        
+        tracing::debug!("Mock matching: mass cancel {:?}", filter);
+        
+        // The mock keeps no books, so nothing is reported as removed
+        Ok(Vec::new())
+    }
+
+    async fn expire_orders(&self, now: DateTime<Utc>) -> OmsResult<Vec<Uuid>> {
+        self.expiry_sweeps.lock().unwrap().push(now);
+        
+        tracing::debug!("Mock matching: expiry sweep at {}", now);
+        
+        // The mock keeps no books, so nothing is reported as expired
+        Ok(Vec::new())
+    }
+
     async fn modify_order(
         &self,
         old_order_id: Uuid,
         new_order: &Order,
     ) -> OmsResult<()> {
         // Simulate cancel then submit
-        self.cancel_order(old_order_id).await?;
+        self.cancel_order(&new_order.instrument_id, old_order_id).await?;
         self.submit_order(new_order).await
     }
+
+    async fn amend_order(
+        &self,
+        _instrument_id: &str,
+        order_id: Uuid,
+        _new_price: Option<f64>,
+        _new_quantity: Option<u32>,
+    ) -> OmsResult<()> {
+        // Simulate some async delay
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        
+        self.amended_orders.lock().unwrap().push(order_id);
+        
+        tracing::debug!("Mock matching: amended order {}", order_id);
+        
+        Ok(())
+    }
+
+    async fn book_block_trade(
+        &self,
+        rfq_id: Uuid,
+        taker: &Order,
+        _maker: &Order,
+    ) -> OmsResult<()> {
+        self.block_trades.lock().unwrap().push(rfq_id);
+        
+        tracing::debug!("Mock matching: booked block trade of RFQ {} on {}", rfq_id, taker.instrument_id);
+        
+        Ok(())
+    }
+
+    async fn mass_quote(&self, user_id: Uuid, quotes: &[Order]) -> OmsResult<MassQuoteOutcome> {
+        let mut mass_quotes = self.mass_quotes.lock().unwrap();
+        
+        // The mock keeps no books: every quote rests, replacing the
+        // user's previous mass quote whole
+        let replaced = mass_quotes
+            .iter()
+            .rev()
+            .find(|(user, _)| *user == user_id)
+            .map(|(_, ids)| ids.clone())
+            .unwrap_or_default();
+        mass_quotes.push((user_id, quotes.iter().map(|quote| quote.order_id).collect()));
+        
+        tracing::debug!("Mock matching: mass quote of {} quotes for user {}", quotes.len(), user_id);
+        
+        Ok(MassQuoteOutcome { replaced, rejected: Vec::new() })
+    }
+
+    async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> OmsResult<bool> {
+        self.mmp_resets.lock().unwrap().push((user_id, underlying.to_string()));
+        
+        tracing::debug!("Mock matching: MMP reset for user {} in {}", user_id, underlying);
+        
+        // The mock never trips protection
+        Ok(false)
+    }
 }
 
 // ==================== HTTP Implementation ====================
@@ -113,15 +299,17 @@ impl MatchingClient for MockMatchingClient {
 #[cfg(feature = "client")]
 pub mod http {
     use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
     use reqwest::Client;
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
-    use crate::types::Order;
+    use crate::types::{MassCancelFilter, Order};
     use common::types::Side as OrderSide;
     use crate::error::OmsError;
     use crate::store::traits::OmsResult;
-    use super::MatchingClient;
+    use super::{MassQuoteOutcome, MatchingClient};
     use common::types::TimeInForce as CommonTimeInForce;
+    use common::types::OrderType as CommonOrderType;
 
     /// Request format for matching engine API
     #[derive(Debug, Serialize)]
@@ -133,6 +321,21 @@ pub mod http {
         price: f64,
         quantity: u32,
         time_in_force: Option<String>,
+        order_type: Option<String>,
+        post_only: Option<String>,
+        all_or_none: bool,
+        min_quantity: Option<u32>,
+        expires_at: Option<DateTime<Utc>>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        legs: Vec<ComboLeg>,
+    }
+
+    /// Combo leg format for matching engine API
+    #[derive(Debug, Serialize)]
+    struct ComboLeg {
+        instrument_id: String,
+        side: OrderSide,
+        ratio: u32,
     }
 
     /// Response from matching engine
@@ -142,6 +345,116 @@ pub mod http {
         message: Option<String>,
     }
 
+    /// Mass cancel response from matching engine
+    #[derive(Debug, Deserialize)]
+    struct MassCancelResponse {
+        success: bool,
+        #[serde(default)]
+        cancelled_order_ids: Vec<Uuid>,
+        message: Option<String>,
+    }
+
+    /// Expiry sweep request format for matching engine API
+    #[derive(Debug, Serialize)]
+    struct ExpireOrdersRequest {
+        now: DateTime<Utc>,
+    }
+
+    /// Expiry sweep response from matching engine
+    #[derive(Debug, Deserialize)]
+    struct ExpireOrdersResponse {
+        success: bool,
+        #[serde(default)]
+        expired_order_ids: Vec<Uuid>,
+        message: Option<String>,
+    }
+
+    /// Amend request format for matching engine API
+    #[derive(Debug, Serialize)]
+    struct AmendOrderRequest {
+        price: Option<f64>,
+        quantity: Option<u32>,
+    }
+
+    /// Block trade request format for matching engine API
+    #[derive(Debug, Serialize)]
+    struct BlockTradeRequest {
+        rfq_id: Uuid,
+        instrument_id: String,
+        price: f64,
+        quantity: u32,
+        taker_order_id: Uuid,
+        taker_user_id: Uuid,
+        taker_side: String,
+        maker_order_id: Uuid,
+        maker_user_id: Uuid,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        legs: Vec<ComboLeg>,
+    }
+
+    /// One side of a quote in matching engine API format
+    #[derive(Debug, Serialize)]
+    struct QuoteLevel {
+        order_id: Uuid,
+        price: f64,
+        quantity: u32,
+    }
+
+    /// A bid and/or ask on one instrument in matching engine API format
+    #[derive(Debug, Serialize)]
+    struct QuoteEntry {
+        instrument_id: String,
+        bid: Option<QuoteLevel>,
+        ask: Option<QuoteLevel>,
+    }
+
+    /// Mass quote request format for matching engine API
+    #[derive(Debug, Serialize)]
+    struct MassQuoteRequest {
+        user_id: Uuid,
+        quotes: Vec<QuoteEntry>,
+    }
+
+    /// Mass quote response from matching engine
+    #[derive(Debug, Deserialize)]
+    struct MassQuoteResponse {
+        success: bool,
+        #[serde(default)]
+        replaced_order_ids: Vec<Uuid>,
+        #[serde(default)]
+        rejected_order_ids: Vec<Uuid>,
+        message: Option<String>,
+    }
+
+    /// MMP reset response from matching engine
+    #[derive(Debug, Deserialize)]
+    struct MmpResetResponse {
+        success: bool,
+        #[serde(default)]
+        reset: bool,
+        message: Option<String>,
+    }
+
+    /// Combo legs of an order in matching engine API format
+    fn combo_legs(order: &Order) -> Vec<ComboLeg> {
+        order
+            .legs
+            .iter()
+            .map(|leg| ComboLeg {
+                instrument_id: leg.instrument_id.clone(),
+                side: leg.side,
+                ratio: leg.ratio,
+            })
+            .collect()
+    }
+
+    fn side_str(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
     /// HTTP-based matching client
     pub struct HttpMatchingClient {
         client: Client,
@@ -163,26 +476,33 @@ pub mod http {
         async fn submit_order(&self, order: &Order) -> OmsResult<()> {
             let url = format!("{}/api/v1/internal/orders", self.base_url);
 
-            let side_str = match order.side {
-                OrderSide::Buy => "buy",
-                OrderSide::Sell => "sell",
-            };
-
             let tif_str = match order.time_in_force {
                 CommonTimeInForce::Gtc => Some("gtc".to_string()),
                 CommonTimeInForce::Ioc => Some("ioc".to_string()),
                 CommonTimeInForce::Fok => Some("fok".to_string()),
-                CommonTimeInForce::Day => Some("gtc".to_string()), // DAY treated as GTC
+                CommonTimeInForce::Gtd => Some("gtd".to_string()),
+                CommonTimeInForce::Day => Some("day".to_string()),
+            };
+
+            let order_type_str = match order.order_type {
+                CommonOrderType::Market => Some("market".to_string()),
+                _ => Some("limit".to_string()),
             };
 
             let request = SubmitOrderRequest {
                 instrument_id: order.instrument_id.clone(),
                 order_id: Some(order.order_id),
                 user_id: order.user_id,
-                side: side_str.to_string(),
+                side: side_str(order.side).to_string(),
                 price: order.price.unwrap_or(0.0),
                 quantity: order.quantity,
                 time_in_force: tif_str,
+                order_type: order_type_str,
+                post_only: Some(order.post_only.to_string()),
+                all_or_none: order.all_or_none,
+                min_quantity: order.min_quantity,
+                expires_at: order.expires_at,
+                legs: combo_legs(order),
             };
             let response = self.client
                 .post(&url)
@@ -199,13 +519,8 @@ pub mod http {
             Ok(())
         }
 
-        async fn cancel_order(&self, order_id: Uuid) -> OmsResult<()> {
-            // Note: We need instrument_id for proper cancellation
-            // For now, we'll try a common pattern - this may need refinement
-            // The matching service expects: /api/v1/internal/orders/:instrument_id/:order_id
-            // Since we don't have instrument_id here, we'll use a placeholder
-            // This is a limitation - the client should ideally know the instrument
-            let url = format!("{}/api/v1/internal/orders/__unknown__/{}", self.base_url, order_id);
+        async fn cancel_order(&self, instrument_id: &str, order_id: Uuid) -> OmsResult<()> {
+            let url = format!("{}/api/v1/internal/orders/{}/{}", self.base_url, instrument_id, order_id);
             
             let response = self.client
                 .delete(&url)
@@ -222,17 +537,494 @@ pub mod http {
             Ok(())
         }
 
+        async fn mass_cancel(&self, filter: &MassCancelFilter) -> OmsResult<Vec<Uuid>> {
+            let url = format!("{}/api/v1/internal/orders", self.base_url);
+
+            let response = self.client
+                .delete(&url)
+                .query(filter)
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: MassCancelResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                return Err(OmsError::MatchingUnavailable(
+                    result.message.unwrap_or_else(|| "Mass cancel rejected by matching engine".to_string()),
+                ));
+            }
+
+            Ok(result.cancelled_order_ids)
+        }
+
+        async fn expire_orders(&self, now: DateTime<Utc>) -> OmsResult<Vec<Uuid>> {
+            let url = format!("{}/api/v1/internal/expiry", self.base_url);
+
+            let response = self.client
+                .post(&url)
+                .json(&ExpireOrdersRequest { now })
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: ExpireOrdersResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                return Err(OmsError::MatchingUnavailable(
+                    result.message.unwrap_or_else(|| "Expiry sweep rejected by matching engine".to_string()),
+                ));
+            }
+
+            Ok(result.expired_order_ids)
+        }
+
+        async fn modify_order(
+            &self,
+            old_order_id: Uuid,
+            new_order: &Order,
+        ) -> OmsResult<()> {
+            // Cancel old (the replacement is on the same instrument)
+            self.cancel_order(&new_order.instrument_id, old_order_id).await?;
+            
+            // Submit new
+            self.submit_order(new_order).await
+        }
+
+        async fn amend_order(
+            &self,
+            _instrument_id: &str,
+            order_id: Uuid,
+            new_price: Option<f64>,
+            new_quantity: Option<u32>,
+        ) -> OmsResult<()> {
+            let url = format!("{}/api/v1/internal/orders/{}", self.base_url, order_id);
+
+            let request = AmendOrderRequest {
+                price: new_price,
+                quantity: new_quantity,
+            };
+            let response = self.client
+                .patch(&url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: SubmitOrderResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                return Err(OmsError::OrderNotModifiable(
+                    result.message.unwrap_or_else(|| "Amend rejected by matching engine".to_string()),
+                ));
+            }
+
+            Ok(())
+        }
+
+        async fn book_block_trade(
+            &self,
+            rfq_id: Uuid,
+            taker: &Order,
+            maker: &Order,
+        ) -> OmsResult<()> {
+            let url = format!("{}/api/v1/internal/block-trades", self.base_url);
+
+            let request = BlockTradeRequest {
+                rfq_id,
+                instrument_id: taker.instrument_id.clone(),
+                price: taker.price.unwrap_or(0.0),
+                quantity: taker.quantity,
+                taker_order_id: taker.order_id,
+                taker_user_id: taker.user_id,
+                taker_side: side_str(taker.side).to_string(),
+                maker_order_id: maker.order_id,
+                maker_user_id: maker.user_id,
+                legs: combo_legs(taker),
+            };
+            let response = self.client
+                .post(&url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: SubmitOrderResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                return Err(OmsError::InvalidOrder(
+                    result.message.unwrap_or_else(|| "Block trade rejected by matching engine".to_string()),
+                ));
+            }
+
+            Ok(())
+        }
+
+        async fn mass_quote(&self, user_id: Uuid, quotes: &[Order]) -> OmsResult<MassQuoteOutcome> {
+            let url = format!("{}/api/v1/internal/mass-quotes", self.base_url);
+
+            // The engine API takes one entry per side of an instrument
+            let entries = quotes
+                .iter()
+                .map(|quote| {
+                    let level = Some(QuoteLevel {
+                        order_id: quote.order_id,
+                        price: quote.price.unwrap_or(0.0),
+                        quantity: quote.quantity,
+                    });
+                    let (bid, ask) = match quote.side {
+                        OrderSide::Buy => (level, None),
+                        OrderSide::Sell => (None, level),
+                    };
+                    QuoteEntry {
+                        instrument_id: quote.instrument_id.clone(),
+                        bid,
+                        ask,
+                    }
+                })
+                .collect();
+            let response = self.client
+                .post(&url)
+                .json(&MassQuoteRequest { user_id, quotes: entries })
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: MassQuoteResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                let message = result.message.unwrap_or_else(|| "Mass quote rejected by matching engine".to_string());
+                return Err(if message.starts_with("Quoting frozen") {
+                    OmsError::QuotingFrozen(message)
+                } else {
+                    OmsError::InvalidOrder(message)
+                });
+            }
+
+            Ok(MassQuoteOutcome {
+                replaced: result.replaced_order_ids,
+                rejected: result.rejected_order_ids,
+            })
+        }
+
+        async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> OmsResult<bool> {
+            let url = format!("{}/api/v1/internal/mmp/{}/{}/reset", self.base_url, user_id, underlying);
+
+            let response = self.client
+                .post(&url)
+                .send()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(OmsError::MatchingUnavailable(error_text));
+            }
+
+            let result: MmpResetResponse = response
+                .json()
+                .await
+                .map_err(|e| OmsError::MatchingUnavailable(e.to_string()))?;
+            if !result.success {
+                return Err(OmsError::MatchingUnavailable(
+                    result.message.unwrap_or_else(|| "MMP reset rejected by matching engine".to_string()),
+                ));
+            }
+
+            Ok(result.reset)
+        }
+    }
+}
+
+// ==================== gRPC Implementation ====================
+
+#[cfg(feature = "grpc")]
+pub mod grpc {
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use matching_engine::grpc::proto;
+    use matching_engine::grpc::MatchingGrpcClient;
+    use tonic::{Code, Status};
+    use uuid::Uuid;
+    use crate::types::{MassCancelFilter, Order};
+    use common::types::Side as OrderSide;
+    use crate::error::OmsError;
+    use crate::store::traits::OmsResult;
+    use super::{MassQuoteOutcome, MatchingClient};
+    use common::types::TimeInForce as CommonTimeInForce;
+    use common::types::OrderType as CommonOrderType;
+    use common::types::PostOnly;
+
+    /// gRPC-based matching client
+    pub struct GrpcMatchingClient {
+        client: MatchingGrpcClient,
+    }
+
+    impl GrpcMatchingClient {
+        /// Create a client for a matching node; connects on first use
+        pub fn new(endpoint: &str) -> OmsResult<Self> {
+            let client = MatchingGrpcClient::connect_lazy(endpoint)
+                .map_err(|e| OmsError::ConfigError(format!("Invalid matching endpoint {}: {}", endpoint, e)))?;
+            Ok(Self { client })
+        }
+
+        /// Create a client over an existing matching gRPC client
+        pub fn from_client(client: MatchingGrpcClient) -> Self {
+            Self { client }
+        }
+    }
+
+    fn side(side: OrderSide) -> proto::Side {
+        match side {
+            OrderSide::Buy => proto::Side::Buy,
+            OrderSide::Sell => proto::Side::Sell,
+        }
+    }
+
+    /// Map a rejected submit to the OMS error for its status
+    fn submit_error(status: Status) -> OmsError {
+        let message = status.message().to_string();
+        match status.code() {
+            Code::InvalidArgument => OmsError::InvalidOrder(message),
+            Code::NotFound => OmsError::InstrumentNotFound(message),
+            Code::FailedPrecondition => OmsError::InstrumentNotTradable(message),
+            _ => OmsError::MatchingUnavailable(message),
+        }
+    }
+
+    /// Combo legs of an order in message form
+    fn combo_legs(order: &Order) -> Vec<proto::ComboLeg> {
+        order
+            .legs
+            .iter()
+            .map(|leg| proto::ComboLeg {
+                instrument_id: leg.instrument_id.clone(),
+                side: side(leg.side) as i32,
+                ratio: leg.ratio,
+            })
+            .collect()
+    }
+
+    fn unavailable(status: Status) -> OmsError {
+        OmsError::MatchingUnavailable(status.message().to_string())
+    }
+
+    /// Parse order IDs returned by the matching engine
+    fn order_ids(ids: Vec<String>) -> OmsResult<Vec<Uuid>> {
+        ids.iter()
+            .map(|id| Uuid::parse_str(id).map_err(|e| OmsError::MatchingUnavailable(e.to_string())))
+            .collect()
+    }
+
+    #[async_trait]
+    impl MatchingClient for GrpcMatchingClient {
+        async fn submit_order(&self, order: &Order) -> OmsResult<()> {
+            let time_in_force = match order.time_in_force {
+                CommonTimeInForce::Gtc => proto::TimeInForce::Gtc,
+                CommonTimeInForce::Ioc => proto::TimeInForce::Ioc,
+                CommonTimeInForce::Fok => proto::TimeInForce::Fok,
+                CommonTimeInForce::Gtd => proto::TimeInForce::Gtd,
+                CommonTimeInForce::Day => proto::TimeInForce::Day,
+            };
+
+            let order_type = match order.order_type {
+                CommonOrderType::Market => proto::OrderType::Market,
+                _ => proto::OrderType::Limit,
+            };
+
+            let post_only = match order.post_only {
+                PostOnly::None => proto::PostOnly::None,
+                PostOnly::Reject => proto::PostOnly::Reject,
+                PostOnly::Reprice => proto::PostOnly::Reprice,
+            };
+
+            let request = proto::SubmitOrderRequest {
+                instrument_id: order.instrument_id.clone(),
+                order_id: order.order_id.to_string(),
+                user_id: order.user_id.to_string(),
+                side: side(order.side) as i32,
+                price: order.price.unwrap_or(0.0),
+                quantity: order.quantity,
+                time_in_force: time_in_force as i32,
+                order_type: order_type as i32,
+                post_only: post_only as i32,
+                all_or_none: order.all_or_none,
+                min_quantity: order.min_quantity,
+                expires_at: order.expires_at.map(proto::timestamp),
+                legs: combo_legs(order),
+                ..Default::default()
+            };
+            self.client.submit_order(request).await.map_err(submit_error)?;
+
+            Ok(())
+        }
+
+        async fn cancel_order(&self, instrument_id: &str, order_id: Uuid) -> OmsResult<()> {
+            let request = proto::CancelOrderRequest {
+                instrument_id: instrument_id.to_string(),
+                order_id: order_id.to_string(),
+            };
+
+            // Not found is ok - order might not be in the book
+            match self.client.cancel_order(request).await {
+                Ok(_) => Ok(()),
+                Err(status) if status.code() == Code::NotFound => Ok(()),
+                Err(status) => Err(unavailable(status)),
+            }
+        }
+
+        async fn mass_cancel(&self, filter: &MassCancelFilter) -> OmsResult<Vec<Uuid>> {
+            let request = proto::MassCancelRequest {
+                user_id: filter.user_id.map(|id| id.to_string()),
+                instrument_id: filter.instrument_id.clone(),
+                underlying: filter.underlying.clone(),
+                side: filter.side.map(side).unwrap_or(proto::Side::Unspecified) as i32,
+            };
+            let response = self.client.mass_cancel(request).await.map_err(unavailable)?;
+
+            order_ids(response.cancelled_order_ids)
+        }
+
+        async fn expire_orders(&self, now: DateTime<Utc>) -> OmsResult<Vec<Uuid>> {
+            let request = proto::ExpireOrdersRequest {
+                now: Some(proto::timestamp(now)),
+            };
+            let response = self.client.expire_orders(request).await.map_err(unavailable)?;
+
+            order_ids(response.expired_order_ids)
+        }
+
         async fn modify_order(
             &self,
             old_order_id: Uuid,
             new_order: &Order,
         ) -> OmsResult<()> {
-            // Cancel old
-            self.cancel_order(old_order_id).await?;
+            // Cancel old (the replacement is on the same instrument)
+            self.cancel_order(&new_order.instrument_id, old_order_id).await?;
             
             // Submit new
             self.submit_order(new_order).await
         }
+
+        async fn amend_order(
+            &self,
+            _instrument_id: &str,
+            order_id: Uuid,
+            new_price: Option<f64>,
+            new_quantity: Option<u32>,
+        ) -> OmsResult<()> {
+            // The matching engine looks the instrument up from the order
+            let request = proto::AmendOrderRequest {
+                order_id: order_id.to_string(),
+                price: new_price,
+                quantity: new_quantity,
+            };
+
+            match self.client.amend_order(request).await {
+                Ok(_) => Ok(()),
+                Err(status) if matches!(
+                    status.code(),
+                    Code::InvalidArgument | Code::NotFound | Code::FailedPrecondition
+                ) => Err(OmsError::OrderNotModifiable(status.message().to_string())),
+                Err(status) => Err(unavailable(status)),
+            }
+        }
+
+        async fn book_block_trade(
+            &self,
+            rfq_id: Uuid,
+            taker: &Order,
+            maker: &Order,
+        ) -> OmsResult<()> {
+            let request = proto::BookBlockTradeRequest {
+                rfq_id: rfq_id.to_string(),
+                instrument_id: taker.instrument_id.clone(),
+                price: taker.price.unwrap_or(0.0),
+                quantity: taker.quantity,
+                taker_order_id: taker.order_id.to_string(),
+                taker_user_id: taker.user_id.to_string(),
+                taker_side: side(taker.side) as i32,
+                maker_order_id: maker.order_id.to_string(),
+                maker_user_id: maker.user_id.to_string(),
+                legs: combo_legs(taker),
+            };
+            self.client.book_block_trade(request).await.map_err(submit_error)?;
+
+            Ok(())
+        }
+
+        async fn mass_quote(&self, user_id: Uuid, quotes: &[Order]) -> OmsResult<MassQuoteOutcome> {
+            let request = proto::MassQuoteRequest {
+                user_id: user_id.to_string(),
+                quotes: quotes
+                    .iter()
+                    .map(|quote| proto::Quote {
+                        instrument_id: quote.instrument_id.clone(),
+                        side: side(quote.side) as i32,
+                        price: quote.price.unwrap_or(0.0),
+                        quantity: quote.quantity,
+                        order_id: Some(quote.order_id.to_string()),
+                    })
+                    .collect(),
+            };
+            let response = match self.client.mass_quote(request).await {
+                Ok(response) => response,
+                Err(status) if status.code() == Code::FailedPrecondition => {
+                    return Err(OmsError::QuotingFrozen(status.message().to_string()));
+                }
+                Err(status) => return Err(submit_error(status)),
+            };
+
+            Ok(MassQuoteOutcome {
+                replaced: order_ids(response.replaced_order_ids)?,
+                rejected: order_ids(response.rejected_order_ids)?,
+            })
+        }
+
+        async fn reset_mmp(&self, user_id: Uuid, underlying: &str) -> OmsResult<bool> {
+            let request = proto::ResetMmpRequest {
+                user_id: user_id.to_string(),
+                underlying: underlying.to_string(),
+            };
+            let response = self.client.reset_mmp(request).await.map_err(unavailable)?;
+
+            Ok(response.reset)
+        }
     }
 }
 
@@ -271,7 +1063,7 @@ mod tests {
         let order = create_test_order();
         
         client.submit_order(&order).await.unwrap();
-        client.cancel_order(order.order_id).await.unwrap();
+        client.cancel_order(&order.instrument_id, order.order_id).await.unwrap();
         
         let cancelled = client.get_cancelled_orders();
         assert!(cancelled.contains(&order.order_id));
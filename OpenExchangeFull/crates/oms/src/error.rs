@@ -14,6 +14,10 @@ pub enum OmsError {
     #[error("Order not found: {0}")]
     NotFound(Uuid),
 
+    /// RFQ not found
+    #[error("RFQ not found: {0}")]
+    RfqNotFound(Uuid),
+
     /// Order already exists
     #[error("Order already exists: {0}")]
     OrderExists(Uuid),
@@ -42,6 +46,10 @@ pub enum OmsError {
     #[error("Instrument not tradable: {0}")]
     InstrumentNotTradable(String),
 
+    /// Market maker protection froze the maker's quoting
+    #[error("Quoting frozen: {0}")]
+    QuotingFrozen(String),
+
     /// Risk rejected
     #[error("Risk rejected: {0}")]
     RiskRejected(String),
@@ -9,6 +9,9 @@
 //! - Risk engine integration
 //! - Matching engine integration
 //! - Order modification and cancellation
+//! - GTD and DAY order expiry sweeps
+//! - Request-for-quote workflow for off-book block trades
+//! - Mass quoting under market maker protection
 //! - Order history and fills
 //!
 //! # Feature Flags
@@ -16,20 +19,23 @@
 //! - `postgres` - Enable PostgreSQL storage
 //! - `api` - Enable HTTP API
 //! - `client` - Enable HTTP clients for external services
+//! - `grpc` - Enable the gRPC matching engine client
 
 pub mod types;
 pub mod error;
 pub mod store;
 pub mod clients;
 pub mod manager;
+pub mod rfq;
 
 #[cfg(feature = "api")]
 pub mod api;
 
 // Re-export commonly used types
-pub use types::{Order, OrderFill, OrderStatus, Environment};
+pub use types::{MassQuote, Order, OrderFill, OrderStatus, Environment};
 pub use error::{OmsError, Result};
-pub use manager::OrderManager;
+pub use manager::{spawn_expiry_sweeper, OrderManager};
+pub use rfq::{Rfq, RfqQuote, RfqStatus};
 
 // Store exports
 pub use store::traits::OrderStore;
@@ -40,10 +46,13 @@ pub use store::postgres::PostgresOrderStore;
 
 // Client exports
 pub use clients::risk::{RiskClient, RiskCheckResult, MockRiskClient};
-pub use clients::matching::{MatchingClient, MockMatchingClient};
+pub use clients::matching::{MassQuoteOutcome, MatchingClient, MockMatchingClient};
 
 #[cfg(feature = "client")]
 pub use clients::risk::http::HttpRiskClient;
 
 #[cfg(feature = "client")]
 pub use clients::matching::http::HttpMatchingClient;
+
+#[cfg(feature = "grpc")]
+pub use clients::matching::grpc::GrpcMatchingClient;
@@ -1,8 +1,11 @@
 //! Order Manager - core business logic for order handling
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use uuid::Uuid;
-use crate::types::{Order, OrderFill, OrderStatus, Environment};
+use crate::types::{MassCancelFilter, MassQuote, Order, OrderFill, OrderLeg, OrderStatus, Environment};
+use crate::rfq::{Rfq, RfqQuote, RfqStatus};
 use crate::store::traits::{OrderStore, OmsResult};
 use crate::clients::risk::RiskClient;
 use crate::clients::matching::MatchingClient;
@@ -15,6 +18,10 @@ pub struct OrderManager {
     risk_client: Arc<dyn RiskClient>,
     matching_client: Arc<dyn MatchingClient>,
     address_book: Arc<AddressBook>,
+    trading_hours: config::TradingHours,
+    rfq_config: config::RfqConfig,
+    /// Open and closed RFQs; held across an accept so a request books once
+    rfqs: Mutex<HashMap<Environment, HashMap<Uuid, Rfq>>>,
 }
 
 impl OrderManager {
@@ -30,9 +37,24 @@ impl OrderManager {
             risk_client,
             matching_client,
             address_book,
+            trading_hours: config::default_trading_hours(),
+            rfq_config: config::RfqConfig::default(),
+            rfqs: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Set the trading hours whose session end DAY orders expire at
+    pub fn with_trading_hours(mut self, trading_hours: config::TradingHours) -> Self {
+        self.trading_hours = trading_hours;
+        self
+    }
+
+    /// Set the RFQ TTLs and minimum block sizes
+    pub fn with_rfq_config(mut self, rfq_config: config::RfqConfig) -> Self {
+        self.rfq_config = rfq_config;
+        self
+    }
+
     /// Submit a new order
     ///
     /// Flow:
@@ -50,6 +72,9 @@ impl OrderManager {
 
         // Step 1: Basic validation
         self.validate_order(&order)?;
+        if order.time_in_force == common::types::TimeInForce::Day {
+            order.expires_at = Some(self.trading_hours.next_session_end(order.created_at));
+        }
 
         // Step 2: Store with PendingRisk status
         order.status = OrderStatus::PendingRisk;
@@ -112,7 +137,7 @@ impl OrderManager {
         // For now, we skip this as required_margin is the margin amount, not the lock ID
 
         // Cancel in matching engine
-        self.matching_client.cancel_order(order_id).await?;
+        self.matching_client.cancel_order(&order.instrument_id, order_id).await?;
 
         // Update order status
         order.status = OrderStatus::Cancelled;
@@ -124,6 +149,273 @@ impl OrderManager {
         Ok(order)
     }
 
+    /// Cancel every cancellable order selected by a filter
+    ///
+    /// Flow:
+    /// 1. Reject an empty filter (it would cancel every order)
+    /// 2. Mass cancel in matching engine
+    /// 3. Move every selected order to Cancelled in one store operation
+    pub async fn mass_cancel(
+        &self,
+        filter: MassCancelFilter,
+        env: Environment,
+    ) -> OmsResult<Vec<Order>> {
+        tracing::info!("Mass cancelling orders matching {:?}", filter);
+
+        if filter.is_empty() {
+            return Err(OmsError::ValidationError(
+                "Mass cancel requires a user, instrument, underlying or side".to_string(),
+            ));
+        }
+
+        // Cancel in matching engine
+        let removed = self.matching_client.mass_cancel(&filter).await?;
+
+        // Update order statuses
+        let cancelled = self.order_store.cancel_matching(&filter, env).await?;
+
+        tracing::info!(
+            "Mass cancel removed {} orders from the book, cancelled {} orders",
+            removed.len(),
+            cancelled.len()
+        );
+
+        Ok(cancelled)
+    }
+
+    /// Expire every resting GTD and DAY order due at `now`
+    ///
+    /// Flow:
+    /// 1. Remove expired orders from the matching engine books
+    /// 2. Move every due order to Expired in one store operation
+    pub async fn expire_orders(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        env: Environment,
+    ) -> OmsResult<Vec<Order>> {
+        // Expire in matching engine
+        let removed = self.matching_client.expire_orders(now).await?;
+
+        // Update order statuses
+        let expired = self.order_store.expire_due(now, env).await?;
+
+        if !removed.is_empty() || !expired.is_empty() {
+            tracing::info!(
+                "Expiry sweep removed {} orders from the book, expired {} orders",
+                removed.len(),
+                expired.len()
+            );
+        }
+
+        Ok(expired)
+    }
+
+    /// Amend an open order's price and/or total quantity
+    ///
+    /// Flow:
+    /// 1. Validate the amend against the current order
+    /// 2. Re-check risk if the amend increases quantity or changes price
+    /// 3. Amend in matching engine (priority kept on quantity decrease)
+    /// 4. Persist the amended order
+    pub async fn amend_order(
+        &self,
+        order_id: Uuid,
+        new_price: Option<f64>,
+        new_quantity: Option<u32>,
+        env: Environment,
+    ) -> OmsResult<Order> {
+        tracing::info!("Amending order {}: price={:?} quantity={:?}", order_id, new_price, new_quantity);
+
+        // Get order
+        let mut order = self.order_store
+            .get(order_id, env)
+            .await?
+            .ok_or(OmsError::NotFound(order_id))?;
+
+        // Check if modifiable (must be resting in the book)
+        if !order.is_active() {
+            return Err(OmsError::OrderNotModifiable(
+                format!("Cannot amend order in {:?} status", order.status)
+            ));
+        }
+
+        if new_price.is_none() && new_quantity.is_none() {
+            return Err(OmsError::ValidationError("Amend requires a new price or quantity".to_string()));
+        }
+        if let Some(price) = new_price {
+            if order.order_type == common::types::OrderType::Market {
+                return Err(OmsError::ValidationError("Market orders cannot be re-priced".to_string()));
+            }
+            if price <= 0.0 {
+                return Err(OmsError::ValidationError("Price must be greater than 0".to_string()));
+            }
+        }
+        if let Some(quantity) = new_quantity {
+            if quantity <= order.filled_quantity {
+                return Err(OmsError::ValidationError(format!(
+                    "Quantity must exceed filled quantity ({})",
+                    order.filled_quantity
+                )));
+            }
+        }
+
+        let price_changed = new_price.is_some() && new_price != order.price;
+        let quantity_increased = new_quantity.is_some_and(|q| q > order.quantity);
+
+        let mut amended = order.clone();
+        if let Some(price) = new_price {
+            amended.price = Some(price);
+        }
+        if let Some(quantity) = new_quantity {
+            amended.quantity = quantity;
+        }
+
+        // Larger or re-priced orders need fresh risk approval
+        if price_changed || quantity_increased {
+            let risk_result = self.risk_client
+                .check_order(&amended, &amended.instrument_id)
+                .await?;
+            if !risk_result.approved {
+                return Err(OmsError::RiskRejected(
+                    risk_result.reason.unwrap_or_else(|| "Amend rejected by risk".to_string())
+                ));
+            }
+            amended.required_margin = risk_result.required_margin;
+        }
+
+        // Amend in matching engine (engine works on open quantity)
+        self.matching_client
+            .amend_order(
+                &order.instrument_id,
+                order_id,
+                if price_changed { amended.price } else { None },
+                new_quantity.map(|q| q - order.filled_quantity),
+            )
+            .await?;
+
+        order = amended;
+        order.updated_at = chrono::Utc::now();
+        self.order_store.update(&order, env).await?;
+
+        tracing::info!("Order {} amended", order_id);
+
+        Ok(order)
+    }
+
+    /// Replace a maker's quotes with new bids and asks
+    ///
+    /// Flow:
+    /// 1. Validate each quote as a GTC post-only limit order of the maker
+    /// 2. Check risk for each; rejected quotes are stored Rejected and not sent
+    /// 3. Mass quote the approved ones in the matching engine, which
+    ///    replaces the maker's previous quotes on those instruments
+    /// 4. Cancel the replaced quotes and reject those that would have crossed
+    pub async fn mass_quote(
+        &self,
+        user_id: Uuid,
+        quotes: Vec<Order>,
+        env: Environment,
+    ) -> OmsResult<MassQuote> {
+        tracing::info!("Mass quoting {} quotes for user {}", quotes.len(), user_id);
+
+        // Step 1: Validate every quote before storing any
+        if quotes.is_empty() {
+            return Err(OmsError::ValidationError("Mass quote requires at least one quote".to_string()));
+        }
+        for quote in &quotes {
+            if quote.user_id != user_id {
+                return Err(OmsError::ValidationError(format!(
+                    "Quote {} belongs to another user",
+                    quote.order_id
+                )));
+            }
+            if quote.order_type != common::types::OrderType::Limit
+                || quote.time_in_force != common::types::TimeInForce::Gtc
+                || quote.post_only == common::types::PostOnly::None
+                || quote.is_combo()
+            {
+                return Err(OmsError::ValidationError(
+                    "Quotes must be GTC post-only limit orders on outright instruments".to_string(),
+                ));
+            }
+            self.validate_order(quote)?;
+        }
+
+        // Step 2: Store and check risk
+        let mut stored = Vec::with_capacity(quotes.len());
+        for mut quote in quotes {
+            quote.status = OrderStatus::PendingRisk;
+            let mut quote = self.order_store.create(quote, env).await?;
+
+            let risk_result = self.risk_client
+                .check_order(&quote, &quote.instrument_id)
+                .await?;
+            if risk_result.approved {
+                quote.status = OrderStatus::Open;
+                quote.risk_approved_at = Some(chrono::Utc::now());
+                quote.required_margin = risk_result.required_margin;
+            } else {
+                quote.status = OrderStatus::Rejected;
+                quote.risk_rejection_reason = risk_result.reason;
+            }
+            self.order_store.update(&quote, env).await?;
+            stored.push(quote);
+        }
+
+        // Step 3: Send the approved quotes to matching
+        let approved: Vec<Order> = stored
+            .iter()
+            .filter(|quote| quote.status == OrderStatus::Open)
+            .cloned()
+            .collect();
+        let outcome = match self.matching_client.mass_quote(user_id, &approved).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                for quote in stored.iter_mut().filter(|quote| quote.status == OrderStatus::Open) {
+                    quote.status = OrderStatus::Rejected;
+                    quote.updated_at = chrono::Utc::now();
+                    self.order_store.update(quote, env).await?;
+                }
+                tracing::warn!("Mass quote for user {} rejected: {}", user_id, e);
+                return Err(e);
+            }
+        };
+
+        // Step 4: Record what the engine replaced and rejected
+        let replaced = self.apply_cancels(&outcome.replaced, env).await?;
+        for quote in stored.iter_mut().filter(|quote| outcome.rejected.contains(&quote.order_id)) {
+            quote.status = OrderStatus::Rejected;
+            quote.updated_at = chrono::Utc::now();
+            self.order_store.update(quote, env).await?;
+        }
+
+        tracing::info!(
+            "Mass quote for user {} replaced {} quotes, {} of {} rejected",
+            user_id,
+            replaced.len(),
+            stored.iter().filter(|quote| quote.status == OrderStatus::Rejected).count(),
+            stored.len()
+        );
+
+        Ok(MassQuote { quotes: stored, replaced })
+    }
+
+    /// Let a maker quote again in an underlying after market maker
+    /// protection froze it
+    ///
+    /// Returns false if quoting was not frozen.
+    pub async fn reset_mmp(
+        &self,
+        user_id: Uuid,
+        underlying: &str,
+    ) -> OmsResult<bool> {
+        let reset = self.matching_client.reset_mmp(user_id, underlying).await?;
+        if reset {
+            tracing::info!("Market maker protection reset for user {} in {}", user_id, underlying);
+        }
+        Ok(reset)
+    }
+
     /// Apply a fill from matching engine
     pub async fn apply_fill(
         &self,
@@ -147,8 +439,18 @@ impl OrderManager {
             ));
         }
 
-        // Apply fill to order
-        order.apply_fill(fill.quantity, fill.price);
+        // Apply fill to order (a combo order's fills are leg trades)
+        match fill.instrument_id.as_deref().filter(|_| order.is_combo()) {
+            Some(leg) => {
+                if !order.apply_leg_fill(leg, fill.quantity, fill.price) {
+                    return Err(OmsError::ValidationError(format!(
+                        "Order {} has no leg on {}",
+                        order.order_id, leg
+                    )));
+                }
+            }
+            None => order.apply_fill(fill.quantity, fill.price),
+        }
         self.order_store.update(&order, env).await?;
 
         // Store fill record
@@ -160,6 +462,280 @@ impl OrderManager {
         Ok(order)
     }
 
+    /// Record orders the matching engine removed from its books on its own
+    ///
+    /// Used for quotes replaced by a mass quote or cancelled by market
+    /// maker protection, and for orders cancelled by self-trade
+    /// prevention. Unknown and already closed orders are skipped; returns
+    /// the orders moved to Cancelled.
+    pub async fn apply_cancels(
+        &self,
+        order_ids: &[Uuid],
+        env: Environment,
+    ) -> OmsResult<Vec<Order>> {
+        let mut cancelled = Vec::new();
+        for order_id in order_ids {
+            let Some(mut order) = self.order_store.get(*order_id, env).await? else {
+                continue;
+            };
+            if !order.can_cancel() {
+                continue;
+            }
+            order.status = OrderStatus::Cancelled;
+            order.updated_at = chrono::Utc::now();
+            self.order_store.update(&order, env).await?;
+            cancelled.push(order);
+        }
+        Ok(cancelled)
+    }
+
+    /// Open a request for quote
+    ///
+    /// The request stays open for the configured TTL and must be at least
+    /// the minimum block size of its underlying.
+    pub async fn request_quote(
+        &self,
+        mut rfq: Rfq,
+        env: Environment,
+    ) -> OmsResult<Rfq> {
+        tracing::info!("Requesting quote {} on {} for user {}", rfq.rfq_id, rfq.instrument_id, rfq.requester_id);
+
+        if rfq.instrument_id.is_empty() {
+            return Err(OmsError::ValidationError("Instrument ID is required".to_string()));
+        }
+        let underlying = rfq.instrument_id.split('-').next().unwrap_or(&rfq.instrument_id);
+        let min_size = self.rfq_config.min_block_size_for(underlying).max(1);
+        if rfq.quantity < min_size {
+            return Err(OmsError::ValidationError(format!(
+                "Quantity must be at least the minimum block size ({})",
+                min_size
+            )));
+        }
+        if rfq.is_combo() {
+            validate_legs(&rfq.instrument_id, &rfq.legs)?;
+        }
+        rfq.makers.sort();
+        rfq.makers.dedup();
+        if rfq.makers.is_empty() || rfq.makers.contains(&rfq.requester_id) {
+            return Err(OmsError::ValidationError(
+                "An RFQ needs at least one maker other than the requester".to_string(),
+            ));
+        }
+
+        let now = chrono::Utc::now();
+        rfq.status = RfqStatus::Open;
+        rfq.quotes.clear();
+        rfq.created_at = now;
+        rfq.updated_at = now;
+        rfq.expires_at = now + chrono::Duration::seconds(self.rfq_config.request_ttl_secs as i64);
+
+        let mut rfqs = self.rfqs.lock().await;
+        rfqs.entry(env).or_default().insert(rfq.rfq_id, rfq.clone());
+        Ok(rfq)
+    }
+
+    /// Submit a maker's firm quote on an open RFQ
+    ///
+    /// The quote stays live for `ttl_secs`, capped at the configured quote
+    /// TTL and the request's own expiry, and replaces the maker's previous
+    /// quote.
+    pub async fn submit_quote(
+        &self,
+        rfq_id: Uuid,
+        maker_id: Uuid,
+        price: f64,
+        ttl_secs: Option<u64>,
+        env: Environment,
+    ) -> OmsResult<RfqQuote> {
+        let mut rfqs = self.rfqs.lock().await;
+        let rfq = rfqs
+            .get_mut(&env)
+            .and_then(|rfqs| rfqs.get_mut(&rfq_id))
+            .ok_or(OmsError::RfqNotFound(rfq_id))?;
+
+        let now = chrono::Utc::now();
+        rfq.expire(now);
+        if !rfq.is_open() {
+            return Err(OmsError::InvalidState(format!("RFQ {} is {}", rfq_id, rfq.status)));
+        }
+        if !rfq.makers.contains(&maker_id) {
+            return Err(OmsError::ValidationError(format!("Maker {} was not asked to quote", maker_id)));
+        }
+        // Combo prices are net of the legs and may be zero or negative
+        if !price.is_finite() || (!rfq.is_combo() && price <= 0.0) {
+            return Err(OmsError::ValidationError("Price must be greater than 0".to_string()));
+        }
+
+        let ttl = ttl_secs.unwrap_or(self.rfq_config.quote_ttl_secs).min(self.rfq_config.quote_ttl_secs);
+        let quote = RfqQuote {
+            quote_id: Uuid::new_v4(),
+            maker_id,
+            price,
+            expires_at: (now + chrono::Duration::seconds(ttl as i64)).min(rfq.expires_at),
+            created_at: now,
+        };
+        rfq.quotes.retain(|existing| existing.maker_id != maker_id);
+        rfq.quotes.push(quote.clone());
+        rfq.updated_at = now;
+
+        tracing::info!("Maker {} quoted {} on RFQ {}", maker_id, price, rfq_id);
+
+        Ok(quote)
+    }
+
+    /// Accept a live quote and book the block trade
+    ///
+    /// Flow:
+    /// 1. Create the taker's and maker's FOK orders at the quoted price
+    /// 2. Check risk for both; a rejected maker's quote is withdrawn
+    /// 3. If approved: book the block trade in the matching engine, whose
+    ///    trades fill both orders like any other trade
+    /// 4. If rejected: reject both orders and keep the RFQ open
+    pub async fn accept_quote(
+        &self,
+        rfq_id: Uuid,
+        quote_id: Uuid,
+        user_id: Uuid,
+        env: Environment,
+    ) -> OmsResult<Rfq> {
+        tracing::info!("Accepting quote {} on RFQ {}", quote_id, rfq_id);
+
+        let mut rfqs = self.rfqs.lock().await;
+        let rfq = rfqs
+            .get_mut(&env)
+            .and_then(|rfqs| rfqs.get_mut(&rfq_id))
+            .ok_or(OmsError::RfqNotFound(rfq_id))?;
+
+        if rfq.requester_id != user_id {
+            return Err(OmsError::ValidationError("Only the requester can accept a quote".to_string()));
+        }
+        rfq.expire(chrono::Utc::now());
+        if !rfq.is_open() {
+            return Err(OmsError::InvalidState(format!("RFQ {} is {}", rfq_id, rfq.status)));
+        }
+        let quote = rfq
+            .quote(quote_id)
+            .cloned()
+            .ok_or_else(|| OmsError::ValidationError(format!("Quote {} is not live", quote_id)))?;
+
+        // Step 1: Store both sides with PendingRisk status
+        let mut taker = rfq.order(rfq.requester_id, rfq.side, quote.price);
+        let mut maker = rfq.order(quote.maker_id, rfq.side.opposite(), quote.price);
+        taker = self.order_store.create(taker, env).await?;
+        maker = self.order_store.create(maker, env).await?;
+
+        // Step 2: Check risk for both sides
+        let taker_risk = self.risk_client.check_order(&taker, &taker.instrument_id).await?;
+        let maker_risk = self.risk_client.check_order(&maker, &maker.instrument_id).await?;
+        if !taker_risk.approved || !maker_risk.approved {
+            let reason = if !taker_risk.approved {
+                taker_risk.reason.clone().unwrap_or_else(|| "Taker rejected by risk".to_string())
+            } else {
+                rfq.quotes.retain(|q| q.quote_id != quote_id);
+                maker_risk.reason.clone().unwrap_or_else(|| "Maker rejected by risk".to_string())
+            };
+            for (order, risk) in [(&mut taker, &taker_risk), (&mut maker, &maker_risk)] {
+                order.status = OrderStatus::Rejected;
+                order.risk_rejection_reason = risk.reason.clone().or_else(|| Some(reason.clone()));
+                order.updated_at = chrono::Utc::now();
+                self.order_store.update(order, env).await?;
+            }
+            tracing::warn!("Block trade on RFQ {} rejected by risk: {}", rfq_id, reason);
+            return Err(OmsError::RiskRejected(reason));
+        }
+
+        // Step 3: Book the block trade
+        for (order, risk) in [(&mut taker, &taker_risk), (&mut maker, &maker_risk)] {
+            order.status = OrderStatus::Open;
+            order.risk_approved_at = Some(chrono::Utc::now());
+            order.required_margin = risk.required_margin;
+            self.order_store.update(order, env).await?;
+        }
+        if let Err(e) = self.matching_client.book_block_trade(rfq_id, &taker, &maker).await {
+            for order in [&mut taker, &mut maker] {
+                order.status = OrderStatus::Cancelled;
+                order.updated_at = chrono::Utc::now();
+                self.order_store.update(order, env).await?;
+            }
+            tracing::warn!("Block trade on RFQ {} not booked: {}", rfq_id, e);
+            return Err(e);
+        }
+
+        rfq.status = RfqStatus::Filled;
+        rfq.accepted_quote_id = Some(quote_id);
+        rfq.taker_order_id = Some(taker.order_id);
+        rfq.maker_order_id = Some(maker.order_id);
+        rfq.updated_at = chrono::Utc::now();
+
+        tracing::info!("Block trade on RFQ {} booked with maker {}", rfq_id, quote.maker_id);
+
+        Ok(rfq.clone())
+    }
+
+    /// Cancel an open RFQ
+    pub async fn cancel_rfq(
+        &self,
+        rfq_id: Uuid,
+        user_id: Uuid,
+        env: Environment,
+    ) -> OmsResult<Rfq> {
+        let mut rfqs = self.rfqs.lock().await;
+        let rfq = rfqs
+            .get_mut(&env)
+            .and_then(|rfqs| rfqs.get_mut(&rfq_id))
+            .ok_or(OmsError::RfqNotFound(rfq_id))?;
+
+        if rfq.requester_id != user_id {
+            return Err(OmsError::ValidationError("Only the requester can cancel an RFQ".to_string()));
+        }
+        rfq.expire(chrono::Utc::now());
+        if !rfq.is_open() {
+            return Err(OmsError::InvalidState(format!("RFQ {} is {}", rfq_id, rfq.status)));
+        }
+        rfq.status = RfqStatus::Cancelled;
+        rfq.quotes.clear();
+        rfq.updated_at = chrono::Utc::now();
+
+        tracing::info!("RFQ {} cancelled", rfq_id);
+
+        Ok(rfq.clone())
+    }
+
+    /// Get an RFQ by ID
+    pub async fn get_rfq(
+        &self,
+        rfq_id: Uuid,
+        env: Environment,
+    ) -> OmsResult<Option<Rfq>> {
+        let mut rfqs = self.rfqs.lock().await;
+        Ok(rfqs.get_mut(&env).and_then(|rfqs| rfqs.get_mut(&rfq_id)).map(|rfq| {
+            rfq.expire(chrono::Utc::now());
+            rfq.clone()
+        }))
+    }
+
+    /// List the RFQs a user requested or was asked to quote on, newest first
+    pub async fn list_rfqs(
+        &self,
+        user_id: Uuid,
+        env: Environment,
+    ) -> OmsResult<Vec<Rfq>> {
+        let now = chrono::Utc::now();
+        let mut rfqs = self.rfqs.lock().await;
+        let mut listed: Vec<Rfq> = rfqs
+            .entry(env)
+            .or_default()
+            .values_mut()
+            .filter(|rfq| rfq.requester_id == user_id || rfq.makers.contains(&user_id))
+            .map(|rfq| {
+                rfq.expire(now);
+                rfq.clone()
+            })
+            .collect();
+        listed.sort_by_key(|rfq| std::cmp::Reverse(rfq.created_at));
+        Ok(listed)
+    }
+
     /// Get an order by ID
     pub async fn get_order(
         &self,
@@ -217,7 +793,8 @@ impl OrderManager {
             if order.price.is_none() {
                 return Err(OmsError::ValidationError("Limit orders require a price".to_string()));
             }
-            if let Some(price) = order.price {
+            // Combo prices are net of the legs and may be zero or negative
+            if let Some(price) = order.price.filter(|_| !order.is_combo()) {
                 if price <= 0.0 {
                     return Err(OmsError::ValidationError("Price must be greater than 0".to_string()));
                 }
@@ -229,11 +806,59 @@ impl OrderManager {
             return Err(OmsError::ValidationError("Market orders should not have a price".to_string()));
         }
 
+        // Validate execution instructions
+        if let Some(min_quantity) = order.min_quantity {
+            if min_quantity == 0 || min_quantity > order.quantity {
+                return Err(OmsError::ValidationError(
+                    "Minimum quantity must be between 1 and the order quantity".to_string(),
+                ));
+            }
+        }
+        if order.post_only != common::types::PostOnly::None {
+            if order.order_type != common::types::OrderType::Limit {
+                return Err(OmsError::ValidationError("Post-only orders must be limit orders".to_string()));
+            }
+            if matches!(
+                order.time_in_force,
+                common::types::TimeInForce::Ioc | common::types::TimeInForce::Fok
+            ) {
+                return Err(OmsError::ValidationError(
+                    "Post-only orders cannot be IOC or FOK".to_string(),
+                ));
+            }
+        }
+
+        // Validate expiry against time in force
+        match order.time_in_force {
+            common::types::TimeInForce::Gtd => match order.expires_at {
+                None => {
+                    return Err(OmsError::ValidationError("GTD orders require an expiry".to_string()));
+                }
+                Some(expires_at) if expires_at <= order.created_at => {
+                    return Err(OmsError::ValidationError("Expiry must be in the future".to_string()));
+                }
+                Some(_) => {}
+            },
+            common::types::TimeInForce::Day if order.expires_at.is_some() => {
+                return Err(OmsError::ValidationError(
+                    "DAY orders expire at the session end and take no expiry".to_string(),
+                ));
+            }
+            _ if order.expires_at.is_some() => {
+                return Err(OmsError::ValidationError("Only GTD orders take an expiry".to_string()));
+            }
+            _ => {}
+        }
+
         // Validate instrument ID is not empty
         if order.instrument_id.is_empty() {
             return Err(OmsError::ValidationError("Instrument ID is required".to_string()));
         }
 
+        if order.is_combo() {
+            validate_legs(&order.instrument_id, &order.legs)?;
+        }
+
         Ok(())
     }
 
@@ -243,6 +868,36 @@ impl OrderManager {
     }
 }
 
+/// Validate combo legs: distinct outright instruments of the combo's
+/// underlying, one at ratio 1
+fn validate_legs(combo_id: &str, legs: &[OrderLeg]) -> OmsResult<()> {
+    if legs.len() < 2 {
+        return Err(OmsError::ValidationError("Combo orders need at least two legs".to_string()));
+    }
+    for (i, leg) in legs.iter().enumerate() {
+        if leg.ratio == 0 {
+            return Err(OmsError::ValidationError("Leg ratios must be positive".to_string()));
+        }
+        if leg.instrument_id.is_empty()
+            || leg.instrument_id == combo_id
+            || legs[..i].iter().any(|other| other.instrument_id == leg.instrument_id)
+        {
+            return Err(OmsError::ValidationError(
+                "Legs must be on distinct outright instruments".to_string(),
+            ));
+        }
+        if leg.instrument_id.split('-').next() != combo_id.split('-').next() {
+            return Err(OmsError::ValidationError(
+                "Legs must be on the combo's underlying".to_string(),
+            ));
+        }
+    }
+    if !legs.iter().any(|leg| leg.ratio == 1) {
+        return Err(OmsError::ValidationError("At least one leg must have ratio 1".to_string()));
+    }
+    Ok(())
+}
+
 /// Create an OrderManager with mock clients (for testing/development)
 pub fn create_with_mocks(
     order_store: Arc<dyn OrderStore>,
@@ -255,6 +910,30 @@ pub fn create_with_mocks(
     )
 }
 
+/// Periodically expire due GTD and DAY orders in each environment
+///
+/// Sweeps every `period` until the task is aborted; a failed sweep is
+/// logged and retried on the next tick.
+pub fn spawn_expiry_sweeper(
+    manager: Arc<OrderManager>,
+    envs: Vec<Environment>,
+    period: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            let now = chrono::Utc::now();
+            for env in &envs {
+                if let Err(e) = manager.expire_orders(now, *env).await {
+                    tracing::warn!("Expiry sweep failed for {:?}: {}", env, e);
+                }
+            }
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,6 +1004,123 @@ mod tests {
         assert_eq!(cancelled.status, OrderStatus::Cancelled);
     }
 
+    #[tokio::test]
+    async fn test_mass_cancel_by_user_and_underlying() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store.clone());
+        let user_id = Uuid::new_v4();
+
+        let mut submitted = Vec::new();
+        for instrument_id in ["BTC-20260315-50000-C", "BTC-20260315-40000-P", "ETH-20260315-3000-C"] {
+            let mut order = create_test_order();
+            order.user_id = user_id;
+            order.instrument_id = instrument_id.to_string();
+            submitted.push(manager.submit_order(order, Environment::Static).await.unwrap());
+        }
+        let other_user = manager.submit_order(create_test_order(), Environment::Static).await.unwrap();
+
+        let filter = MassCancelFilter {
+            user_id: Some(user_id),
+            underlying: Some("BTC".to_string()),
+            ..Default::default()
+        };
+        let cancelled = manager.mass_cancel(filter, Environment::Static).await.unwrap();
+
+        let mut ids: Vec<Uuid> = cancelled.iter().map(|o| o.order_id).collect();
+        ids.sort();
+        let mut expected = vec![submitted[0].order_id, submitted[1].order_id];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        let status = |order_id| {
+            let store = store.clone();
+            async move { store.get(order_id, Environment::Static).await.unwrap().unwrap().status }
+        };
+        assert_eq!(status(submitted[0].order_id).await, OrderStatus::Cancelled);
+        assert_eq!(status(submitted[2].order_id).await, OrderStatus::Open);
+        assert_eq!(status(other_user.order_id).await, OrderStatus::Open);
+
+        // An empty filter would cancel everything
+        let result = manager.mass_cancel(MassCancelFilter::default(), Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_expire_gtd_and_day_orders() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store.clone());
+        let now = chrono::Utc::now();
+
+        let mut gtd = create_test_order();
+        gtd.time_in_force = TimeInForce::Gtd;
+        gtd.expires_at = Some(now + chrono::Duration::minutes(5));
+        let gtd = manager.submit_order(gtd, Environment::Static).await.unwrap();
+
+        let mut day = create_test_order();
+        day.time_in_force = TimeInForce::Day;
+        let day = manager.submit_order(day, Environment::Static).await.unwrap();
+        let session_end = day.expires_at.expect("DAY order gets the session end");
+        assert!(session_end > now && session_end <= now + chrono::Duration::days(1));
+
+        let gtc = manager.submit_order(create_test_order(), Environment::Static).await.unwrap();
+
+        let expired = manager.expire_orders(now + chrono::Duration::minutes(5), Environment::Static).await.unwrap();
+        assert_eq!(expired.iter().map(|o| o.order_id).collect::<Vec<_>>(), vec![gtd.order_id]);
+        assert_eq!(expired[0].status, OrderStatus::Expired);
+
+        let expired = manager.expire_orders(session_end, Environment::Static).await.unwrap();
+        assert_eq!(expired.iter().map(|o| o.order_id).collect::<Vec<_>>(), vec![day.order_id]);
+        let gtc = store.get(gtc.order_id, Environment::Static).await.unwrap().unwrap();
+        assert_eq!(gtc.status, OrderStatus::Open);
+
+        // GTD needs a future expiry; nothing else takes one
+        let mut past = create_test_order();
+        past.time_in_force = TimeInForce::Gtd;
+        past.expires_at = Some(now - chrono::Duration::minutes(1));
+        let result = manager.submit_order(past, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+
+        let mut expiring_gtc = create_test_order();
+        expiring_gtc.expires_at = Some(now + chrono::Duration::minutes(1));
+        let result = manager.submit_order(expiring_gtc, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_amend_order() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store.clone());
+        
+        let order = create_test_order();
+        let order = manager.submit_order(order, Environment::Static).await.unwrap();
+        
+        let amended = manager
+            .amend_order(order.order_id, Some(151.0), Some(6), Environment::Static)
+            .await
+            .unwrap();
+        
+        assert_eq!(amended.price, Some(151.0));
+        assert_eq!(amended.quantity, 6);
+        assert_eq!(amended.status, OrderStatus::Open);
+    }
+
+    #[tokio::test]
+    async fn test_amend_below_filled_quantity_fails() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store.clone());
+        
+        let order = create_test_order();
+        let order = manager.submit_order(order, Environment::Static).await.unwrap();
+        let fill = OrderFill::new(order.order_id, Uuid::new_v4(), 4, 150.0, true);
+        manager.apply_fill(order.order_id, fill, Environment::Static).await.unwrap();
+        
+        let result = manager
+            .amend_order(order.order_id, None, Some(4), Environment::Static)
+            .await;
+        
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+    }
+
     #[tokio::test]
     async fn test_cancel_filled_order_fails() {
         let store = Arc::new(InMemoryOrderStore::new());
@@ -341,4 +1137,144 @@ mod tests {
         
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_execution_instruction_validation() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store);
+
+        let mut order = create_test_order();
+        order.min_quantity = Some(11);
+        let result = manager.submit_order(order, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+
+        let mut order = create_test_order();
+        order.post_only = common::types::PostOnly::Reject;
+        order.time_in_force = TimeInForce::Ioc;
+        let result = manager.submit_order(order, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+
+        let mut order = create_test_order();
+        order.post_only = common::types::PostOnly::Reprice;
+        order.all_or_none = true;
+        order.min_quantity = Some(10);
+        let result = manager.submit_order(order, Environment::Static).await.unwrap();
+        assert_eq!(result.status, OrderStatus::Open);
+        assert!(result.all_or_none);
+    }
+
+    #[tokio::test]
+    async fn test_combo_order_applies_leg_fills() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store);
+
+        let mut order = create_test_order();
+        order.instrument_id = "BTC-20260315-STRADDLE".to_string();
+        order.price = Some(-5.0);
+        order.legs = vec![crate::types::OrderLeg::new("BTC-20260315-50000-C", Side::Buy, 1)];
+        let result = manager.submit_order(order.clone(), Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+
+        order.legs.push(crate::types::OrderLeg::new("BTC-20260315-50000-P", Side::Buy, 1));
+        let order = manager.submit_order(order, Environment::Static).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Open);
+
+        for (leg, price) in [("BTC-20260315-50000-C", 150.0), ("BTC-20260315-50000-P", 120.0)] {
+            let mut fill = OrderFill::new(order.order_id, Uuid::new_v4(), 10, price, false);
+            fill.instrument_id = Some(leg.to_string());
+            manager.apply_fill(order.order_id, fill, Environment::Static).await.unwrap();
+        }
+
+        let order = manager.get_order(order.order_id, Environment::Static).await.unwrap().unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.avg_fill_price, Some(270.0));
+        assert_eq!(order.legs[1].filled_quantity, 10);
+    }
+
+    #[tokio::test]
+    async fn test_rfq_quote_accepted_as_block_trade() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let matching = Arc::new(crate::clients::matching::MockMatchingClient::new());
+        let mut rfq_config = config::RfqConfig::default();
+        rfq_config.min_block_sizes.insert("BTC".to_string(), 25);
+        let manager = OrderManager::new(
+            store.clone(),
+            Arc::new(crate::clients::risk::MockRiskClient::new()),
+            matching.clone(),
+            AddressBook::new(),
+        )
+        .with_rfq_config(rfq_config);
+
+        let (taker, maker, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let request = |quantity| Rfq::new(taker, "BTC-20260315-50000-C".to_string(), Side::Buy, quantity, vec![maker]);
+        let result = manager.request_quote(request(10), Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+        let rfq = manager.request_quote(request(50), Environment::Static).await.unwrap();
+
+        // Only invited makers quote; a new quote replaces the maker's last one
+        let result = manager.submit_quote(rfq.rfq_id, other, 150.0, None, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+        manager.submit_quote(rfq.rfq_id, maker, 155.0, None, Environment::Static).await.unwrap();
+        let quote = manager.submit_quote(rfq.rfq_id, maker, 152.0, Some(3600), Environment::Static).await.unwrap();
+        assert!(quote.expires_at <= chrono::Utc::now() + chrono::Duration::seconds(10));
+        assert_eq!(manager.get_rfq(rfq.rfq_id, Environment::Static).await.unwrap().unwrap().quotes.len(), 1);
+
+        let result = manager.accept_quote(rfq.rfq_id, quote.quote_id, maker, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+        let filled = manager.accept_quote(rfq.rfq_id, quote.quote_id, taker, Environment::Static).await.unwrap();
+        assert_eq!(filled.status, RfqStatus::Filled);
+        assert_eq!(matching.get_block_trades(), vec![rfq.rfq_id]);
+        assert!(matching.get_submitted_orders().is_empty());
+
+        let taker_order = store.get(filled.taker_order_id.unwrap(), Environment::Static).await.unwrap().unwrap();
+        let maker_order = store.get(filled.maker_order_id.unwrap(), Environment::Static).await.unwrap().unwrap();
+        assert_eq!((taker_order.side, taker_order.price, taker_order.quantity), (Side::Buy, Some(152.0), 50));
+        assert_eq!((maker_order.user_id, maker_order.side), (maker, Side::Sell));
+        assert_eq!(maker_order.status, OrderStatus::Open);
+
+        // A filled RFQ takes no more quotes
+        let result = manager.submit_quote(rfq.rfq_id, maker, 150.0, None, Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::InvalidState(_))));
+        assert_eq!(manager.list_rfqs(maker, Environment::Static).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mass_quote_cancels_replaced_quotes() {
+        let store = Arc::new(InMemoryOrderStore::new());
+        let manager = create_with_mocks(store.clone());
+        let maker = Uuid::new_v4();
+        let quote = |side, price| {
+            let mut order = create_test_order();
+            order.user_id = maker;
+            order.side = side;
+            order.price = Some(price);
+            order.post_only = common::types::PostOnly::Reject;
+            order
+        };
+
+        let first = manager
+            .mass_quote(maker, vec![quote(Side::Buy, 145.0), quote(Side::Sell, 155.0)], Environment::Static)
+            .await
+            .unwrap();
+        assert!(first.replaced.is_empty());
+        assert!(first.quotes.iter().all(|q| q.status == OrderStatus::Open));
+
+        let second = manager
+            .mass_quote(maker, vec![quote(Side::Buy, 146.0)], Environment::Static)
+            .await
+            .unwrap();
+        assert_eq!(second.replaced.len(), 2);
+        for replaced in &first.quotes {
+            let order = store.get(replaced.order_id, Environment::Static).await.unwrap().unwrap();
+            assert_eq!(order.status, OrderStatus::Cancelled);
+        }
+
+        // Quotes are post-only orders of the maker
+        let mut taker = quote(Side::Buy, 150.0);
+        taker.post_only = common::types::PostOnly::None;
+        let result = manager.mass_quote(maker, vec![taker], Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+        let result = manager.mass_quote(Uuid::new_v4(), vec![quote(Side::Buy, 150.0)], Environment::Static).await;
+        assert!(matches!(result, Err(OmsError::ValidationError(_))));
+    }
 }
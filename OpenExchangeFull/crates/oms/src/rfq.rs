@@ -0,0 +1,200 @@
+//! Request for quote (RFQ) types
+//!
+//! A taker requests quotes on an instrument or combo from selected makers.
+//! Makers answer with firm quotes for the full quantity that stay live
+//! until their TTL; the taker accepts one before the request expires, and
+//! the agreed trade is booked as a block trade outside the order book.
+//!
+//! RFQs are kept in memory by the [`OrderManager`](crate::OrderManager);
+//! the orders and fills of an accepted quote are what gets persisted.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::types::{OrderType, Side, TimeInForce};
+use crate::types::{Order, OrderLeg};
+
+/// RFQ status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RfqStatus {
+    /// Open for quotes
+    Open,
+    /// A quote was accepted and booked
+    Filled,
+    /// Cancelled by the requester
+    Cancelled,
+    /// Expired without an accepted quote
+    Expired,
+}
+
+impl std::fmt::Display for RfqStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RfqStatus::Open => write!(f, "open"),
+            RfqStatus::Filled => write!(f, "filled"),
+            RfqStatus::Cancelled => write!(f, "cancelled"),
+            RfqStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+/// A maker's firm quote on an RFQ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RfqQuote {
+    /// Unique quote identifier
+    pub quote_id: Uuid,
+    /// Maker who quoted
+    pub maker_id: Uuid,
+    /// Price the maker trades the full quantity at (net price for a combo)
+    pub price: f64,
+    /// When the quote stops being firm
+    pub expires_at: DateTime<Utc>,
+    /// Quote creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request for quote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rfq {
+    /// Unique RFQ identifier
+    pub rfq_id: Uuid,
+    /// Taker who requested the quote
+    pub requester_id: Uuid,
+    /// Instrument (or combo) to trade
+    pub instrument_id: String,
+    /// Legs of a combo (empty for an outright)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub legs: Vec<OrderLeg>,
+    /// Side of the taker
+    pub side: Side,
+    /// Number of contracts (combo units for a combo)
+    pub quantity: u32,
+    /// Makers invited to quote
+    pub makers: Vec<Uuid>,
+    /// Current status
+    pub status: RfqStatus,
+    /// Live quotes, at most one per maker
+    pub quotes: Vec<RfqQuote>,
+    /// Quote the taker accepted
+    #[serde(default)]
+    pub accepted_quote_id: Option<Uuid>,
+    /// Taker's order of the booked block trade
+    #[serde(default)]
+    pub taker_order_id: Option<Uuid>,
+    /// Maker's order of the booked block trade
+    #[serde(default)]
+    pub maker_order_id: Option<Uuid>,
+    /// When the request stops accepting quotes
+    pub expires_at: DateTime<Utc>,
+    /// RFQ creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Rfq {
+    /// Create an open RFQ; the manager sets its expiry
+    pub fn new(
+        requester_id: Uuid,
+        instrument_id: String,
+        side: Side,
+        quantity: u32,
+        makers: Vec<Uuid>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            rfq_id: Uuid::new_v4(),
+            requester_id,
+            instrument_id,
+            legs: Vec::new(),
+            side,
+            quantity,
+            makers,
+            status: RfqStatus::Open,
+            quotes: Vec::new(),
+            accepted_quote_id: None,
+            taker_order_id: None,
+            maker_order_id: None,
+            expires_at: now,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Check if this RFQ is on a combo
+    pub fn is_combo(&self) -> bool {
+        !self.legs.is_empty()
+    }
+
+    /// Check if the RFQ still accepts quotes
+    pub fn is_open(&self) -> bool {
+        self.status == RfqStatus::Open
+    }
+
+    /// Drop quotes expired at `now`, and expire the RFQ itself if due
+    pub fn expire(&mut self, now: DateTime<Utc>) {
+        if !self.is_open() {
+            return;
+        }
+        self.quotes.retain(|quote| quote.expires_at > now);
+        if self.expires_at <= now {
+            self.status = RfqStatus::Expired;
+            self.quotes.clear();
+            self.updated_at = now;
+        }
+    }
+
+    /// Get a live quote by ID
+    pub fn quote(&self, quote_id: Uuid) -> Option<&RfqQuote> {
+        self.quotes.iter().find(|quote| quote.quote_id == quote_id)
+    }
+
+    /// Order one side of the block trade is booked under
+    ///
+    /// A fill-or-kill limit order at the quoted price for the full
+    /// quantity, carrying the RFQ's legs.
+    pub fn order(&self, user_id: Uuid, side: Side, price: f64) -> Order {
+        let mut order = Order::new(
+            user_id,
+            self.instrument_id.clone(),
+            side,
+            OrderType::Limit,
+            TimeInForce::Fok,
+            Some(price),
+            self.quantity,
+        );
+        order.legs = self.legs.clone();
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expire_drops_quotes_then_the_request() {
+        let now = Utc::now();
+        let mut rfq = Rfq::new(Uuid::new_v4(), "BTC-20260315-50000-C".to_string(), Side::Buy, 50, vec![]);
+        rfq.expires_at = now + chrono::Duration::seconds(60);
+        for ttl in [5, 30] {
+            rfq.quotes.push(RfqQuote {
+                quote_id: Uuid::new_v4(),
+                maker_id: Uuid::new_v4(),
+                price: 150.0,
+                expires_at: now + chrono::Duration::seconds(ttl),
+                created_at: now,
+            });
+        }
+
+        rfq.expire(now + chrono::Duration::seconds(10));
+        assert_eq!(rfq.quotes.len(), 1);
+        assert!(rfq.is_open());
+
+        rfq.expire(now + chrono::Duration::seconds(60));
+        assert_eq!(rfq.status, RfqStatus::Expired);
+        assert!(rfq.quotes.is_empty());
+    }
+}
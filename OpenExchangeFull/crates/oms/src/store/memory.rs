@@ -1,10 +1,11 @@
 //! In-memory order store implementation
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::RwLock;
 use uuid::Uuid;
-use crate::types::{Order, OrderFill, OrderStatus, Environment};
+use crate::types::{MassCancelFilter, Order, OrderFill, OrderStatus, Environment};
 use crate::store::traits::{OrderStore, OmsResult};
 use crate::error::OmsError;
 
@@ -186,6 +187,53 @@ impl OrderStore for InMemoryOrderStore {
         Ok(result)
     }
 
+    async fn cancel_matching(&self, filter: &MassCancelFilter, env: Environment) -> OmsResult<Vec<Order>> {
+        let mut orders = self.orders.write().unwrap();
+        let now = chrono::Utc::now();
+
+        let mut cancelled: Vec<Order> = orders
+            .get_mut(&env)
+            .map(|m| {
+                m.values_mut()
+                    .filter(|o| o.can_cancel() && filter.matches(o))
+                    .map(|o| {
+                        o.status = OrderStatus::Cancelled;
+                        o.updated_at = now;
+                        o.clone()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Oldest first, like the engine's sequence order
+        cancelled.sort_by_key(|o| o.created_at);
+
+        Ok(cancelled)
+    }
+
+    async fn expire_due(&self, now: DateTime<Utc>, env: Environment) -> OmsResult<Vec<Order>> {
+        let mut orders = self.orders.write().unwrap();
+        let updated_at = chrono::Utc::now();
+
+        let mut expired: Vec<Order> = orders
+            .get_mut(&env)
+            .map(|m| {
+                m.values_mut()
+                    .filter(|o| o.is_active() && o.expires_at.is_some_and(|at| at <= now))
+                    .map(|o| {
+                        o.status = OrderStatus::Expired;
+                        o.updated_at = updated_at;
+                        o.clone()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        expired.sort_by_key(|o| o.created_at);
+
+        Ok(expired)
+    }
+
     async fn create_fill(&self, fill: OrderFill, env: Environment) -> OmsResult<OrderFill> {
         let mut fills = self.fills.write().unwrap();
         let env_fills = fills.entry(env).or_insert_with(HashMap::new);
@@ -3,13 +3,15 @@
 #[cfg(feature = "postgres")]
 use async_trait::async_trait;
 #[cfg(feature = "postgres")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "postgres")]
 use sqlx::{postgres::PgPool, Row};
 #[cfg(feature = "postgres")]
 use std::sync::Arc;
 #[cfg(feature = "postgres")]
 use uuid::Uuid;
 #[cfg(feature = "postgres")]
-use crate::types::{Order, OrderFill, OrderStatus, Environment};
+use crate::types::{MassCancelFilter, Order, OrderFill, OrderStatus, Environment};
 #[cfg(feature = "postgres")]
 use crate::store::traits::{OrderStore, OmsResult};
 #[cfg(feature = "postgres")]
@@ -46,6 +48,7 @@ impl PostgresOrderStore {
 impl OrderStore for PostgresOrderStore {
     async fn create(&self, order: Order, env: Environment) -> OmsResult<Order> {
         let table = self.table_name(env);
+        let legs = Self::legs_json(&order)?;
         
         let result = sqlx::query(&format!(
             r#"
@@ -53,8 +56,9 @@ impl OrderStore for PostgresOrderStore {
                 order_id, user_id, instrument_id, side, order_type, time_in_force,
                 price, quantity, filled_quantity, avg_fill_price, status,
                 client_order_id, risk_approved_at, risk_rejection_reason,
-                required_margin, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                required_margin, created_at, updated_at,
+                post_only, all_or_none, min_quantity, expires_at, legs
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
             RETURNING order_id
             "#,
             table
@@ -76,6 +80,11 @@ impl OrderStore for PostgresOrderStore {
             .bind(order.required_margin)
             .bind(order.created_at)
             .bind(order.updated_at)
+            .bind(order.post_only.to_string())
+            .bind(order.all_or_none)
+            .bind(order.min_quantity.map(|q| q as i32))
+            .bind(order.expires_at)
+            .bind(legs)
             .fetch_one(&*self.pool)
             .await
             .map_err(|e| OmsError::StorageError(e.to_string()))?;
@@ -128,6 +137,7 @@ impl OrderStore for PostgresOrderStore {
 
     async fn update(&self, order: &Order, env: Environment) -> OmsResult<()> {
         let table = self.table_name(env);
+        let legs = Self::legs_json(order)?;
         
         sqlx::query(&format!(
             r#"
@@ -138,8 +148,9 @@ impl OrderStore for PostgresOrderStore {
                 risk_approved_at = $4,
                 risk_rejection_reason = $5,
                 required_margin = $6,
-                updated_at = $7
-            WHERE order_id = $8
+                updated_at = $7,
+                legs = $8
+            WHERE order_id = $9
             "#,
             table
         ))
@@ -150,6 +161,7 @@ impl OrderStore for PostgresOrderStore {
             .bind(&order.risk_rejection_reason)
             .bind(order.required_margin)
             .bind(order.updated_at)
+            .bind(legs)
             .bind(order.order_id)
             .execute(&*self.pool)
             .await
@@ -246,6 +258,68 @@ impl OrderStore for PostgresOrderStore {
             .collect()
     }
 
+    async fn cancel_matching(&self, filter: &MassCancelFilter, env: Environment) -> OmsResult<Vec<Order>> {
+        let table = self.table_name(env);
+        
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE {} SET
+                status = 'cancelled',
+                updated_at = $1
+            WHERE status IN ('pending_risk', 'open', 'partially_filled')
+                AND ($2::uuid IS NULL OR user_id = $2)
+                AND ($3::text IS NULL OR instrument_id = $3)
+                AND ($4::text IS NULL OR split_part(instrument_id, '-', 1) = $4)
+                AND ($5::text IS NULL OR side = $5)
+            RETURNING *
+            "#,
+            table
+        ))
+            .bind(chrono::Utc::now())
+            .bind(filter.user_id)
+            .bind(&filter.instrument_id)
+            .bind(&filter.underlying)
+            .bind(filter.side.map(|side| format!("{:?}", side).to_lowercase()))
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| OmsError::StorageError(e.to_string()))?;
+
+        let mut cancelled = rows.iter()
+            .map(|row| self.row_to_order(row))
+            .collect::<OmsResult<Vec<Order>>>()?;
+        cancelled.sort_by_key(|o| o.created_at);
+
+        Ok(cancelled)
+    }
+
+    async fn expire_due(&self, now: DateTime<Utc>, env: Environment) -> OmsResult<Vec<Order>> {
+        let table = self.table_name(env);
+        
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE {} SET
+                status = 'expired',
+                updated_at = $1
+            WHERE status IN ('open', 'partially_filled')
+                AND expires_at <= $2
+            RETURNING *
+            "#,
+            table
+        ))
+            .bind(chrono::Utc::now())
+            .bind(now)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| OmsError::StorageError(e.to_string()))?;
+
+        let mut expired = rows.iter()
+            .map(|row| self.row_to_order(row))
+            .collect::<OmsResult<Vec<Order>>>()?;
+        expired.sort_by_key(|o| o.created_at);
+
+        Ok(expired)
+    }
+
     async fn create_fill(&self, fill: OrderFill, env: Environment) -> OmsResult<OrderFill> {
         let table = self.fills_table_name(env);
         
@@ -254,8 +328,8 @@ impl OrderStore for PostgresOrderStore {
             INSERT INTO {} (
                 fill_id, order_id, trade_id, quantity, price,
                 counterparty_order_id, fee, fee_currency, is_maker,
-                executed_at, created_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                executed_at, created_at, instrument_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             "#,
             table
         ))
@@ -270,6 +344,7 @@ impl OrderStore for PostgresOrderStore {
             .bind(fill.is_maker)
             .bind(fill.executed_at)
             .bind(fill.created_at)
+            .bind(&fill.instrument_id)
             .execute(&*self.pool)
             .await
             .map_err(|e| OmsError::StorageError(e.to_string()))?;
@@ -333,8 +408,18 @@ impl OrderStore for PostgresOrderStore {
 
 #[cfg(feature = "postgres")]
 impl PostgresOrderStore {
+    /// Legs of a combo order as JSON (None for outright orders)
+    fn legs_json(order: &Order) -> OmsResult<Option<String>> {
+        if order.legs.is_empty() {
+            return Ok(None);
+        }
+        serde_json::to_string(&order.legs)
+            .map(Some)
+            .map_err(|e| OmsError::StorageError(e.to_string()))
+    }
+
     fn row_to_order(&self, row: &sqlx::postgres::PgRow) -> OmsResult<Order> {
-        use common::types::{Side, OrderType, TimeInForce};
+        use common::types::{PostOnly, Side, OrderType, TimeInForce};
         
         let side_str: String = row.get("side");
         let order_type_str: String = row.get("order_type");
@@ -356,9 +441,17 @@ impl PostgresOrderStore {
             "ioc" => TimeInForce::Ioc,
             "fok" => TimeInForce::Fok,
             "day" => TimeInForce::Day,
+            "gtd" => TimeInForce::Gtd,
             _ => TimeInForce::Gtc,
         };
 
+        let post_only_str: String = row.get("post_only");
+        let post_only = match post_only_str.as_str() {
+            "reject" => PostOnly::Reject,
+            "reprice" => PostOnly::Reprice,
+            _ => PostOnly::None,
+        };
+
         let status = match status_str.as_str() {
             "pending_risk" => OrderStatus::PendingRisk,
             "open" => OrderStatus::Open,
@@ -370,6 +463,12 @@ impl PostgresOrderStore {
             _ => OrderStatus::PendingRisk,
         };
 
+        let legs = match row.get::<Option<String>, _>("legs") {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| OmsError::StorageError(e.to_string()))?,
+            None => Vec::new(),
+        };
+
         Ok(Order {
             order_id: row.get("order_id"),
             user_id: row.get("user_id"),
@@ -379,6 +478,11 @@ impl PostgresOrderStore {
             time_in_force,
             price: row.get("price"),
             quantity: row.get::<i32, _>("quantity") as u32,
+            post_only,
+            all_or_none: row.get("all_or_none"),
+            min_quantity: row.get::<Option<i32>, _>("min_quantity").map(|q| q as u32),
+            expires_at: row.get("expires_at"),
+            legs,
             filled_quantity: row.get::<i32, _>("filled_quantity") as u32,
             avg_fill_price: row.get("avg_fill_price"),
             status,
@@ -402,6 +506,7 @@ impl PostgresOrderStore {
             fee: row.get("fee"),
             fee_currency: row.get("fee_currency"),
             is_maker: row.get("is_maker"),
+            instrument_id: row.get("instrument_id"),
             executed_at: row.get("executed_at"),
             created_at: row.get("created_at"),
         })
@@ -1,8 +1,9 @@
 //! OrderStore trait definition
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
-use crate::types::{Order, OrderFill, OrderStatus, Environment};
+use crate::types::{MassCancelFilter, Order, OrderFill, OrderStatus, Environment};
 use crate::error::OmsError;
 
 /// OrderStore trait - defines the interface for order storage
@@ -91,6 +92,32 @@ pub trait OrderStore: Send + Sync {
         env: Environment
     ) -> OmsResult<Vec<Order>>;
     
+    /// Cancel every cancellable order selected by a filter
+    ///
+    /// All selected orders in `PendingRisk`, `Open` or `PartiallyFilled`
+    /// status move to `Cancelled` in one operation.
+    ///
+    /// # Arguments
+    /// * `filter` - Which orders to cancel
+    /// * `env` - The environment
+    ///
+    /// # Returns
+    /// The cancelled orders
+    async fn cancel_matching(&self, filter: &MassCancelFilter, env: Environment) -> OmsResult<Vec<Order>>;
+    
+    /// Expire every resting order whose expiry is at or before `now`
+    ///
+    /// `Open` and `PartiallyFilled` orders with an `expires_at` move to
+    /// `Expired` in one operation.
+    ///
+    /// # Arguments
+    /// * `now` - The sweep time
+    /// * `env` - The environment
+    ///
+    /// # Returns
+    /// The expired orders
+    async fn expire_due(&self, now: DateTime<Utc>, env: Environment) -> OmsResult<Vec<Order>>;
+    
     /// Create a fill record
     ///
     /// # Arguments
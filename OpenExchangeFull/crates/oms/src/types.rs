@@ -6,7 +6,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use common::types::{Side, OrderType as CommonOrderType, TimeInForce as CommonTimeInForce};
+use common::types::{
+    PostOnly, Side, OrderType as CommonOrderType, TimeInForce as CommonTimeInForce,
+};
 
 /// Order status in the OMS
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -48,6 +50,36 @@ impl std::fmt::Display for OrderStatus {
     }
 }
 
+/// One leg of a combo (multi-leg) order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderLeg {
+    /// Outright instrument traded by the leg
+    pub instrument_id: String,
+    /// Side of the leg when the combo is bought
+    pub side: Side,
+    /// Contracts of the leg per unit of combo
+    pub ratio: u32,
+    /// Contracts of the leg filled
+    #[serde(default)]
+    pub filled_quantity: u32,
+    /// Average fill price of the leg
+    #[serde(default)]
+    pub avg_fill_price: Option<f64>,
+}
+
+impl OrderLeg {
+    /// Create an unfilled leg
+    pub fn new(instrument_id: impl Into<String>, side: Side, ratio: u32) -> Self {
+        Self {
+            instrument_id: instrument_id.into(),
+            side,
+            ratio,
+            filled_quantity: 0,
+            avg_fill_price: None,
+        }
+    }
+}
+
 /// Order in the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
@@ -67,6 +99,22 @@ pub struct Order {
     pub price: Option<f64>,
     /// Total quantity of contracts
     pub quantity: u32,
+    /// Post-only handling
+    #[serde(default)]
+    pub post_only: PostOnly,
+    /// Fill the whole quantity at once or not at all
+    #[serde(default)]
+    pub all_or_none: bool,
+    /// Minimum quantity that must fill on arrival
+    #[serde(default)]
+    pub min_quantity: Option<u32>,
+    /// When a GTD or DAY order expires
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Legs of a combo order (empty for an outright order); the
+    /// instrument is then the combo and the price its net price
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub legs: Vec<OrderLeg>,
     /// Filled quantity
     pub filled_quantity: u32,
     /// Average fill price
@@ -108,6 +156,11 @@ impl Order {
             time_in_force,
             price,
             quantity,
+            post_only: PostOnly::None,
+            all_or_none: false,
+            min_quantity: None,
+            expires_at: None,
+            legs: Vec::new(),
             filled_quantity: 0,
             avg_fill_price: None,
             status: OrderStatus::PendingRisk,
@@ -131,8 +184,52 @@ impl Order {
         
         self.filled_quantity = new_filled;
         self.updated_at = Utc::now();
+        self.update_fill_status();
+    }
+
+    /// Apply a fill of one leg of a combo order
+    ///
+    /// The combo counts as filled for as many units as every leg has
+    /// filled, per its ratio, and its average price is the net of the leg
+    /// averages (legs the combo buys count positive). Returns false if the
+    /// order has no leg on the instrument.
+    pub fn apply_leg_fill(&mut self, instrument_id: &str, fill_quantity: u32, fill_price: f64) -> bool {
+        let Some(leg) = self.legs.iter_mut().find(|leg| leg.instrument_id == instrument_id) else {
+            return false;
+        };
+        let new_filled = leg.filled_quantity + fill_quantity;
+        let total_value = (leg.avg_fill_price.unwrap_or(0.0) * leg.filled_quantity as f64)
+            + (fill_price * fill_quantity as f64);
+        leg.avg_fill_price = Some(total_value / new_filled as f64);
+        leg.filled_quantity = new_filled;
+
+        self.filled_quantity = self
+            .legs
+            .iter()
+            .map(|leg| leg.filled_quantity / leg.ratio.max(1))
+            .min()
+            .unwrap_or(0);
+        if self.legs.iter().all(|leg| leg.avg_fill_price.is_some()) {
+            let net = self
+                .legs
+                .iter()
+                .map(|leg| {
+                    let value = leg.ratio as f64 * leg.avg_fill_price.unwrap_or(0.0);
+                    match leg.side {
+                        Side::Buy => value,
+                        Side::Sell => -value,
+                    }
+                })
+                .sum();
+            self.avg_fill_price = Some(net);
+        }
+        self.updated_at = Utc::now();
+        self.update_fill_status();
+        true
+    }
 
-        // Update status
+    /// Move to filled or partially filled after a fill
+    fn update_fill_status(&mut self) {
         if self.filled_quantity >= self.quantity {
             self.status = OrderStatus::Filled;
         } else if self.filled_quantity > 0 {
@@ -140,6 +237,11 @@ impl Order {
         }
     }
 
+    /// Check if this is a combo (multi-leg) order
+    pub fn is_combo(&self) -> bool {
+        !self.legs.is_empty()
+    }
+
     /// Check if order can be cancelled
     pub fn can_cancel(&self) -> bool {
         matches!(
@@ -162,6 +264,54 @@ impl Order {
     }
 }
 
+/// Selects the orders cancelled by a mass cancel
+///
+/// Every criterion that is set must match. The underlying is the part of
+/// the instrument ID before the first `-` (e.g. `BTC` for `BTC-50000-C`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MassCancelFilter {
+    /// Only orders of this user
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
+    /// Only orders on this instrument
+    #[serde(default)]
+    pub instrument_id: Option<String>,
+    /// Only orders on instruments of this underlying
+    #[serde(default)]
+    pub underlying: Option<String>,
+    /// Only orders on this side
+    #[serde(default)]
+    pub side: Option<Side>,
+}
+
+impl MassCancelFilter {
+    /// Whether no criterion is set (the filter would select every order)
+    pub fn is_empty(&self) -> bool {
+        self.user_id.is_none()
+            && self.instrument_id.is_none()
+            && self.underlying.is_none()
+            && self.side.is_none()
+    }
+
+    /// Whether an order is selected by the filter
+    pub fn matches(&self, order: &Order) -> bool {
+        let underlying = order.instrument_id.split('-').next().unwrap_or(&order.instrument_id);
+        (self.user_id.is_none() || self.user_id == Some(order.user_id))
+            && (self.instrument_id.is_none() || self.instrument_id.as_deref() == Some(order.instrument_id.as_str()))
+            && (self.underlying.is_none() || self.underlying.as_deref() == Some(underlying))
+            && (self.side.is_none() || self.side == Some(order.side))
+    }
+}
+
+/// Outcome of a mass quote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MassQuote {
+    /// The new quotes: Open, or Rejected by risk or for crossing the book
+    pub quotes: Vec<Order>,
+    /// The maker's previous quotes on the instruments quoted, now Cancelled
+    pub replaced: Vec<Order>,
+}
+
 /// Fill record for an order
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderFill {
@@ -177,6 +327,9 @@ pub struct OrderFill {
     pub price: f64,
     /// Counterparty order ID
     pub counterparty_order_id: Option<Uuid>,
+    /// Instrument traded (the leg, for fills of combo orders)
+    #[serde(default)]
+    pub instrument_id: Option<String>,
     /// Fee charged
     pub fee: f64,
     /// Fee currency
@@ -206,6 +359,7 @@ impl OrderFill {
             quantity,
             price,
             counterparty_order_id: None,
+            instrument_id: None,
             fee: 0.0,
             fee_currency: "USDT".to_string(),
             is_maker,
@@ -301,6 +455,34 @@ mod tests {
         assert_eq!(order.status, OrderStatus::Filled);
     }
 
+    #[test]
+    fn test_combo_order_fills_per_leg() {
+        let mut order = Order::new(
+            Uuid::new_v4(),
+            "BTC-20260315-CALLSPREAD".to_string(),
+            Side::Buy,
+            CommonOrderType::Limit,
+            CommonTimeInForce::Gtc,
+            Some(40.0),
+            5,
+        );
+        order.legs = vec![
+            OrderLeg::new("BTC-20260315-50000-C", Side::Buy, 1),
+            OrderLeg::new("BTC-20260315-55000-C", Side::Sell, 2),
+        ];
+
+        assert!(order.apply_leg_fill("BTC-20260315-50000-C", 2, 150.0));
+        assert_eq!(order.filled_quantity, 0);
+        assert_eq!(order.status, OrderStatus::PendingRisk);
+
+        assert!(order.apply_leg_fill("BTC-20260315-55000-C", 4, 55.0));
+        assert_eq!(order.filled_quantity, 2);
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.avg_fill_price, Some(40.0));
+
+        assert!(!order.apply_leg_fill("ETH-20260315-3000-C", 1, 10.0));
+    }
+
     #[test]
     fn test_order_can_cancel() {
         let mut order = Order::new(
@@ -62,7 +62,7 @@ pub mod websocket;
 // Re-exports for convenience
 pub use config::{ports, ServerConfig};
 pub use error::{Result, ServerError};
-pub use grpc::GrpcServer;
+pub use grpc::{GrpcServer, GrpcServerBuilder};
 pub use health::{HealthClient, HealthState, HealthStatus};
 pub use http::HttpServer;
 pub use port_validator::validate_ports_available;
@@ -138,6 +138,16 @@ impl CombinedServer {
         }
     }
 
+    /// Serve gRPC with the given server instead of the placeholder
+    ///
+    /// Ignored unless a gRPC port is configured.
+    pub fn with_grpc_server(mut self, grpc_server: GrpcServer) -> Self {
+        if self.config.grpc_port.is_some() {
+            self.grpc_server = Some(grpc_server);
+        }
+        self
+    }
+
     /// Create a simple ping/health server with default config for service
     pub fn ping_server(service_name: impl Into<String>) -> Self {
         let service_name = service_name.into();
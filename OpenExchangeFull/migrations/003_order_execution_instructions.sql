@@ -0,0 +1,31 @@
+-- ============================================================================
+-- OMS Database Schema
+-- Migration: 003_order_execution_instructions.sql
+-- Post-only, all-or-none and minimum-quantity order instructions
+-- ============================================================================
+
+-- Post-only handling enum
+DO $$ BEGIN
+    CREATE TYPE post_only_mode AS ENUM ('none', 'reject', 'reprice');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- ============================================================================
+-- ORDERS TABLES
+-- ============================================================================
+
+ALTER TABLE orders_prod
+    ADD COLUMN IF NOT EXISTS post_only post_only_mode NOT NULL DEFAULT 'none',
+    ADD COLUMN IF NOT EXISTS all_or_none BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS min_quantity INTEGER CHECK (min_quantity > 0);
+
+ALTER TABLE orders_virtual
+    ADD COLUMN IF NOT EXISTS post_only post_only_mode NOT NULL DEFAULT 'none',
+    ADD COLUMN IF NOT EXISTS all_or_none BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS min_quantity INTEGER CHECK (min_quantity > 0);
+
+ALTER TABLE orders_static
+    ADD COLUMN IF NOT EXISTS post_only post_only_mode NOT NULL DEFAULT 'none',
+    ADD COLUMN IF NOT EXISTS all_or_none BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS min_quantity INTEGER CHECK (min_quantity > 0);
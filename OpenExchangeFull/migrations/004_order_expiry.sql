@@ -0,0 +1,29 @@
+-- ============================================================================
+-- OMS Database Schema
+-- Migration: 004_order_expiry.sql
+-- Good-till-date orders and expiry timestamps for GTD/DAY orders
+-- ============================================================================
+
+-- Good-till-date time in force
+ALTER TYPE time_in_force ADD VALUE IF NOT EXISTS 'gtd';
+
+-- ============================================================================
+-- ORDERS TABLES
+-- ============================================================================
+
+ALTER TABLE orders_prod
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+ALTER TABLE orders_virtual
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+ALTER TABLE orders_static
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+-- Expiry sweeps only look at resting orders that carry an expiry
+CREATE INDEX IF NOT EXISTS idx_orders_prod_expires_at ON orders_prod(expires_at)
+    WHERE expires_at IS NOT NULL AND status IN ('open', 'partially_filled');
+CREATE INDEX IF NOT EXISTS idx_orders_virtual_expires_at ON orders_virtual(expires_at)
+    WHERE expires_at IS NOT NULL AND status IN ('open', 'partially_filled');
+CREATE INDEX IF NOT EXISTS idx_orders_static_expires_at ON orders_static(expires_at)
+    WHERE expires_at IS NOT NULL AND status IN ('open', 'partially_filled');
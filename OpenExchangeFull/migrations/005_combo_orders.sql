@@ -0,0 +1,56 @@
+-- ============================================================================
+-- OMS Database Schema
+-- Migration: 005_combo_orders.sql
+-- Multi-leg combo orders and per-leg fills
+-- ============================================================================
+
+-- ============================================================================
+-- ORDERS TABLES
+-- ============================================================================
+
+-- Legs of a combo order as JSON (NULL for outright orders)
+ALTER TABLE orders_prod
+    ADD COLUMN IF NOT EXISTS legs TEXT;
+
+ALTER TABLE orders_virtual
+    ADD COLUMN IF NOT EXISTS legs TEXT;
+
+ALTER TABLE orders_static
+    ADD COLUMN IF NOT EXISTS legs TEXT;
+
+-- Combo prices are net of the legs and may be zero or negative (a credit)
+ALTER TABLE orders_prod DROP CONSTRAINT IF EXISTS orders_prod_limit_price;
+ALTER TABLE orders_prod ADD CONSTRAINT orders_prod_limit_price CHECK (
+    (order_type = 'limit' AND price IS NOT NULL AND (price > 0 OR legs IS NOT NULL)) OR
+    (order_type = 'market' AND price IS NULL)
+);
+
+ALTER TABLE orders_virtual DROP CONSTRAINT IF EXISTS orders_virtual_limit_price;
+ALTER TABLE orders_virtual ADD CONSTRAINT orders_virtual_limit_price CHECK (
+    (order_type = 'limit' AND price IS NOT NULL AND (price > 0 OR legs IS NOT NULL)) OR
+    (order_type = 'market' AND price IS NULL)
+);
+
+-- ============================================================================
+-- FILLS TABLES
+-- ============================================================================
+
+-- Leg instrument of a combo fill (NULL for outright fills)
+ALTER TABLE order_fills_prod
+    ADD COLUMN IF NOT EXISTS instrument_id VARCHAR(128);
+
+ALTER TABLE order_fills_virtual
+    ADD COLUMN IF NOT EXISTS instrument_id VARCHAR(128);
+
+ALTER TABLE order_fills_static
+    ADD COLUMN IF NOT EXISTS instrument_id VARCHAR(128);
+
+-- A combo leg may trade at zero
+ALTER TABLE order_fills_prod DROP CONSTRAINT IF EXISTS order_fills_prod_price_check;
+ALTER TABLE order_fills_prod ADD CONSTRAINT order_fills_prod_price_check CHECK (price >= 0);
+
+ALTER TABLE order_fills_virtual DROP CONSTRAINT IF EXISTS order_fills_virtual_price_check;
+ALTER TABLE order_fills_virtual ADD CONSTRAINT order_fills_virtual_price_check CHECK (price >= 0);
+
+ALTER TABLE order_fills_static DROP CONSTRAINT IF EXISTS order_fills_static_price_check;
+ALTER TABLE order_fills_static ADD CONSTRAINT order_fills_static_price_check CHECK (price >= 0);